@@ -0,0 +1,155 @@
+-- ======================================================
+-- FULL-TEXT SEARCH (FTS5)
+-- External-content indexes over the searchable columns,
+-- kept in sync with their source tables via triggers.
+-- ======================================================
+
+-- ======================================================
+-- Companies
+-- ======================================================
+CREATE VIRTUAL TABLE IF NOT EXISTS company_fts USING fts5(
+    name,
+    industry,
+    content = 'company',
+    content_rowid = 'id',
+    tokenize = 'unicode61 remove_diacritics 2'
+);
+
+CREATE TRIGGER IF NOT EXISTS company_fts_ai AFTER INSERT ON company BEGIN
+    INSERT INTO company_fts (rowid, name, industry)
+    VALUES (new.id, new.name, new.industry);
+END;
+
+CREATE TRIGGER IF NOT EXISTS company_fts_ad AFTER DELETE ON company BEGIN
+    INSERT INTO company_fts (company_fts, rowid, name, industry)
+    VALUES ('delete', old.id, old.name, old.industry);
+END;
+
+CREATE TRIGGER IF NOT EXISTS company_fts_au AFTER UPDATE ON company BEGIN
+    INSERT INTO company_fts (company_fts, rowid, name, industry)
+    VALUES ('delete', old.id, old.name, old.industry);
+    INSERT INTO company_fts (rowid, name, industry)
+    VALUES (new.id, new.name, new.industry);
+END;
+
+-- ======================================================
+-- Persons
+-- ======================================================
+CREATE VIRTUAL TABLE IF NOT EXISTS person_fts USING fts5(
+    first_name,
+    last_name,
+    email,
+    content = 'person',
+    content_rowid = 'id',
+    tokenize = 'unicode61 remove_diacritics 2'
+);
+
+CREATE TRIGGER IF NOT EXISTS person_fts_ai AFTER INSERT ON person BEGIN
+    INSERT INTO person_fts (rowid, first_name, last_name, email)
+    VALUES (new.id, new.first_name, new.last_name, new.email);
+END;
+
+CREATE TRIGGER IF NOT EXISTS person_fts_ad AFTER DELETE ON person BEGIN
+    INSERT INTO person_fts (person_fts, rowid, first_name, last_name, email)
+    VALUES ('delete', old.id, old.first_name, old.last_name, old.email);
+END;
+
+CREATE TRIGGER IF NOT EXISTS person_fts_au AFTER UPDATE ON person BEGIN
+    INSERT INTO person_fts (person_fts, rowid, first_name, last_name, email)
+    VALUES ('delete', old.id, old.first_name, old.last_name, old.email);
+    INSERT INTO person_fts (rowid, first_name, last_name, email)
+    VALUES (new.id, new.first_name, new.last_name, new.email);
+END;
+
+-- ======================================================
+-- Job Listings
+-- ======================================================
+CREATE VIRTUAL TABLE IF NOT EXISTS job_listing_fts USING fts5(
+    title,
+    description,
+    content = 'job_listing',
+    content_rowid = 'id',
+    tokenize = 'unicode61 remove_diacritics 2'
+);
+
+CREATE TRIGGER IF NOT EXISTS job_listing_fts_ai AFTER INSERT ON job_listing BEGIN
+    INSERT INTO job_listing_fts (rowid, title, description)
+    VALUES (new.id, new.title, new.description);
+END;
+
+CREATE TRIGGER IF NOT EXISTS job_listing_fts_ad AFTER DELETE ON job_listing BEGIN
+    INSERT INTO job_listing_fts (job_listing_fts, rowid, title, description)
+    VALUES ('delete', old.id, old.title, old.description);
+END;
+
+CREATE TRIGGER IF NOT EXISTS job_listing_fts_au AFTER UPDATE ON job_listing BEGIN
+    INSERT INTO job_listing_fts (job_listing_fts, rowid, title, description)
+    VALUES ('delete', old.id, old.title, old.description);
+    INSERT INTO job_listing_fts (rowid, title, description)
+    VALUES (new.id, new.title, new.description);
+END;
+
+-- ======================================================
+-- Interactions
+-- ======================================================
+CREATE VIRTUAL TABLE IF NOT EXISTS interaction_fts USING fts5(
+    subject,
+    summary,
+    content = 'interaction',
+    content_rowid = 'id',
+    tokenize = 'unicode61 remove_diacritics 2'
+);
+
+CREATE TRIGGER IF NOT EXISTS interaction_fts_ai AFTER INSERT ON interaction BEGIN
+    INSERT INTO interaction_fts (rowid, subject, summary)
+    VALUES (new.id, new.subject, new.summary);
+END;
+
+CREATE TRIGGER IF NOT EXISTS interaction_fts_ad AFTER DELETE ON interaction BEGIN
+    INSERT INTO interaction_fts (interaction_fts, rowid, subject, summary)
+    VALUES ('delete', old.id, old.subject, old.summary);
+END;
+
+CREATE TRIGGER IF NOT EXISTS interaction_fts_au AFTER UPDATE ON interaction BEGIN
+    INSERT INTO interaction_fts (interaction_fts, rowid, subject, summary)
+    VALUES ('delete', old.id, old.subject, old.summary);
+    INSERT INTO interaction_fts (rowid, subject, summary)
+    VALUES (new.id, new.subject, new.summary);
+END;
+
+-- ======================================================
+-- Notes
+-- ======================================================
+CREATE VIRTUAL TABLE IF NOT EXISTS note_fts USING fts5(
+    title,
+    content,
+    content = 'note',
+    content_rowid = 'id',
+    tokenize = 'unicode61 remove_diacritics 2'
+);
+
+CREATE TRIGGER IF NOT EXISTS note_fts_ai AFTER INSERT ON note BEGIN
+    INSERT INTO note_fts (rowid, title, content)
+    VALUES (new.id, new.title, new.content);
+END;
+
+CREATE TRIGGER IF NOT EXISTS note_fts_ad AFTER DELETE ON note BEGIN
+    INSERT INTO note_fts (note_fts, rowid, title, content)
+    VALUES ('delete', old.id, old.title, old.content);
+END;
+
+CREATE TRIGGER IF NOT EXISTS note_fts_au AFTER UPDATE ON note BEGIN
+    INSERT INTO note_fts (note_fts, rowid, title, content)
+    VALUES ('delete', old.id, old.title, old.content);
+    INSERT INTO note_fts (rowid, title, content)
+    VALUES (new.id, new.title, new.content);
+END;
+
+-- ======================================================
+-- Index existing rows
+-- ======================================================
+INSERT INTO company_fts (company_fts) VALUES ('rebuild');
+INSERT INTO person_fts (person_fts) VALUES ('rebuild');
+INSERT INTO job_listing_fts (job_listing_fts) VALUES ('rebuild');
+INSERT INTO interaction_fts (interaction_fts) VALUES ('rebuild');
+INSERT INTO note_fts (note_fts) VALUES ('rebuild');
@@ -0,0 +1,49 @@
+-- ======================================================
+-- APPLICATION STAGE HISTORY
+-- One row per stage an application has entered, written by
+-- triggers so every code path that touches `stage` is covered.
+-- ======================================================
+CREATE TABLE IF NOT EXISTS application_stage_event (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    application_id INTEGER NOT NULL REFERENCES application(id) ON DELETE CASCADE,
+    from_stage TEXT,
+    to_stage TEXT NOT NULL,
+    changed_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_stage_event_application
+    ON application_stage_event (application_id, changed_at);
+
+-- Initial stage: back-dated to applied_date when an older application is entered
+CREATE TRIGGER IF NOT EXISTS application_stage_event_ai AFTER INSERT ON application BEGIN
+    INSERT INTO application_stage_event (application_id, from_stage, to_stage, changed_at)
+    VALUES (
+        new.id,
+        NULL,
+        new.stage,
+        CASE
+            WHEN date(new.applied_date) < date(new.created_at)
+                THEN datetime(new.applied_date)
+            ELSE new.created_at
+        END
+    );
+END;
+
+CREATE TRIGGER IF NOT EXISTS application_stage_event_au
+AFTER UPDATE OF stage ON application
+WHEN old.stage IS NOT new.stage
+BEGIN
+    INSERT INTO application_stage_event (application_id, from_stage, to_stage)
+    VALUES (new.id, old.stage, new.stage);
+END;
+
+-- ======================================================
+-- Backfill: only the current stage is known for existing rows
+-- ======================================================
+INSERT INTO application_stage_event (application_id, from_stage, to_stage, changed_at)
+SELECT
+    id,
+    NULL,
+    stage,
+    CASE WHEN stage = 'applied' THEN datetime(applied_date) ELSE updated_at END
+FROM application;
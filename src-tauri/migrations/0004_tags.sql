@@ -0,0 +1,61 @@
+-- ======================================================
+-- TAGS
+-- Free-form labels shared by all entities. `entity_tag` is
+-- polymorphic, so links are removed by triggers instead of
+-- foreign keys when a tagged record is deleted.
+-- ======================================================
+CREATE TABLE IF NOT EXISTS tag (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL COLLATE NOCASE UNIQUE,
+    color TEXT, -- '#rrggbb'
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CHECK (length(trim(name)) > 0),
+    CHECK (color IS NULL OR color GLOB '#[0-9a-fA-F][0-9a-fA-F][0-9a-fA-F][0-9a-fA-F][0-9a-fA-F][0-9a-fA-F]')
+);
+
+CREATE TABLE IF NOT EXISTS entity_tag (
+    tag_id INTEGER NOT NULL REFERENCES tag(id) ON DELETE CASCADE,
+    entity_type TEXT NOT NULL,
+    entity_id INTEGER NOT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (tag_id, entity_type, entity_id),
+    CHECK (
+        entity_type IN (
+            'company', 'person', 'job_listing', 'application',
+            'interaction', 'note', 'reminder'
+        )
+    )
+);
+
+CREATE INDEX IF NOT EXISTS idx_entity_tag_entity
+    ON entity_tag (entity_type, entity_id);
+
+-- Drop links of deleted records (including cascaded deletes)
+CREATE TRIGGER IF NOT EXISTS company_entity_tag_ad AFTER DELETE ON company BEGIN
+    DELETE FROM entity_tag WHERE entity_type = 'company' AND entity_id = old.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS person_entity_tag_ad AFTER DELETE ON person BEGIN
+    DELETE FROM entity_tag WHERE entity_type = 'person' AND entity_id = old.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS job_listing_entity_tag_ad AFTER DELETE ON job_listing BEGIN
+    DELETE FROM entity_tag WHERE entity_type = 'job_listing' AND entity_id = old.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS application_entity_tag_ad AFTER DELETE ON application BEGIN
+    DELETE FROM entity_tag WHERE entity_type = 'application' AND entity_id = old.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS interaction_entity_tag_ad AFTER DELETE ON interaction BEGIN
+    DELETE FROM entity_tag WHERE entity_type = 'interaction' AND entity_id = old.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS note_entity_tag_ad AFTER DELETE ON note BEGIN
+    DELETE FROM entity_tag WHERE entity_type = 'note' AND entity_id = old.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS reminder_entity_tag_ad AFTER DELETE ON reminder BEGIN
+    DELETE FROM entity_tag WHERE entity_type = 'reminder' AND entity_id = old.id;
+END;
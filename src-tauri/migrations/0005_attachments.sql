@@ -0,0 +1,48 @@
+-- ======================================================
+-- ATTACHMENTS
+-- Files are copied into a content-addressed store in the
+-- profile folder; one row per distinct file (by sha256).
+-- `attachment_link` ties a file to any number of records and
+-- is cleaned up by triggers like `entity_tag`.
+-- ======================================================
+CREATE TABLE IF NOT EXISTS attachment (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    sha256 TEXT NOT NULL UNIQUE,
+    filename TEXT NOT NULL,       -- name of the file when first added
+    mime_type TEXT NOT NULL,
+    size_bytes INTEGER NOT NULL,
+    storage_path TEXT NOT NULL,   -- relative to the attachment store
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CHECK (length(sha256) = 64),
+    CHECK (size_bytes >= 0)
+);
+
+CREATE TABLE IF NOT EXISTS attachment_link (
+    attachment_id INTEGER NOT NULL REFERENCES attachment(id) ON DELETE CASCADE,
+    entity_type TEXT NOT NULL,
+    entity_id INTEGER NOT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (attachment_id, entity_type, entity_id),
+    CHECK (entity_type IN ('company', 'person', 'job_listing', 'application'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_attachment_link_entity
+    ON attachment_link (entity_type, entity_id);
+
+-- Drop links of deleted records; unlinked files are removed by the
+-- orphan cleanup
+CREATE TRIGGER IF NOT EXISTS company_attachment_link_ad AFTER DELETE ON company BEGIN
+    DELETE FROM attachment_link WHERE entity_type = 'company' AND entity_id = old.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS person_attachment_link_ad AFTER DELETE ON person BEGIN
+    DELETE FROM attachment_link WHERE entity_type = 'person' AND entity_id = old.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS job_listing_attachment_link_ad AFTER DELETE ON job_listing BEGIN
+    DELETE FROM attachment_link WHERE entity_type = 'job_listing' AND entity_id = old.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS application_attachment_link_ad AFTER DELETE ON application BEGIN
+    DELETE FROM attachment_link WHERE entity_type = 'application' AND entity_id = old.id;
+END;
@@ -0,0 +1,18 @@
+-- ======================================================
+-- APPLICATION CONTACTS
+-- People involved in an application, with their role in it.
+-- One row per person and application; re-adding a person
+-- updates the role.
+-- ======================================================
+CREATE TABLE IF NOT EXISTS application_contact (
+    application_id INTEGER NOT NULL REFERENCES application(id) ON DELETE CASCADE,
+    person_id INTEGER NOT NULL REFERENCES person(id) ON DELETE CASCADE,
+    role TEXT NOT NULL DEFAULT 'other',
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (application_id, person_id),
+    CHECK (role IN ('recruiter', 'referrer', 'interviewer', 'hiring_manager', 'other'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_application_contact_person_id
+    ON application_contact (person_id);
@@ -0,0 +1,21 @@
+-- ======================================================
+-- REMINDER NOTIFICATIONS
+-- `notified_at` is set when the scheduler claims a due
+-- reminder, so each reminder is announced once. Snoozing
+-- clears it and sets `snoozed_until` (UTC).
+-- ======================================================
+ALTER TABLE reminder ADD COLUMN notified_at DATETIME;
+ALTER TABLE reminder ADD COLUMN snoozed_until DATETIME;
+
+CREATE INDEX IF NOT EXISTS idx_reminder_pending_notification
+    ON reminder (reminder_date)
+    WHERE is_completed = 0 AND notified_at IS NULL;
+
+-- A rescheduled or reopened reminder is announced again
+CREATE TRIGGER IF NOT EXISTS reminder_notification_reset
+AFTER UPDATE OF reminder_date, is_completed ON reminder
+WHEN new.reminder_date IS NOT old.reminder_date
+    OR (old.is_completed = 1 AND new.is_completed = 0)
+BEGIN
+    UPDATE reminder SET notified_at = NULL, snoozed_until = NULL WHERE id = new.id;
+END;
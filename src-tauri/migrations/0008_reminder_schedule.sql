@@ -0,0 +1,25 @@
+-- ======================================================
+-- REMINDER SCHEDULE
+-- `reminder_time` is a wall-clock time (HH:MM:SS) in
+-- `timezone` (IANA name, NULL = the device's local time);
+-- without a time the reminder is due all day.
+-- `recurrence` is an RRULE subset, e.g.
+-- 'FREQ=WEEKLY;INTERVAL=1;UNTIL=20251231'. It lives on the
+-- open occurrence and moves to the next one on completion.
+-- ======================================================
+ALTER TABLE reminder ADD COLUMN reminder_time TEXT;
+ALTER TABLE reminder ADD COLUMN timezone TEXT;
+ALTER TABLE reminder ADD COLUMN recurrence TEXT;
+
+-- A moved reminder is announced again, also when only its time changes
+DROP TRIGGER IF EXISTS reminder_notification_reset;
+
+CREATE TRIGGER IF NOT EXISTS reminder_notification_reset
+AFTER UPDATE OF reminder_date, reminder_time, timezone, is_completed ON reminder
+WHEN new.reminder_date IS NOT old.reminder_date
+    OR new.reminder_time IS NOT old.reminder_time
+    OR new.timezone IS NOT old.timezone
+    OR (old.is_completed = 1 AND new.is_completed = 0)
+BEGIN
+    UPDATE reminder SET notified_at = NULL, snoozed_until = NULL WHERE id = new.id;
+END;
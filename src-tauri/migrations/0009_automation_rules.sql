@@ -0,0 +1,36 @@
+-- ======================================================
+-- AUTOMATION RULES
+-- A rule pairs a trigger (JSON, tagged by `event`) with an
+-- action (JSON, tagged by `type`); see `db::models::rule`.
+-- ======================================================
+CREATE TABLE IF NOT EXISTS automation_rule (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL,
+    enabled BOOLEAN NOT NULL DEFAULT 1,
+    rule_trigger TEXT NOT NULL,
+    rule_action TEXT NOT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CHECK (length(trim(name)) > 0),
+    CHECK (json_valid(rule_trigger) AND json_extract(rule_trigger, '$.event') IS NOT NULL),
+    CHECK (json_valid(rule_action) AND json_extract(rule_action, '$.type') IS NOT NULL)
+);
+
+-- ======================================================
+-- Rule runs: what each firing did. `anchor` is set for
+-- inactivity rules (the last activity date) so they fire
+-- once per quiet period and application.
+-- ======================================================
+CREATE TABLE IF NOT EXISTS automation_rule_run (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    rule_id INTEGER NOT NULL REFERENCES automation_rule(id) ON DELETE CASCADE,
+    application_id INTEGER REFERENCES application(id) ON DELETE CASCADE,
+    anchor TEXT,
+    entity_type TEXT,
+    entity_id INTEGER,
+    fired_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (rule_id, application_id, anchor)
+);
+
+CREATE INDEX IF NOT EXISTS idx_automation_rule_run_application
+    ON automation_rule_run (application_id);
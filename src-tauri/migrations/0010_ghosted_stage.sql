@@ -0,0 +1,72 @@
+-- ======================================================
+-- GHOSTED STAGE
+-- For applications that never got an answer. SQLite cannot
+-- change a CHECK constraint in place, so the table is rebuilt
+-- with the same ids; the migrator runs with foreign keys off,
+-- so rows referencing applications are left untouched.
+-- ======================================================
+CREATE TABLE application_new (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    job_listing_id INTEGER REFERENCES job_listing(id) ON DELETE SET NULL,
+    stage TEXT NOT NULL DEFAULT 'applied',
+    applied_date DATE NOT NULL DEFAULT (date('now')),
+    application_notes TEXT,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CHECK (
+        stage IN (
+            'applied', 'screening', 'assessment', 'interviewing',
+            'offered', 'negotiation', 'accepted', 'rejected',
+            'withdrawn', 'on_hold', 'ghosted', 'other'
+        )
+    )
+);
+
+INSERT INTO application_new (
+    id, job_listing_id, stage, applied_date, application_notes, created_at, updated_at
+)
+SELECT id, job_listing_id, stage, applied_date, application_notes, created_at, updated_at
+FROM application;
+
+-- Keep ids of deleted applications from being reused
+DELETE FROM sqlite_sequence WHERE name = 'application_new';
+INSERT INTO sqlite_sequence (name, seq)
+SELECT 'application_new', seq FROM sqlite_sequence WHERE name = 'application';
+
+DROP TABLE application;
+ALTER TABLE application_new RENAME TO application;
+
+CREATE INDEX IF NOT EXISTS idx_application_job_listing_id ON application(job_listing_id);
+
+-- ======================================================
+-- Triggers dropped with the old table (0003, 0004, 0005)
+-- ======================================================
+CREATE TRIGGER IF NOT EXISTS application_stage_event_ai AFTER INSERT ON application BEGIN
+    INSERT INTO application_stage_event (application_id, from_stage, to_stage, changed_at)
+    VALUES (
+        new.id,
+        NULL,
+        new.stage,
+        CASE
+            WHEN date(new.applied_date) < date(new.created_at)
+                THEN datetime(new.applied_date)
+            ELSE new.created_at
+        END
+    );
+END;
+
+CREATE TRIGGER IF NOT EXISTS application_stage_event_au
+AFTER UPDATE OF stage ON application
+WHEN old.stage IS NOT new.stage
+BEGIN
+    INSERT INTO application_stage_event (application_id, from_stage, to_stage)
+    VALUES (new.id, old.stage, new.stage);
+END;
+
+CREATE TRIGGER IF NOT EXISTS application_entity_tag_ad AFTER DELETE ON application BEGIN
+    DELETE FROM entity_tag WHERE entity_type = 'application' AND entity_id = old.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS application_attachment_link_ad AFTER DELETE ON application BEGIN
+    DELETE FROM attachment_link WHERE entity_type = 'application' AND entity_id = old.id;
+END;
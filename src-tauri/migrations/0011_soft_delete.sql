@@ -0,0 +1,35 @@
+-- ======================================================
+-- SOFT DELETE AND ARCHIVE
+-- Deleting a record moves it to the trash by setting
+-- `deleted_at`; it is removed for good when the trash is
+-- emptied. Archived records (`archived_at`) are kept out of
+-- default lists but otherwise stay fully usable.
+-- ======================================================
+ALTER TABLE company ADD COLUMN archived_at DATETIME;
+ALTER TABLE company ADD COLUMN deleted_at DATETIME;
+
+ALTER TABLE person ADD COLUMN archived_at DATETIME;
+ALTER TABLE person ADD COLUMN deleted_at DATETIME;
+
+ALTER TABLE job_listing ADD COLUMN archived_at DATETIME;
+ALTER TABLE job_listing ADD COLUMN deleted_at DATETIME;
+
+ALTER TABLE application ADD COLUMN archived_at DATETIME;
+ALTER TABLE application ADD COLUMN deleted_at DATETIME;
+
+ALTER TABLE interaction ADD COLUMN archived_at DATETIME;
+ALTER TABLE interaction ADD COLUMN deleted_at DATETIME;
+
+ALTER TABLE note ADD COLUMN archived_at DATETIME;
+ALTER TABLE note ADD COLUMN deleted_at DATETIME;
+
+ALTER TABLE reminder ADD COLUMN archived_at DATETIME;
+ALTER TABLE reminder ADD COLUMN deleted_at DATETIME;
+
+CREATE INDEX IF NOT EXISTS idx_company_deleted_at ON company(deleted_at) WHERE deleted_at IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_person_deleted_at ON person(deleted_at) WHERE deleted_at IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_job_listing_deleted_at ON job_listing(deleted_at) WHERE deleted_at IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_application_deleted_at ON application(deleted_at) WHERE deleted_at IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_interaction_deleted_at ON interaction(deleted_at) WHERE deleted_at IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_note_deleted_at ON note(deleted_at) WHERE deleted_at IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_reminder_deleted_at ON reminder(deleted_at) WHERE deleted_at IS NOT NULL;
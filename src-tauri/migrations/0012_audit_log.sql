@@ -0,0 +1,31 @@
+-- ======================================================
+-- AUDIT LOG
+-- One row per change made through the app. `changes` maps
+-- each changed field (payload name) to its values, e.g.
+-- {"city": {"before": "Berlin", "after": "Hamburg"}}.
+-- No foreign keys: the history outlives the record.
+-- ======================================================
+CREATE TABLE IF NOT EXISTS audit_log (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    entity_type TEXT NOT NULL,
+    entity_id INTEGER NOT NULL,
+    action TEXT NOT NULL,
+    changes TEXT NOT NULL DEFAULT '{}',
+    origin TEXT,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CHECK (
+        entity_type IN (
+            'company', 'person', 'job_listing', 'application',
+            'interaction', 'note', 'reminder'
+        )
+    ),
+    CHECK (
+        action IN (
+            'create', 'update', 'delete', 'restore',
+            'archive', 'unarchive', 'purge', 'revert'
+        )
+    ),
+    CHECK (json_valid(changes))
+);
+
+CREATE INDEX IF NOT EXISTS idx_audit_log_entity ON audit_log (entity_type, entity_id, id);
@@ -0,0 +1,110 @@
+-- ======================================================
+-- SALARY NORMALIZATION
+-- Job listings get a pay period, and the currency becomes
+-- any ISO 4217 code ('other' turns into 'XXX', the code for
+-- "no currency"). SQLite cannot change a CHECK constraint in
+-- place, so the table is rebuilt with the same ids, like in
+-- 0010.
+-- ======================================================
+CREATE TABLE job_listing_new (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    company_id INTEGER NOT NULL REFERENCES company(id) ON DELETE CASCADE,
+    title TEXT NOT NULL,
+    work_type TEXT,
+    category TEXT,
+    seniority_level TEXT,
+    salary_min INTEGER,
+    salary_max INTEGER,
+    currency TEXT,
+    description TEXT,
+    url TEXT,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    archived_at DATETIME,
+    deleted_at DATETIME,
+    pay_period TEXT,
+    CHECK (
+        work_type IN (
+            'full_time', 'part_time', 'internship', 'contract',
+            'freelance', 'remote', 'in_office', 'hybrid', 'other'
+        )
+    ),
+    CHECK (
+        seniority_level IN (
+            'junior', 'mid', 'senior', 'lead', 'manager', 'other'
+        )
+    ),
+    CHECK (currency GLOB '[A-Z][A-Z][A-Z]'),
+    CHECK (pay_period IN ('hourly', 'daily', 'monthly', 'yearly')),
+    CHECK (salary_min IS NULL OR salary_max IS NULL OR salary_min <= salary_max)
+);
+
+INSERT INTO job_listing_new (
+    id, company_id, title, work_type, category, seniority_level,
+    salary_min, salary_max, currency, description, url,
+    created_at, updated_at, archived_at, deleted_at
+)
+SELECT
+    id, company_id, title, work_type, category, seniority_level,
+    salary_min, salary_max,
+    CASE currency WHEN 'other' THEN 'XXX' ELSE currency END,
+    description, url,
+    created_at, updated_at, archived_at, deleted_at
+FROM job_listing;
+
+-- Keep ids of deleted job listings from being reused
+DELETE FROM sqlite_sequence WHERE name = 'job_listing_new';
+INSERT INTO sqlite_sequence (name, seq)
+SELECT 'job_listing_new', seq FROM sqlite_sequence WHERE name = 'job_listing';
+
+DROP TABLE job_listing;
+ALTER TABLE job_listing_new RENAME TO job_listing;
+
+CREATE INDEX IF NOT EXISTS idx_job_listing_company_id ON job_listing(company_id);
+CREATE INDEX IF NOT EXISTS idx_job_listing_deleted_at ON job_listing(deleted_at) WHERE deleted_at IS NOT NULL;
+
+-- ======================================================
+-- Triggers dropped with the old table (0002, 0004, 0005)
+-- ======================================================
+CREATE TRIGGER IF NOT EXISTS job_listing_fts_ai AFTER INSERT ON job_listing BEGIN
+    INSERT INTO job_listing_fts (rowid, title, description)
+    VALUES (new.id, new.title, new.description);
+END;
+
+CREATE TRIGGER IF NOT EXISTS job_listing_fts_ad AFTER DELETE ON job_listing BEGIN
+    INSERT INTO job_listing_fts (job_listing_fts, rowid, title, description)
+    VALUES ('delete', old.id, old.title, old.description);
+END;
+
+CREATE TRIGGER IF NOT EXISTS job_listing_fts_au AFTER UPDATE ON job_listing BEGIN
+    INSERT INTO job_listing_fts (job_listing_fts, rowid, title, description)
+    VALUES ('delete', old.id, old.title, old.description);
+    INSERT INTO job_listing_fts (rowid, title, description)
+    VALUES (new.id, new.title, new.description);
+END;
+
+CREATE TRIGGER IF NOT EXISTS job_listing_entity_tag_ad AFTER DELETE ON job_listing BEGIN
+    DELETE FROM entity_tag WHERE entity_type = 'job_listing' AND entity_id = old.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS job_listing_attachment_link_ad AFTER DELETE ON job_listing BEGIN
+    DELETE FROM attachment_link WHERE entity_type = 'job_listing' AND entity_id = old.id;
+END;
+
+-- ======================================================
+-- EXCHANGE RATES
+-- Maintained by the user. `rate` is the value of one unit of
+-- `currency` in the base currency, so the base itself has
+-- rate 1. At most one row is the base.
+-- ======================================================
+CREATE TABLE IF NOT EXISTS exchange_rate (
+    currency TEXT PRIMARY KEY,
+    rate REAL NOT NULL,
+    is_base BOOLEAN NOT NULL DEFAULT 0,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CHECK (currency GLOB '[A-Z][A-Z][A-Z]'),
+    CHECK (rate > 0),
+    CHECK (is_base = 0 OR rate = 1)
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_exchange_rate_base ON exchange_rate(is_base) WHERE is_base = 1;
@@ -0,0 +1,15 @@
+-- ======================================================
+-- AUDIT OPERATIONS
+-- One row per command that can be undone. The audit entries
+-- a command writes carry its id, so undoing it touches those
+-- entries only, whatever else was recorded in the meantime
+-- (scheduled rules, other windows).
+-- ======================================================
+CREATE TABLE IF NOT EXISTS audit_operation (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+ALTER TABLE audit_log ADD COLUMN operation_id INTEGER;
+
+CREATE INDEX IF NOT EXISTS idx_audit_log_operation ON audit_log (operation_id) WHERE operation_id IS NOT NULL;
@@ -0,0 +1,38 @@
+use crate::commands::command_utils::{active_pool, parse_optional_date};
+use crate::db::connection::DbState;
+use crate::db::queries::analytics::DateRange;
+use crate::services::analytics_service::get_pipeline_report_service;
+use crate::services::service_types::JsonResult;
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+#[serde(tag = "action", content = "payload")]
+pub enum AnalyticsCommand {
+    #[serde(rename_all = "camelCase")]
+    GetPipelineReport {
+        from: Option<String>,
+        to: Option<String>,
+    },
+}
+
+#[tauri::command]
+pub async fn handle_analytics_command(
+    db: tauri::State<'_, DbState>,
+    command: AnalyticsCommand,
+) -> JsonResult {
+    let pool = active_pool(&db)?;
+
+    match command {
+        // ======================================================
+        // Pipeline Report
+        // ======================================================
+        AnalyticsCommand::GetPipelineReport { from, to } => {
+            let range = DateRange {
+                from: parse_optional_date("from", from)?,
+                to: parse_optional_date("to", to)?,
+            };
+
+            get_pipeline_report_service(&pool, &range).await
+        }
+    }
+}
@@ -1,12 +1,23 @@
-use crate::commands::command_utils::{parse_optional_date, parse_required_date};
+use crate::commands::command_utils::{
+    active_pool, after_write, parse_optional_date, parse_required_date,
+};
+use crate::db::connection::DbState;
 use crate::db::models::enums::Stage;
+use crate::db::models::patch::Patch;
+use crate::db::queries::list_query::ListOptions;
 use crate::services::application_service::{
     create_application_service, delete_application_service, get_all_applications_service,
-    get_application_by_id_service, update_application_service,
+    get_application_by_id_service, get_application_timeline_service,
+    ghost_stale_applications_service, list_stale_applications_service, update_application_service,
+    DEFAULT_STALE_DAYS,
 };
-use crate::services::service_types::JsonResult;
+use crate::services::detail_service::get_application_detail_service;
+use crate::services::service_types::ServiceResult;
+use crate::services::undo_service::undoable;
+use crate::settings::AppSettings;
+use chrono::Local;
 use serde::Deserialize;
-use sqlx::SqlitePool;
+use std::sync::Mutex;
 
 #[derive(Deserialize)]
 #[serde(tag = "action", content = "payload")]
@@ -21,25 +32,56 @@ pub enum ApplicationCommand {
     #[serde(rename_all = "camelCase")]
     Update {
         id: i64,
-        job_listing_id: Option<i64>,
+        #[serde(default)]
+        job_listing_id: Patch<i64>,
         stage: Option<Stage>,
         applied_date: Option<String>,
-        application_notes: Option<String>,
+        #[serde(default)]
+        application_notes: Patch<String>,
     },
     GetById {
         id: i64,
     },
-    ListAll,
+    /// The record together with its related records.
+    GetDetail {
+        id: i64,
+    },
+    ListAll(Option<ListOptions>),
     Delete {
         id: i64,
     },
+    GetTimeline {
+        id: i64,
+    },
+    /// Open applications without an interaction or stage change for
+    /// `days` days (default 30), oldest activity first.
+    ListStale {
+        days: Option<i64>,
+    },
+    /// Moves the stale applications, or only those among `ids`, to `ghosted`.
+    GhostStale {
+        days: Option<i64>,
+        ids: Option<Vec<i64>>,
+    },
 }
 #[tauri::command]
 pub async fn handle_application_command(
-    pool: tauri::State<'_, SqlitePool>,
+    db: tauri::State<'_, DbState>,
+    settings: tauri::State<'_, Mutex<AppSettings>>,
     command: ApplicationCommand,
-) -> JsonResult {
-    match command {
+) -> ServiceResult {
+    let pool = active_pool(&db)?;
+
+    // Only these writes can change the calendar feed
+    let refresh_calendar = matches!(
+        command,
+        ApplicationCommand::Create { .. }
+            | ApplicationCommand::Update { .. }
+            | ApplicationCommand::Delete { .. }
+            | ApplicationCommand::GhostStale { .. }
+    );
+
+    let result = match command {
         // ======================================================
         // Create
         // ======================================================
@@ -49,14 +91,18 @@ pub async fn handle_application_command(
             applied_date,
             application_notes,
         } => {
-            let parsed_date = parse_required_date(applied_date)?;
+            let parsed_date = parse_required_date("appliedDate", applied_date)?;
 
-            create_application_service(
+            undoable(
+                &db,
                 &pool,
-                job_listing_id,
-                stage.as_ref(),
-                &parsed_date,
-                application_notes.as_deref(),
+                create_application_service(
+                    &pool,
+                    job_listing_id,
+                    stage.as_ref(),
+                    &parsed_date,
+                    application_notes.as_deref(),
+                ),
             )
             .await
         }
@@ -71,15 +117,19 @@ pub async fn handle_application_command(
             applied_date,
             application_notes,
         } => {
-            let parsed_date = parse_optional_date(applied_date)?;
+            let parsed_date = parse_optional_date("appliedDate", applied_date)?;
 
-            update_application_service(
+            undoable(
+                &db,
                 &pool,
-                &id,
-                job_listing_id,
-                stage.as_ref(),
-                parsed_date.as_ref(),
-                application_notes.as_deref(),
+                update_application_service(
+                    &pool,
+                    &id,
+                    job_listing_id,
+                    stage.as_ref(),
+                    parsed_date.as_ref(),
+                    application_notes.as_deref(),
+                ),
             )
             .await
         }
@@ -88,15 +138,55 @@ pub async fn handle_application_command(
         // Get by ID
         // ======================================================
         ApplicationCommand::GetById { id } => get_application_by_id_service(&pool, &id).await,
+        ApplicationCommand::GetDetail { id } => get_application_detail_service(&pool, &id).await,
 
         // ======================================================
         // List All
         // ======================================================
-        ApplicationCommand::ListAll => get_all_applications_service(&pool).await,
+        ApplicationCommand::ListAll(options) => {
+            get_all_applications_service(&pool, &options.unwrap_or_default()).await
+        }
 
         // ======================================================
         // Delete
         // ======================================================
-        ApplicationCommand::Delete { id } => delete_application_service(&pool, &id).await,
+        ApplicationCommand::Delete { id } => {
+            undoable(&db, &pool, delete_application_service(&pool, &id)).await
+        }
+
+        // ======================================================
+        // Stage Timeline
+        // ======================================================
+        ApplicationCommand::GetTimeline { id } => {
+            get_application_timeline_service(&pool, &id).await
+        }
+
+        // ======================================================
+        // Stale Applications
+        // ======================================================
+        ApplicationCommand::ListStale { days } => {
+            let today = Local::now().date_naive();
+            list_stale_applications_service(&pool, days.unwrap_or(DEFAULT_STALE_DAYS), &today).await
+        }
+        ApplicationCommand::GhostStale { days, ids } => {
+            let today = Local::now().date_naive();
+            undoable(
+                &db,
+                &pool,
+                ghost_stale_applications_service(
+                    &pool,
+                    days.unwrap_or(DEFAULT_STALE_DAYS),
+                    &today,
+                    ids.as_deref(),
+                ),
+            )
+            .await
+        }
+    };
+
+    if refresh_calendar {
+        after_write(&pool, &settings, &result).await;
     }
+
+    result
 }
@@ -0,0 +1,60 @@
+use crate::commands::command_utils::active_pool;
+use crate::db::connection::DbState;
+use crate::db::models::enums::ContactRole;
+use crate::services::application_contact_service::{
+    attach_contact_service, detach_contact_service, get_applications_for_contact_service,
+    get_contacts_for_application_service,
+};
+use crate::services::service_types::ServiceResult;
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+#[serde(tag = "action", content = "payload")]
+pub enum ApplicationContactCommand {
+    /// Adds the person to the application or changes their role.
+    #[serde(rename_all = "camelCase")]
+    Attach {
+        application_id: i64,
+        person_id: i64,
+        role: ContactRole,
+    },
+    #[serde(rename_all = "camelCase")]
+    Detach { application_id: i64, person_id: i64 },
+    #[serde(rename_all = "camelCase")]
+    ListForApplication { application_id: i64 },
+    #[serde(rename_all = "camelCase")]
+    ListForPerson { person_id: i64 },
+}
+
+#[tauri::command]
+pub async fn handle_application_contact_command(
+    db: tauri::State<'_, DbState>,
+    command: ApplicationContactCommand,
+) -> ServiceResult {
+    let pool = active_pool(&db)?;
+
+    match command {
+        // ======================================================
+        // Attach / Detach
+        // ======================================================
+        ApplicationContactCommand::Attach {
+            application_id,
+            person_id,
+            role,
+        } => attach_contact_service(&pool, &application_id, &person_id, &role).await,
+        ApplicationContactCommand::Detach {
+            application_id,
+            person_id,
+        } => detach_contact_service(&pool, &application_id, &person_id).await,
+
+        // ======================================================
+        // Lists
+        // ======================================================
+        ApplicationContactCommand::ListForApplication { application_id } => {
+            get_contacts_for_application_service(&pool, &application_id).await
+        }
+        ApplicationContactCommand::ListForPerson { person_id } => {
+            get_applications_for_contact_service(&pool, &person_id).await
+        }
+    }
+}
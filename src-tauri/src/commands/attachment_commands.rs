@@ -0,0 +1,98 @@
+use crate::commands::command_utils::active_pool;
+use crate::db::connection::DbState;
+use crate::db::models::enums::EntityType;
+use crate::services::attachment_service::{
+    add_attachment_service, attachment_store, cleanup_attachments_service,
+    delete_attachment_service, list_attachments_service, open_attachment_service,
+    unlink_attachment_service,
+};
+use crate::services::service_types::ServiceResult;
+use crate::settings::AppSettings;
+use serde::Deserialize;
+use std::path::Path;
+use std::sync::Mutex;
+use tauri_plugin_opener::OpenerExt;
+
+#[derive(Deserialize)]
+#[serde(tag = "action", content = "payload")]
+pub enum AttachmentCommand {
+    /// Copies the file at `path` into the store and links it.
+    #[serde(rename_all = "camelCase")]
+    Add {
+        path: String,
+        entity_type: EntityType,
+        entity_id: i64,
+    },
+    #[serde(rename_all = "camelCase")]
+    ListForEntity {
+        entity_type: EntityType,
+        entity_id: i64,
+    },
+    Open {
+        id: i64,
+    },
+    /// Removes the attachment from one record only.
+    #[serde(rename_all = "camelCase")]
+    Unlink {
+        id: i64,
+        entity_type: EntityType,
+        entity_id: i64,
+    },
+    Delete {
+        id: i64,
+    },
+    CleanupOrphans,
+}
+
+#[tauri::command]
+pub async fn handle_attachment_command(
+    app: tauri::AppHandle,
+    db: tauri::State<'_, DbState>,
+    settings: tauri::State<'_, Mutex<AppSettings>>,
+    command: AttachmentCommand,
+) -> ServiceResult {
+    let pool = active_pool(&db)?;
+    let store = attachment_store(&settings.lock().unwrap());
+
+    match command {
+        // ======================================================
+        // Add
+        // ======================================================
+        AttachmentCommand::Add {
+            path,
+            entity_type,
+            entity_id,
+        } => add_attachment_service(&pool, &store, Path::new(&path), entity_type, &entity_id).await,
+
+        // ======================================================
+        // List
+        // ======================================================
+        AttachmentCommand::ListForEntity {
+            entity_type,
+            entity_id,
+        } => list_attachments_service(&pool, entity_type, &entity_id).await,
+
+        // ======================================================
+        // Open (default application)
+        // ======================================================
+        AttachmentCommand::Open { id } => {
+            open_attachment_service(&pool, &store, &id, |path| {
+                app.opener()
+                    .open_path(path.to_string_lossy(), None::<&str>)
+                    .map_err(|e| e.to_string())
+            })
+            .await
+        }
+
+        // ======================================================
+        // Unlink / Delete
+        // ======================================================
+        AttachmentCommand::Unlink {
+            id,
+            entity_type,
+            entity_id,
+        } => unlink_attachment_service(&pool, &store, &id, entity_type, &entity_id).await,
+        AttachmentCommand::Delete { id } => delete_attachment_service(&pool, &store, &id).await,
+        AttachmentCommand::CleanupOrphans => cleanup_attachments_service(&pool, &store).await,
+    }
+}
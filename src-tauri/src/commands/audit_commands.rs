@@ -0,0 +1,59 @@
+use crate::commands::command_utils::{active_pool, after_write};
+use crate::db::connection::DbState;
+use crate::db::models::enums::EntityType;
+use crate::services::audit_service::{get_history_service, revert_service};
+use crate::services::service_types::ServiceResult;
+use crate::services::undo_service::undoable;
+use crate::settings::AppSettings;
+use serde::Deserialize;
+use std::sync::Mutex;
+
+#[derive(Deserialize)]
+#[serde(tag = "action", content = "payload")]
+pub enum AuditCommand {
+    /// Every recorded change of one record, newest first.
+    #[serde(rename_all = "camelCase")]
+    GetHistory { entity_type: EntityType, id: i64 },
+    /// Puts the record back the way it was right after entry `auditId`.
+    #[serde(rename_all = "camelCase")]
+    Revert {
+        entity_type: EntityType,
+        id: i64,
+        audit_id: i64,
+    },
+}
+
+#[tauri::command]
+pub async fn handle_audit_command(
+    db: tauri::State<'_, DbState>,
+    settings: tauri::State<'_, Mutex<AppSettings>>,
+    command: AuditCommand,
+) -> ServiceResult {
+    let pool = active_pool(&db)?;
+
+    let refresh_calendar = matches!(command, AuditCommand::Revert { .. });
+
+    let result = match command {
+        AuditCommand::GetHistory { entity_type, id } => {
+            get_history_service(&pool, entity_type, &id).await
+        }
+        AuditCommand::Revert {
+            entity_type,
+            id,
+            audit_id,
+        } => {
+            undoable(
+                &db,
+                &pool,
+                revert_service(&pool, entity_type, &id, &audit_id),
+            )
+            .await
+        }
+    };
+
+    if refresh_calendar {
+        after_write(&pool, &settings, &result).await;
+    }
+
+    result
+}
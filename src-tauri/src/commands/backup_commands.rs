@@ -0,0 +1,67 @@
+use crate::commands::command_utils::{active_pool, after_write};
+use crate::db::connection::DbState;
+use crate::services::backup_service::{
+    create_backup_service, list_backups_service, restore_backup_service, set_backup_policy_service,
+};
+use crate::services::service_types::JsonResult;
+use crate::settings::AppSettings;
+use serde::Deserialize;
+use std::sync::Mutex;
+
+#[derive(Deserialize)]
+#[serde(tag = "action", content = "payload")]
+pub enum BackupCommand {
+    ListBackups,
+    CreateBackup,
+    #[serde(rename_all = "camelCase")]
+    RestoreBackup {
+        file_name: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    SetPolicy {
+        keep_daily: usize,
+        keep_weekly: usize,
+    },
+}
+
+#[tauri::command]
+pub async fn handle_backup_command(
+    db: tauri::State<'_, DbState>,
+    settings: tauri::State<'_, Mutex<AppSettings>>,
+    command: BackupCommand,
+) -> JsonResult {
+    match command {
+        // ======================================================
+        // List Backups
+        // ======================================================
+        BackupCommand::ListBackups => list_backups_service(&settings),
+
+        // ======================================================
+        // Create Backup
+        // ======================================================
+        BackupCommand::CreateBackup => {
+            let pool = active_pool(&db)?;
+            create_backup_service(&pool, &settings).await
+        }
+
+        // ======================================================
+        // Restore Backup
+        // ======================================================
+        BackupCommand::RestoreBackup { file_name } => {
+            let result = restore_backup_service(&db, &settings, &file_name).await;
+            // The restored database is open under a new pool
+            if let Ok(pool) = active_pool(&db) {
+                after_write(&pool, &settings, &result).await;
+            }
+            result
+        }
+
+        // ======================================================
+        // Set Retention Policy
+        // ======================================================
+        BackupCommand::SetPolicy {
+            keep_daily,
+            keep_weekly,
+        } => set_backup_policy_service(&settings, keep_daily, keep_weekly),
+    }
+}
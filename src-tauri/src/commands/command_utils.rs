@@ -1,20 +1,50 @@
-use chrono::NaiveDate;
+use crate::db::connection::DbState;
+use crate::error::AppError;
+use crate::services::calendar_service::refresh_calendar_feed;
+use crate::settings::AppSettings;
+use chrono::{NaiveDate, NaiveTime};
+use sqlx::SqlitePool;
+use std::sync::Mutex;
 
-/// Parse a required date string (YYYY-MM-DD).
-pub fn parse_required_date(s: String) -> Result<NaiveDate, String> {
-    NaiveDate::parse_from_str(&s, "%Y-%m-%d").map_err(|_| {
-        serde_json::json!({
-            "status": "error",
-            "message": "Invalid date format. Expected YYYY-MM-DD."
-        })
-        .to_string()
-    })
+/// Parse a required date string (YYYY-MM-DD); `field` names the payload field in errors.
+pub fn parse_required_date(field: &str, s: String) -> Result<NaiveDate, AppError> {
+    NaiveDate::parse_from_str(&s, "%Y-%m-%d")
+        .map_err(|_| AppError::invalid_field(field, "Invalid date format. Expected YYYY-MM-DD."))
 }
 
 /// Parse an optional date string (YYYY-MM-DD).
-pub fn parse_optional_date(s: Option<String>) -> Result<Option<NaiveDate>, String> {
+pub fn parse_optional_date(field: &str, s: Option<String>) -> Result<Option<NaiveDate>, AppError> {
     match s {
-        Some(v) => Ok(Some(parse_required_date(v)?)),
+        Some(v) => Ok(Some(parse_required_date(field, v)?)),
         None => Ok(None),
     }
 }
+
+/// Parse a time of day (HH:MM or HH:MM:SS).
+pub fn parse_required_time(field: &str, s: String) -> Result<NaiveTime, AppError> {
+    NaiveTime::parse_from_str(&s, "%H:%M:%S")
+        .or_else(|_| NaiveTime::parse_from_str(&s, "%H:%M"))
+        .map_err(|_| AppError::invalid_field(field, "Invalid time format. Expected HH:MM."))
+}
+
+/// Fetch the active profile's pool, failing while the database is still opening.
+pub fn active_pool(db: &DbState) -> Result<SqlitePool, AppError> {
+    db.pool().ok_or_else(|| AppError::Database {
+        message: "Database is not ready yet. Please try again in a moment.".to_string(),
+    })
+}
+
+/// Runs after a write command: regenerates the calendar feed if the write
+/// succeeded. Reminders and interviews change through more than their own
+/// commands (names of linked records, rules, trash, undo, merges, imports),
+/// so every handler that can touch them calls this.
+pub async fn after_write<T, E>(
+    pool: &SqlitePool,
+    settings: &Mutex<AppSettings>,
+    result: &Result<T, E>,
+) {
+    if result.is_ok() {
+        let settings = settings.lock().unwrap().clone();
+        refresh_calendar_feed(pool, &settings).await;
+    }
+}
@@ -1,11 +1,19 @@
+use crate::commands::command_utils::{active_pool, after_write};
+use crate::db::connection::DbState;
 use crate::db::models::enums::WorkType;
+use crate::db::models::patch::Patch;
+use crate::db::queries::list_query::ListOptions;
 use crate::services::company_service::{
     create_company_service, delete_company_service, get_all_companies_service,
     get_company_by_id_service, update_company_service,
 };
-use crate::services::service_types::JsonResult;
+use crate::services::dedupe_service::{find_duplicate_companies_service, merge_companies_service};
+use crate::services::detail_service::get_company_detail_service;
+use crate::services::service_types::ServiceResult;
+use crate::services::undo_service::undoable;
+use crate::settings::AppSettings;
 use serde::Deserialize;
-use sqlx::SqlitePool;
+use std::sync::Mutex;
 
 #[derive(Deserialize)]
 #[serde(tag = "action", content = "payload")]
@@ -26,30 +34,66 @@ pub enum CompanyCommand {
     Update {
         id: i64,
         name: Option<String>,
-        street_address: Option<String>,
-        zip_code: Option<String>,
-        city: Option<String>,
-        country: Option<String>,
-        default_work_type: Option<WorkType>,
-        industry: Option<String>,
-        website: Option<String>,
-        phone_number: Option<String>,
+        #[serde(default)]
+        street_address: Patch<String>,
+        #[serde(default)]
+        zip_code: Patch<String>,
+        #[serde(default)]
+        city: Patch<String>,
+        #[serde(default)]
+        country: Patch<String>,
+        #[serde(default)]
+        default_work_type: Patch<WorkType>,
+        #[serde(default)]
+        industry: Patch<String>,
+        #[serde(default)]
+        website: Patch<String>,
+        #[serde(default)]
+        phone_number: Patch<String>,
     },
     GetById {
         id: i64,
     },
-    ListAll,
+    /// The record together with its related records.
+    GetDetail {
+        id: i64,
+    },
+    ListAll(Option<ListOptions>),
     Delete {
         id: i64,
     },
+    /// Pairs that are probably the same company, best match first.
+    #[serde(rename_all = "camelCase")]
+    FindDuplicates {
+        min_score: Option<f64>,
+    },
+    /// Moves everything pointing at `duplicateId` to `survivorId` and
+    /// trashes the duplicate.
+    #[serde(rename_all = "camelCase")]
+    Merge {
+        survivor_id: i64,
+        duplicate_id: i64,
+    },
 }
 
 #[tauri::command]
 pub async fn handle_company_command(
-    pool: tauri::State<'_, SqlitePool>,
+    db: tauri::State<'_, DbState>,
+    settings: tauri::State<'_, Mutex<AppSettings>>,
     command: CompanyCommand,
-) -> JsonResult {
-    match command {
+) -> ServiceResult {
+    let pool = active_pool(&db)?;
+
+    // Only these writes can change the calendar feed
+    let refresh_calendar = matches!(
+        command,
+        CompanyCommand::Create { .. }
+            | CompanyCommand::Update { .. }
+            | CompanyCommand::Delete { .. }
+            | CompanyCommand::Merge { .. }
+    );
+
+    let result = match command {
         // ======================================================
         // Create
         // ======================================================
@@ -64,17 +108,21 @@ pub async fn handle_company_command(
             website,
             phone_number,
         } => {
-            create_company_service(
+            undoable(
+                &db,
                 &pool,
-                &name,
-                street_address.as_deref(),
-                zip_code.as_deref(),
-                city.as_deref(),
-                country.as_deref(),
-                default_work_type.as_ref(),
-                industry.as_deref(),
-                website.as_deref(),
-                phone_number.as_deref(),
+                create_company_service(
+                    &pool,
+                    &name,
+                    street_address.as_deref(),
+                    zip_code.as_deref(),
+                    city.as_deref(),
+                    country.as_deref(),
+                    default_work_type.as_ref(),
+                    industry.as_deref(),
+                    website.as_deref(),
+                    phone_number.as_deref(),
+                ),
             )
             .await
         }
@@ -94,18 +142,22 @@ pub async fn handle_company_command(
             website,
             phone_number,
         } => {
-            update_company_service(
+            undoable(
+                &db,
                 &pool,
-                &id,
-                name.as_deref(),
-                street_address.as_deref(),
-                zip_code.as_deref(),
-                city.as_deref(),
-                country.as_deref(),
-                default_work_type.as_ref(),
-                industry.as_deref(),
-                website.as_deref(),
-                phone_number.as_deref(),
+                update_company_service(
+                    &pool,
+                    &id,
+                    name.as_deref(),
+                    street_address.as_deref(),
+                    zip_code.as_deref(),
+                    city.as_deref(),
+                    country.as_deref(),
+                    default_work_type.as_ref(),
+                    industry.as_deref(),
+                    website.as_deref(),
+                    phone_number.as_deref(),
+                ),
             )
             .await
         }
@@ -114,15 +166,44 @@ pub async fn handle_company_command(
         // Get by ID
         // ======================================================
         CompanyCommand::GetById { id } => get_company_by_id_service(&pool, &id).await,
+        CompanyCommand::GetDetail { id } => get_company_detail_service(&pool, &id).await,
 
         // ======================================================
         // List All
         // ======================================================
-        CompanyCommand::ListAll => get_all_companies_service(&pool).await,
+        CompanyCommand::ListAll(options) => {
+            get_all_companies_service(&pool, &options.unwrap_or_default()).await
+        }
 
         // ======================================================
         // Delete
         // ======================================================
-        CompanyCommand::Delete { id } => delete_company_service(&pool, &id).await,
+        CompanyCommand::Delete { id } => {
+            undoable(&db, &pool, delete_company_service(&pool, &id)).await
+        }
+
+        // ======================================================
+        // Duplicates
+        // ======================================================
+        CompanyCommand::FindDuplicates { min_score } => {
+            find_duplicate_companies_service(&pool, min_score).await
+        }
+        CompanyCommand::Merge {
+            survivor_id,
+            duplicate_id,
+        } => {
+            undoable(
+                &db,
+                &pool,
+                merge_companies_service(&pool, &survivor_id, &duplicate_id),
+            )
+            .await
+        }
+    };
+
+    if refresh_calendar {
+        after_write(&pool, &settings, &result).await;
     }
+
+    result
 }
@@ -0,0 +1,48 @@
+use crate::commands::command_utils::active_pool;
+use crate::db::connection::DbState;
+use crate::db::models::enums::Currency;
+use crate::services::exchange_rate_service::{
+    delete_exchange_rate_service, list_exchange_rates_service, set_base_currency_service,
+    set_exchange_rate_service,
+};
+use crate::services::service_types::ServiceResult;
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+#[serde(tag = "action", content = "payload")]
+pub enum ExchangeRateCommand {
+    ListRates,
+    /// One unit of `currency` is worth `rate` units of the base currency.
+    SetRate {
+        currency: Currency,
+        rate: f64,
+    },
+    DeleteRate {
+        currency: Currency,
+    },
+    /// Rescales the other rates to the new base.
+    SetBaseCurrency {
+        currency: Currency,
+    },
+}
+
+#[tauri::command]
+pub async fn handle_exchange_rate_command(
+    db: tauri::State<'_, DbState>,
+    command: ExchangeRateCommand,
+) -> ServiceResult {
+    let pool = active_pool(&db)?;
+
+    match command {
+        ExchangeRateCommand::ListRates => list_exchange_rates_service(&pool).await,
+        ExchangeRateCommand::SetRate { currency, rate } => {
+            set_exchange_rate_service(&pool, &currency, rate).await
+        }
+        ExchangeRateCommand::DeleteRate { currency } => {
+            delete_exchange_rate_service(&pool, &currency).await
+        }
+        ExchangeRateCommand::SetBaseCurrency { currency } => {
+            set_base_currency_service(&pool, &currency).await
+        }
+    }
+}
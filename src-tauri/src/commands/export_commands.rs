@@ -0,0 +1,73 @@
+use crate::commands::command_utils::{active_pool, after_write};
+use crate::db::connection::DbState;
+use crate::services::attachment_service::attachment_store;
+use crate::services::calendar_service::{export_ics_service, ReminderComponent};
+use crate::services::export_service::{export_json_service, import_json_service, JsonImportMode};
+use crate::services::service_types::JsonResult;
+use crate::settings::AppSettings;
+use serde::Deserialize;
+use std::sync::Mutex;
+
+#[derive(Deserialize)]
+#[serde(tag = "action", content = "payload")]
+pub enum ExportCommand {
+    #[serde(rename_all = "camelCase")]
+    ExportJson { path: Option<String> },
+    #[serde(rename_all = "camelCase")]
+    ImportJson {
+        path: Option<String>,
+        content: Option<String>,
+        mode: JsonImportMode,
+    },
+    #[serde(rename_all = "camelCase")]
+    ExportIcs {
+        path: Option<String>,
+        reminders_as: Option<ReminderComponent>,
+    },
+}
+
+#[tauri::command]
+pub async fn handle_export_command(
+    db: tauri::State<'_, DbState>,
+    settings: tauri::State<'_, Mutex<AppSettings>>,
+    command: ExportCommand,
+) -> JsonResult {
+    let pool = active_pool(&db)?;
+
+    match command {
+        // ======================================================
+        // Export JSON
+        // ======================================================
+        ExportCommand::ExportJson { path } => {
+            export_json_service(&pool, &settings, path.as_deref()).await
+        }
+
+        // ======================================================
+        // Import JSON
+        // ======================================================
+        ExportCommand::ImportJson {
+            path,
+            content,
+            mode,
+        } => {
+            let store = attachment_store(&settings.lock().unwrap());
+            let result =
+                import_json_service(&pool, &store, path.as_deref(), content.as_deref(), mode).await;
+            after_write(&pool, &settings, &result).await;
+            result
+        }
+
+        // ======================================================
+        // Export ICS
+        // ======================================================
+        ExportCommand::ExportIcs { path, reminders_as } => {
+            export_ics_service(
+                &pool,
+                &settings,
+                path.as_deref(),
+                reminders_as.unwrap_or_default(),
+            )
+            .await
+        }
+    }
+}
@@ -0,0 +1,47 @@
+use crate::commands::command_utils::{active_pool, after_write};
+use crate::db::connection::DbState;
+use crate::services::import_service::{
+    import_csv_service, preview_csv_service, CsvSource, ImportEntity, ImportRequest,
+};
+use crate::services::service_types::JsonResult;
+use crate::settings::AppSettings;
+use serde::Deserialize;
+use std::sync::Mutex;
+
+#[derive(Deserialize)]
+#[serde(tag = "action", content = "payload")]
+pub enum ImportCommand {
+    #[serde(rename_all = "camelCase")]
+    Preview {
+        entity: ImportEntity,
+        #[serde(flatten)]
+        source: CsvSource,
+    },
+    Import(ImportRequest),
+}
+
+#[tauri::command]
+pub async fn handle_import_command(
+    db: tauri::State<'_, DbState>,
+    settings: tauri::State<'_, Mutex<AppSettings>>,
+    command: ImportCommand,
+) -> JsonResult {
+    match command {
+        // ======================================================
+        // Preview (headers, sample rows, target fields)
+        // ======================================================
+        ImportCommand::Preview { entity, source } => preview_csv_service(entity, &source),
+
+        // ======================================================
+        // Import (or dry run)
+        // ======================================================
+        ImportCommand::Import(request) => {
+            let pool = active_pool(&db)?;
+            let result = import_csv_service(&pool, &request).await;
+            if !request.dry_run {
+                after_write(&pool, &settings, &result).await;
+            }
+            result
+        }
+    }
+}
@@ -1,12 +1,17 @@
-use crate::command_utils::{parse_optional_date, parse_required_date};
+use crate::command_utils::{active_pool, after_write, parse_optional_date, parse_required_date};
+use crate::db::connection::DbState;
 use crate::db::models::enums::InteractionType;
+use crate::db::models::patch::Patch;
+use crate::db::queries::list_query::ListOptions;
 use crate::services::interaction_service::{
     create_interaction_service, delete_interaction_service, get_all_interactions_service,
     get_interaction_by_id_service, update_interaction_service,
 };
-use crate::services::service_types::JsonResult;
+use crate::services::service_types::ServiceResult;
+use crate::services::undo_service::undoable;
+use crate::settings::AppSettings;
 use serde::Deserialize;
-use sqlx::SqlitePool;
+use std::sync::Mutex;
 
 #[derive(Deserialize)]
 #[serde(tag = "action", content = "payload")]
@@ -27,17 +32,23 @@ pub enum InteractionCommand {
         id: i64,
         interaction_type: Option<InteractionType>,
         interaction_date: Option<String>,
-        subject: Option<String>,
-        summary: Option<String>,
-        medium: Option<String>,
-        application_id: Option<i64>,
-        person_id: Option<i64>,
-        company_id: Option<i64>,
+        #[serde(default)]
+        subject: Patch<String>,
+        #[serde(default)]
+        summary: Patch<String>,
+        #[serde(default)]
+        medium: Patch<String>,
+        #[serde(default)]
+        application_id: Patch<i64>,
+        #[serde(default)]
+        person_id: Patch<i64>,
+        #[serde(default)]
+        company_id: Patch<i64>,
     },
     GetById {
         id: i64,
     },
-    ListAll,
+    ListAll(Option<ListOptions>),
     Delete {
         id: i64,
     },
@@ -45,10 +56,21 @@ pub enum InteractionCommand {
 
 #[tauri::command]
 pub async fn handle_interaction_command(
-    pool: tauri::State<'_, SqlitePool>,
+    db: tauri::State<'_, DbState>,
+    settings: tauri::State<'_, Mutex<AppSettings>>,
     command: InteractionCommand,
-) -> JsonResult {
-    match command {
+) -> ServiceResult {
+    let pool = active_pool(&db)?;
+
+    // Only these writes can change the calendar feed
+    let refresh_calendar = matches!(
+        command,
+        InteractionCommand::Create { .. }
+            | InteractionCommand::Update { .. }
+            | InteractionCommand::Delete { .. }
+    );
+
+    let result = match command {
         // ======================================================
         // Create
         // ======================================================
@@ -62,18 +84,22 @@ pub async fn handle_interaction_command(
             person_id,
             company_id,
         } => {
-            let parsed_date = parse_required_date(interaction_date)?;
+            let parsed_date = parse_required_date("interactionDate", interaction_date)?;
 
-            create_interaction_service(
+            undoable(
+                &db,
                 &pool,
-                &interaction_type,
-                &parsed_date,
-                subject.as_deref(),
-                summary.as_deref(),
-                medium.as_deref(),
-                application_id,
-                person_id,
-                company_id,
+                create_interaction_service(
+                    &pool,
+                    &interaction_type,
+                    &parsed_date,
+                    subject.as_deref(),
+                    summary.as_deref(),
+                    medium.as_deref(),
+                    application_id,
+                    person_id,
+                    company_id,
+                ),
             )
             .await
         }
@@ -92,19 +118,23 @@ pub async fn handle_interaction_command(
             person_id,
             company_id,
         } => {
-            let parsed_date = parse_optional_date(interaction_date)?;
+            let parsed_date = parse_optional_date("interactionDate", interaction_date)?;
 
-            update_interaction_service(
+            undoable(
+                &db,
                 &pool,
-                &id,
-                interaction_type.as_ref(),
-                parsed_date.as_ref(),
-                subject.as_deref(),
-                summary.as_deref(),
-                medium.as_deref(),
-                application_id,
-                person_id,
-                company_id,
+                update_interaction_service(
+                    &pool,
+                    &id,
+                    interaction_type.as_ref(),
+                    parsed_date.as_ref(),
+                    subject.as_deref(),
+                    summary.as_deref(),
+                    medium.as_deref(),
+                    application_id,
+                    person_id,
+                    company_id,
+                ),
             )
             .await
         }
@@ -117,11 +147,21 @@ pub async fn handle_interaction_command(
         // ======================================================
         // List All
         // ======================================================
-        InteractionCommand::ListAll => get_all_interactions_service(&pool).await,
+        InteractionCommand::ListAll(options) => {
+            get_all_interactions_service(&pool, &options.unwrap_or_default()).await
+        }
 
         // ======================================================
         // Delete
         // ======================================================
-        InteractionCommand::Delete { id } => delete_interaction_service(&pool, &id).await,
+        InteractionCommand::Delete { id } => {
+            undoable(&db, &pool, delete_interaction_service(&pool, &id)).await
+        }
+    };
+
+    if refresh_calendar {
+        after_write(&pool, &settings, &result).await;
     }
+
+    result
 }
@@ -1,11 +1,18 @@
-use crate::db::models::enums::{Currency, SeniorityLevel, WorkType};
+use crate::commands::command_utils::{active_pool, after_write};
+use crate::db::connection::DbState;
+use crate::db::models::enums::{Currency, PayPeriod, SeniorityLevel, WorkType};
+use crate::db::models::patch::Patch;
+use crate::db::queries::list_query::ListOptions;
+use crate::services::detail_service::get_job_listing_detail_service;
 use crate::services::job_listing_service::{
     create_job_listing_service, delete_job_listing_service, get_all_job_listings_service,
     get_job_listing_by_id_service, update_job_listing_service,
 };
-use crate::services::service_types::JsonResult;
+use crate::services::service_types::ServiceResult;
+use crate::services::undo_service::undoable;
+use crate::settings::AppSettings;
 use serde::Deserialize;
-use sqlx::SqlitePool;
+use std::sync::Mutex;
 
 #[derive(Deserialize)]
 #[serde(tag = "action", content = "payload")]
@@ -20,6 +27,7 @@ pub enum JobListingCommand {
         salary_min: Option<i64>,
         salary_max: Option<i64>,
         currency: Option<Currency>,
+        pay_period: Option<PayPeriod>,
         description: Option<String>,
         url: Option<String>,
     },
@@ -28,19 +36,33 @@ pub enum JobListingCommand {
         id: i64,
         company_id: Option<i64>,
         title: Option<String>,
-        work_type: Option<WorkType>,
-        category: Option<String>,
-        seniority_level: Option<SeniorityLevel>,
-        salary_min: Option<i64>,
-        salary_max: Option<i64>,
-        currency: Option<Currency>,
-        description: Option<String>,
-        url: Option<String>,
+        #[serde(default)]
+        work_type: Patch<WorkType>,
+        #[serde(default)]
+        category: Patch<String>,
+        #[serde(default)]
+        seniority_level: Patch<SeniorityLevel>,
+        #[serde(default)]
+        salary_min: Patch<i64>,
+        #[serde(default)]
+        salary_max: Patch<i64>,
+        #[serde(default)]
+        currency: Patch<Currency>,
+        #[serde(default)]
+        pay_period: Patch<PayPeriod>,
+        #[serde(default)]
+        description: Patch<String>,
+        #[serde(default)]
+        url: Patch<String>,
     },
     GetById {
         id: i64,
     },
-    ListAll,
+    /// The record together with its related records.
+    GetDetail {
+        id: i64,
+    },
+    ListAll(Option<ListOptions>),
     Delete {
         id: i64,
     },
@@ -48,10 +70,21 @@ pub enum JobListingCommand {
 
 #[tauri::command]
 pub async fn handle_job_listing_command(
-    pool: tauri::State<'_, SqlitePool>,
+    db: tauri::State<'_, DbState>,
+    settings: tauri::State<'_, Mutex<AppSettings>>,
     command: JobListingCommand,
-) -> JsonResult {
-    match command {
+) -> ServiceResult {
+    let pool = active_pool(&db)?;
+
+    // Only these writes can change the calendar feed
+    let refresh_calendar = matches!(
+        command,
+        JobListingCommand::Create { .. }
+            | JobListingCommand::Update { .. }
+            | JobListingCommand::Delete { .. }
+    );
+
+    let result = match command {
         // ======================================================
         // Create
         // ======================================================
@@ -64,21 +97,27 @@ pub async fn handle_job_listing_command(
             salary_min,
             salary_max,
             currency,
+            pay_period,
             description,
             url,
         } => {
-            create_job_listing_service(
+            undoable(
+                &db,
                 &pool,
-                company_id,
-                &title,
-                work_type.as_ref(),
-                category.as_deref(),
-                seniority_level.as_ref(),
-                salary_min,
-                salary_max,
-                currency.as_ref(),
-                description.as_deref(),
-                url.as_deref(),
+                create_job_listing_service(
+                    &pool,
+                    company_id,
+                    &title,
+                    work_type.as_ref(),
+                    category.as_deref(),
+                    seniority_level.as_ref(),
+                    salary_min,
+                    salary_max,
+                    currency.as_ref(),
+                    pay_period.as_ref(),
+                    description.as_deref(),
+                    url.as_deref(),
+                ),
             )
             .await
         }
@@ -96,22 +135,28 @@ pub async fn handle_job_listing_command(
             salary_min,
             salary_max,
             currency,
+            pay_period,
             description,
             url,
         } => {
-            update_job_listing_service(
+            undoable(
+                &db,
                 &pool,
-                &id,
-                company_id,
-                title.as_deref(),
-                work_type.as_ref(),
-                category.as_deref(),
-                seniority_level.as_ref(),
-                salary_min,
-                salary_max,
-                currency.as_ref(),
-                description.as_deref(),
-                url.as_deref(),
+                update_job_listing_service(
+                    &pool,
+                    &id,
+                    company_id,
+                    title.as_deref(),
+                    work_type.as_ref(),
+                    category.as_deref(),
+                    seniority_level.as_ref(),
+                    salary_min,
+                    salary_max,
+                    currency.as_ref(),
+                    pay_period.as_ref(),
+                    description.as_deref(),
+                    url.as_deref(),
+                ),
             )
             .await
         }
@@ -120,15 +165,26 @@ pub async fn handle_job_listing_command(
         // Get by ID
         // ======================================================
         JobListingCommand::GetById { id } => get_job_listing_by_id_service(&pool, &id).await,
+        JobListingCommand::GetDetail { id } => get_job_listing_detail_service(&pool, &id).await,
 
         // ======================================================
         // List All
         // ======================================================
-        JobListingCommand::ListAll => get_all_job_listings_service(&pool).await,
+        JobListingCommand::ListAll(options) => {
+            get_all_job_listings_service(&pool, &options.unwrap_or_default()).await
+        }
 
         // ======================================================
         // Delete
         // ======================================================
-        JobListingCommand::Delete { id } => delete_job_listing_service(&pool, &id).await,
+        JobListingCommand::Delete { id } => {
+            undoable(&db, &pool, delete_job_listing_service(&pool, &id)).await
+        }
+    };
+
+    if refresh_calendar {
+        after_write(&pool, &settings, &result).await;
     }
+
+    result
 }
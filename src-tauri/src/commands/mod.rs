@@ -1,16 +1,44 @@
+pub mod analytics_commands;
 pub mod application_commands;
+pub mod application_contact_commands;
+pub mod attachment_commands;
+pub mod audit_commands;
+pub mod backup_commands;
 pub mod command_utils;
 pub mod company_commands;
+pub mod exchange_rate_commands;
+pub mod export_commands;
+pub mod import_commands;
 pub mod interaction_commands;
 pub mod job_listing_commands;
 pub mod note_commands;
 pub mod person_commands;
+pub mod profile_commands;
 pub mod reminder_commands;
+pub mod rule_commands;
+pub mod search_commands;
+pub mod tag_commands;
+pub mod trash_commands;
+pub mod undo_commands;
 
+pub use analytics_commands::handle_analytics_command;
 pub use application_commands::handle_application_command;
+pub use application_contact_commands::handle_application_contact_command;
+pub use attachment_commands::handle_attachment_command;
+pub use audit_commands::handle_audit_command;
+pub use backup_commands::handle_backup_command;
 pub use company_commands::handle_company_command;
+pub use exchange_rate_commands::handle_exchange_rate_command;
+pub use export_commands::handle_export_command;
+pub use import_commands::handle_import_command;
 pub use interaction_commands::handle_interaction_command;
 pub use job_listing_commands::handle_job_listing_command;
 pub use note_commands::handle_note_command;
 pub use person_commands::handle_person_command;
+pub use profile_commands::handle_profile_command;
 pub use reminder_commands::handle_reminder_command;
+pub use rule_commands::handle_rule_command;
+pub use search_commands::handle_search_command;
+pub use tag_commands::handle_tag_command;
+pub use trash_commands::handle_trash_command;
+pub use undo_commands::handle_undo_command;
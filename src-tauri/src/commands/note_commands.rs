@@ -1,11 +1,15 @@
+use crate::commands::command_utils::active_pool;
+use crate::db::connection::DbState;
 use crate::db::models::enums::NoteType;
+use crate::db::models::patch::Patch;
+use crate::db::queries::list_query::ListOptions;
 use crate::services::note_service::{
     create_note_service, delete_note_service, get_all_notes_service, get_note_by_id_service,
     update_note_service,
 };
-use crate::services::service_types::JsonResult;
+use crate::services::service_types::ServiceResult;
+use crate::services::undo_service::undoable;
 use serde::Deserialize;
-use sqlx::SqlitePool;
 
 #[derive(Deserialize)]
 #[serde(tag = "action", content = "payload")]
@@ -24,19 +28,27 @@ pub enum NoteCommand {
     #[serde(rename_all = "camelCase")]
     Update {
         id: i64,
-        interaction_id: Option<i64>,
-        job_listing_id: Option<i64>,
-        application_id: Option<i64>,
-        person_id: Option<i64>,
-        company_id: Option<i64>,
-        note_type: Option<NoteType>,
-        title: Option<String>,
-        content: Option<String>,
+        #[serde(default)]
+        interaction_id: Patch<i64>,
+        #[serde(default)]
+        job_listing_id: Patch<i64>,
+        #[serde(default)]
+        application_id: Patch<i64>,
+        #[serde(default)]
+        person_id: Patch<i64>,
+        #[serde(default)]
+        company_id: Patch<i64>,
+        #[serde(default)]
+        note_type: Patch<NoteType>,
+        #[serde(default)]
+        title: Patch<String>,
+        #[serde(default)]
+        content: Patch<String>,
     },
     GetById {
         id: i64,
     },
-    ListAll,
+    ListAll(Option<ListOptions>),
     Delete {
         id: i64,
     },
@@ -44,9 +56,11 @@ pub enum NoteCommand {
 
 #[tauri::command]
 pub async fn handle_note_command(
-    pool: tauri::State<'_, SqlitePool>,
+    db: tauri::State<'_, DbState>,
     command: NoteCommand,
-) -> JsonResult {
+) -> ServiceResult {
+    let pool = active_pool(&db)?;
+
     match command {
         // ======================================================
         // Create
@@ -61,16 +75,20 @@ pub async fn handle_note_command(
             title,
             content,
         } => {
-            create_note_service(
+            undoable(
+                &db,
                 &pool,
-                interaction_id,
-                job_listing_id,
-                application_id,
-                person_id,
-                company_id,
-                note_type.as_ref(),
-                title.as_deref(),
-                content.as_deref(),
+                create_note_service(
+                    &pool,
+                    interaction_id,
+                    job_listing_id,
+                    application_id,
+                    person_id,
+                    company_id,
+                    note_type.as_ref(),
+                    title.as_deref(),
+                    content.as_deref(),
+                ),
             )
             .await
         }
@@ -89,17 +107,21 @@ pub async fn handle_note_command(
             title,
             content,
         } => {
-            update_note_service(
+            undoable(
+                &db,
                 &pool,
-                &id,
-                interaction_id,
-                job_listing_id,
-                application_id,
-                person_id,
-                company_id,
-                note_type.as_ref(),
-                title.as_deref(),
-                content.as_deref(),
+                update_note_service(
+                    &pool,
+                    &id,
+                    interaction_id,
+                    job_listing_id,
+                    application_id,
+                    person_id,
+                    company_id,
+                    note_type.as_ref(),
+                    title.as_deref(),
+                    content.as_deref(),
+                ),
             )
             .await
         }
@@ -112,11 +134,13 @@ pub async fn handle_note_command(
         // ======================================================
         // List All
         // ======================================================
-        NoteCommand::ListAll => get_all_notes_service(&pool).await,
+        NoteCommand::ListAll(options) => {
+            get_all_notes_service(&pool, &options.unwrap_or_default()).await
+        }
 
         // ======================================================
         // Delete
         // ======================================================
-        NoteCommand::Delete { id } => delete_note_service(&pool, &id).await,
+        NoteCommand::Delete { id } => undoable(&db, &pool, delete_note_service(&pool, &id)).await,
     }
 }
@@ -1,11 +1,19 @@
+use crate::commands::command_utils::{active_pool, after_write};
+use crate::db::connection::DbState;
 use crate::db::models::enums::Role;
+use crate::db::models::patch::Patch;
+use crate::db::queries::list_query::ListOptions;
+use crate::services::dedupe_service::{find_duplicate_persons_service, merge_persons_service};
+use crate::services::detail_service::get_person_detail_service;
 use crate::services::person_service::{
     create_person_service, delete_person_service, get_all_persons_service,
     get_person_by_id_service, update_person_service,
 };
-use crate::services::service_types::JsonResult;
+use crate::services::service_types::ServiceResult;
+use crate::services::undo_service::undoable;
+use crate::settings::AppSettings;
 use serde::Deserialize;
-use sqlx::SqlitePool;
+use std::sync::Mutex;
 
 #[derive(Deserialize)]
 #[serde(tag = "action", content = "payload")]
@@ -25,27 +33,60 @@ pub enum PersonCommand {
         id: i64,
         first_name: Option<String>,
         last_name: Option<String>,
-        email: Option<String>,
-        phone_number: Option<String>,
-        role: Option<Role>,
-        linkedin_url: Option<String>,
-        company_id: Option<i64>,
+        #[serde(default)]
+        email: Patch<String>,
+        #[serde(default)]
+        phone_number: Patch<String>,
+        #[serde(default)]
+        role: Patch<Role>,
+        #[serde(default)]
+        linkedin_url: Patch<String>,
+        #[serde(default)]
+        company_id: Patch<i64>,
     },
     GetById {
         id: i64,
     },
-    ListAll,
+    /// The record together with its related records.
+    GetDetail {
+        id: i64,
+    },
+    ListAll(Option<ListOptions>),
     Delete {
         id: i64,
     },
+    /// Pairs that are probably the same person, best match first.
+    #[serde(rename_all = "camelCase")]
+    FindDuplicates {
+        min_score: Option<f64>,
+    },
+    /// Moves everything pointing at `duplicateId` to `survivorId` and
+    /// trashes the duplicate.
+    #[serde(rename_all = "camelCase")]
+    Merge {
+        survivor_id: i64,
+        duplicate_id: i64,
+    },
 }
 
 #[tauri::command]
 pub async fn handle_person_command(
-    pool: tauri::State<'_, SqlitePool>,
+    db: tauri::State<'_, DbState>,
+    settings: tauri::State<'_, Mutex<AppSettings>>,
     command: PersonCommand,
-) -> JsonResult {
-    match command {
+) -> ServiceResult {
+    let pool = active_pool(&db)?;
+
+    // Only these writes can change the calendar feed
+    let refresh_calendar = matches!(
+        command,
+        PersonCommand::Create { .. }
+            | PersonCommand::Update { .. }
+            | PersonCommand::Delete { .. }
+            | PersonCommand::Merge { .. }
+    );
+
+    let result = match command {
         PersonCommand::Create {
             first_name,
             last_name,
@@ -55,15 +96,19 @@ pub async fn handle_person_command(
             linkedin_url,
             company_id,
         } => {
-            create_person_service(
+            undoable(
+                &db,
                 &pool,
-                &first_name,
-                &last_name,
-                email.as_deref(),
-                phone_number.as_deref(),
-                role.as_ref(),
-                linkedin_url.as_deref(),
-                company_id,
+                create_person_service(
+                    &pool,
+                    &first_name,
+                    &last_name,
+                    email.as_deref(),
+                    phone_number.as_deref(),
+                    role.as_ref(),
+                    linkedin_url.as_deref(),
+                    company_id,
+                ),
             )
             .await
         }
@@ -78,22 +123,55 @@ pub async fn handle_person_command(
             linkedin_url,
             company_id,
         } => {
-            update_person_service(
+            undoable(
+                &db,
                 &pool,
-                &id,
-                first_name.as_deref(),
-                last_name.as_deref(),
-                email.as_deref(),
-                phone_number.as_deref(),
-                role.as_ref(),
-                linkedin_url.as_deref(),
-                company_id,
+                update_person_service(
+                    &pool,
+                    &id,
+                    first_name.as_deref(),
+                    last_name.as_deref(),
+                    email.as_deref(),
+                    phone_number.as_deref(),
+                    role.as_ref(),
+                    linkedin_url.as_deref(),
+                    company_id,
+                ),
             )
             .await
         }
 
         PersonCommand::GetById { id } => get_person_by_id_service(&pool, &id).await,
-        PersonCommand::ListAll => get_all_persons_service(&pool).await,
-        PersonCommand::Delete { id } => delete_person_service(&pool, &id).await,
+        PersonCommand::GetDetail { id } => get_person_detail_service(&pool, &id).await,
+        PersonCommand::ListAll(options) => {
+            get_all_persons_service(&pool, &options.unwrap_or_default()).await
+        }
+        PersonCommand::Delete { id } => {
+            undoable(&db, &pool, delete_person_service(&pool, &id)).await
+        }
+
+        // ======================================================
+        // Duplicates
+        // ======================================================
+        PersonCommand::FindDuplicates { min_score } => {
+            find_duplicate_persons_service(&pool, min_score).await
+        }
+        PersonCommand::Merge {
+            survivor_id,
+            duplicate_id,
+        } => {
+            undoable(
+                &db,
+                &pool,
+                merge_persons_service(&pool, &survivor_id, &duplicate_id),
+            )
+            .await
+        }
+    };
+
+    if refresh_calendar {
+        after_write(&pool, &settings, &result).await;
     }
+
+    result
 }
@@ -0,0 +1,54 @@
+use crate::commands::command_utils::{active_pool, after_write};
+use crate::db::connection::DbState;
+use crate::services::profile_service::{
+    create_profile_service, delete_profile_service, list_profiles_service, switch_profile_service,
+};
+use crate::services::service_types::JsonResult;
+use crate::settings::AppSettings;
+use serde::Deserialize;
+use std::sync::Mutex;
+
+#[derive(Deserialize)]
+#[serde(tag = "action", content = "payload")]
+pub enum ProfileCommand {
+    ListAll,
+    Create { name: String },
+    Switch { name: String },
+    Delete { name: String },
+}
+
+#[tauri::command]
+pub async fn handle_profile_command(
+    db: tauri::State<'_, DbState>,
+    settings: tauri::State<'_, Mutex<AppSettings>>,
+    command: ProfileCommand,
+) -> JsonResult {
+    match command {
+        // ======================================================
+        // List All
+        // ======================================================
+        ProfileCommand::ListAll => list_profiles_service(&settings),
+
+        // ======================================================
+        // Create
+        // ======================================================
+        ProfileCommand::Create { name } => create_profile_service(&settings, &name).await,
+
+        // ======================================================
+        // Switch
+        // ======================================================
+        ProfileCommand::Switch { name } => {
+            let result = switch_profile_service(&db, &settings, &name).await;
+            // The new profile's feed may be from its last session
+            if let Ok(pool) = active_pool(&db) {
+                after_write(&pool, &settings, &result).await;
+            }
+            result
+        }
+
+        // ======================================================
+        // Delete
+        // ======================================================
+        ProfileCommand::Delete { name } => delete_profile_service(&settings, &name),
+    }
+}
@@ -1,11 +1,20 @@
-use crate::command_utils::{parse_optional_date, parse_required_date};
+use crate::command_utils::{
+    active_pool, after_write, parse_optional_date, parse_required_date, parse_required_time,
+};
+use crate::db::connection::DbState;
+use crate::db::models::patch::Patch;
+use crate::db::queries::list_query::ListOptions;
 use crate::services::reminder_service::{
     create_reminder_service, delete_reminder_service, get_all_reminders_service,
-    get_reminder_by_id_service, update_reminder_service,
+    get_reminder_by_id_service, get_upcoming_reminders_service, mark_reminder_done_service,
+    snooze_reminder_service, update_reminder_service, DEFAULT_UPCOMING_DAYS,
 };
-use crate::services::service_types::JsonResult;
+use crate::services::service_types::ServiceResult;
+use crate::services::undo_service::undoable;
+use crate::settings::AppSettings;
+use chrono::{Duration, Local};
 use serde::Deserialize;
-use sqlx::SqlitePool;
+use std::sync::Mutex;
 
 #[derive(Deserialize)]
 #[serde(tag = "action", content = "payload")]
@@ -19,6 +28,9 @@ pub enum ReminderCommand {
         company_id: Option<i64>,
         person_id: Option<i64>,
         reminder_date: String,
+        reminder_time: Option<String>,
+        timezone: Option<String>,
+        recurrence: Option<String>,
         title: String,
         message: Option<String>,
         is_completed: bool,
@@ -26,32 +38,71 @@ pub enum ReminderCommand {
     #[serde(rename_all = "camelCase")]
     Update {
         id: i64,
-        application_id: Option<i64>,
-        interaction_id: Option<i64>,
-        note_id: Option<i64>,
-        job_listing_id: Option<i64>,
-        company_id: Option<i64>,
-        person_id: Option<i64>,
+        #[serde(default)]
+        application_id: Patch<i64>,
+        #[serde(default)]
+        interaction_id: Patch<i64>,
+        #[serde(default)]
+        note_id: Patch<i64>,
+        #[serde(default)]
+        job_listing_id: Patch<i64>,
+        #[serde(default)]
+        company_id: Patch<i64>,
+        #[serde(default)]
+        person_id: Patch<i64>,
         reminder_date: Option<String>,
+        #[serde(default)]
+        reminder_time: Patch<String>,
+        #[serde(default)]
+        timezone: Patch<String>,
+        #[serde(default)]
+        recurrence: Patch<String>,
         title: Option<String>,
-        message: Option<String>,
+        #[serde(default)]
+        message: Patch<String>,
         is_completed: Option<bool>,
     },
     GetById {
         id: i64,
     },
-    ListAll,
+    ListAll(Option<ListOptions>),
+    /// Occurrences of open reminders within `from..=to`
+    /// (default: today and the following 30 days).
+    ListUpcoming {
+        from: Option<String>,
+        to: Option<String>,
+    },
     Delete {
         id: i64,
     },
+    /// Announce the reminder again after `minutes`.
+    Snooze {
+        id: i64,
+        minutes: i64,
+    },
+    MarkDone {
+        id: i64,
+    },
 }
 
 #[tauri::command]
 pub async fn handle_reminder_command(
-    pool: tauri::State<'_, SqlitePool>,
+    db: tauri::State<'_, DbState>,
+    settings: tauri::State<'_, Mutex<AppSettings>>,
     command: ReminderCommand,
-) -> JsonResult {
-    match command {
+) -> ServiceResult {
+    let pool = active_pool(&db)?;
+
+    // Only these writes can change the calendar feed
+    let refresh_calendar = matches!(
+        command,
+        ReminderCommand::Create { .. }
+            | ReminderCommand::Update { .. }
+            | ReminderCommand::Delete { .. }
+            | ReminderCommand::MarkDone { .. }
+    );
+
+    let result = match command {
         // ======================================================
         // Create
         // ======================================================
@@ -63,23 +114,36 @@ pub async fn handle_reminder_command(
             company_id,
             person_id,
             reminder_date,
+            reminder_time,
+            timezone,
+            recurrence,
             title,
             message,
             is_completed,
         } => {
-            let parsed_date = parse_required_date(reminder_date)?;
-            create_reminder_service(
+            let parsed_date = parse_required_date("reminderDate", reminder_date)?;
+            let parsed_time = reminder_time
+                .map(|t| parse_required_time("reminderTime", t))
+                .transpose()?;
+            undoable(
+                &db,
                 &pool,
-                application_id,
-                interaction_id,
-                note_id,
-                job_listing_id,
-                company_id,
-                person_id,
-                &parsed_date,
-                &title,
-                message.as_deref(),
-                is_completed,
+                create_reminder_service(
+                    &pool,
+                    application_id,
+                    interaction_id,
+                    note_id,
+                    job_listing_id,
+                    company_id,
+                    person_id,
+                    &parsed_date,
+                    parsed_time.as_ref(),
+                    timezone.as_deref(),
+                    recurrence.as_deref(),
+                    &title,
+                    message.as_deref(),
+                    is_completed,
+                ),
             )
             .await
         }
@@ -96,24 +160,35 @@ pub async fn handle_reminder_command(
             company_id,
             person_id,
             reminder_date,
+            reminder_time,
+            timezone,
+            recurrence,
             title,
             message,
             is_completed,
         } => {
-            let parsed_date = parse_optional_date(reminder_date)?;
-            update_reminder_service(
+            let parsed_date = parse_optional_date("reminderDate", reminder_date)?;
+            let parsed_time = reminder_time.try_map(|t| parse_required_time("reminderTime", t))?;
+            undoable(
+                &db,
                 &pool,
-                &id,
-                application_id,
-                interaction_id,
-                note_id,
-                job_listing_id,
-                company_id,
-                person_id,
-                parsed_date.as_ref(),
-                title.as_deref(),
-                message.as_deref(),
-                is_completed,
+                update_reminder_service(
+                    &pool,
+                    &id,
+                    application_id,
+                    interaction_id,
+                    note_id,
+                    job_listing_id,
+                    company_id,
+                    person_id,
+                    parsed_date.as_ref(),
+                    parsed_time.as_ref(),
+                    timezone.as_deref(),
+                    recurrence.as_deref(),
+                    title.as_deref(),
+                    message.as_deref(),
+                    is_completed,
+                ),
             )
             .await
         }
@@ -126,11 +201,42 @@ pub async fn handle_reminder_command(
         // ======================================================
         // List All
         // ======================================================
-        ReminderCommand::ListAll => get_all_reminders_service(&pool).await,
+        ReminderCommand::ListAll(options) => {
+            get_all_reminders_service(&pool, &options.unwrap_or_default()).await
+        }
+
+        // ======================================================
+        // List Upcoming
+        // ======================================================
+        ReminderCommand::ListUpcoming { from, to } => {
+            let from =
+                parse_optional_date("from", from)?.unwrap_or_else(|| Local::now().date_naive());
+            let to = parse_optional_date("to", to)?
+                .unwrap_or_else(|| from + Duration::days(DEFAULT_UPCOMING_DAYS));
+            get_upcoming_reminders_service(&pool, &from, &to).await
+        }
 
         // ======================================================
         // Delete
         // ======================================================
-        ReminderCommand::Delete { id } => delete_reminder_service(&pool, &id).await,
+        ReminderCommand::Delete { id } => {
+            undoable(&db, &pool, delete_reminder_service(&pool, &id)).await
+        }
+
+        // ======================================================
+        // Snooze / Mark Done (from a due notification)
+        // ======================================================
+        ReminderCommand::Snooze { id, minutes } => {
+            snooze_reminder_service(&pool, &id, minutes).await
+        }
+        ReminderCommand::MarkDone { id } => {
+            undoable(&db, &pool, mark_reminder_done_service(&pool, &id)).await
+        }
+    };
+
+    if refresh_calendar {
+        after_write(&pool, &settings, &result).await;
     }
+
+    result
 }
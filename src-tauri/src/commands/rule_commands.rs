@@ -0,0 +1,101 @@
+use crate::commands::command_utils::{active_pool, after_write};
+use crate::db::connection::DbState;
+use crate::db::models::rule::{RuleAction, RuleTrigger};
+use crate::services::rule_service::{
+    create_rule_service, delete_rule_service, get_all_rules_service, get_rule_by_id_service,
+    get_rule_runs_service, run_inactivity_rules_service, update_rule_service,
+};
+use crate::services::service_types::ServiceResult;
+use crate::settings::AppSettings;
+use chrono::Local;
+use serde::Deserialize;
+use std::sync::Mutex;
+
+fn default_enabled() -> bool {
+    true
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "action", content = "payload")]
+pub enum RuleCommand {
+    /// e.g. `{"name": "Follow up", "trigger": {"event": "application_created"},
+    /// "action": {"type": "create_reminder", "title": "Follow up with {company}", "daysAfter": 7}}`
+    Create {
+        name: String,
+        #[serde(default = "default_enabled")]
+        enabled: bool,
+        trigger: RuleTrigger,
+        action: RuleAction,
+    },
+    Update {
+        id: i64,
+        name: Option<String>,
+        enabled: Option<bool>,
+        trigger: Option<RuleTrigger>,
+        action: Option<RuleAction>,
+    },
+    Delete {
+        id: i64,
+    },
+    GetById {
+        id: i64,
+    },
+    ListAll,
+    /// What the rule has done, newest first.
+    ListRuns {
+        id: i64,
+    },
+    /// Check `no_interaction` rules now.
+    RunInactivity,
+}
+
+#[tauri::command]
+pub async fn handle_rule_command(
+    db: tauri::State<'_, DbState>,
+    settings: tauri::State<'_, Mutex<AppSettings>>,
+    command: RuleCommand,
+) -> ServiceResult {
+    let pool = active_pool(&db)?;
+
+    // Only inactivity rules create reminders outside other commands
+    let refresh_calendar = matches!(command, RuleCommand::RunInactivity);
+
+    let result = match command {
+        RuleCommand::Create {
+            name,
+            enabled,
+            trigger,
+            action,
+        } => create_rule_service(&pool, &name, enabled, &trigger, &action).await,
+        RuleCommand::Update {
+            id,
+            name,
+            enabled,
+            trigger,
+            action,
+        } => {
+            update_rule_service(
+                &pool,
+                &id,
+                name.as_deref(),
+                enabled,
+                trigger.as_ref(),
+                action.as_ref(),
+            )
+            .await
+        }
+        RuleCommand::Delete { id } => delete_rule_service(&pool, &id).await,
+        RuleCommand::GetById { id } => get_rule_by_id_service(&pool, &id).await,
+        RuleCommand::ListAll => get_all_rules_service(&pool).await,
+        RuleCommand::ListRuns { id } => get_rule_runs_service(&pool, &id).await,
+        RuleCommand::RunInactivity => {
+            run_inactivity_rules_service(&pool, &Local::now().date_naive()).await
+        }
+    };
+
+    if refresh_calendar {
+        after_write(&pool, &settings, &result).await;
+    }
+
+    result
+}
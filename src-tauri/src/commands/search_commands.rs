@@ -0,0 +1,26 @@
+use crate::commands::command_utils::active_pool;
+use crate::db::connection::DbState;
+use crate::services::search_service::search_service;
+use crate::services::service_types::JsonResult;
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+#[serde(tag = "action", content = "payload")]
+pub enum SearchCommand {
+    Search { query: String, limit: Option<i64> },
+}
+
+#[tauri::command]
+pub async fn handle_search_command(
+    db: tauri::State<'_, DbState>,
+    command: SearchCommand,
+) -> JsonResult {
+    let pool = active_pool(&db)?;
+
+    match command {
+        // ======================================================
+        // Search
+        // ======================================================
+        SearchCommand::Search { query, limit } => search_service(&pool, &query, limit).await,
+    }
+}
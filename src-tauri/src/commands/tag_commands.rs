@@ -0,0 +1,99 @@
+use crate::commands::command_utils::active_pool;
+use crate::db::connection::DbState;
+use crate::db::models::enums::EntityType;
+use crate::services::service_types::ServiceResult;
+use crate::services::tag_service::{
+    attach_tag_service, create_tag_service, delete_tag_service, detach_tag_service,
+    get_all_tags_service, get_entity_tags_service, merge_tags_service, rename_tag_service,
+    set_tag_color_service,
+};
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+#[serde(tag = "action", content = "payload")]
+pub enum TagCommand {
+    Create {
+        name: String,
+        color: Option<String>,
+    },
+    Rename {
+        id: i64,
+        name: String,
+    },
+    /// A missing or null color removes it.
+    SetColor {
+        id: i64,
+        color: Option<String>,
+    },
+    #[serde(rename_all = "camelCase")]
+    Merge {
+        source_ids: Vec<i64>,
+        target_id: i64,
+    },
+    Delete {
+        id: i64,
+    },
+    ListAll,
+    #[serde(rename_all = "camelCase")]
+    Attach {
+        tag_id: i64,
+        entity_type: EntityType,
+        entity_id: i64,
+    },
+    #[serde(rename_all = "camelCase")]
+    Detach {
+        tag_id: i64,
+        entity_type: EntityType,
+        entity_id: i64,
+    },
+    #[serde(rename_all = "camelCase")]
+    GetForEntity {
+        entity_type: EntityType,
+        entity_id: i64,
+    },
+}
+
+#[tauri::command]
+pub async fn handle_tag_command(
+    db: tauri::State<'_, DbState>,
+    command: TagCommand,
+) -> ServiceResult {
+    let pool = active_pool(&db)?;
+
+    match command {
+        // ======================================================
+        // Tag management
+        // ======================================================
+        TagCommand::Create { name, color } => {
+            create_tag_service(&pool, &name, color.as_deref()).await
+        }
+        TagCommand::Rename { id, name } => rename_tag_service(&pool, &id, &name).await,
+        TagCommand::SetColor { id, color } => {
+            set_tag_color_service(&pool, &id, color.as_deref()).await
+        }
+        TagCommand::Merge {
+            source_ids,
+            target_id,
+        } => merge_tags_service(&pool, &source_ids, &target_id).await,
+        TagCommand::Delete { id } => delete_tag_service(&pool, &id).await,
+        TagCommand::ListAll => get_all_tags_service(&pool).await,
+
+        // ======================================================
+        // Tagging records
+        // ======================================================
+        TagCommand::Attach {
+            tag_id,
+            entity_type,
+            entity_id,
+        } => attach_tag_service(&pool, &tag_id, entity_type, &entity_id).await,
+        TagCommand::Detach {
+            tag_id,
+            entity_type,
+            entity_id,
+        } => detach_tag_service(&pool, &tag_id, entity_type, &entity_id).await,
+        TagCommand::GetForEntity {
+            entity_type,
+            entity_id,
+        } => get_entity_tags_service(&pool, entity_type, &entity_id).await,
+    }
+}
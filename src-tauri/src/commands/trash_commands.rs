@@ -0,0 +1,82 @@
+use crate::commands::command_utils::{active_pool, after_write};
+use crate::db::connection::DbState;
+use crate::db::models::enums::EntityType;
+use crate::services::service_types::ServiceResult;
+use crate::services::trash_service::{
+    empty_trash_service, list_trash_service, restore_service, set_archived_service,
+};
+use crate::services::undo_service::undoable;
+use crate::settings::AppSettings;
+use serde::Deserialize;
+use std::sync::Mutex;
+
+#[derive(Deserialize)]
+#[serde(tag = "action", content = "payload")]
+pub enum TrashCommand {
+    /// Deleting a record through its own command moves it here.
+    #[serde(rename_all = "camelCase")]
+    Restore { entity_type: EntityType, id: i64 },
+    #[serde(rename_all = "camelCase")]
+    ListTrash { entity_type: Option<EntityType> },
+    /// Deletes trashed records for good.
+    #[serde(rename_all = "camelCase")]
+    EmptyTrash {
+        entity_type: Option<EntityType>,
+        older_than_days: Option<i64>,
+    },
+    #[serde(rename_all = "camelCase")]
+    Archive { entity_type: EntityType, id: i64 },
+    #[serde(rename_all = "camelCase")]
+    Unarchive { entity_type: EntityType, id: i64 },
+}
+
+#[tauri::command]
+pub async fn handle_trash_command(
+    db: tauri::State<'_, DbState>,
+    settings: tauri::State<'_, Mutex<AppSettings>>,
+    command: TrashCommand,
+) -> ServiceResult {
+    let pool = active_pool(&db)?;
+
+    // Only these writes can change the calendar feed
+    let refresh_calendar = matches!(
+        command,
+        TrashCommand::Restore { .. }
+            | TrashCommand::EmptyTrash { .. }
+            | TrashCommand::Archive { .. }
+            | TrashCommand::Unarchive { .. }
+    );
+
+    let result = match command {
+        TrashCommand::Restore { entity_type, id } => {
+            undoable(&db, &pool, restore_service(&pool, entity_type, &id)).await
+        }
+        TrashCommand::ListTrash { entity_type } => list_trash_service(&pool, entity_type).await,
+        TrashCommand::EmptyTrash {
+            entity_type,
+            older_than_days,
+        } => empty_trash_service(&pool, entity_type, older_than_days).await,
+        TrashCommand::Archive { entity_type, id } => {
+            undoable(
+                &db,
+                &pool,
+                set_archived_service(&pool, entity_type, &id, true),
+            )
+            .await
+        }
+        TrashCommand::Unarchive { entity_type, id } => {
+            undoable(
+                &db,
+                &pool,
+                set_archived_service(&pool, entity_type, &id, false),
+            )
+            .await
+        }
+    };
+
+    if refresh_calendar {
+        after_write(&pool, &settings, &result).await;
+    }
+
+    result
+}
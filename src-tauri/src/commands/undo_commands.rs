@@ -0,0 +1,42 @@
+use crate::commands::command_utils::{active_pool, after_write};
+use crate::db::connection::DbState;
+use crate::services::service_types::ServiceResult;
+use crate::services::undo_service::{redo_service, undo_service, undo_status_service};
+use crate::settings::AppSettings;
+use serde::Deserialize;
+use std::sync::Mutex;
+
+#[derive(Deserialize)]
+#[serde(tag = "action", content = "payload")]
+pub enum UndoCommand {
+    /// Undoes the last `steps` changes of this session (default 1).
+    Undo { steps: Option<u32> },
+    /// Redoes the last `steps` undone changes (default 1).
+    Redo { steps: Option<u32> },
+    /// How many changes can be undone and redone.
+    Status,
+}
+
+#[tauri::command]
+pub async fn handle_undo_command(
+    db: tauri::State<'_, DbState>,
+    settings: tauri::State<'_, Mutex<AppSettings>>,
+    command: UndoCommand,
+) -> ServiceResult {
+    let pool = active_pool(&db)?;
+
+    // Only these writes can change the calendar feed
+    let refresh_calendar = matches!(command, UndoCommand::Undo { .. } | UndoCommand::Redo { .. });
+
+    let result = match command {
+        UndoCommand::Undo { steps } => undo_service(&pool, &db, steps).await,
+        UndoCommand::Redo { steps } => redo_service(&pool, &db, steps).await,
+        UndoCommand::Status => undo_status_service(&db),
+    };
+
+    if refresh_calendar {
+        after_write(&pool, &settings, &result).await;
+    }
+
+    result
+}
@@ -0,0 +1,196 @@
+use chrono::{Datelike, Local, NaiveDate, NaiveDateTime, Timelike};
+use serde::Serialize;
+use sqlx::{Error, SqlitePool};
+use std::{
+    collections::HashSet,
+    fs,
+    path::{Path, PathBuf},
+};
+
+const PREFIX: &str = "jobtrackr-";
+const EXTENSION: &str = ".db";
+const TIMESTAMP_FORMAT: &str = "%Y%m%d-%H%M%S";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BackupKind {
+    Manual,
+    Daily,
+    PreMigration,
+    PreRestore,
+}
+
+impl BackupKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BackupKind::Manual => "manual",
+            BackupKind::Daily => "daily",
+            BackupKind::PreMigration => "pre-migration",
+            BackupKind::PreRestore => "pre-restore",
+        }
+    }
+
+    fn parse(s: &str) -> Option<Self> {
+        match s {
+            "manual" => Some(BackupKind::Manual),
+            "daily" => Some(BackupKind::Daily),
+            "pre-migration" => Some(BackupKind::PreMigration),
+            "pre-restore" => Some(BackupKind::PreRestore),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupFile {
+    pub file_name: String,
+    pub path: PathBuf,
+    pub kind: BackupKind,
+    pub created_at: NaiveDateTime,
+    pub size_bytes: u64,
+}
+
+/// Snapshots live next to the database they were taken from.
+pub fn backup_dir(db_path: &Path) -> PathBuf {
+    db_path
+        .parent()
+        .map(|p| p.join("backups"))
+        .unwrap_or_else(|| PathBuf::from("backups"))
+}
+
+fn parse_file_name(file_name: &str) -> Option<(NaiveDateTime, BackupKind)> {
+    let stem = file_name.strip_prefix(PREFIX)?.strip_suffix(EXTENSION)?;
+    // jobtrackr-YYYYMMDD-HHMMSS-<kind>[-n].db
+    let timestamp = stem.get(..15)?;
+    let rest = stem.get(15..)?;
+    let created_at = NaiveDateTime::parse_from_str(timestamp, TIMESTAMP_FORMAT).ok()?;
+    let rest = rest.strip_prefix('-')?;
+    let kind = BackupKind::parse(rest).or_else(|| {
+        let (kind, counter) = rest.rsplit_once('-')?;
+        counter.parse::<u32>().ok()?;
+        BackupKind::parse(kind)
+    })?;
+    Some((created_at, kind))
+}
+
+// ======================================================
+// Create snapshot
+// ======================================================
+
+/// Writes a consistent copy of the open database using `VACUUM INTO`.
+/// Safe to run while the pool is in use.
+pub async fn create_snapshot(
+    pool: &SqlitePool,
+    db_path: &Path,
+    kind: BackupKind,
+) -> Result<BackupFile, Error> {
+    let dir = backup_dir(db_path);
+    fs::create_dir_all(&dir)?;
+
+    let created_at = Local::now().naive_local().with_nanosecond(0).unwrap();
+    let base = format!(
+        "{}{}-{}",
+        PREFIX,
+        created_at.format(TIMESTAMP_FORMAT),
+        kind.as_str()
+    );
+    let mut file_name = format!("{}{}", base, EXTENSION);
+    let mut counter = 1;
+    while dir.join(&file_name).exists() {
+        counter += 1;
+        file_name = format!("{}-{}{}", base, counter, EXTENSION);
+    }
+    let path = dir.join(&file_name);
+
+    sqlx::query("VACUUM INTO ?")
+        .bind(path.to_string_lossy().to_string())
+        .execute(pool)
+        .await?;
+
+    let size_bytes = fs::metadata(&path)?.len();
+
+    Ok(BackupFile {
+        file_name,
+        path,
+        kind,
+        created_at,
+        size_bytes,
+    })
+}
+
+// ======================================================
+// List snapshots
+// ======================================================
+
+/// All recognised snapshots for a database, newest first.
+pub fn list_snapshots(db_path: &Path) -> std::io::Result<Vec<BackupFile>> {
+    let dir = backup_dir(db_path);
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut backups: Vec<BackupFile> = fs::read_dir(&dir)?
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let file_name = entry.file_name().into_string().ok()?;
+            let (created_at, kind) = parse_file_name(&file_name)?;
+            let size_bytes = entry.metadata().ok()?.len();
+            Some(BackupFile {
+                path: entry.path(),
+                file_name,
+                kind,
+                created_at,
+                size_bytes,
+            })
+        })
+        .collect();
+
+    backups.sort_by(|a, b| {
+        b.created_at
+            .cmp(&a.created_at)
+            .then_with(|| b.file_name.cmp(&a.file_name))
+    });
+    Ok(backups)
+}
+
+// ======================================================
+// Rotation
+// ======================================================
+
+/// Picks the automatic snapshots that fall outside the retention policy.
+///
+/// The newest snapshot of each of the last `keep_daily` days and of each of
+/// the last `keep_weekly` ISO weeks is kept. Manual snapshots are never expired.
+/// `backups` must be sorted newest first, as returned by `list_snapshots`.
+pub fn select_expired(
+    backups: &[BackupFile],
+    keep_daily: usize,
+    keep_weekly: usize,
+) -> Vec<&BackupFile> {
+    let mut days: HashSet<NaiveDate> = HashSet::new();
+    let mut weeks: HashSet<(i32, u32)> = HashSet::new();
+    let mut expired = Vec::new();
+
+    for backup in backups.iter().filter(|b| b.kind != BackupKind::Manual) {
+        let day = backup.created_at.date();
+        let iso = day.iso_week();
+        let week = (iso.year(), iso.week());
+
+        let mut keep = false;
+        if !days.contains(&day) && days.len() < keep_daily {
+            days.insert(day);
+            keep = true;
+        }
+        if !weeks.contains(&week) && weeks.len() < keep_weekly {
+            weeks.insert(week);
+            keep = true;
+        }
+
+        if !keep {
+            expired.push(backup);
+        }
+    }
+
+    expired
+}
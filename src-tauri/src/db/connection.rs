@@ -1,34 +1,129 @@
-use crate::db::schema::INIT_SQL;
+use crate::db::backup::{create_snapshot, BackupKind};
+use crate::db::migrator::{current_version, pending_migrations, run_migrations, MigrationError};
 use crate::logger::*;
 use sqlx::{sqlite::SqlitePoolOptions, SqlitePool};
-use std::{fs, fs::OpenOptions, path::PathBuf};
+use std::{
+    fs,
+    fs::OpenOptions,
+    path::Path,
+    sync::{Mutex, MutexGuard, RwLock},
+};
 
-pub async fn init_db() -> Result<SqlitePool, sqlx::Error> {
-    // 1. Determine persistent path: ~/.JobTrackr/jobtrackr.db
-    let home_dir = dirs::home_dir().ok_or_else(|| {
-        error!("Failed to locate home directory");
-        sqlx::Error::Io(std::io::Error::new(
-            std::io::ErrorKind::NotFound,
-            "Missing home directory",
-        ))
-    })?;
+/// Managed Tauri state holding the pool of the active profile.
+///
+/// The pool is swapped when the user switches profiles, so commands fetch a
+/// fresh handle per call instead of holding on to one. The undo history
+/// belongs to the open database and is dropped along with it.
+#[derive(Default)]
+pub struct DbState {
+    pool: RwLock<Option<SqlitePool>>,
+    undo: Mutex<UndoStack>,
+}
+
+impl DbState {
+    /// Returns a handle to the active pool, or None while the database is still opening.
+    pub fn pool(&self) -> Option<SqlitePool> {
+        self.pool.read().unwrap().clone()
+    }
+
+    /// Removes the active pool, e.g. to close it before replacing the database file.
+    pub fn take(&self) -> Option<SqlitePool> {
+        self.undo_stack().clear();
+        self.pool.write().unwrap().take()
+    }
+
+    /// Installs a new pool and returns the previous one so the caller can close it.
+    pub fn replace(&self, pool: SqlitePool) -> Option<SqlitePool> {
+        self.undo_stack().clear();
+        self.pool.write().unwrap().replace(pool)
+    }
+
+    /// Undo/redo history of this session. Do not hold across an `.await`.
+    pub fn undo_stack(&self) -> MutexGuard<'_, UndoStack> {
+        self.undo.lock().unwrap()
+    }
+}
+
+/// Most operations kept for undo.
+pub const MAX_UNDO: usize = 100;
+
+/// The audit entries written by one command, stamped with `id`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Operation {
+    pub id: i64,
+}
+
+#[derive(Debug, Default)]
+pub struct UndoStack {
+    done: Vec<Operation>,
+    undone: Vec<Operation>,
+}
+
+impl UndoStack {
+    /// A new operation; whatever was undone can no longer be redone.
+    pub fn push(&mut self, op: Operation) {
+        self.undone.clear();
+        self.done.push(op);
+        if self.done.len() > MAX_UNDO {
+            self.done.remove(0);
+        }
+    }
+
+    pub fn pop_done(&mut self) -> Option<Operation> {
+        self.done.pop()
+    }
 
-    let base_dir = home_dir.join(".JobTrackr");
-    if let Err(e) = fs::create_dir_all(&base_dir) {
-        error!("Failed to create ~/.JobTrackr directory: {}", e);
-        return Err(sqlx::Error::Io(e));
+    pub fn pop_undone(&mut self) -> Option<Operation> {
+        self.undone.pop()
+    }
+
+    /// Puts an undone operation on the redo side.
+    pub fn push_undone(&mut self, op: Operation) {
+        self.undone.push(op);
+    }
+
+    /// Puts a redone operation back on the undo side, keeping the rest of
+    /// the redo side.
+    pub fn push_redone(&mut self, op: Operation) {
+        self.done.push(op);
+    }
+
+    pub fn undo_count(&self) -> usize {
+        self.done.len()
+    }
+
+    pub fn redo_count(&self) -> usize {
+        self.undone.len()
+    }
+
+    pub fn clear(&mut self) {
+        self.done.clear();
+        self.undone.clear();
+    }
+}
+
+pub async fn init_db(db_path: &Path) -> Result<SqlitePool, MigrationError> {
+    // 1. Ensure the profile directory exists
+    if let Some(base_dir) = db_path.parent() {
+        if let Err(e) = fs::create_dir_all(base_dir) {
+            error!(
+                "Failed to create data directory {}: {}",
+                base_dir.display(),
+                e
+            );
+            return Err(sqlx::Error::Io(e).into());
+        }
     }
 
-    let db_path: PathBuf = base_dir.join("jobtrackr.db");
     let db_url = format!("sqlite://{}", db_path.display());
     info!("Opening database at {}", db_url);
 
     // 2. Ensure file exists before opening
     if !db_path.exists() {
         info!("Database file not found — creating empty file.");
-        if let Err(e) = OpenOptions::new().create(true).write(true).open(&db_path) {
+        if let Err(e) = OpenOptions::new().create(true).write(true).open(db_path) {
             error!("Failed to create database file: {}", e);
-            return Err(sqlx::Error::Io(e));
+            return Err(sqlx::Error::Io(e).into());
         }
     }
 
@@ -38,52 +133,39 @@ pub async fn init_db() -> Result<SqlitePool, sqlx::Error> {
         .connect(&db_url)
         .await?;
 
-    // 4. Check schema existence
-    let (table_count,): (i64,) =
-        match sqlx::query_as("SELECT COUNT(name) FROM sqlite_master WHERE type='table';")
-            .fetch_one(&pool)
-            .await
-        {
-            Ok(res) => res,
+    // 4. Snapshot existing data before changing the schema
+    let pending = pending_migrations(&pool).await?;
+    if !pending.is_empty() && current_version(&pool).await? > 0 {
+        info!(
+            "{} pending migration(s) — creating pre-migration backup.",
+            pending.len()
+        );
+        match create_snapshot(&pool, db_path, BackupKind::PreMigration).await {
+            Ok(backup) => info!("Pre-migration backup written to {}", backup.path.display()),
             Err(e) => {
-                error!("Failed to query sqlite_master: {}", e);
-                return Err(e);
-            }
-        };
-
-    // 5. Initialize schema if empty
-    if table_count == 0 {
-        info!("Database empty — initializing schema...");
-        for stmt in INIT_SQL.split_terminator(';') {
-            let trimmed = stmt.trim();
-            if !trimmed.is_empty() {
-                if let Err(e) = sqlx::query(trimmed).execute(&pool).await {
-                    error!("Schema statement failed: {}", e);
-                    return Err(e);
-                }
+                error!("Pre-migration backup failed: {}", e);
+                return Err(e.into());
             }
         }
-        pool.close().await;
-        info!("Schema successfully created at {}", db_path.display());
-    } else {
-        info!("Existing schema detected — skipping initialization.");
     }
 
+    // 5. Bring the schema up to date
+    let version = run_migrations(&pool).await.map_err(|e| {
+        error!("Schema migration failed: {}", e);
+        e
+    })?;
+
     // 6. Verify file exists physically
     if !db_path.exists() {
         error!("Database file was not created — check write permissions");
         return Err(sqlx::Error::Io(std::io::Error::new(
             std::io::ErrorKind::NotFound,
             "Database file missing after initialization",
-        )));
+        ))
+        .into());
     }
 
-    // 7. Reconnect after schema initialization
-    let pool = SqlitePoolOptions::new()
-        .max_connections(1)
-        .connect(&db_url)
-        .await?;
-
+    info!("Schema version: {}", version);
     info!("Database ready for use at {}", db_path.display());
     Ok(pool)
 }
@@ -0,0 +1,277 @@
+use crate::db::schema::{Migration, MIGRATIONS};
+use crate::logger::*;
+use sha2::{Digest, Sha256};
+use sqlx::{Connection, Executor, FromRow, SqliteConnection, SqlitePool};
+use std::fmt;
+
+#[derive(FromRow, Debug)]
+pub struct AppliedMigration {
+    pub version: i64,
+    pub name: String,
+    pub checksum: String,
+}
+
+#[derive(Debug)]
+pub enum MigrationError {
+    Database(sqlx::Error),
+    ChecksumMismatch {
+        version: i64,
+        name: String,
+    },
+    UnknownVersion {
+        version: i64,
+    },
+    DatabaseAhead {
+        database_version: i64,
+        binary_version: i64,
+    },
+    ForeignKeyViolation {
+        version: i64,
+    },
+}
+
+impl fmt::Display for MigrationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MigrationError::Database(e) => write!(f, "database error: {}", e),
+            MigrationError::ChecksumMismatch { version, name } => write!(
+                f,
+                "migration {:04}_{} was modified after it was applied",
+                version, name
+            ),
+            MigrationError::UnknownVersion { version } => write!(
+                f,
+                "migration {:04} is recorded in the database but unknown to this build",
+                version
+            ),
+            MigrationError::DatabaseAhead {
+                database_version,
+                binary_version,
+            } => write!(
+                f,
+                "database schema version {} is newer than this build supports ({}); please update JobTrackr",
+                database_version, binary_version
+            ),
+            MigrationError::ForeignKeyViolation { version } => write!(
+                f,
+                "migration {:04} left foreign key violations behind",
+                version
+            ),
+        }
+    }
+}
+
+impl std::error::Error for MigrationError {}
+
+impl From<sqlx::Error> for MigrationError {
+    fn from(e: sqlx::Error) -> Self {
+        MigrationError::Database(e)
+    }
+}
+
+// ======================================================
+// Helpers
+// ======================================================
+
+/// Hex-encoded SHA-256 of a migration's SQL, used to detect edited files.
+pub fn checksum(sql: &str) -> String {
+    Sha256::digest(sql.as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Highest migration version known to this build.
+pub fn latest_version() -> i64 {
+    MIGRATIONS.last().map(|m| m.version).unwrap_or(0)
+}
+
+async fn ensure_migrations_table(conn: &mut SqliteConnection) -> Result<(), sqlx::Error> {
+    conn.execute(
+        r#"
+        CREATE TABLE IF NOT EXISTS schema_migrations (
+            version INTEGER PRIMARY KEY,
+            name TEXT NOT NULL,
+            checksum TEXT NOT NULL,
+            applied_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+        )
+        "#,
+    )
+    .await?;
+    Ok(())
+}
+
+/// Databases created before the migration runner existed already contain the
+/// 0001 schema but no bookkeeping rows. Record them as being at version 1.
+async fn baseline_legacy_database(conn: &mut SqliteConnection) -> Result<(), sqlx::Error> {
+    let (recorded,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM schema_migrations")
+        .fetch_one(&mut *conn)
+        .await?;
+    if recorded > 0 {
+        return Ok(());
+    }
+
+    let (legacy,): (i64,) = sqlx::query_as(
+        "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = 'company'",
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+    if legacy == 0 {
+        return Ok(());
+    }
+
+    let init = &MIGRATIONS[0];
+    info!(
+        "Existing pre-migration schema detected — baselining at version {}.",
+        init.version
+    );
+    sqlx::query("INSERT INTO schema_migrations (version, name, checksum) VALUES (?, ?, ?)")
+        .bind(init.version)
+        .bind(init.name)
+        .bind(checksum(init.sql))
+        .execute(&mut *conn)
+        .await?;
+    Ok(())
+}
+
+async fn fetch_applied(conn: &mut SqliteConnection) -> Result<Vec<AppliedMigration>, sqlx::Error> {
+    sqlx::query_as::<_, AppliedMigration>(
+        "SELECT version, name, checksum FROM schema_migrations ORDER BY version ASC",
+    )
+    .fetch_all(conn)
+    .await
+}
+
+/// Checks the recorded migrations against the embedded ones and returns the
+/// migrations that still need to be applied.
+fn resolve_pending(
+    applied: &[AppliedMigration],
+) -> Result<Vec<&'static Migration>, MigrationError> {
+    let binary_version = latest_version();
+    if let Some(newest) = applied.last() {
+        if newest.version > binary_version {
+            return Err(MigrationError::DatabaseAhead {
+                database_version: newest.version,
+                binary_version,
+            });
+        }
+    }
+
+    for record in applied {
+        let known = MIGRATIONS
+            .iter()
+            .find(|m| m.version == record.version)
+            .ok_or(MigrationError::UnknownVersion {
+                version: record.version,
+            })?;
+        if checksum(known.sql) != record.checksum {
+            return Err(MigrationError::ChecksumMismatch {
+                version: known.version,
+                name: known.name.to_string(),
+            });
+        }
+    }
+
+    Ok(MIGRATIONS
+        .iter()
+        .filter(|m| !applied.iter().any(|a| a.version == m.version))
+        .collect())
+}
+
+async fn apply_migration(
+    conn: &mut SqliteConnection,
+    migration: &Migration,
+) -> Result<(), MigrationError> {
+    let mut tx = conn.begin().await?;
+
+    tx.execute(migration.sql).await?;
+
+    let violations = sqlx::query("PRAGMA foreign_key_check")
+        .fetch_all(&mut *tx)
+        .await?;
+    if !violations.is_empty() {
+        tx.rollback().await?;
+        return Err(MigrationError::ForeignKeyViolation {
+            version: migration.version,
+        });
+    }
+
+    sqlx::query("INSERT INTO schema_migrations (version, name, checksum) VALUES (?, ?, ?)")
+        .bind(migration.version)
+        .bind(migration.name)
+        .bind(checksum(migration.sql))
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+    Ok(())
+}
+
+// ======================================================
+// Public API
+// ======================================================
+
+/// Current schema version recorded in the database (0 for an empty database).
+pub async fn current_version(pool: &SqlitePool) -> Result<i64, MigrationError> {
+    let mut conn = pool.acquire().await?;
+    ensure_migrations_table(&mut conn).await?;
+    baseline_legacy_database(&mut conn).await?;
+    let applied = fetch_applied(&mut conn).await?;
+    Ok(applied.last().map(|m| m.version).unwrap_or(0))
+}
+
+/// Verifies already-applied migrations and returns those still pending.
+pub async fn pending_migrations(
+    pool: &SqlitePool,
+) -> Result<Vec<&'static Migration>, MigrationError> {
+    let mut conn = pool.acquire().await?;
+    ensure_migrations_table(&mut conn).await?;
+    baseline_legacy_database(&mut conn).await?;
+    let applied = fetch_applied(&mut conn).await?;
+    resolve_pending(&applied)
+}
+
+/// Applies every pending migration in order, each inside its own transaction.
+///
+/// Foreign key enforcement is switched off while migrating so that table
+/// rebuilds do not trigger cascades; integrity is checked with
+/// `PRAGMA foreign_key_check` before each commit instead.
+///
+/// Returns the schema version after the run.
+pub async fn run_migrations(pool: &SqlitePool) -> Result<i64, MigrationError> {
+    let mut conn = pool.acquire().await?;
+    ensure_migrations_table(&mut conn).await?;
+    baseline_legacy_database(&mut conn).await?;
+
+    let applied = fetch_applied(&mut conn).await?;
+    let pending = resolve_pending(&applied)?;
+
+    if pending.is_empty() {
+        info!("Schema up to date at version {}.", latest_version());
+        return Ok(latest_version());
+    }
+
+    conn.execute("PRAGMA foreign_keys = OFF").await?;
+
+    let mut result = Ok(());
+    for migration in pending {
+        info!(
+            "Applying migration {:04}_{}...",
+            migration.version, migration.name
+        );
+        if let Err(e) = apply_migration(&mut conn, migration).await {
+            error!(
+                "Migration {:04}_{} failed: {}",
+                migration.version, migration.name, e
+            );
+            result = Err(e);
+            break;
+        }
+    }
+
+    conn.execute("PRAGMA foreign_keys = ON").await?;
+    result?;
+
+    info!("Schema migrated to version {}.", latest_version());
+    Ok(latest_version())
+}
@@ -1,4 +1,6 @@
+pub mod backup;
 pub mod connection;
+pub mod migrator;
 pub mod models;
 pub mod queries;
 pub mod schema;
@@ -1,5 +1,10 @@
-use serde::{Deserialize, Serialize};
-use sqlx::Type;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use sqlx::encode::IsNull;
+use sqlx::error::BoxDynError;
+use sqlx::sqlite::{SqliteArgumentValue, SqliteTypeInfo, SqliteValueRef};
+use sqlx::{Decode, Encode, Sqlite, Type};
+use std::fmt;
+use std::str::FromStr;
 
 // ======================================================
 // Work Type
@@ -94,34 +99,137 @@ impl SeniorityLevel {
 // ======================================================
 // Currency
 // ======================================================
-#[derive(Type, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// Active ISO 4217 codes, sorted so they can be binary-searched.
+const ISO_4217: [&str; 181] = [
+    "AED", "AFN", "ALL", "AMD", "ANG", "AOA", "ARS", "AUD", "AWG", "AZN", "BAM", "BBD", "BDT",
+    "BGN", "BHD", "BIF", "BMD", "BND", "BOB", "BOV", "BRL", "BSD", "BTN", "BWP", "BYN", "BZD",
+    "CAD", "CDF", "CHE", "CHF", "CHW", "CLF", "CLP", "CNY", "COP", "COU", "CRC", "CUP", "CVE",
+    "CZK", "DJF", "DKK", "DOP", "DZD", "EGP", "ERN", "ETB", "EUR", "FJD", "FKP", "GBP", "GEL",
+    "GHS", "GIP", "GMD", "GNF", "GTQ", "GYD", "HKD", "HNL", "HTG", "HUF", "IDR", "ILS", "INR",
+    "IQD", "IRR", "ISK", "JMD", "JOD", "JPY", "KES", "KGS", "KHR", "KMF", "KPW", "KRW", "KWD",
+    "KYD", "KZT", "LAK", "LBP", "LKR", "LRD", "LSL", "LYD", "MAD", "MDL", "MGA", "MKD", "MMK",
+    "MNT", "MOP", "MRU", "MUR", "MVR", "MWK", "MXN", "MXV", "MYR", "MZN", "NAD", "NGN", "NIO",
+    "NOK", "NPR", "NZD", "OMR", "PAB", "PEN", "PGK", "PHP", "PKR", "PLN", "PYG", "QAR", "RON",
+    "RSD", "RUB", "RWF", "SAR", "SBD", "SCR", "SDG", "SEK", "SGD", "SHP", "SLE", "SLL", "SOS",
+    "SRD", "SSP", "STN", "SVC", "SYP", "SZL", "THB", "TJS", "TMT", "TND", "TOP", "TRY", "TTD",
+    "TWD", "TZS", "UAH", "UGX", "USD", "USN", "UYI", "UYU", "UYW", "UZS", "VED", "VES", "VND",
+    "VUV", "WST", "XAF", "XAG", "XAU", "XBA", "XBB", "XBC", "XBD", "XCD", "XCG", "XDR", "XOF",
+    "XPD", "XPF", "XPT", "XSU", "XTS", "XUA", "XXX", "YER", "ZAR", "ZMW", "ZWG", "ZWL",
+];
+
+/// An ISO 4217 currency code such as `EUR`. `XXX` ("no currency") takes
+/// the place of the former `other`, which is still accepted when parsing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Currency(&'static str);
+
+impl Currency {
+    pub const USD: Currency = Currency("USD");
+    pub const EUR: Currency = Currency("EUR");
+    pub const GBP: Currency = Currency("GBP");
+    pub const DKK: Currency = Currency("DKK");
+    pub const XXX: Currency = Currency("XXX");
+
+    pub fn as_str(&self) -> &'static str {
+        self.0
+    }
+}
+
+impl fmt::Display for Currency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.0)
+    }
+}
+
+impl FromStr for Currency {
+    type Err = String;
+
+    /// Case-insensitive; surrounding whitespace is ignored.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let code = s.trim().to_ascii_uppercase();
+        if code == "OTHER" {
+            return Ok(Currency::XXX);
+        }
+        ISO_4217
+            .binary_search(&code.as_str())
+            .map(|i| Currency(ISO_4217[i]))
+            .map_err(|_| format!("'{}' is not an ISO 4217 currency code", s.trim()))
+    }
+}
+
+impl Serialize for Currency {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for Currency {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let code = String::deserialize(deserializer)?;
+        code.parse().map_err(de::Error::custom)
+    }
+}
+
+impl Type<Sqlite> for Currency {
+    fn type_info() -> SqliteTypeInfo {
+        <str as Type<Sqlite>>::type_info()
+    }
+
+    fn compatible(ty: &SqliteTypeInfo) -> bool {
+        <str as Type<Sqlite>>::compatible(ty)
+    }
+}
+
+impl<'q> Encode<'q, Sqlite> for Currency {
+    fn encode_by_ref(&self, buf: &mut Vec<SqliteArgumentValue<'q>>) -> IsNull {
+        <&str as Encode<'q, Sqlite>>::encode(self.0, buf)
+    }
+}
+
+impl<'r> Decode<'r, Sqlite> for Currency {
+    fn decode(value: SqliteValueRef<'r>) -> Result<Self, BoxDynError> {
+        let code = <&str as Decode<'r, Sqlite>>::decode(value)?;
+        Ok(code.parse::<Currency>()?)
+    }
+}
+
+// ======================================================
+// Pay Period
+// ======================================================
+#[derive(Type, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[sqlx(type_name = "TEXT")]
-pub enum Currency {
-    #[sqlx(rename = "USD")]
-    #[serde(rename = "USD")]
-    USD,
-    #[sqlx(rename = "EUR")]
-    #[serde(rename = "EUR")]
-    EUR,
-    #[sqlx(rename = "GBP")]
-    #[serde(rename = "GBP")]
-    GBP,
-    #[sqlx(rename = "DKK")]
-    #[serde(rename = "DKK")]
-    DKK,
-    #[sqlx(rename = "other")]
-    #[serde(rename = "other")]
-    Other,
+pub enum PayPeriod {
+    #[sqlx(rename = "hourly")]
+    #[serde(rename = "hourly")]
+    Hourly,
+    #[sqlx(rename = "daily")]
+    #[serde(rename = "daily")]
+    Daily,
+    #[sqlx(rename = "monthly")]
+    #[serde(rename = "monthly")]
+    Monthly,
+    #[sqlx(rename = "yearly")]
+    #[serde(rename = "yearly")]
+    Yearly,
 }
 
-impl Currency {
+impl PayPeriod {
     pub fn as_str(&self) -> &'static str {
         match self {
-            Currency::USD => "USD",
-            Currency::EUR => "EUR",
-            Currency::GBP => "GBP",
-            Currency::DKK => "DKK",
-            Currency::Other => "other",
+            PayPeriod::Hourly => "hourly",
+            PayPeriod::Daily => "daily",
+            PayPeriod::Monthly => "monthly",
+            PayPeriod::Yearly => "yearly",
+        }
+    }
+
+    /// How many of these periods are paid in a year, assuming full-time
+    /// work: 40 hours a week, 5 days a week, 52 weeks.
+    pub fn per_year(&self) -> i64 {
+        match self {
+            PayPeriod::Hourly => 2080,
+            PayPeriod::Daily => 260,
+            PayPeriod::Monthly => 12,
+            PayPeriod::Yearly => 1,
         }
     }
 }
@@ -169,6 +277,41 @@ impl Role {
     }
 }
 
+// ======================================================
+// Contact Role (Person on an Application)
+// ======================================================
+#[derive(Type, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[sqlx(type_name = "TEXT")]
+pub enum ContactRole {
+    #[sqlx(rename = "recruiter")]
+    #[serde(rename = "recruiter")]
+    Recruiter,
+    #[sqlx(rename = "referrer")]
+    #[serde(rename = "referrer")]
+    Referrer,
+    #[sqlx(rename = "interviewer")]
+    #[serde(rename = "interviewer")]
+    Interviewer,
+    #[sqlx(rename = "hiring_manager")]
+    #[serde(rename = "hiring_manager")]
+    HiringManager,
+    #[sqlx(rename = "other")]
+    #[serde(rename = "other")]
+    Other,
+}
+
+impl ContactRole {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ContactRole::Recruiter => "recruiter",
+            ContactRole::Referrer => "referrer",
+            ContactRole::Interviewer => "interviewer",
+            ContactRole::HiringManager => "hiring_manager",
+            ContactRole::Other => "other",
+        }
+    }
+}
+
 // ======================================================
 // Application Stage
 // ======================================================
@@ -205,6 +348,10 @@ pub enum Stage {
     #[sqlx(rename = "on_hold")]
     #[serde(rename = "on_hold")]
     OnHold,
+    /// Never heard back
+    #[sqlx(rename = "ghosted")]
+    #[serde(rename = "ghosted")]
+    Ghosted,
     #[sqlx(rename = "other")]
     #[serde(rename = "other")]
     Other,
@@ -223,6 +370,7 @@ impl Stage {
             Stage::Rejected => "rejected",
             Stage::Withdrawn => "withdrawn",
             Stage::OnHold => "on_hold",
+            Stage::Ghosted => "ghosted",
             Stage::Other => "other",
         }
     }
@@ -328,3 +476,51 @@ impl ReminderStatus {
         }
     }
 }
+
+// ======================================================
+// Entity Type
+// ======================================================
+#[derive(Type, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[sqlx(type_name = "TEXT")]
+pub enum EntityType {
+    #[sqlx(rename = "company")]
+    #[serde(rename = "company")]
+    Company,
+    #[sqlx(rename = "person")]
+    #[serde(rename = "person")]
+    Person,
+    #[sqlx(rename = "job_listing")]
+    #[serde(rename = "job_listing")]
+    JobListing,
+    #[sqlx(rename = "application")]
+    #[serde(rename = "application")]
+    Application,
+    #[sqlx(rename = "interaction")]
+    #[serde(rename = "interaction")]
+    Interaction,
+    #[sqlx(rename = "note")]
+    #[serde(rename = "note")]
+    Note,
+    #[sqlx(rename = "reminder")]
+    #[serde(rename = "reminder")]
+    Reminder,
+}
+
+impl EntityType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EntityType::Company => "company",
+            EntityType::Person => "person",
+            EntityType::JobListing => "job_listing",
+            EntityType::Application => "application",
+            EntityType::Interaction => "interaction",
+            EntityType::Note => "note",
+            EntityType::Reminder => "reminder",
+        }
+    }
+
+    /// Name of the table backing this entity.
+    pub fn table_name(&self) -> &'static str {
+        self.as_str()
+    }
+}
@@ -1 +1,3 @@
 pub mod enums;
+pub mod patch;
+pub mod rule;
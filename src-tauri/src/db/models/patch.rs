@@ -0,0 +1,72 @@
+use serde::{Deserialize, Deserializer};
+use std::ops::Deref;
+
+/// Change to one nullable column in a partial update.
+///
+/// In an update payload a missing field is `Unchanged`, `null` is `Clear`
+/// and any other value is `Set`. Payload fields of this type need
+/// `#[serde(default)]` so that a missing field deserializes at all.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Patch<T> {
+    #[default]
+    Unchanged,
+    Clear,
+    Set(T),
+}
+
+impl<T> Patch<T> {
+    /// For required columns: `None` leaves the column unchanged.
+    pub fn from_option(value: Option<T>) -> Self {
+        value.map_or(Patch::Unchanged, Patch::Set)
+    }
+
+    pub fn as_ref(&self) -> Patch<&T> {
+        match self {
+            Patch::Unchanged => Patch::Unchanged,
+            Patch::Clear => Patch::Clear,
+            Patch::Set(v) => Patch::Set(v),
+        }
+    }
+
+    pub fn as_deref(&self) -> Patch<&T::Target>
+    where
+        T: Deref,
+    {
+        match self {
+            Patch::Unchanged => Patch::Unchanged,
+            Patch::Clear => Patch::Clear,
+            Patch::Set(v) => Patch::Set(v.deref()),
+        }
+    }
+
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> Patch<U> {
+        match self {
+            Patch::Unchanged => Patch::Unchanged,
+            Patch::Clear => Patch::Clear,
+            Patch::Set(v) => Patch::Set(f(v)),
+        }
+    }
+
+    /// Like `map`, for conversions that can fail (e.g. validation).
+    pub fn try_map<U, E>(self, f: impl FnOnce(T) -> Result<U, E>) -> Result<Patch<U>, E> {
+        Ok(match self {
+            Patch::Unchanged => Patch::Unchanged,
+            Patch::Clear => Patch::Clear,
+            Patch::Set(v) => Patch::Set(f(v)?),
+        })
+    }
+
+    pub fn is_unchanged(&self) -> bool {
+        matches!(self, Patch::Unchanged)
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Patch<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        // Only reached when the field is present; absence is handled by `default`
+        Ok(match Option::<T>::deserialize(deserializer)? {
+            Some(value) => Patch::Set(value),
+            None => Patch::Clear,
+        })
+    }
+}
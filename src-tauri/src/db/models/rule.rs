@@ -0,0 +1,84 @@
+use crate::db::models::enums::{InteractionType, NoteType, Stage};
+use serde::{Deserialize, Serialize};
+
+// ======================================================
+// Rule Trigger
+// ======================================================
+/// What a rule reacts to. Stored as JSON tagged by `event`, e.g.
+/// `{"event": "stage_changed", "stage": "interviewing"}`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(
+    tag = "event",
+    rename_all = "snake_case",
+    rename_all_fields = "camelCase"
+)]
+pub enum RuleTrigger {
+    ApplicationCreated,
+    /// Any stage when `stage` is omitted
+    StageChanged {
+        #[serde(default)]
+        stage: Option<Stage>,
+    },
+    /// Any interaction type when `interactionType` is omitted
+    InteractionLogged {
+        #[serde(default)]
+        interaction_type: Option<InteractionType>,
+    },
+    /// An open application without interactions for `days` days
+    NoInteraction {
+        days: i64,
+    },
+}
+
+impl RuleTrigger {
+    pub fn event(&self) -> &'static str {
+        match self {
+            RuleTrigger::ApplicationCreated => "application_created",
+            RuleTrigger::StageChanged { .. } => "stage_changed",
+            RuleTrigger::InteractionLogged { .. } => "interaction_logged",
+            RuleTrigger::NoInteraction { .. } => "no_interaction",
+        }
+    }
+}
+
+// ======================================================
+// Rule Action
+// ======================================================
+/// What a rule does. Stored as JSON tagged by `type`. Titles and texts
+/// may use `{company}`, `{job}` and `{stage}`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(
+    tag = "type",
+    rename_all = "snake_case",
+    rename_all_fields = "camelCase"
+)]
+pub enum RuleAction {
+    /// Due `daysAfter` days after the rule fires
+    CreateReminder {
+        title: String,
+        #[serde(default)]
+        message: Option<String>,
+        #[serde(default)]
+        days_after: i64,
+    },
+    CreateNote {
+        title: String,
+        #[serde(default)]
+        content: Option<String>,
+        #[serde(default)]
+        note_type: Option<NoteType>,
+    },
+    SetStage {
+        stage: Stage,
+    },
+}
+
+impl RuleAction {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            RuleAction::CreateReminder { .. } => "create_reminder",
+            RuleAction::CreateNote { .. } => "create_note",
+            RuleAction::SetStage { .. } => "set_stage",
+        }
+    }
+}
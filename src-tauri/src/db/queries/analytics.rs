@@ -0,0 +1,187 @@
+use crate::db::models::enums::{SeniorityLevel, Stage, WorkType};
+use chrono::NaiveDate;
+use serde::Serialize;
+use sqlx::{Error, FromRow, SqlitePool};
+
+/// Inclusive range on `application.applied_date`; open ends are unbounded.
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DateRange {
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+}
+
+impl DateRange {
+    fn bounds(&self) -> (Option<String>, Option<String>) {
+        (
+            self.from.map(|d| d.format("%Y-%m-%d").to_string()),
+            self.to.map(|d| d.format("%Y-%m-%d").to_string()),
+        )
+    }
+}
+
+#[derive(FromRow, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StageCount {
+    pub stage: Stage,
+    pub count: i64,
+}
+
+#[derive(FromRow, Debug)]
+pub struct StageVisit {
+    pub application_id: i64,
+    pub stage: Stage,
+}
+
+#[derive(FromRow, Debug)]
+pub struct ApplicationOutcome {
+    pub application_id: i64,
+    pub company: Option<String>,
+    pub industry: Option<String>,
+    pub work_type: Option<WorkType>,
+    pub seniority_level: Option<SeniorityLevel>,
+    pub responded: bool,
+    pub days_to_first_interaction: Option<f64>,
+}
+
+#[derive(FromRow, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WeeklyCount {
+    pub week_start: NaiveDate,
+    pub count: i64,
+}
+
+// Shared WHERE clause, trashed applications left out; binds (from, from, to, to)
+const IN_RANGE: &str = "a.deleted_at IS NULL \
+    AND (? IS NULL OR a.applied_date >= ?) AND (? IS NULL OR a.applied_date <= ?)";
+
+// ======================================================
+// Applications per current stage
+// ======================================================
+pub async fn count_by_stage(
+    pool: &SqlitePool,
+    range: &DateRange,
+) -> Result<Vec<StageCount>, Error> {
+    let (from, to) = range.bounds();
+    let sql = format!(
+        "SELECT a.stage AS stage, COUNT(*) AS count FROM application a WHERE {} GROUP BY a.stage",
+        IN_RANGE
+    );
+
+    sqlx::query_as::<_, StageCount>(&sql)
+        .bind(&from)
+        .bind(&from)
+        .bind(&to)
+        .bind(&to)
+        .fetch_all(pool)
+        .await
+}
+
+// ======================================================
+// Every stage each application has entered
+// ======================================================
+pub async fn get_stage_visits(
+    pool: &SqlitePool,
+    range: &DateRange,
+) -> Result<Vec<StageVisit>, Error> {
+    let (from, to) = range.bounds();
+    let sql = format!(
+        r#"
+        SELECT DISTINCT e.application_id AS application_id, e.to_stage AS stage
+        FROM application_stage_event e
+        JOIN application a ON a.id = e.application_id
+        WHERE {}
+        "#,
+        IN_RANGE
+    );
+
+    sqlx::query_as::<_, StageVisit>(&sql)
+        .bind(&from)
+        .bind(&from)
+        .bind(&to)
+        .bind(&to)
+        .fetch_all(pool)
+        .await
+}
+
+// ======================================================
+// Per-application response data
+// ======================================================
+/// An application counts as "responded" once it has any interaction or has
+/// moved to a stage other than `applied` / `withdrawn` / `ghosted` (a rejection
+/// is a response).
+pub async fn get_application_outcomes(
+    pool: &SqlitePool,
+    range: &DateRange,
+) -> Result<Vec<ApplicationOutcome>, Error> {
+    let (from, to) = range.bounds();
+    let sql = format!(
+        r#"
+        SELECT
+            a.id AS application_id,
+            c.name AS company,
+            c.industry AS industry,
+            jl.work_type AS work_type,
+            jl.seniority_level AS seniority_level,
+            (
+                EXISTS (
+                    SELECT 1 FROM interaction i
+                    WHERE i.application_id = a.id AND i.deleted_at IS NULL
+                )
+                OR EXISTS (
+                    SELECT 1 FROM application_stage_event e
+                    WHERE e.application_id = a.id
+                      AND e.to_stage NOT IN ('applied', 'withdrawn', 'ghosted')
+                )
+            ) AS responded,
+            (
+                SELECT julianday(MIN(i.interaction_date)) - julianday(a.applied_date)
+                FROM interaction i
+                WHERE i.application_id = a.id
+                  AND i.deleted_at IS NULL
+                  AND i.interaction_date >= a.applied_date
+            ) AS days_to_first_interaction
+        FROM application a
+        LEFT JOIN job_listing jl ON jl.id = a.job_listing_id
+        LEFT JOIN company c ON c.id = jl.company_id
+        WHERE {}
+        "#,
+        IN_RANGE
+    );
+
+    sqlx::query_as::<_, ApplicationOutcome>(&sql)
+        .bind(&from)
+        .bind(&from)
+        .bind(&to)
+        .bind(&to)
+        .fetch_all(pool)
+        .await
+}
+
+// ======================================================
+// Applications per week (weeks start on Monday)
+// ======================================================
+pub async fn count_by_week(
+    pool: &SqlitePool,
+    range: &DateRange,
+) -> Result<Vec<WeeklyCount>, Error> {
+    let (from, to) = range.bounds();
+    let sql = format!(
+        r#"
+        SELECT date(a.applied_date, '-6 days', 'weekday 1') AS week_start, COUNT(*) AS count
+        FROM application a
+        WHERE {}
+        GROUP BY week_start
+        ORDER BY week_start ASC
+        "#,
+        IN_RANGE
+    );
+
+    sqlx::query_as::<_, WeeklyCount>(&sql)
+        .bind(&from)
+        .bind(&from)
+        .bind(&to)
+        .bind(&to)
+        .fetch_all(pool)
+        .await
+}
@@ -1,10 +1,15 @@
-use crate::db::models::enums::Stage;
-use crate::utils::sql_utils::build_update_sql;
+use crate::db::models::enums::{EntityType, Stage};
+use crate::db::models::patch::Patch;
+use crate::db::queries::list_query::{
+    col, fetch_page, ColumnKind, ListError, ListOptions, ListPage, ListSpec,
+};
+use crate::db::queries::trash;
+use crate::utils::sql_utils::{bind_sql_values, build_update_sql, SqlValue};
 use chrono::{NaiveDate, NaiveDateTime};
-use serde::Serialize;
-use sqlx::{query_as, Error, FromRow, SqlitePool};
+use serde::{Deserialize, Serialize};
+use sqlx::{query_as, Error, FromRow, SqliteExecutor, SqlitePool};
 
-#[derive(FromRow, Debug, Serialize)]
+#[derive(FromRow, Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Application {
     pub id: i64,
@@ -14,13 +19,15 @@ pub struct Application {
     pub application_notes: Option<String>,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
+    pub archived_at: Option<NaiveDateTime>,
+    pub deleted_at: Option<NaiveDateTime>,
 }
 
 // ======================================================
 // Create
 // ======================================================
 pub async fn create_application(
-    pool: &SqlitePool,
+    executor: impl SqliteExecutor<'_>,
     job_listing_id: Option<i64>,
     stage: Option<&Stage>,
     applied_date: &NaiveDate,
@@ -46,21 +53,26 @@ pub async fn create_application(
             applied_date AS "applied_date!: NaiveDate",
             application_notes,
             created_at AS "created_at!: NaiveDateTime",
-            updated_at AS "updated_at!: NaiveDateTime"
+            updated_at AS "updated_at!: NaiveDateTime",
+            archived_at AS "archived_at: NaiveDateTime",
+            deleted_at AS "deleted_at: NaiveDateTime"
         "#,
         job_listing_id,
         stage_str,
         applied_date_str,
         application_notes
     )
-    .fetch_one(pool)
+    .fetch_one(executor)
     .await
 }
 
 // ======================================================
 // Get by ID
 // ======================================================
-pub async fn get_application_by_id(pool: &SqlitePool, id: i64) -> Result<Application, Error> {
+pub async fn get_application_by_id(
+    executor: impl SqliteExecutor<'_>,
+    id: i64,
+) -> Result<Application, Error> {
     query_as!(
         Application,
         r#"
@@ -71,13 +83,15 @@ pub async fn get_application_by_id(pool: &SqlitePool, id: i64) -> Result<Applica
             applied_date AS "applied_date!: NaiveDate",
             application_notes,
             created_at AS "created_at!: NaiveDateTime",
-            updated_at AS "updated_at!: NaiveDateTime"
+            updated_at AS "updated_at!: NaiveDateTime",
+            archived_at AS "archived_at: NaiveDateTime",
+            deleted_at AS "deleted_at: NaiveDateTime"
         FROM application
-        WHERE id = ?
+        WHERE id = ? AND deleted_at IS NULL
         "#,
         id
     )
-    .fetch_one(pool)
+    .fetch_one(executor)
     .await
 }
 
@@ -95,8 +109,11 @@ pub async fn get_all_applications(pool: &SqlitePool) -> Result<Vec<Application>,
             applied_date AS "applied_date!: NaiveDate",
             application_notes,
             created_at AS "created_at!: NaiveDateTime",
-            updated_at AS "updated_at!: NaiveDateTime"
+            updated_at AS "updated_at!: NaiveDateTime",
+            archived_at AS "archived_at: NaiveDateTime",
+            deleted_at AS "deleted_at: NaiveDateTime"
         FROM application
+        WHERE deleted_at IS NULL AND archived_at IS NULL
         ORDER BY applied_date DESC
         "#
     )
@@ -107,6 +124,7 @@ pub async fn get_all_applications(pool: &SqlitePool) -> Result<Vec<Application>,
 // ======================================================
 // Get by Job Listing ID
 // ======================================================
+/// Archived applications included.
 pub async fn get_applications_by_job_listing_id(
     pool: &SqlitePool,
     job_listing_id: i64,
@@ -121,9 +139,11 @@ pub async fn get_applications_by_job_listing_id(
             applied_date AS "applied_date!: NaiveDate",
             application_notes,
             created_at AS "created_at!: NaiveDateTime",
-            updated_at AS "updated_at!: NaiveDateTime"
+            updated_at AS "updated_at!: NaiveDateTime",
+            archived_at AS "archived_at: NaiveDateTime",
+            deleted_at AS "deleted_at: NaiveDateTime"
         FROM application
-        WHERE job_listing_id = ?
+        WHERE job_listing_id = ? AND deleted_at IS NULL
         ORDER BY applied_date DESC
         "#,
         job_listing_id
@@ -136,49 +156,116 @@ pub async fn get_applications_by_job_listing_id(
 // Update
 // ======================================================
 pub async fn update_application(
-    pool: &SqlitePool,
+    executor: impl SqliteExecutor<'_>,
     id: i64,
-    job_listing_id: Option<i64>,
+    job_listing_id: Patch<i64>,
     stage: Option<&Stage>,
     applied_date: Option<&NaiveDate>,
-    application_notes: Option<&str>,
+    application_notes: Patch<&str>,
 ) -> Result<Application, Error> {
-    let job_listing_id_str = job_listing_id.map(|v| v.to_string());
-    let stage_str = stage.map(|s| s.as_str());
-    let applied_date_str = applied_date.map(|d| d.format("%Y-%m-%d").to_string());
-    let applied_date_ref = applied_date_str.as_deref();
-
-    let fields: Vec<(&str, Option<&str>)> = vec![
-        ("job_listing_id", job_listing_id_str.as_deref()),
-        ("stage", stage_str),
-        ("applied_date", applied_date_ref),
-        ("application_notes", application_notes),
+    let fields = vec![
+        ("job_listing_id", job_listing_id.map(SqlValue::from)),
+        (
+            "stage",
+            Patch::from_option(stage).map(|v| SqlValue::from(v.as_str())),
+        ),
+        (
+            "applied_date",
+            Patch::from_option(applied_date).map(SqlValue::from),
+        ),
+        ("application_notes", application_notes.map(SqlValue::from)),
     ];
 
     let (sql, binds) = build_update_sql("application", "id", id, fields);
 
-    let mut query = sqlx::query_as::<_, Application>(&sql);
-    for val in &binds {
-        query = query.bind(val);
-    }
-
-    query.fetch_one(pool).await
+    bind_sql_values(sqlx::query_as::<_, Application>(&sql), &binds)
+        .fetch_one(executor)
+        .await
 }
 
 // ======================================================
-// Delete
+// Stale applications
 // ======================================================
-pub async fn delete_application(pool: &SqlitePool, id: i64) -> Result<i64, Error> {
-    let row = sqlx::query!(
+/// An open application and the date of its latest activity.
+#[derive(FromRow, Debug)]
+pub struct StaleApplication {
+    #[sqlx(flatten)]
+    pub application: Application,
+    pub last_activity: NaiveDate,
+}
+
+/// Open applications without an interaction or stage change after `cutoff`.
+/// Applications still count as active on their applied date. Archived and
+/// trashed applications are left out. Oldest first.
+pub async fn list_stale_applications(
+    executor: impl SqliteExecutor<'_>,
+    cutoff: &NaiveDate,
+) -> Result<Vec<StaleApplication>, Error> {
+    sqlx::query_as::<_, StaleApplication>(
         r#"
-        DELETE FROM application
-        WHERE id = ?
-        RETURNING id AS "id!: i64"
+        SELECT *
+        FROM (
+            SELECT
+                a.*,
+                MAX(
+                    a.applied_date,
+                    COALESCE(
+                        (SELECT MAX(i.interaction_date) FROM interaction i
+                         WHERE i.application_id = a.id AND i.deleted_at IS NULL),
+                        a.applied_date
+                    ),
+                    COALESCE(
+                        (SELECT date(MAX(e.changed_at)) FROM application_stage_event e
+                         WHERE e.application_id = a.id),
+                        a.applied_date
+                    )
+                ) AS last_activity
+            FROM application a
+            WHERE a.stage NOT IN ('accepted', 'rejected', 'withdrawn', 'ghosted')
+              AND a.deleted_at IS NULL
+              AND a.archived_at IS NULL
+        )
+        WHERE last_activity <= ?
+        ORDER BY last_activity ASC, id ASC
         "#,
-        id
     )
-    .fetch_one(pool)
-    .await?;
+    .bind(cutoff.format("%Y-%m-%d").to_string())
+    .fetch_all(executor)
+    .await
+}
 
-    Ok(row.id)
+// ======================================================
+// Delete
+// ======================================================
+/// Moves the application to the trash.
+pub async fn delete_application(pool: &SqlitePool, id: i64) -> Result<i64, Error> {
+    trash::move_to_trash(pool, EntityType::Application, id).await
+}
+
+// ======================================================
+// List (filtered / sorted / paged)
+// ======================================================
+pub const APPLICATION_LIST: ListSpec = ListSpec {
+    table: "application",
+    columns: &[
+        col("id", "id", ColumnKind::Integer),
+        col("jobListingId", "job_listing_id", ColumnKind::Integer),
+        col("stage", "stage", ColumnKind::Text),
+        col("appliedDate", "applied_date", ColumnKind::Date),
+        col("applicationNotes", "application_notes", ColumnKind::Text),
+        col("createdAt", "created_at", ColumnKind::DateTime),
+        col("updatedAt", "updated_at", ColumnKind::DateTime),
+        col("archivedAt", "archived_at", ColumnKind::DateTime),
+        col("deletedAt", "deleted_at", ColumnKind::DateTime),
+    ],
+    default_order: "applied_date DESC",
+    tag_entity: Some(EntityType::Application),
+    soft_delete: true,
+};
+
+pub async fn list_applications(
+    pool: &SqlitePool,
+    options: &ListOptions,
+) -> Result<ListPage<Application>, ListError> {
+    fetch_page(pool, &APPLICATION_LIST, options).await
 }
@@ -0,0 +1,137 @@
+use crate::db::models::enums::ContactRole;
+use crate::db::queries::application::Application;
+use crate::db::queries::person::Person;
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::{Error, FromRow, SqlitePool};
+
+#[derive(FromRow, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApplicationContact {
+    pub application_id: i64,
+    pub person_id: i64,
+    pub role: ContactRole,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+/// A person together with their role in one application.
+#[derive(FromRow, Debug)]
+pub struct ContactPerson {
+    #[sqlx(flatten)]
+    pub person: Person,
+    pub contact_role: ContactRole,
+}
+
+/// An application together with the person's role in it.
+#[derive(FromRow, Debug)]
+pub struct ContactApplication {
+    #[sqlx(flatten)]
+    pub application: Application,
+    pub contact_role: ContactRole,
+}
+
+// ======================================================
+// Attach (insert or change role)
+// ======================================================
+/// Adds the person to the application, or updates their role if they
+/// are already on it. Fails with RowNotFound if either does not exist.
+pub async fn attach_contact(
+    pool: &SqlitePool,
+    application_id: i64,
+    person_id: i64,
+    role: &ContactRole,
+) -> Result<ApplicationContact, Error> {
+    // Check up front so a missing record reads as "not found"
+    // rather than a foreign key violation
+    sqlx::query_scalar::<_, i64>("SELECT id FROM application WHERE id = ? AND deleted_at IS NULL")
+        .bind(application_id)
+        .fetch_one(pool)
+        .await?;
+    sqlx::query_scalar::<_, i64>("SELECT id FROM person WHERE id = ? AND deleted_at IS NULL")
+        .bind(person_id)
+        .fetch_one(pool)
+        .await?;
+
+    sqlx::query_as::<_, ApplicationContact>(
+        r#"
+        INSERT INTO application_contact (application_id, person_id, role)
+        VALUES (?, ?, ?)
+        ON CONFLICT (application_id, person_id)
+        DO UPDATE SET role = excluded.role, updated_at = CURRENT_TIMESTAMP
+        RETURNING *
+        "#,
+    )
+    .bind(application_id)
+    .bind(person_id)
+    .bind(role.as_str())
+    .fetch_one(pool)
+    .await
+}
+
+// ======================================================
+// Detach
+// ======================================================
+/// Returns whether the person was on the application.
+pub async fn detach_contact(
+    pool: &SqlitePool,
+    application_id: i64,
+    person_id: i64,
+) -> Result<bool, Error> {
+    let result =
+        sqlx::query("DELETE FROM application_contact WHERE application_id = ? AND person_id = ?")
+            .bind(application_id)
+            .bind(person_id)
+            .execute(pool)
+            .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+// ======================================================
+// Read
+// ======================================================
+pub async fn get_contacts_by_application_id(
+    pool: &SqlitePool,
+    application_id: i64,
+) -> Result<Vec<ContactPerson>, Error> {
+    sqlx::query_as::<_, ContactPerson>(
+        r#"
+        SELECT p.*, ac.role AS contact_role
+        FROM application_contact ac
+        JOIN person p ON p.id = ac.person_id
+        WHERE ac.application_id = ? AND p.deleted_at IS NULL
+        ORDER BY p.last_name COLLATE NOCASE, p.first_name COLLATE NOCASE, p.id
+        "#,
+    )
+    .bind(application_id)
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn get_applications_by_contact_id(
+    pool: &SqlitePool,
+    person_id: i64,
+) -> Result<Vec<ContactApplication>, Error> {
+    sqlx::query_as::<_, ContactApplication>(
+        r#"
+        SELECT a.*, ac.role AS contact_role
+        FROM application_contact ac
+        JOIN application a ON a.id = ac.application_id
+        WHERE ac.person_id = ? AND a.deleted_at IS NULL
+        ORDER BY a.applied_date DESC, a.id DESC
+        "#,
+    )
+    .bind(person_id)
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn list_application_contacts(
+    pool: &SqlitePool,
+) -> Result<Vec<ApplicationContact>, Error> {
+    sqlx::query_as::<_, ApplicationContact>(
+        "SELECT * FROM application_contact ORDER BY application_id, person_id",
+    )
+    .fetch_all(pool)
+    .await
+}
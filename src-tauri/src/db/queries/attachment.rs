@@ -0,0 +1,206 @@
+use crate::db::models::enums::EntityType;
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::{Error, FromRow, SqlitePool};
+
+#[derive(FromRow, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Attachment {
+    pub id: i64,
+    pub sha256: String,
+    pub filename: String,
+    pub mime_type: String,
+    pub size_bytes: i64,
+    pub storage_path: String,
+    pub created_at: NaiveDateTime,
+}
+
+/// An attachment as seen from one record.
+#[derive(FromRow, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LinkedAttachment {
+    #[sqlx(flatten)]
+    #[serde(flatten)]
+    pub attachment: Attachment,
+    pub linked_at: NaiveDateTime,
+}
+
+/// One attachment link; used by the JSON export.
+#[derive(FromRow, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AttachmentLink {
+    pub attachment_id: i64,
+    pub entity_type: EntityType,
+    pub entity_id: i64,
+    pub created_at: NaiveDateTime,
+}
+
+// ======================================================
+// Create
+// ======================================================
+pub async fn find_attachment_by_sha256(
+    pool: &SqlitePool,
+    sha256: &str,
+) -> Result<Option<Attachment>, Error> {
+    sqlx::query_as::<_, Attachment>("SELECT * FROM attachment WHERE sha256 = ?")
+        .bind(sha256)
+        .fetch_optional(pool)
+        .await
+}
+
+pub async fn create_attachment(
+    pool: &SqlitePool,
+    sha256: &str,
+    filename: &str,
+    mime_type: &str,
+    size_bytes: i64,
+    storage_path: &str,
+) -> Result<Attachment, Error> {
+    sqlx::query_as::<_, Attachment>(
+        r#"
+        INSERT INTO attachment (sha256, filename, mime_type, size_bytes, storage_path)
+        VALUES (?, ?, ?, ?, ?)
+        RETURNING *
+        "#,
+    )
+    .bind(sha256)
+    .bind(filename)
+    .bind(mime_type)
+    .bind(size_bytes)
+    .bind(storage_path)
+    .fetch_one(pool)
+    .await
+}
+
+// ======================================================
+// Read
+// ======================================================
+pub async fn get_attachment_by_id(pool: &SqlitePool, id: i64) -> Result<Attachment, Error> {
+    sqlx::query_as::<_, Attachment>("SELECT * FROM attachment WHERE id = ?")
+        .bind(id)
+        .fetch_one(pool)
+        .await
+}
+
+pub async fn list_attachments_for_entity(
+    pool: &SqlitePool,
+    entity_type: EntityType,
+    entity_id: i64,
+) -> Result<Vec<LinkedAttachment>, Error> {
+    sqlx::query_as::<_, LinkedAttachment>(
+        r#"
+        SELECT a.*, l.created_at AS linked_at
+        FROM attachment_link l
+        JOIN attachment a ON a.id = l.attachment_id
+        WHERE l.entity_type = ? AND l.entity_id = ?
+        ORDER BY l.created_at DESC, a.id DESC
+        "#,
+    )
+    .bind(entity_type.as_str())
+    .bind(entity_id)
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn list_all_attachments(pool: &SqlitePool) -> Result<Vec<Attachment>, Error> {
+    sqlx::query_as::<_, Attachment>("SELECT * FROM attachment ORDER BY id")
+        .fetch_all(pool)
+        .await
+}
+
+pub async fn list_attachment_links(pool: &SqlitePool) -> Result<Vec<AttachmentLink>, Error> {
+    sqlx::query_as::<_, AttachmentLink>(
+        "SELECT * FROM attachment_link ORDER BY attachment_id, entity_type, entity_id",
+    )
+    .fetch_all(pool)
+    .await
+}
+
+/// Storage paths of all known files, for the orphan sweep.
+pub async fn list_storage_paths(pool: &SqlitePool) -> Result<Vec<String>, Error> {
+    sqlx::query_scalar("SELECT storage_path FROM attachment")
+        .fetch_all(pool)
+        .await
+}
+
+// ======================================================
+// Link / Unlink
+// ======================================================
+/// Links an attachment to a record. Linking twice is a no-op.
+/// Fails with RowNotFound if the record does not exist.
+pub async fn link_attachment(
+    pool: &SqlitePool,
+    attachment_id: i64,
+    entity_type: EntityType,
+    entity_id: i64,
+) -> Result<(), Error> {
+    ensure_entity_exists(pool, entity_type, entity_id).await?;
+
+    sqlx::query(
+        "INSERT OR IGNORE INTO attachment_link (attachment_id, entity_type, entity_id) VALUES (?, ?, ?)",
+    )
+    .bind(attachment_id)
+    .bind(entity_type.as_str())
+    .bind(entity_id)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Returns whether a link was removed.
+pub async fn unlink_attachment(
+    pool: &SqlitePool,
+    attachment_id: i64,
+    entity_type: EntityType,
+    entity_id: i64,
+) -> Result<bool, Error> {
+    let result = sqlx::query(
+        "DELETE FROM attachment_link WHERE attachment_id = ? AND entity_type = ? AND entity_id = ?",
+    )
+    .bind(attachment_id)
+    .bind(entity_type.as_str())
+    .bind(entity_id)
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// attachment_link is polymorphic, so check the record by hand.
+pub async fn ensure_entity_exists(
+    pool: &SqlitePool,
+    entity_type: EntityType,
+    entity_id: i64,
+) -> Result<(), Error> {
+    sqlx::query_scalar::<_, i64>(&format!(
+        "SELECT id FROM {} WHERE id = ?",
+        entity_type.table_name()
+    ))
+    .bind(entity_id)
+    .fetch_one(pool)
+    .await?;
+    Ok(())
+}
+
+// ======================================================
+// Delete
+// ======================================================
+/// Deletes the attachment and all its links; returns its storage path.
+pub async fn delete_attachment(pool: &SqlitePool, id: i64) -> Result<String, Error> {
+    sqlx::query_scalar("DELETE FROM attachment WHERE id = ? RETURNING storage_path")
+        .bind(id)
+        .fetch_one(pool)
+        .await
+}
+
+/// Deletes attachments no record links to; returns their storage paths.
+pub async fn delete_unlinked_attachments(pool: &SqlitePool) -> Result<Vec<String>, Error> {
+    sqlx::query_scalar(
+        r#"
+        DELETE FROM attachment
+        WHERE id NOT IN (SELECT attachment_id FROM attachment_link)
+        RETURNING storage_path
+        "#,
+    )
+    .fetch_all(pool)
+    .await
+}
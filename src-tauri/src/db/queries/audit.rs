@@ -0,0 +1,162 @@
+use crate::db::models::enums::EntityType;
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::sqlite::SqliteRow;
+use sqlx::{Error, FromRow, Row, SqlitePool, Type};
+use std::future::Future;
+
+#[derive(Type, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum AuditAction {
+    Create,
+    Update,
+    /// Moved to the trash
+    Delete,
+    Restore,
+    Archive,
+    Unarchive,
+    /// Deleted for good
+    Purge,
+    Revert,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditEntry {
+    pub id: i64,
+    pub entity_type: EntityType,
+    pub entity_id: i64,
+    pub action: AuditAction,
+    /// `{field: {"before": .., "after": ..}}` for every changed field
+    pub changes: Value,
+    /// Command (or rule) the change came from, e.g. `company.Update`
+    pub origin: Option<String>,
+    /// Undoable command the change was part of
+    pub operation_id: Option<i64>,
+    pub created_at: NaiveDateTime,
+}
+
+/// `changes` is a JSON column.
+impl<'r> FromRow<'r, SqliteRow> for AuditEntry {
+    fn from_row(row: &'r SqliteRow) -> Result<Self, Error> {
+        let changes: String = row.try_get("changes")?;
+        Ok(AuditEntry {
+            id: row.try_get("id")?,
+            entity_type: row.try_get("entity_type")?,
+            entity_id: row.try_get("entity_id")?,
+            action: row.try_get("action")?,
+            changes: serde_json::from_str(&changes).map_err(|e| Error::ColumnDecode {
+                index: "changes".to_string(),
+                source: Box::new(e),
+            })?,
+            origin: row.try_get("origin")?,
+            operation_id: row.try_get("operation_id")?,
+            created_at: row.try_get("created_at")?,
+        })
+    }
+}
+
+// ======================================================
+// Operations
+// ======================================================
+tokio::task_local! {
+    /// Operation the entries recorded by the current task belong to.
+    static OPERATION: i64;
+}
+
+/// Starts a new operation and returns its id.
+pub async fn begin_operation(pool: &SqlitePool) -> Result<i64, Error> {
+    sqlx::query_scalar("INSERT INTO audit_operation DEFAULT VALUES RETURNING id")
+        .fetch_one(pool)
+        .await
+}
+
+/// Runs `future` with every entry it records stamped with `operation_id`.
+/// Entries recorded by other tasks meanwhile are not.
+pub async fn in_operation<F: Future>(operation_id: i64, future: F) -> F::Output {
+    OPERATION.scope(operation_id, future).await
+}
+
+fn current_operation() -> Option<i64> {
+    OPERATION.try_with(|id| *id).ok()
+}
+
+/// Drops an operation that recorded nothing.
+pub async fn discard_operation(pool: &SqlitePool, operation_id: i64) -> Result<(), Error> {
+    sqlx::query("DELETE FROM audit_operation WHERE id = ?")
+        .bind(operation_id)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+// ======================================================
+// Record
+// ======================================================
+/// Stamped with the current operation, if any (see [`in_operation`]).
+pub async fn record_entry(
+    pool: &SqlitePool,
+    entity_type: EntityType,
+    entity_id: i64,
+    action: AuditAction,
+    changes: &Value,
+    origin: Option<&str>,
+) -> Result<AuditEntry, Error> {
+    sqlx::query_as::<_, AuditEntry>(
+        r#"
+        INSERT INTO audit_log (entity_type, entity_id, action, changes, origin, operation_id)
+        VALUES (?, ?, ?, ?, ?, ?)
+        RETURNING *
+        "#,
+    )
+    .bind(entity_type.as_str())
+    .bind(entity_id)
+    .bind(action)
+    .bind(changes.to_string())
+    .bind(origin)
+    .bind(current_operation())
+    .fetch_one(pool)
+    .await
+}
+
+// ======================================================
+// Read
+// ======================================================
+pub async fn get_entry(pool: &SqlitePool, id: i64) -> Result<AuditEntry, Error> {
+    sqlx::query_as::<_, AuditEntry>("SELECT * FROM audit_log WHERE id = ?")
+        .bind(id)
+        .fetch_one(pool)
+        .await
+}
+
+/// Everything recorded for one record, newest first.
+pub async fn get_history(
+    pool: &SqlitePool,
+    entity_type: EntityType,
+    entity_id: i64,
+) -> Result<Vec<AuditEntry>, Error> {
+    sqlx::query_as::<_, AuditEntry>(
+        r#"
+        SELECT * FROM audit_log
+        WHERE entity_type = ? AND entity_id = ?
+        ORDER BY id DESC
+        "#,
+    )
+    .bind(entity_type.as_str())
+    .bind(entity_id)
+    .fetch_all(pool)
+    .await
+}
+
+/// Entries recorded as part of one operation, oldest first.
+pub async fn get_operation_entries(
+    pool: &SqlitePool,
+    operation_id: i64,
+) -> Result<Vec<AuditEntry>, Error> {
+    sqlx::query_as::<_, AuditEntry>("SELECT * FROM audit_log WHERE operation_id = ? ORDER BY id")
+        .bind(operation_id)
+        .fetch_all(pool)
+        .await
+}
@@ -0,0 +1,101 @@
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use sqlx::{Error, FromRow, SqlitePool};
+
+/// A pending reminder with the names it should be shown with.
+#[derive(FromRow, Debug)]
+pub struct CalendarReminder {
+    pub id: i64,
+    pub reminder_date: NaiveDate,
+    pub reminder_time: Option<NaiveTime>,
+    pub timezone: Option<String>,
+    pub recurrence: Option<String>,
+    pub title: String,
+    pub message: Option<String>,
+    pub company: Option<String>,
+    pub person: Option<String>,
+    pub job_title: Option<String>,
+    pub updated_at: NaiveDateTime,
+}
+
+/// An interview interaction with the names it should be shown with.
+#[derive(FromRow, Debug)]
+pub struct CalendarInterview {
+    pub id: i64,
+    pub interaction_date: NaiveDate,
+    pub subject: Option<String>,
+    pub summary: Option<String>,
+    pub medium: Option<String>,
+    pub company: Option<String>,
+    pub person: Option<String>,
+    pub job_title: Option<String>,
+    pub updated_at: NaiveDateTime,
+}
+
+// ======================================================
+// Pending reminders
+// ======================================================
+/// Company and job fall back to the linked application's listing.
+pub async fn get_pending_reminders(pool: &SqlitePool) -> Result<Vec<CalendarReminder>, Error> {
+    sqlx::query_as::<_, CalendarReminder>(
+        r#"
+        SELECT
+            r.id AS id,
+            r.reminder_date AS reminder_date,
+            r.reminder_time AS reminder_time,
+            r.timezone AS timezone,
+            r.recurrence AS recurrence,
+            r.title AS title,
+            r.message AS message,
+            COALESCE(c.name, ac.name) AS company,
+            NULLIF(TRIM(COALESCE(p.first_name, '') || ' ' || COALESCE(p.last_name, '')), '') AS person,
+            COALESCE(jl.title, ajl.title) AS job_title,
+            r.updated_at AS updated_at
+        FROM reminder r
+        LEFT JOIN application a ON a.id = r.application_id
+        LEFT JOIN job_listing ajl ON ajl.id = a.job_listing_id
+        LEFT JOIN company ac ON ac.id = ajl.company_id
+        LEFT JOIN job_listing jl ON jl.id = r.job_listing_id
+        LEFT JOIN company c ON c.id = r.company_id
+        LEFT JOIN person p ON p.id = r.person_id
+        WHERE r.is_completed = 0
+          AND r.deleted_at IS NULL
+          AND r.archived_at IS NULL
+        ORDER BY r.reminder_date ASC, r.reminder_time ASC, r.id ASC
+        "#,
+    )
+    .fetch_all(pool)
+    .await
+}
+
+// ======================================================
+// Interviews
+// ======================================================
+/// Company and job fall back to the linked application's listing.
+pub async fn get_interviews(pool: &SqlitePool) -> Result<Vec<CalendarInterview>, Error> {
+    sqlx::query_as::<_, CalendarInterview>(
+        r#"
+        SELECT
+            i.id AS id,
+            i.interaction_date AS interaction_date,
+            i.subject AS subject,
+            i.summary AS summary,
+            i.medium AS medium,
+            COALESCE(c.name, ac.name) AS company,
+            NULLIF(TRIM(COALESCE(p.first_name, '') || ' ' || COALESCE(p.last_name, '')), '') AS person,
+            ajl.title AS job_title,
+            i.updated_at AS updated_at
+        FROM interaction i
+        LEFT JOIN application a ON a.id = i.application_id
+        LEFT JOIN job_listing ajl ON ajl.id = a.job_listing_id
+        LEFT JOIN company ac ON ac.id = ajl.company_id
+        LEFT JOIN company c ON c.id = i.company_id
+        LEFT JOIN person p ON p.id = i.person_id
+        WHERE i.interaction_type = 'interview'
+          AND i.deleted_at IS NULL
+          AND i.archived_at IS NULL
+        ORDER BY i.interaction_date ASC, i.id ASC
+        "#,
+    )
+    .fetch_all(pool)
+    .await
+}
@@ -1,10 +1,15 @@
-use crate::db::models::enums::WorkType;
-use crate::utils::sql_utils::build_update_sql;
+use crate::db::models::enums::{EntityType, WorkType};
+use crate::db::models::patch::Patch;
+use crate::db::queries::list_query::{
+    col, fetch_page, ColumnKind, ListError, ListOptions, ListPage, ListSpec,
+};
+use crate::db::queries::trash;
+use crate::utils::sql_utils::{bind_sql_values, build_update_sql, SqlValue};
 use chrono::NaiveDateTime;
-use serde::Serialize;
-use sqlx::{query, query_as, Error, FromRow, SqlitePool};
+use serde::{Deserialize, Serialize};
+use sqlx::{query_as, Error, FromRow, SqlitePool};
 
-#[derive(FromRow, Debug, Serialize)]
+#[derive(FromRow, Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Company {
     pub id: i64,
@@ -19,6 +24,8 @@ pub struct Company {
     pub phone_number: Option<String>,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
+    pub archived_at: Option<NaiveDateTime>,
+    pub deleted_at: Option<NaiveDateTime>,
 }
 
 // ======================================================
@@ -65,7 +72,9 @@ pub async fn create_company(
             website,
             phone_number,
             created_at AS "created_at!: NaiveDateTime",
-            updated_at AS "updated_at!: NaiveDateTime"
+            updated_at AS "updated_at!: NaiveDateTime",
+            archived_at AS "archived_at: NaiveDateTime",
+            deleted_at AS "deleted_at: NaiveDateTime"
         "#,
         name,
         street_address,
@@ -102,9 +111,11 @@ pub async fn get_company_by_id(pool: &SqlitePool, id: i64) -> Result<Company, Er
             website,
             phone_number,
             created_at AS "created_at!: NaiveDateTime",
-            updated_at AS "updated_at!: NaiveDateTime"
+            updated_at AS "updated_at!: NaiveDateTime",
+            archived_at AS "archived_at: NaiveDateTime",
+            deleted_at AS "deleted_at: NaiveDateTime"
         FROM company
-        WHERE id = ?
+        WHERE id = ? AND deleted_at IS NULL
         "#,
         id
     )
@@ -131,8 +142,11 @@ pub async fn get_all_companies(pool: &SqlitePool) -> Result<Vec<Company>, Error>
             website,
             phone_number,
             created_at AS "created_at!: NaiveDateTime",
-            updated_at AS "updated_at!: NaiveDateTime"
+            updated_at AS "updated_at!: NaiveDateTime",
+            archived_at AS "archived_at: NaiveDateTime",
+            deleted_at AS "deleted_at: NaiveDateTime"
         FROM company
+        WHERE deleted_at IS NULL AND archived_at IS NULL
         ORDER BY name COLLATE NOCASE
         "#
     )
@@ -147,53 +161,74 @@ pub async fn update_company(
     pool: &SqlitePool,
     id: i64,
     name: Option<&str>,
-    street_address: Option<&str>,
-    zip_code: Option<&str>,
-    city: Option<&str>,
-    country: Option<&str>,
-    default_work_type: Option<&WorkType>,
-    industry: Option<&str>,
-    website: Option<&str>,
-    phone_number: Option<&str>,
+    street_address: Patch<&str>,
+    zip_code: Patch<&str>,
+    city: Patch<&str>,
+    country: Patch<&str>,
+    default_work_type: Patch<&WorkType>,
+    industry: Patch<&str>,
+    website: Patch<&str>,
+    phone_number: Patch<&str>,
 ) -> Result<Company, Error> {
-    let worktype_str = default_work_type.map(|t| t.as_str());
-
     let fields = vec![
-        ("name", name),
-        ("street_address", street_address),
-        ("zip_code", zip_code),
-        ("city", city),
-        ("country", country),
-        ("default_work_type", worktype_str),
-        ("industry", industry),
-        ("website", website),
-        ("phone_number", phone_number),
+        ("name", Patch::from_option(name).map(SqlValue::from)),
+        ("street_address", street_address.map(SqlValue::from)),
+        ("zip_code", zip_code.map(SqlValue::from)),
+        ("city", city.map(SqlValue::from)),
+        ("country", country.map(SqlValue::from)),
+        (
+            "default_work_type",
+            default_work_type.map(|v| SqlValue::from(v.as_str())),
+        ),
+        ("industry", industry.map(SqlValue::from)),
+        ("website", website.map(SqlValue::from)),
+        ("phone_number", phone_number.map(SqlValue::from)),
     ];
 
     let (sql, binds) = build_update_sql("company", "id", id, fields);
 
-    let mut query = sqlx::query_as::<_, Company>(&sql);
-    for val in &binds {
-        query = query.bind(val);
-    }
-
-    query.fetch_one(pool).await
+    bind_sql_values(sqlx::query_as::<_, Company>(&sql), &binds)
+        .fetch_one(pool)
+        .await
 }
 
 // ======================================================
 // Delete
 // ======================================================
+/// Moves the company to the trash, together with its job listings.
 pub async fn delete_company(pool: &SqlitePool, id: i64) -> Result<i64, Error> {
-    let row = query!(
-        r#"
-        DELETE FROM company
-        WHERE id = ?
-        RETURNING id AS "id!: i64"
-        "#,
-        id
-    )
-    .fetch_one(pool)
-    .await?;
+    trash::move_to_trash(pool, EntityType::Company, id).await
+}
 
-    Ok(row.id)
+// ======================================================
+// List (filtered / sorted / paged)
+// ======================================================
+pub const COMPANY_LIST: ListSpec = ListSpec {
+    table: "company",
+    columns: &[
+        col("id", "id", ColumnKind::Integer),
+        col("name", "name", ColumnKind::Text),
+        col("streetAddress", "street_address", ColumnKind::Text),
+        col("zipCode", "zip_code", ColumnKind::Text),
+        col("city", "city", ColumnKind::Text),
+        col("country", "country", ColumnKind::Text),
+        col("defaultWorkType", "default_work_type", ColumnKind::Text),
+        col("industry", "industry", ColumnKind::Text),
+        col("website", "website", ColumnKind::Text),
+        col("phoneNumber", "phone_number", ColumnKind::Text),
+        col("createdAt", "created_at", ColumnKind::DateTime),
+        col("updatedAt", "updated_at", ColumnKind::DateTime),
+        col("archivedAt", "archived_at", ColumnKind::DateTime),
+        col("deletedAt", "deleted_at", ColumnKind::DateTime),
+    ],
+    default_order: "name COLLATE NOCASE ASC",
+    tag_entity: Some(EntityType::Company),
+    soft_delete: true,
+};
+
+pub async fn list_companies(
+    pool: &SqlitePool,
+    options: &ListOptions,
+) -> Result<ListPage<Company>, ListError> {
+    fetch_page(pool, &COMPANY_LIST, options).await
 }
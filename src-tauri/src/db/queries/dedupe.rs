@@ -0,0 +1,335 @@
+//! Reads for duplicate detection and the merge of one record into another.
+
+use crate::db::models::enums::EntityType;
+use crate::db::queries::company::Company;
+use crate::db::queries::person::Person;
+use chrono::{SubsecRound, Utc};
+use serde_json::{json, Map, Value};
+use sqlx::{Error, SqlitePool};
+
+/// Columns pointing at a company or a person, as (entity, column).
+fn references(entity_type: EntityType) -> &'static [(EntityType, &'static str)] {
+    match entity_type {
+        EntityType::Company => &[
+            (EntityType::JobListing, "company_id"),
+            (EntityType::Person, "company_id"),
+            (EntityType::Interaction, "company_id"),
+            (EntityType::Note, "company_id"),
+            (EntityType::Reminder, "company_id"),
+        ],
+        EntityType::Person => &[
+            (EntityType::Interaction, "person_id"),
+            (EntityType::Note, "person_id"),
+            (EntityType::Reminder, "person_id"),
+        ],
+        _ => &[],
+    }
+}
+
+/// Columns of the survivor filled in from the duplicate where empty.
+fn fill_columns(entity_type: EntityType) -> &'static [&'static str] {
+    match entity_type {
+        EntityType::Company => &[
+            "street_address",
+            "zip_code",
+            "city",
+            "country",
+            "default_work_type",
+            "industry",
+            "website",
+            "phone_number",
+        ],
+        EntityType::Person => &[
+            "email",
+            "phone_number",
+            "role",
+            "linkedin_url",
+            "company_id",
+        ],
+        _ => &[],
+    }
+}
+
+/// A table linking rows of `parent` to records by (entity_type, entity_id).
+struct LinkTable {
+    table: &'static str,
+    /// Column holding the id of the linked `parent` row
+    key: &'static str,
+    parent: &'static str,
+    /// Name of the linked ids in the audit log
+    field: &'static str,
+}
+
+const LINK_TABLES: [LinkTable; 2] = [
+    LinkTable {
+        table: "entity_tag",
+        key: "tag_id",
+        parent: "tag",
+        field: "tagIds",
+    },
+    LinkTable {
+        table: "attachment_link",
+        key: "attachment_id",
+        parent: "attachment",
+        field: "attachmentIds",
+    },
+];
+
+/// Audit fields holding the links of a company or person, see [`get_links`].
+pub const LINK_FIELDS: [&str; 3] = ["tagIds", "attachmentIds", "applicationContacts"];
+
+/// A record that now points at the survivor; `column` pointed at the
+/// duplicate before.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MovedReference {
+    pub entity_type: EntityType,
+    pub id: i64,
+    pub column: &'static str,
+}
+
+// ======================================================
+// Candidates
+// ======================================================
+/// Live companies, archived ones included.
+pub async fn list_company_candidates(pool: &SqlitePool) -> Result<Vec<Company>, Error> {
+    sqlx::query_as::<_, Company>("SELECT * FROM company WHERE deleted_at IS NULL ORDER BY id")
+        .fetch_all(pool)
+        .await
+}
+
+/// Live persons, archived ones included.
+pub async fn list_person_candidates(pool: &SqlitePool) -> Result<Vec<Person>, Error> {
+    sqlx::query_as::<_, Person>("SELECT * FROM person WHERE deleted_at IS NULL ORDER BY id")
+        .fetch_all(pool)
+        .await
+}
+
+// ======================================================
+// Merge
+// ======================================================
+/// Merges `duplicate` into `survivor` in one transaction: every row
+/// pointing at the duplicate is re-pointed, its tags, attachments and
+/// (for people) application contacts are copied over, the survivor's empty
+/// fields are filled in from it, and the duplicate goes to the trash. Both must
+/// be live records of `entity_type` (a company or a person).
+pub async fn merge_records(
+    pool: &SqlitePool,
+    entity_type: EntityType,
+    survivor: i64,
+    duplicate: i64,
+) -> Result<Vec<MovedReference>, Error> {
+    let table = entity_type.table_name();
+    let mut tx = pool.begin().await?;
+
+    let live_sql = format!(
+        "SELECT COUNT(*) FROM {} WHERE id IN (?, ?) AND deleted_at IS NULL",
+        table
+    );
+    let live: i64 = sqlx::query_scalar(&live_sql)
+        .bind(survivor)
+        .bind(duplicate)
+        .fetch_one(&mut *tx)
+        .await?;
+    if live != 2 {
+        return Err(Error::RowNotFound);
+    }
+
+    // 1. Re-point references
+    let mut moved = Vec::new();
+    for &(referrer, column) in references(entity_type) {
+        let sql = format!(
+            "UPDATE {} SET {col} = ?, updated_at = CURRENT_TIMESTAMP WHERE {col} = ? RETURNING id",
+            referrer.table_name(),
+            col = column
+        );
+        let ids: Vec<i64> = sqlx::query_scalar(&sql)
+            .bind(survivor)
+            .bind(duplicate)
+            .fetch_all(&mut *tx)
+            .await?;
+        moved.extend(ids.into_iter().map(|id| MovedReference {
+            entity_type: referrer,
+            id,
+            column,
+        }));
+    }
+
+    // 2. Copy links the survivor lacks; the duplicate keeps its own, so
+    //    restoring it from the trash brings them back
+    for link in &LINK_TABLES {
+        let sql = format!(
+            r#"
+            INSERT OR IGNORE INTO {t} ({k}, entity_type, entity_id, created_at)
+            SELECT {k}, entity_type, ?, created_at FROM {t}
+            WHERE entity_type = ? AND entity_id = ?
+            "#,
+            t = link.table,
+            k = link.key
+        );
+        sqlx::query(&sql)
+            .bind(survivor)
+            .bind(entity_type.as_str())
+            .bind(duplicate)
+            .execute(&mut *tx)
+            .await?;
+    }
+    if entity_type == EntityType::Person {
+        sqlx::query(
+            r#"
+            INSERT OR IGNORE INTO application_contact
+                (application_id, person_id, role, created_at, updated_at)
+            SELECT application_id, ?, role, created_at, updated_at
+            FROM application_contact WHERE person_id = ?
+            "#,
+        )
+        .bind(survivor)
+        .bind(duplicate)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    // 3. Fill in what the survivor lacks
+    let fills: Vec<String> = fill_columns(entity_type)
+        .iter()
+        .map(|c| format!("{c} = COALESCE({c}, (SELECT {c} FROM {table} WHERE id = ?))"))
+        .collect();
+    let sql = format!("UPDATE {} SET {} WHERE id = ?", table, fills.join(", "));
+    let mut query = sqlx::query(&sql);
+    for _ in fill_columns(entity_type) {
+        query = query.bind(duplicate);
+    }
+    query.bind(survivor).execute(&mut *tx).await?;
+
+    // 4. Trash the duplicate; nothing points at it anymore
+    let sql = format!("UPDATE {} SET deleted_at = ? WHERE id = ?", table);
+    sqlx::query(&sql)
+        .bind(Utc::now().naive_utc().trunc_subsecs(0))
+        .bind(duplicate)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+    Ok(moved)
+}
+
+// ======================================================
+// Links
+// ======================================================
+/// Links of a company or person as audit fields: the linked `tagIds` and
+/// `attachmentIds`, and for people their `applicationContacts`
+/// (`{applicationId, role}`), each sorted.
+pub async fn get_links(
+    pool: &SqlitePool,
+    entity_type: EntityType,
+    id: i64,
+) -> Result<Map<String, Value>, Error> {
+    let mut links = Map::new();
+    for link in &LINK_TABLES {
+        let sql = format!(
+            "SELECT {k} FROM {t} WHERE entity_type = ? AND entity_id = ? ORDER BY {k}",
+            t = link.table,
+            k = link.key
+        );
+        let ids: Vec<i64> = sqlx::query_scalar(&sql)
+            .bind(entity_type.as_str())
+            .bind(id)
+            .fetch_all(pool)
+            .await?;
+        links.insert(link.field.to_string(), json!(ids));
+    }
+
+    if entity_type == EntityType::Person {
+        let contacts: Vec<(i64, String)> = sqlx::query_as(
+            "SELECT application_id, role FROM application_contact WHERE person_id = ? ORDER BY application_id",
+        )
+        .bind(id)
+        .fetch_all(pool)
+        .await?;
+        let contacts: Vec<Value> = contacts
+            .into_iter()
+            .map(|(application_id, role)| json!({ "applicationId": application_id, "role": role }))
+            .collect();
+        links.insert("applicationContacts".to_string(), json!(contacts));
+    }
+    Ok(links)
+}
+
+/// Makes the links named in `fields` (as returned by [`get_links`]) exactly
+/// those listed. Other fields are ignored, as are links to rows that no
+/// longer exist.
+pub async fn set_links(
+    pool: &SqlitePool,
+    entity_type: EntityType,
+    id: i64,
+    fields: &Map<String, Value>,
+) -> Result<(), Error> {
+    let mut tx = pool.begin().await?;
+
+    for link in &LINK_TABLES {
+        let Some(ids) = fields.get(link.field) else {
+            continue;
+        };
+        let ids = ids.to_string();
+        let sql = format!(
+            r#"
+            DELETE FROM {t}
+            WHERE entity_type = ? AND entity_id = ?
+              AND {k} NOT IN (SELECT value FROM json_each(?))
+            "#,
+            t = link.table,
+            k = link.key
+        );
+        sqlx::query(&sql)
+            .bind(entity_type.as_str())
+            .bind(id)
+            .bind(&ids)
+            .execute(&mut *tx)
+            .await?;
+        let sql = format!(
+            r#"
+            INSERT OR IGNORE INTO {t} ({k}, entity_type, entity_id)
+            SELECT p.id, ?, ? FROM json_each(?) j JOIN {p} p ON p.id = j.value
+            "#,
+            t = link.table,
+            k = link.key,
+            p = link.parent
+        );
+        sqlx::query(&sql)
+            .bind(entity_type.as_str())
+            .bind(id)
+            .bind(&ids)
+            .execute(&mut *tx)
+            .await?;
+    }
+
+    if let (EntityType::Person, Some(contacts)) = (entity_type, fields.get("applicationContacts")) {
+        let contacts = contacts.to_string();
+        sqlx::query(
+            r#"
+            DELETE FROM application_contact
+            WHERE person_id = ?
+              AND application_id NOT IN (
+                  SELECT json_extract(value, '$.applicationId') FROM json_each(?)
+              )
+            "#,
+        )
+        .bind(id)
+        .bind(&contacts)
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query(
+            r#"
+            INSERT OR IGNORE INTO application_contact (application_id, person_id, role)
+            SELECT a.id, ?, json_extract(j.value, '$.role')
+            FROM json_each(?) j
+            JOIN application a ON a.id = json_extract(j.value, '$.applicationId')
+            "#,
+        )
+        .bind(id)
+        .bind(&contacts)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    tx.commit().await
+}
@@ -0,0 +1,310 @@
+use crate::db::models::enums::{Currency, EntityType};
+use crate::db::queries::application_contact::ApplicationContact;
+use crate::db::queries::attachment::Attachment;
+use crate::db::queries::exchange_rate::ExchangeRate;
+use crate::db::queries::import::insert_row;
+use crate::db::queries::list_query::{to_sql_value, ListError, ListSpec, SqlValue};
+use crate::db::queries::rule::{to_json, AutomationRule, RuleRun};
+use crate::db::queries::tag::Tag;
+use chrono::NaiveDateTime;
+use serde_json::Value;
+use sqlx::{Error, SqliteConnection};
+
+// ======================================================
+// Row count
+// ======================================================
+pub async fn count_rows(conn: &mut SqliteConnection, spec: &ListSpec) -> Result<i64, Error> {
+    sqlx::query_scalar(&format!("SELECT COUNT(*) FROM {}", spec.table))
+        .fetch_one(conn)
+        .await
+}
+
+// ======================================================
+// Insert a serialized record
+// ======================================================
+/// Inserts a record given in its JSON payload shape (camelCase fields).
+/// Only columns known to the spec are written, computed ones never; `id` is
+/// kept only if asked.
+pub async fn insert_record(
+    conn: &mut SqliteConnection,
+    spec: &ListSpec,
+    record: &Value,
+    keep_id: bool,
+) -> Result<i64, ListError> {
+    let mut values: Vec<(&str, SqlValue)> = Vec::with_capacity(spec.columns.len());
+
+    for def in spec.columns {
+        if def.computed || (def.column == "id" && !keep_id) {
+            continue;
+        }
+        // Missing values fall back to the column default
+        match record.get(def.field) {
+            None | Some(Value::Null) => continue,
+            Some(v) => values.push((def.column, to_sql_value(def, v)?)),
+        }
+    }
+
+    Ok(insert_row(conn, spec.table, &values).await?)
+}
+
+// ======================================================
+// Drop trigger-generated stage history
+// ======================================================
+pub async fn delete_stage_events(
+    conn: &mut SqliteConnection,
+    application_id: i64,
+) -> Result<(), Error> {
+    sqlx::query("DELETE FROM application_stage_event WHERE application_id = ?")
+        .bind(application_id)
+        .execute(conn)
+        .await?;
+    Ok(())
+}
+
+// ======================================================
+// Tags
+// ======================================================
+/// Returns the ID of the tag with the same name (case-insensitive),
+/// inserting the tag if there is none.
+pub async fn find_or_insert_tag(
+    conn: &mut SqliteConnection,
+    tag: &Tag,
+    keep_id: bool,
+) -> Result<i64, Error> {
+    let existing: Option<i64> = sqlx::query_scalar("SELECT id FROM tag WHERE name = ?")
+        .bind(&tag.name)
+        .fetch_optional(&mut *conn)
+        .await?;
+    if let Some(id) = existing {
+        return Ok(id);
+    }
+
+    sqlx::query_scalar(
+        "INSERT INTO tag (id, name, color, created_at, updated_at) VALUES (?, ?, ?, ?, ?) RETURNING id",
+    )
+    .bind(keep_id.then_some(tag.id))
+    .bind(&tag.name)
+    .bind(&tag.color)
+    .bind(tag.created_at)
+    .bind(tag.updated_at)
+    .fetch_one(conn)
+    .await
+}
+
+pub async fn insert_entity_tag(
+    conn: &mut SqliteConnection,
+    tag_id: i64,
+    entity_type: EntityType,
+    entity_id: i64,
+    created_at: NaiveDateTime,
+) -> Result<(), Error> {
+    sqlx::query(
+        "INSERT OR IGNORE INTO entity_tag (tag_id, entity_type, entity_id, created_at) VALUES (?, ?, ?, ?)",
+    )
+    .bind(tag_id)
+    .bind(entity_type.as_str())
+    .bind(entity_id)
+    .bind(created_at)
+    .execute(conn)
+    .await?;
+    Ok(())
+}
+
+// ======================================================
+// Application contacts
+// ======================================================
+/// Inserts a contact under (possibly remapped) application and person IDs.
+pub async fn insert_application_contact(
+    conn: &mut SqliteConnection,
+    application_id: i64,
+    person_id: i64,
+    contact: &ApplicationContact,
+) -> Result<(), Error> {
+    sqlx::query(
+        r#"
+        INSERT OR IGNORE INTO application_contact
+            (application_id, person_id, role, created_at, updated_at)
+        VALUES (?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(application_id)
+    .bind(person_id)
+    .bind(contact.role.as_str())
+    .bind(contact.created_at)
+    .bind(contact.updated_at)
+    .execute(conn)
+    .await?;
+    Ok(())
+}
+
+// ======================================================
+// Attachments
+// ======================================================
+/// Returns the ID of the attachment with the same content (sha256),
+/// inserting the attachment if there is none.
+pub async fn find_or_insert_attachment(
+    conn: &mut SqliteConnection,
+    attachment: &Attachment,
+    keep_id: bool,
+) -> Result<i64, Error> {
+    let existing: Option<i64> = sqlx::query_scalar("SELECT id FROM attachment WHERE sha256 = ?")
+        .bind(&attachment.sha256)
+        .fetch_optional(&mut *conn)
+        .await?;
+    if let Some(id) = existing {
+        return Ok(id);
+    }
+
+    sqlx::query_scalar(
+        r#"
+        INSERT INTO attachment
+            (id, sha256, filename, mime_type, size_bytes, storage_path, created_at)
+        VALUES (?, ?, ?, ?, ?, ?, ?)
+        RETURNING id
+        "#,
+    )
+    .bind(keep_id.then_some(attachment.id))
+    .bind(&attachment.sha256)
+    .bind(&attachment.filename)
+    .bind(&attachment.mime_type)
+    .bind(attachment.size_bytes)
+    .bind(&attachment.storage_path)
+    .bind(attachment.created_at)
+    .fetch_one(conn)
+    .await
+}
+
+pub async fn insert_attachment_link(
+    conn: &mut SqliteConnection,
+    attachment_id: i64,
+    entity_type: EntityType,
+    entity_id: i64,
+    created_at: NaiveDateTime,
+) -> Result<(), Error> {
+    sqlx::query(
+        "INSERT OR IGNORE INTO attachment_link (attachment_id, entity_type, entity_id, created_at) VALUES (?, ?, ?, ?)",
+    )
+    .bind(attachment_id)
+    .bind(entity_type.as_str())
+    .bind(entity_id)
+    .bind(created_at)
+    .execute(conn)
+    .await?;
+    Ok(())
+}
+
+// ======================================================
+// Automation rules
+// ======================================================
+/// Returns the ID of an identical rule (same name, trigger and action),
+/// inserting the rule if there is none, so merging never makes a rule
+/// fire twice.
+pub async fn find_or_insert_rule(
+    conn: &mut SqliteConnection,
+    rule: &AutomationRule,
+    keep_id: bool,
+) -> Result<i64, Error> {
+    let (trigger, action) = (to_json(&rule.trigger), to_json(&rule.action));
+    let existing: Option<i64> = sqlx::query_scalar(
+        "SELECT id FROM automation_rule WHERE name = ? AND rule_trigger = ? AND rule_action = ?",
+    )
+    .bind(&rule.name)
+    .bind(&trigger)
+    .bind(&action)
+    .fetch_optional(&mut *conn)
+    .await?;
+    if let Some(id) = existing {
+        return Ok(id);
+    }
+
+    sqlx::query_scalar(
+        r#"
+        INSERT INTO automation_rule
+            (id, name, enabled, rule_trigger, rule_action, created_at, updated_at)
+        VALUES (?, ?, ?, ?, ?, ?, ?)
+        RETURNING id
+        "#,
+    )
+    .bind(keep_id.then_some(rule.id))
+    .bind(&rule.name)
+    .bind(rule.enabled)
+    .bind(&trigger)
+    .bind(&action)
+    .bind(rule.created_at)
+    .bind(rule.updated_at)
+    .fetch_one(conn)
+    .await
+}
+
+/// Inserts a run under (possibly remapped) rule, application and record
+/// IDs; a run already recorded for the same rule, application and anchor
+/// is kept.
+pub async fn insert_rule_run(
+    conn: &mut SqliteConnection,
+    run: &RuleRun,
+    rule_id: i64,
+    application_id: Option<i64>,
+    entity: Option<(EntityType, i64)>,
+    keep_id: bool,
+) -> Result<(), Error> {
+    sqlx::query(
+        r#"
+        INSERT OR IGNORE INTO automation_rule_run
+            (id, rule_id, application_id, anchor, entity_type, entity_id, fired_at)
+        VALUES (?, ?, ?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(keep_id.then_some(run.id))
+    .bind(rule_id)
+    .bind(application_id)
+    .bind(&run.anchor)
+    .bind(entity.map(|(t, _)| t.as_str()))
+    .bind(entity.map(|(_, id)| id))
+    .bind(run.fired_at)
+    .execute(conn)
+    .await?;
+    Ok(())
+}
+
+// ======================================================
+// Exchange rates
+// ======================================================
+/// Adds the rates of currencies that have none yet; existing rates and
+/// the base currency are never changed. The dump's rates are relative to
+/// its own base, so with a base already set they are converted to it,
+/// which needs a rate for that base in the dump (nothing is added
+/// otherwise). Returns how many rates were added.
+pub async fn insert_exchange_rates(
+    conn: &mut SqliteConnection,
+    rates: &[ExchangeRate],
+) -> Result<u64, Error> {
+    let base: Option<Currency> =
+        sqlx::query_scalar("SELECT currency FROM exchange_rate WHERE is_base = 1")
+            .fetch_optional(&mut *conn)
+            .await?;
+    let scale = match base {
+        None => 1.0,
+        Some(base) => match rates.iter().find(|r| r.currency == base) {
+            Some(r) => r.rate,
+            None => return Ok(0),
+        },
+    };
+
+    let mut added = 0;
+    for r in rates {
+        let result = sqlx::query(
+            r#"
+            INSERT OR IGNORE INTO exchange_rate (currency, rate, is_base, updated_at)
+            VALUES (?, ?, ?, ?)
+            "#,
+        )
+        .bind(r.currency)
+        .bind(r.rate / scale)
+        .bind(r.is_base && base.is_none())
+        .bind(r.updated_at)
+        .execute(&mut *conn)
+        .await?;
+        added += result.rows_affected();
+    }
+    Ok(added)
+}
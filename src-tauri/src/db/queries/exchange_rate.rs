@@ -0,0 +1,114 @@
+use crate::db::models::enums::Currency;
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::{Error, FromRow, SqliteExecutor, SqlitePool};
+
+/// One unit of `currency` is worth `rate` units of the base currency.
+#[derive(FromRow, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExchangeRate {
+    pub currency: Currency,
+    pub rate: f64,
+    pub is_base: bool,
+    pub updated_at: NaiveDateTime,
+}
+
+// ======================================================
+// Get
+// ======================================================
+/// Base currency first, then by code.
+pub async fn list_rates<'e>(executor: impl SqliteExecutor<'e>) -> Result<Vec<ExchangeRate>, Error> {
+    sqlx::query_as::<_, ExchangeRate>(
+        "SELECT * FROM exchange_rate ORDER BY is_base DESC, currency ASC",
+    )
+    .fetch_all(executor)
+    .await
+}
+
+pub async fn get_base_currency(pool: &SqlitePool) -> Result<Option<Currency>, Error> {
+    sqlx::query_scalar("SELECT currency FROM exchange_rate WHERE is_base = 1")
+        .fetch_optional(pool)
+        .await
+}
+
+// ======================================================
+// Set / Delete
+// ======================================================
+/// Adds or replaces the rate of `currency`.
+pub async fn set_rate(
+    pool: &SqlitePool,
+    currency: &Currency,
+    rate: f64,
+) -> Result<ExchangeRate, Error> {
+    sqlx::query_as::<_, ExchangeRate>(
+        r#"
+        INSERT INTO exchange_rate (currency, rate)
+        VALUES (?, ?)
+        ON CONFLICT (currency) DO UPDATE
+            SET rate = excluded.rate, updated_at = CURRENT_TIMESTAMP
+        RETURNING *
+        "#,
+    )
+    .bind(currency)
+    .bind(rate)
+    .fetch_one(pool)
+    .await
+}
+
+pub async fn delete_rate(pool: &SqlitePool, currency: &Currency) -> Result<u64, Error> {
+    let result = sqlx::query("DELETE FROM exchange_rate WHERE currency = ?")
+        .bind(currency)
+        .execute(pool)
+        .await?;
+    Ok(result.rows_affected())
+}
+
+// ======================================================
+// Base currency
+// ======================================================
+/// Makes `currency` the base. Other rates are rescaled so they keep their
+/// meaning, which needs a rate for `currency` unless there is no base yet
+/// (`RowNotFound` otherwise).
+pub async fn set_base_currency(
+    pool: &SqlitePool,
+    currency: &Currency,
+) -> Result<Vec<ExchangeRate>, Error> {
+    let mut tx = pool.begin().await?;
+
+    let has_base: bool =
+        sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM exchange_rate WHERE is_base = 1)")
+            .fetch_one(&mut *tx)
+            .await?;
+
+    if has_base {
+        let factor: f64 = sqlx::query_scalar("SELECT rate FROM exchange_rate WHERE currency = ?")
+            .bind(currency)
+            .fetch_one(&mut *tx)
+            .await?;
+
+        // The old base stops being one before its rate changes
+        sqlx::query("UPDATE exchange_rate SET is_base = 0 WHERE is_base = 1")
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("UPDATE exchange_rate SET rate = rate / ?, updated_at = CURRENT_TIMESTAMP")
+            .bind(factor)
+            .execute(&mut *tx)
+            .await?;
+    }
+
+    sqlx::query(
+        r#"
+        INSERT INTO exchange_rate (currency, rate, is_base)
+        VALUES (?, 1, 1)
+        ON CONFLICT (currency) DO UPDATE
+            SET rate = 1, is_base = 1, updated_at = CURRENT_TIMESTAMP
+        "#,
+    )
+    .bind(currency)
+    .execute(&mut *tx)
+    .await?;
+
+    let rates = list_rates(&mut *tx).await?;
+    tx.commit().await?;
+    Ok(rates)
+}
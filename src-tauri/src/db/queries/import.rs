@@ -0,0 +1,88 @@
+use crate::db::queries::list_query::SqlValue;
+use sqlx::{Error, SqliteConnection};
+
+// ======================================================
+// Company lookup (case-insensitive)
+// ======================================================
+pub async fn find_company_id_by_name(
+    conn: &mut SqliteConnection,
+    name: &str,
+) -> Result<Option<i64>, Error> {
+    sqlx::query_scalar(
+        "SELECT id FROM company WHERE name = ? COLLATE NOCASE AND deleted_at IS NULL ORDER BY id LIMIT 1",
+    )
+    .bind(name.trim())
+    .fetch_optional(conn)
+    .await
+}
+
+pub async fn create_company_by_name(conn: &mut SqliteConnection, name: &str) -> Result<i64, Error> {
+    sqlx::query_scalar("INSERT INTO company (name) VALUES (?) RETURNING id")
+        .bind(name.trim())
+        .fetch_one(conn)
+        .await
+}
+
+// ======================================================
+// Duplicate checks
+// ======================================================
+pub async fn find_job_listing_id(
+    conn: &mut SqliteConnection,
+    company_id: i64,
+    title: &str,
+) -> Result<Option<i64>, Error> {
+    sqlx::query_scalar(
+        r#"
+        SELECT id FROM job_listing
+        WHERE company_id = ? AND title = ? COLLATE NOCASE AND deleted_at IS NULL
+        LIMIT 1
+        "#,
+    )
+    .bind(company_id)
+    .bind(title.trim())
+    .fetch_optional(conn)
+    .await
+}
+
+pub async fn find_person_id_by_email(
+    conn: &mut SqliteConnection,
+    email: &str,
+) -> Result<Option<i64>, Error> {
+    sqlx::query_scalar(
+        "SELECT id FROM person WHERE email = ? COLLATE NOCASE AND deleted_at IS NULL LIMIT 1",
+    )
+    .bind(email.trim())
+    .fetch_optional(conn)
+    .await
+}
+
+// ======================================================
+// Generic insert
+// ======================================================
+/// Inserts one row from already validated `(column, value)` pairs.
+/// Column names must come from the importer's fixed field list.
+pub async fn insert_row(
+    conn: &mut SqliteConnection,
+    table: &str,
+    values: &[(&str, SqlValue)],
+) -> Result<i64, Error> {
+    let columns: Vec<&str> = values.iter().map(|(c, _)| *c).collect();
+    let placeholders = vec!["?"; values.len()].join(", ");
+    let sql = format!(
+        "INSERT INTO {} ({}) VALUES ({}) RETURNING id",
+        table,
+        columns.join(", "),
+        placeholders
+    );
+
+    let mut query = sqlx::query_scalar::<_, i64>(&sql);
+    for (_, value) in values {
+        query = match value {
+            SqlValue::Integer(i) => query.bind(*i),
+            SqlValue::Real(r) => query.bind(*r),
+            SqlValue::Text(t) => query.bind(t.as_str()),
+        };
+    }
+
+    query.fetch_one(conn).await
+}
@@ -1,10 +1,15 @@
-use crate::db::models::enums::InteractionType;
-use crate::utils::sql_utils::build_update_sql;
+use crate::db::models::enums::{EntityType, InteractionType};
+use crate::db::models::patch::Patch;
+use crate::db::queries::list_query::{
+    col, fetch_page, ColumnKind, ListError, ListOptions, ListPage, ListSpec,
+};
+use crate::db::queries::trash;
+use crate::utils::sql_utils::{bind_sql_values, build_update_sql, SqlValue};
 use chrono::{NaiveDate, NaiveDateTime};
-use serde::Serialize;
-use sqlx::{query, query_as, Error, FromRow, SqlitePool};
+use serde::{Deserialize, Serialize};
+use sqlx::{query_as, Error, FromRow, SqliteExecutor, SqlitePool};
 
-#[derive(FromRow, Debug, Serialize)]
+#[derive(FromRow, Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Interaction {
     pub id: i64,
@@ -18,13 +23,15 @@ pub struct Interaction {
     pub company_id: Option<i64>,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
+    pub archived_at: Option<NaiveDateTime>,
+    pub deleted_at: Option<NaiveDateTime>,
 }
 
 // ======================================================
 // Create
 // ======================================================
 pub async fn create_interaction(
-    pool: &SqlitePool,
+    executor: impl SqliteExecutor<'_>,
     interaction_type: &InteractionType,
     interaction_date: &NaiveDate,
     subject: Option<&str>,
@@ -37,7 +44,7 @@ pub async fn create_interaction(
     let interaction_type_str = interaction_type.as_str();
     let interaction_date_str = interaction_date.format("%Y-%m-%d").to_string();
 
-    let interaction = query_as!(
+    query_as!(
         Interaction,
         r#"
         INSERT INTO interaction (
@@ -53,7 +60,7 @@ pub async fn create_interaction(
         VALUES (?, ?, ?, ?, ?, ?, ?, ?)
         RETURNING
             id AS "id!: i64",
-            interaction_type AS "interaction_type: InteractionType",
+            interaction_type AS "interaction_type!: InteractionType",
             interaction_date AS "interaction_date!: NaiveDate",
             subject,
             summary,
@@ -62,7 +69,9 @@ pub async fn create_interaction(
             person_id,
             company_id,
             created_at AS "created_at!: NaiveDateTime",
-            updated_at AS "updated_at!: NaiveDateTime"
+            updated_at AS "updated_at!: NaiveDateTime",
+            archived_at AS "archived_at: NaiveDateTime",
+            deleted_at AS "deleted_at: NaiveDateTime"
         "#,
         interaction_type_str,
         interaction_date_str,
@@ -73,10 +82,8 @@ pub async fn create_interaction(
         person_id,
         company_id
     )
-    .fetch_one(pool)
-    .await?;
-
-    Ok(interaction)
+    .fetch_one(executor)
+    .await
 }
 
 // ======================================================
@@ -88,7 +95,7 @@ pub async fn get_interaction_by_id(pool: &SqlitePool, id: i64) -> Result<Interac
         r#"
         SELECT
             id AS "id!: i64",
-            interaction_type AS "interaction_type: InteractionType",
+            interaction_type AS "interaction_type!: InteractionType",
             interaction_date AS "interaction_date!: NaiveDate",
             subject,
             summary,
@@ -97,9 +104,11 @@ pub async fn get_interaction_by_id(pool: &SqlitePool, id: i64) -> Result<Interac
             person_id,
             company_id,
             created_at AS "created_at!: NaiveDateTime",
-            updated_at AS "updated_at!: NaiveDateTime"
+            updated_at AS "updated_at!: NaiveDateTime",
+            archived_at AS "archived_at: NaiveDateTime",
+            deleted_at AS "deleted_at: NaiveDateTime"
         FROM interaction
-        WHERE id = ?
+        WHERE id = ? AND deleted_at IS NULL
         "#,
         id
     )
@@ -116,7 +125,7 @@ pub async fn get_all_interactions(pool: &SqlitePool) -> Result<Vec<Interaction>,
         r#"
         SELECT
             id AS "id!: i64",
-            interaction_type AS "interaction_type: InteractionType",
+            interaction_type AS "interaction_type!: InteractionType",
             interaction_date AS "interaction_date!: NaiveDate",
             subject,
             summary,
@@ -125,8 +134,11 @@ pub async fn get_all_interactions(pool: &SqlitePool) -> Result<Vec<Interaction>,
             person_id,
             company_id,
             created_at AS "created_at!: NaiveDateTime",
-            updated_at AS "updated_at!: NaiveDateTime"
+            updated_at AS "updated_at!: NaiveDateTime",
+            archived_at AS "archived_at: NaiveDateTime",
+            deleted_at AS "deleted_at: NaiveDateTime"
         FROM interaction
+        WHERE deleted_at IS NULL AND archived_at IS NULL
         ORDER BY interaction_date DESC
         "#
     )
@@ -146,7 +158,7 @@ pub async fn get_interactions_by_application_id(
         r#"
         SELECT
             id AS "id!: i64",
-            interaction_type AS "interaction_type: InteractionType",
+            interaction_type AS "interaction_type!: InteractionType",
             interaction_date AS "interaction_date!: NaiveDate",
             subject,
             summary,
@@ -155,9 +167,11 @@ pub async fn get_interactions_by_application_id(
             person_id,
             company_id,
             created_at AS "created_at!: NaiveDateTime",
-            updated_at AS "updated_at!: NaiveDateTime"
+            updated_at AS "updated_at!: NaiveDateTime",
+            archived_at AS "archived_at: NaiveDateTime",
+            deleted_at AS "deleted_at: NaiveDateTime"
         FROM interaction
-        WHERE application_id = ?
+        WHERE application_id = ? AND deleted_at IS NULL
         ORDER BY interaction_date DESC
         "#,
         application_id
@@ -178,7 +192,7 @@ pub async fn get_interactions_by_person_id(
         r#"
         SELECT
             id AS "id!: i64",
-            interaction_type AS "interaction_type: InteractionType",
+            interaction_type AS "interaction_type!: InteractionType",
             interaction_date AS "interaction_date!: NaiveDate",
             subject,
             summary,
@@ -187,9 +201,11 @@ pub async fn get_interactions_by_person_id(
             person_id,
             company_id,
             created_at AS "created_at!: NaiveDateTime",
-            updated_at AS "updated_at!: NaiveDateTime"
+            updated_at AS "updated_at!: NaiveDateTime",
+            archived_at AS "archived_at: NaiveDateTime",
+            deleted_at AS "deleted_at: NaiveDateTime"
         FROM interaction
-        WHERE person_id = ?
+        WHERE person_id = ? AND deleted_at IS NULL
         ORDER BY interaction_date DESC
         "#,
         person_id
@@ -210,7 +226,7 @@ pub async fn get_interactions_by_company_id(
         r#"
         SELECT
             id AS "id!: i64",
-            interaction_type AS "interaction_type: InteractionType",
+            interaction_type AS "interaction_type!: InteractionType",
             interaction_date AS "interaction_date!: NaiveDate",
             subject,
             summary,
@@ -219,9 +235,11 @@ pub async fn get_interactions_by_company_id(
             person_id,
             company_id,
             created_at AS "created_at!: NaiveDateTime",
-            updated_at AS "updated_at!: NaiveDateTime"
+            updated_at AS "updated_at!: NaiveDateTime",
+            archived_at AS "archived_at: NaiveDateTime",
+            deleted_at AS "deleted_at: NaiveDateTime"
         FROM interaction
-        WHERE company_id = ?
+        WHERE company_id = ? AND deleted_at IS NULL
         ORDER BY interaction_date DESC
         "#,
         company_id
@@ -238,56 +256,73 @@ pub async fn update_interaction(
     id: i64,
     interaction_type: Option<&InteractionType>,
     interaction_date: Option<&NaiveDate>,
-    subject: Option<&str>,
-    summary: Option<&str>,
-    medium: Option<&str>,
-    application_id: Option<i64>,
-    person_id: Option<i64>,
-    company_id: Option<i64>,
+    subject: Patch<&str>,
+    summary: Patch<&str>,
+    medium: Patch<&str>,
+    application_id: Patch<i64>,
+    person_id: Patch<i64>,
+    company_id: Patch<i64>,
 ) -> Result<Interaction, Error> {
-    let interaction_type_str = interaction_type.map(|i| i.as_str());
-    let interaction_date_s = interaction_date.map(|d| d.format("%Y-%m-%d").to_string());
-    let interaction_date_ref = interaction_date_s.as_deref();
-
-    let application_id_s = application_id.map(|v| v.to_string());
-    let person_id_s = person_id.map(|v| v.to_string());
-    let company_id_s = company_id.map(|v| v.to_string());
-
-    let fields: Vec<(&str, Option<&str>)> = vec![
-        ("interaction_type", interaction_type_str),
-        ("interaction_date", interaction_date_ref),
-        ("subject", subject),
-        ("summary", summary),
-        ("medium", medium),
-        ("application_id", application_id_s.as_deref()),
-        ("person_id", person_id_s.as_deref()),
-        ("company_id", company_id_s.as_deref()),
+    let fields = vec![
+        (
+            "interaction_type",
+            Patch::from_option(interaction_type).map(|v| SqlValue::from(v.as_str())),
+        ),
+        (
+            "interaction_date",
+            Patch::from_option(interaction_date).map(SqlValue::from),
+        ),
+        ("subject", subject.map(SqlValue::from)),
+        ("summary", summary.map(SqlValue::from)),
+        ("medium", medium.map(SqlValue::from)),
+        ("application_id", application_id.map(SqlValue::from)),
+        ("person_id", person_id.map(SqlValue::from)),
+        ("company_id", company_id.map(SqlValue::from)),
     ];
 
     let (sql, binds) = build_update_sql("interaction", "id", id, fields);
 
-    let mut query = sqlx::query_as::<_, Interaction>(&sql);
-    for val in &binds {
-        query = query.bind(val);
-    }
-
-    query.fetch_one(pool).await
+    bind_sql_values(sqlx::query_as::<_, Interaction>(&sql), &binds)
+        .fetch_one(pool)
+        .await
 }
 
 // ======================================================
 // Delete
 // ======================================================
+/// Moves the interaction to the trash.
 pub async fn delete_interaction(pool: &SqlitePool, id: i64) -> Result<i64, Error> {
-    let row = query!(
-        r#"
-        DELETE FROM interaction
-        WHERE id = ?
-        RETURNING id AS "id!: i64"
-        "#,
-        id
-    )
-    .fetch_one(pool)
-    .await?;
+    trash::move_to_trash(pool, EntityType::Interaction, id).await
+}
+
+// ======================================================
+// List (filtered / sorted / paged)
+// ======================================================
+pub const INTERACTION_LIST: ListSpec = ListSpec {
+    table: "interaction",
+    columns: &[
+        col("id", "id", ColumnKind::Integer),
+        col("interactionType", "interaction_type", ColumnKind::Text),
+        col("interactionDate", "interaction_date", ColumnKind::Date),
+        col("subject", "subject", ColumnKind::Text),
+        col("summary", "summary", ColumnKind::Text),
+        col("medium", "medium", ColumnKind::Text),
+        col("applicationId", "application_id", ColumnKind::Integer),
+        col("personId", "person_id", ColumnKind::Integer),
+        col("companyId", "company_id", ColumnKind::Integer),
+        col("createdAt", "created_at", ColumnKind::DateTime),
+        col("updatedAt", "updated_at", ColumnKind::DateTime),
+        col("archivedAt", "archived_at", ColumnKind::DateTime),
+        col("deletedAt", "deleted_at", ColumnKind::DateTime),
+    ],
+    default_order: "interaction_date DESC",
+    tag_entity: Some(EntityType::Interaction),
+    soft_delete: true,
+};
 
-    Ok(row.id)
+pub async fn list_interactions(
+    pool: &SqlitePool,
+    options: &ListOptions,
+) -> Result<ListPage<Interaction>, ListError> {
+    fetch_page(pool, &INTERACTION_LIST, options).await
 }
@@ -1,10 +1,15 @@
-use crate::db::models::enums::{Currency, SeniorityLevel, WorkType};
-use crate::utils::sql_utils::build_update_sql;
+use crate::db::models::enums::{Currency, EntityType, PayPeriod, SeniorityLevel, WorkType};
+use crate::db::models::patch::Patch;
+use crate::db::queries::list_query::{
+    col, computed, fetch_page, ColumnKind, ListError, ListOptions, ListPage, ListSpec,
+};
+use crate::db::queries::trash;
+use crate::utils::sql_utils::{bind_sql_values, build_update_sql, SqlValue};
 use chrono::NaiveDateTime;
-use serde::Serialize;
-use sqlx::{query, query_as, Error, FromRow, SqlitePool};
+use serde::{Deserialize, Serialize};
+use sqlx::{query_as, Error, FromRow, SqlitePool};
 
-#[derive(FromRow, Debug, Serialize)]
+#[derive(FromRow, Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct JobListing {
     pub id: i64,
@@ -16,10 +21,13 @@ pub struct JobListing {
     pub salary_min: Option<i64>,
     pub salary_max: Option<i64>,
     pub currency: Option<Currency>,
+    pub pay_period: Option<PayPeriod>,
     pub description: Option<String>,
     pub url: Option<String>,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
+    pub archived_at: Option<NaiveDateTime>,
+    pub deleted_at: Option<NaiveDateTime>,
 }
 
 // ======================================================
@@ -35,14 +43,16 @@ pub async fn create_job_listing(
     salary_min: Option<i64>,
     salary_max: Option<i64>,
     currency: Option<&Currency>,
+    pay_period: Option<&PayPeriod>,
     description: Option<&str>,
     url: Option<&str>,
 ) -> Result<JobListing, Error> {
     let work_type_str = work_type.map(|t| t.as_str());
     let seniority_level_str = seniority_level.map(|l| l.as_str());
     let currency_str = currency.map(|c| c.as_str());
+    let pay_period_str = pay_period.map(|p| p.as_str());
 
-    let job_listing = query_as!(
+    query_as!(
         JobListing,
         r#"
         INSERT INTO job_listing (
@@ -54,10 +64,11 @@ pub async fn create_job_listing(
             salary_min,
             salary_max,
             currency,
+            pay_period,
             description,
             url
         )
-        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
         RETURNING
             id AS "id!: i64",
             company_id,
@@ -68,10 +79,13 @@ pub async fn create_job_listing(
             salary_min,
             salary_max,
             currency AS "currency: Currency",
+            pay_period AS "pay_period: PayPeriod",
             description,
             url,
             created_at AS "created_at!: NaiveDateTime",
-            updated_at AS "updated_at!: NaiveDateTime"
+            updated_at AS "updated_at!: NaiveDateTime",
+            archived_at AS "archived_at: NaiveDateTime",
+            deleted_at AS "deleted_at: NaiveDateTime"
         "#,
         company_id,
         title,
@@ -81,13 +95,12 @@ pub async fn create_job_listing(
         salary_min,
         salary_max,
         currency_str,
+        pay_period_str,
         description,
         url
     )
     .fetch_one(pool)
-    .await?;
-
-    Ok(job_listing)
+    .await
 }
 
 // ======================================================
@@ -107,12 +120,15 @@ pub async fn get_job_listing_by_id(pool: &SqlitePool, id: i64) -> Result<JobList
             salary_min,
             salary_max,
             currency AS "currency: Currency",
+            pay_period AS "pay_period: PayPeriod",
             description,
             url,
             created_at AS "created_at!: NaiveDateTime",
-            updated_at AS "updated_at!: NaiveDateTime"
+            updated_at AS "updated_at!: NaiveDateTime",
+            archived_at AS "archived_at: NaiveDateTime",
+            deleted_at AS "deleted_at: NaiveDateTime"
         FROM job_listing
-        WHERE id = ?
+        WHERE id = ? AND deleted_at IS NULL
         "#,
         id
     )
@@ -137,11 +153,15 @@ pub async fn get_all_job_listings(pool: &SqlitePool) -> Result<Vec<JobListing>,
             salary_min,
             salary_max,
             currency AS "currency: Currency",
+            pay_period AS "pay_period: PayPeriod",
             description,
             url,
             created_at AS "created_at!: NaiveDateTime",
-            updated_at AS "updated_at!: NaiveDateTime"
+            updated_at AS "updated_at!: NaiveDateTime",
+            archived_at AS "archived_at: NaiveDateTime",
+            deleted_at AS "deleted_at: NaiveDateTime"
         FROM job_listing
+        WHERE deleted_at IS NULL AND archived_at IS NULL
         ORDER BY created_at DESC
         "#
     )
@@ -152,6 +172,7 @@ pub async fn get_all_job_listings(pool: &SqlitePool) -> Result<Vec<JobListing>,
 // ======================================================
 // Get by Company ID
 // ======================================================
+/// Archived listings included.
 pub async fn get_job_listings_by_company_id(
     pool: &SqlitePool,
     company_id: i64,
@@ -169,12 +190,15 @@ pub async fn get_job_listings_by_company_id(
             salary_min,
             salary_max,
             currency AS "currency: Currency",
+            pay_period AS "pay_period: PayPeriod",
             description,
             url,
             created_at AS "created_at!: NaiveDateTime",
-            updated_at AS "updated_at!: NaiveDateTime"
+            updated_at AS "updated_at!: NaiveDateTime",
+            archived_at AS "archived_at: NaiveDateTime",
+            deleted_at AS "deleted_at: NaiveDateTime"
         FROM job_listing
-        WHERE company_id = ?
+        WHERE company_id = ? AND deleted_at IS NULL
         ORDER BY created_at DESC
         "#,
         company_id
@@ -191,60 +215,109 @@ pub async fn update_job_listing(
     id: i64,
     company_id: Option<i64>,
     title: Option<&str>,
-    work_type: Option<&WorkType>,
-    category: Option<&str>,
-    seniority_level: Option<&SeniorityLevel>,
-    salary_min: Option<i64>,
-    salary_max: Option<i64>,
-    currency: Option<&Currency>,
-    description: Option<&str>,
-    url: Option<&str>,
+    work_type: Patch<&WorkType>,
+    category: Patch<&str>,
+    seniority_level: Patch<&SeniorityLevel>,
+    salary_min: Patch<i64>,
+    salary_max: Patch<i64>,
+    currency: Patch<&Currency>,
+    pay_period: Patch<&PayPeriod>,
+    description: Patch<&str>,
+    url: Patch<&str>,
 ) -> Result<JobListing, Error> {
-    let work_type_str = work_type.map(|t| t.as_str());
-    let seniority_level_str = seniority_level.map(|l| l.as_str());
-    let currency_str = currency.map(|c| c.as_str());
-
-    let company_id_s = company_id.map(|v| v.to_string());
-    let salary_min_s = salary_min.map(|v| v.to_string());
-    let salary_max_s = salary_max.map(|v| v.to_string());
-
-    let fields: Vec<(&str, Option<&str>)> = vec![
-        ("company_id", company_id_s.as_deref()),
-        ("title", title),
-        ("work_type", work_type_str),
-        ("category", category),
-        ("seniority_level", seniority_level_str),
-        ("salary_min", salary_min_s.as_deref()),
-        ("salary_max", salary_max_s.as_deref()),
-        ("currency", currency_str),
-        ("description", description),
-        ("url", url),
+    let fields = vec![
+        (
+            "company_id",
+            Patch::from_option(company_id).map(SqlValue::from),
+        ),
+        ("title", Patch::from_option(title).map(SqlValue::from)),
+        ("work_type", work_type.map(|v| SqlValue::from(v.as_str()))),
+        ("category", category.map(SqlValue::from)),
+        (
+            "seniority_level",
+            seniority_level.map(|v| SqlValue::from(v.as_str())),
+        ),
+        ("salary_min", salary_min.map(SqlValue::from)),
+        ("salary_max", salary_max.map(SqlValue::from)),
+        ("currency", currency.map(|v| SqlValue::from(v.as_str()))),
+        ("pay_period", pay_period.map(|v| SqlValue::from(v.as_str()))),
+        ("description", description.map(SqlValue::from)),
+        ("url", url.map(SqlValue::from)),
     ];
 
     let (sql, binds) = build_update_sql("job_listing", "id", id, fields);
 
-    let mut query = sqlx::query_as::<_, JobListing>(&sql);
-    for val in &binds {
-        query = query.bind(val);
-    }
-
-    query.fetch_one(pool).await
+    bind_sql_values(sqlx::query_as::<_, JobListing>(&sql), &binds)
+        .fetch_one(pool)
+        .await
 }
 
 // ======================================================
 // Delete
 // ======================================================
+/// Moves the job listing to the trash.
 pub async fn delete_job_listing(pool: &SqlitePool, id: i64) -> Result<i64, Error> {
-    let row = query!(
-        r#"
-        DELETE FROM job_listing
-        WHERE id = ?
-        RETURNING id AS "id!: i64"
-        "#,
-        id
-    )
-    .fetch_one(pool)
-    .await?;
+    trash::move_to_trash(pool, EntityType::JobListing, id).await
+}
 
-    Ok(row.id)
+// ======================================================
+// List (filtered / sorted / paged)
+// ======================================================
+/// Yearly salary converted to the base currency, rounded to whole units;
+/// NULL when the listing's currency has no exchange rate. The factors are
+/// those of `PayPeriod::per_year`, a missing pay period counting as yearly;
+/// a const spec cannot call it, so `test_annual_salary_sql_matches_rust`
+/// keeps the two in step.
+macro_rules! annual_salary {
+    ($column:literal) => {
+        concat!(
+            "ROUND(",
+            $column,
+            " * CASE pay_period WHEN 'hourly' THEN 2080 WHEN 'daily' THEN 260",
+            " WHEN 'monthly' THEN 12 ELSE 1 END",
+            " * (SELECT r.rate FROM exchange_rate r WHERE r.currency = job_listing.currency))"
+        )
+    };
+}
+
+pub const JOB_LISTING_LIST: ListSpec = ListSpec {
+    table: "job_listing",
+    columns: &[
+        col("id", "id", ColumnKind::Integer),
+        col("companyId", "company_id", ColumnKind::Integer),
+        col("title", "title", ColumnKind::Text),
+        col("workType", "work_type", ColumnKind::Text),
+        col("category", "category", ColumnKind::Text),
+        col("seniorityLevel", "seniority_level", ColumnKind::Text),
+        col("salaryMin", "salary_min", ColumnKind::Integer),
+        col("salaryMax", "salary_max", ColumnKind::Integer),
+        col("currency", "currency", ColumnKind::Text),
+        col("payPeriod", "pay_period", ColumnKind::Text),
+        computed(
+            "annualSalaryMin",
+            annual_salary!("salary_min"),
+            ColumnKind::Real,
+        ),
+        computed(
+            "annualSalaryMax",
+            annual_salary!("salary_max"),
+            ColumnKind::Real,
+        ),
+        col("description", "description", ColumnKind::Text),
+        col("url", "url", ColumnKind::Text),
+        col("createdAt", "created_at", ColumnKind::DateTime),
+        col("updatedAt", "updated_at", ColumnKind::DateTime),
+        col("archivedAt", "archived_at", ColumnKind::DateTime),
+        col("deletedAt", "deleted_at", ColumnKind::DateTime),
+    ],
+    default_order: "created_at DESC",
+    tag_entity: Some(EntityType::JobListing),
+    soft_delete: true,
+};
+
+pub async fn list_job_listings(
+    pool: &SqlitePool,
+    options: &ListOptions,
+) -> Result<ListPage<JobListing>, ListError> {
+    fetch_page(pool, &JOB_LISTING_LIST, options).await
 }
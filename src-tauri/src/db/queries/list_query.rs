@@ -0,0 +1,560 @@
+use crate::db::models::enums::EntityType;
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::sqlite::SqliteRow;
+use sqlx::{Error, FromRow, SqlitePool};
+use std::fmt;
+
+pub use crate::utils::sql_utils::SqlValue;
+
+/// Upper bound for a single page, regardless of what the client asks for.
+pub const MAX_PAGE_SIZE: i64 = 1000;
+
+/// Upper bound for the number of values in an `in` / `notIn` filter.
+const MAX_IN_VALUES: usize = 500;
+
+// ======================================================
+// Client-facing options
+// ======================================================
+
+/// Filtering, sorting and paging options accepted by every `ListAll` command.
+///
+/// Field names are the camelCase names used in the JSON payloads
+/// (e.g. `appliedDate`), never raw column names.
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ListOptions {
+    pub filters: Vec<Filter>,
+    pub sort: Vec<SortSpec>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+    /// Tag IDs; only records carrying these tags are returned.
+    pub tags: Vec<i64>,
+    /// Whether a record needs all of `tags` or just one of them.
+    pub tag_match: TagMatch,
+    /// Archived records are hidden unless asked for.
+    pub archived: Inclusion,
+    /// Trashed records are hidden unless asked for.
+    pub trashed: Inclusion,
+}
+
+#[derive(Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum TagMatch {
+    #[default]
+    All,
+    Any,
+}
+
+/// How records in a given state (archived, trashed) are treated.
+#[derive(Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum Inclusion {
+    #[default]
+    Exclude,
+    Include,
+    Only,
+}
+
+impl Inclusion {
+    fn condition(self, column: &str) -> Option<String> {
+        match self {
+            Inclusion::Exclude => Some(format!("{} IS NULL", column)),
+            Inclusion::Include => None,
+            Inclusion::Only => Some(format!("{} IS NOT NULL", column)),
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Filter {
+    pub field: String,
+    pub op: FilterOp,
+    #[serde(default)]
+    pub value: Value,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum FilterOp {
+    Eq,
+    Ne,
+    Lt,
+    Lte,
+    Gt,
+    Gte,
+    Contains,
+    StartsWith,
+    In,
+    NotIn,
+    Between,
+    IsNull,
+    IsNotNull,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SortSpec {
+    pub field: String,
+    #[serde(default)]
+    pub direction: SortDirection,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SortDirection {
+    #[default]
+    Asc,
+    Desc,
+}
+
+/// One page of results plus the total number of rows matching the filters.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListPage<T> {
+    pub items: Vec<T>,
+    pub total: i64,
+    pub limit: Option<i64>,
+    pub offset: i64,
+}
+
+/// Paging metadata returned next to `data` in list responses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Pagination {
+    pub total: i64,
+    pub limit: Option<i64>,
+    pub offset: i64,
+    pub has_more: bool,
+}
+
+impl<T> ListPage<T> {
+    pub fn pagination(&self) -> Pagination {
+        Pagination {
+            total: self.total,
+            limit: self.limit,
+            offset: self.offset,
+            has_more: self.offset + (self.items.len() as i64) < self.total,
+        }
+    }
+}
+
+// ======================================================
+// Per-entity column whitelist
+// ======================================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnKind {
+    Integer,
+    Real,
+    Text,
+    Bool,
+    Date,
+    /// `HH:MM:SS` wall-clock time
+    Time,
+    DateTime,
+}
+
+/// Maps a payload field name to a real column and its type.
+#[derive(Debug, Clone, Copy)]
+pub struct ColumnDef {
+    pub field: &'static str,
+    /// Column name, or an SQL expression for computed fields
+    pub column: &'static str,
+    pub kind: ColumnKind,
+    /// Derived from other columns: can be filtered and sorted on, never
+    /// written.
+    pub computed: bool,
+}
+
+pub const fn col(field: &'static str, column: &'static str, kind: ColumnKind) -> ColumnDef {
+    ColumnDef {
+        field,
+        column,
+        kind,
+        computed: false,
+    }
+}
+
+/// A read-only field computed by `expression`, which must stand on its own
+/// inside a larger condition (a function call or parenthesized).
+pub const fn computed(
+    field: &'static str,
+    expression: &'static str,
+    kind: ColumnKind,
+) -> ColumnDef {
+    ColumnDef {
+        field,
+        column: expression,
+        kind,
+        computed: true,
+    }
+}
+
+/// Everything needed to list one table: only columns listed here can be
+/// filtered or sorted on, which keeps user input out of the SQL text.
+pub struct ListSpec {
+    pub table: &'static str,
+    pub columns: &'static [ColumnDef],
+    pub default_order: &'static str,
+    /// `entity_tag.entity_type` of this table, if its records can be tagged.
+    pub tag_entity: Option<EntityType>,
+    /// Whether the table has `archived_at` / `deleted_at` columns.
+    pub soft_delete: bool,
+}
+
+// ======================================================
+// Errors
+// ======================================================
+
+#[derive(Debug)]
+pub enum ListError {
+    /// The options refer to unknown fields or carry unusable values.
+    Invalid(String),
+    Database(Error),
+}
+
+impl fmt::Display for ListError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ListError::Invalid(msg) => write!(f, "invalid list options: {}", msg),
+            ListError::Database(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for ListError {}
+
+impl From<Error> for ListError {
+    fn from(e: Error) -> Self {
+        ListError::Database(e)
+    }
+}
+
+// ======================================================
+// SQL translation
+// ======================================================
+
+/// WHERE / ORDER BY / LIMIT fragments built from validated options.
+#[derive(Debug)]
+pub struct ListSql {
+    pub where_clause: String,
+    pub order_clause: String,
+    pub binds: Vec<SqlValue>,
+    pub limit: Option<i64>,
+    pub offset: i64,
+}
+
+impl ListSpec {
+    fn column(&self, field: &str) -> Result<&ColumnDef, ListError> {
+        self.columns
+            .iter()
+            .find(|c| c.field == field)
+            .ok_or_else(|| ListError::Invalid(format!("unknown field '{}'", field)))
+    }
+
+    /// Validates the options against this table and builds the SQL fragments.
+    pub fn build(&self, options: &ListOptions) -> Result<ListSql, ListError> {
+        let mut conditions = Vec::new();
+        let mut binds = Vec::new();
+
+        for filter in &options.filters {
+            let def = self.column(&filter.field)?;
+            conditions.push(filter_condition(def, filter, &mut binds)?);
+        }
+
+        if !options.tags.is_empty() {
+            conditions.push(self.tag_condition(options, &mut binds)?);
+        }
+
+        if self.soft_delete {
+            conditions.extend(options.archived.condition("archived_at"));
+            conditions.extend(options.trashed.condition("deleted_at"));
+        }
+
+        let mut order = Vec::new();
+        for sort in &options.sort {
+            let def = self.column(&sort.field)?;
+            let collate = if def.kind == ColumnKind::Text {
+                " COLLATE NOCASE"
+            } else {
+                ""
+            };
+            let dir = match sort.direction {
+                SortDirection::Asc => "ASC",
+                SortDirection::Desc => "DESC",
+            };
+            order.push(format!("{}{} {}", def.column, collate, dir));
+        }
+        if order.is_empty() {
+            order.push(self.default_order.to_string());
+        }
+        // Stable tiebreaker so pages never overlap
+        order.push("id ASC".to_string());
+
+        let limit = match options.limit {
+            Some(l) if l < 1 => return Err(ListError::Invalid("limit must be at least 1".into())),
+            Some(l) => Some(l.min(MAX_PAGE_SIZE)),
+            None => None,
+        };
+        let offset = options.offset.unwrap_or(0);
+        if offset < 0 {
+            return Err(ListError::Invalid("offset must not be negative".into()));
+        }
+
+        let where_clause = if conditions.is_empty() {
+            String::new()
+        } else {
+            format!("WHERE {}", conditions.join(" AND "))
+        };
+
+        Ok(ListSql {
+            where_clause,
+            order_clause: format!("ORDER BY {}", order.join(", ")),
+            binds,
+            limit,
+            offset,
+        })
+    }
+
+    fn tag_condition(
+        &self,
+        options: &ListOptions,
+        binds: &mut Vec<SqlValue>,
+    ) -> Result<String, ListError> {
+        let entity_type = self.tag_entity.ok_or_else(|| {
+            ListError::Invalid(format!("'{}' records cannot be tagged", self.table))
+        })?;
+
+        let mut tag_ids = options.tags.clone();
+        tag_ids.sort_unstable();
+        tag_ids.dedup();
+        if tag_ids.len() > MAX_IN_VALUES {
+            return Err(ListError::Invalid(format!(
+                "tags takes at most {} values",
+                MAX_IN_VALUES
+            )));
+        }
+
+        binds.push(SqlValue::from(entity_type.as_str()));
+        binds.extend(tag_ids.iter().map(|id| SqlValue::Integer(*id)));
+
+        let placeholders = vec!["?"; tag_ids.len()].join(", ");
+        let having = match options.tag_match {
+            TagMatch::All => format!(
+                " GROUP BY entity_id HAVING COUNT(DISTINCT tag_id) = {}",
+                tag_ids.len()
+            ),
+            TagMatch::Any => String::new(),
+        };
+
+        Ok(format!(
+            "id IN (SELECT entity_id FROM entity_tag WHERE entity_type = ? AND tag_id IN ({}){})",
+            placeholders, having
+        ))
+    }
+}
+
+fn filter_condition(
+    def: &ColumnDef,
+    filter: &Filter,
+    binds: &mut Vec<SqlValue>,
+) -> Result<String, ListError> {
+    let column = def.column;
+
+    let comparison = |op: &str, binds: &mut Vec<SqlValue>| -> Result<String, ListError> {
+        binds.push(to_sql_value(def, &filter.value)?);
+        Ok(format!("{} {} ?", column, op))
+    };
+
+    match filter.op {
+        FilterOp::Eq => comparison("=", binds),
+        FilterOp::Ne => comparison("IS NOT", binds),
+        FilterOp::Lt => comparison("<", binds),
+        FilterOp::Lte => comparison("<=", binds),
+        FilterOp::Gt => comparison(">", binds),
+        FilterOp::Gte => comparison(">=", binds),
+        FilterOp::Contains | FilterOp::StartsWith => {
+            if def.kind != ColumnKind::Text {
+                return Err(ListError::Invalid(format!(
+                    "'{}' only supports text fields",
+                    if filter.op == FilterOp::Contains {
+                        "contains"
+                    } else {
+                        "startsWith"
+                    }
+                )));
+            }
+            let text = filter.value.as_str().ok_or_else(|| {
+                ListError::Invalid(format!("'{}' expects a text value", def.field))
+            })?;
+            let escaped = escape_like(text);
+            let pattern = if filter.op == FilterOp::Contains {
+                format!("%{}%", escaped)
+            } else {
+                format!("{}%", escaped)
+            };
+            binds.push(SqlValue::Text(pattern));
+            Ok(format!("{} LIKE ? ESCAPE '\\'", column))
+        }
+        FilterOp::In | FilterOp::NotIn => {
+            let values = filter.value.as_array().ok_or_else(|| {
+                ListError::Invalid(format!("'{}' expects a list of values", def.field))
+            })?;
+            if values.is_empty() || values.len() > MAX_IN_VALUES {
+                return Err(ListError::Invalid(format!(
+                    "'{}' expects between 1 and {} values",
+                    def.field, MAX_IN_VALUES
+                )));
+            }
+            for v in values {
+                binds.push(to_sql_value(def, v)?);
+            }
+            let placeholders = vec!["?"; values.len()].join(", ");
+            let op = if filter.op == FilterOp::In {
+                "IN"
+            } else {
+                "NOT IN"
+            };
+            Ok(format!("{} {} ({})", column, op, placeholders))
+        }
+        FilterOp::Between => {
+            let range = filter
+                .value
+                .as_array()
+                .filter(|v| v.len() == 2)
+                .ok_or_else(|| {
+                    ListError::Invalid(format!("'{}' expects a [from, to] pair", def.field))
+                })?;
+            binds.push(to_sql_value(def, &range[0])?);
+            binds.push(to_sql_value(def, &range[1])?);
+            Ok(format!("{} BETWEEN ? AND ?", column))
+        }
+        FilterOp::IsNull => Ok(format!("{} IS NULL", column)),
+        FilterOp::IsNotNull => Ok(format!("{} IS NOT NULL", column)),
+    }
+}
+
+fn escape_like(input: &str) -> String {
+    input
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+/// Converts a JSON value into a bind value matching the column type.
+/// Dates are normalized to the format SQLite stores them in.
+pub fn to_sql_value(def: &ColumnDef, value: &Value) -> Result<SqlValue, ListError> {
+    let invalid = |expected: &str| {
+        ListError::Invalid(format!(
+            "'{}' expects {}, got {}",
+            def.field, expected, value
+        ))
+    };
+
+    match def.kind {
+        ColumnKind::Integer => value
+            .as_i64()
+            .or_else(|| value.as_str().and_then(|s| s.parse().ok()))
+            .map(SqlValue::Integer)
+            .ok_or_else(|| invalid("an integer")),
+        ColumnKind::Real => value
+            .as_f64()
+            .map(SqlValue::Real)
+            .ok_or_else(|| invalid("a number")),
+        ColumnKind::Bool => value
+            .as_bool()
+            .map(|b| SqlValue::Integer(b as i64))
+            .ok_or_else(|| invalid("a boolean")),
+        ColumnKind::Text => match value {
+            Value::String(s) => Ok(SqlValue::Text(s.clone())),
+            Value::Number(n) => Ok(SqlValue::Text(n.to_string())),
+            _ => Err(invalid("a text value")),
+        },
+        ColumnKind::Date => value
+            .as_str()
+            .and_then(|s| NaiveDate::parse_from_str(s, "%Y-%m-%d").ok())
+            .map(|d| SqlValue::Text(d.format("%Y-%m-%d").to_string()))
+            .ok_or_else(|| invalid("a date (YYYY-MM-DD)")),
+        ColumnKind::Time => value
+            .as_str()
+            .and_then(|s| {
+                NaiveTime::parse_from_str(s, "%H:%M:%S%.f")
+                    .or_else(|_| NaiveTime::parse_from_str(s, "%H:%M"))
+                    .ok()
+            })
+            .map(|t| SqlValue::Text(t.format("%H:%M:%S").to_string()))
+            .ok_or_else(|| invalid("a time (HH:MM)")),
+        ColumnKind::DateTime => value
+            .as_str()
+            .and_then(parse_datetime)
+            .map(|dt| SqlValue::Text(dt.format("%Y-%m-%d %H:%M:%S").to_string()))
+            .ok_or_else(|| invalid("a date or datetime")),
+    }
+}
+
+fn parse_datetime(s: &str) -> Option<NaiveDateTime> {
+    NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S%.f")
+        .or_else(|_| NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S%.f"))
+        .or_else(|_| NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S%.fZ"))
+        .ok()
+        .or_else(|| {
+            NaiveDate::parse_from_str(s, "%Y-%m-%d")
+                .ok()
+                .and_then(|d| d.and_hms_opt(0, 0, 0))
+        })
+}
+
+// ======================================================
+// Execution
+// ======================================================
+
+macro_rules! bind_values {
+    ($query:expr, $binds:expr) => {{
+        let mut q = $query;
+        for value in $binds {
+            q = match value {
+                SqlValue::Integer(i) => q.bind(*i),
+                SqlValue::Real(r) => q.bind(*r),
+                SqlValue::Text(t) => q.bind(t.as_str()),
+            };
+        }
+        q
+    }};
+}
+
+/// Runs a filtered, sorted and paged SELECT plus the matching COUNT.
+pub async fn fetch_page<T>(
+    pool: &SqlitePool,
+    spec: &ListSpec,
+    options: &ListOptions,
+) -> Result<ListPage<T>, ListError>
+where
+    T: for<'r> FromRow<'r, SqliteRow> + Send + Unpin,
+{
+    let sql = spec.build(options)?;
+
+    let count_sql = format!("SELECT COUNT(*) FROM {} {}", spec.table, sql.where_clause);
+    let total: i64 = bind_values!(sqlx::query_scalar(&count_sql), &sql.binds)
+        .fetch_one(pool)
+        .await?;
+
+    let select_sql = format!(
+        "SELECT * FROM {} {} {} LIMIT ? OFFSET ?",
+        spec.table, sql.where_clause, sql.order_clause
+    );
+    let items = bind_values!(sqlx::query_as::<_, T>(&select_sql), &sql.binds)
+        .bind(sql.limit.unwrap_or(-1))
+        .bind(sql.offset)
+        .fetch_all(pool)
+        .await?;
+
+    Ok(ListPage {
+        items,
+        total,
+        limit: sql.limit,
+        offset: sql.offset,
+    })
+}
@@ -1,7 +1,22 @@
+pub mod analytics;
 pub mod application;
+pub mod application_contact;
+pub mod attachment;
+pub mod audit;
+pub mod calendar;
 pub mod company;
+pub mod dedupe;
+pub mod dump;
+pub mod exchange_rate;
+pub mod import;
 pub mod interaction;
 pub mod job_listing;
+pub mod list_query;
 pub mod note;
 pub mod person;
 pub mod reminder;
+pub mod rule;
+pub mod search;
+pub mod stage_event;
+pub mod tag;
+pub mod trash;
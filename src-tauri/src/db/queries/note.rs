@@ -1,10 +1,15 @@
-use crate::db::models::enums::NoteType;
-use crate::utils::sql_utils::build_update_sql;
+use crate::db::models::enums::{EntityType, NoteType};
+use crate::db::models::patch::Patch;
+use crate::db::queries::list_query::{
+    col, fetch_page, ColumnKind, ListError, ListOptions, ListPage, ListSpec,
+};
+use crate::db::queries::trash;
+use crate::utils::sql_utils::{bind_sql_values, build_update_sql, SqlValue};
 use chrono::NaiveDateTime;
-use serde::Serialize;
-use sqlx::{query, query_as, Error, FromRow, SqlitePool};
+use serde::{Deserialize, Serialize};
+use sqlx::{query_as, Error, FromRow, SqliteExecutor, SqlitePool};
 
-#[derive(FromRow, Debug, Serialize)]
+#[derive(FromRow, Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Note {
     pub id: i64,
@@ -18,13 +23,15 @@ pub struct Note {
     pub content: Option<String>,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
+    pub archived_at: Option<NaiveDateTime>,
+    pub deleted_at: Option<NaiveDateTime>,
 }
 
 // ======================================================
 // Create
 // ======================================================
 pub async fn create_note(
-    pool: &SqlitePool,
+    executor: impl SqliteExecutor<'_>,
     interaction_id: Option<i64>,
     job_listing_id: Option<i64>,
     application_id: Option<i64>,
@@ -34,7 +41,7 @@ pub async fn create_note(
     title: Option<&str>,
     content: Option<&str>,
 ) -> Result<Note, sqlx::Error> {
-    sqlx::query_as!(
+    query_as!(
         Note,
         r#"
         INSERT INTO note (
@@ -58,7 +65,9 @@ pub async fn create_note(
             title,
             content,
             created_at AS "created_at!: NaiveDateTime",
-            updated_at AS "updated_at!: NaiveDateTime"
+            updated_at AS "updated_at!: NaiveDateTime",
+            archived_at AS "archived_at: NaiveDateTime",
+            deleted_at AS "deleted_at: NaiveDateTime"
         "#,
         interaction_id,
         job_listing_id,
@@ -69,7 +78,7 @@ pub async fn create_note(
         title,
         content
     )
-    .fetch_one(pool)
+    .fetch_one(executor)
     .await
 }
 
@@ -91,9 +100,11 @@ pub async fn get_note_by_id(pool: &SqlitePool, id: i64) -> Result<Note, Error> {
             title,
             content,
             created_at AS "created_at!: NaiveDateTime",
-            updated_at AS "updated_at!: NaiveDateTime"
+            updated_at AS "updated_at!: NaiveDateTime",
+            archived_at AS "archived_at: NaiveDateTime",
+            deleted_at AS "deleted_at: NaiveDateTime"
         FROM note
-        WHERE id = ?
+        WHERE id = ? AND deleted_at IS NULL
         "#,
         id
     )
@@ -119,8 +130,11 @@ pub async fn get_all_notes(pool: &SqlitePool) -> Result<Vec<Note>, Error> {
             title,
             content,
             created_at AS "created_at!: NaiveDateTime",
-            updated_at AS "updated_at!: NaiveDateTime"
+            updated_at AS "updated_at!: NaiveDateTime",
+            archived_at AS "archived_at: NaiveDateTime",
+            deleted_at AS "deleted_at: NaiveDateTime"
         FROM note
+        WHERE deleted_at IS NULL AND archived_at IS NULL
         ORDER BY created_at DESC
         "#
     )
@@ -149,9 +163,11 @@ pub async fn get_notes_by_application_id(
             title,
             content,
             created_at AS "created_at!: NaiveDateTime",
-            updated_at AS "updated_at!: NaiveDateTime"
+            updated_at AS "updated_at!: NaiveDateTime",
+            archived_at AS "archived_at: NaiveDateTime",
+            deleted_at AS "deleted_at: NaiveDateTime"
         FROM note
-        WHERE application_id = ?
+        WHERE application_id = ? AND deleted_at IS NULL
         ORDER BY created_at DESC
         "#,
         application_id
@@ -178,9 +194,11 @@ pub async fn get_notes_by_interaction_id(
             title,
             content,
             created_at AS "created_at!: NaiveDateTime",
-            updated_at AS "updated_at!: NaiveDateTime"
+            updated_at AS "updated_at!: NaiveDateTime",
+            archived_at AS "archived_at: NaiveDateTime",
+            deleted_at AS "deleted_at: NaiveDateTime"
         FROM note
-        WHERE interaction_id = ?
+        WHERE interaction_id = ? AND deleted_at IS NULL
         ORDER BY created_at DESC
         "#,
         interaction_id
@@ -195,57 +213,69 @@ pub async fn get_notes_by_interaction_id(
 pub async fn update_note(
     pool: &SqlitePool,
     id: i64,
-    interaction_id: Option<i64>,
-    job_listing_id: Option<i64>,
-    application_id: Option<i64>,
-    person_id: Option<i64>,
-    company_id: Option<i64>,
-    note_type: Option<&NoteType>,
-    title: Option<&str>,
-    content: Option<&str>,
+    interaction_id: Patch<i64>,
+    job_listing_id: Patch<i64>,
+    application_id: Patch<i64>,
+    person_id: Patch<i64>,
+    company_id: Patch<i64>,
+    note_type: Patch<&NoteType>,
+    title: Patch<&str>,
+    content: Patch<&str>,
 ) -> Result<Note, Error> {
-    let note_type_str = note_type.map(|t| t.as_str());
-    let interaction_id_s = interaction_id.map(|v| v.to_string());
-    let job_listing_id_s = job_listing_id.map(|v| v.to_string());
-    let application_id_s = application_id.map(|v| v.to_string());
-    let person_id_s = person_id.map(|v| v.to_string());
-    let company_id_s = company_id.map(|v| v.to_string());
-
-    let fields: Vec<(&str, Option<&str>)> = vec![
-        ("interaction_id", interaction_id_s.as_deref()),
-        ("job_listing_id", job_listing_id_s.as_deref()),
-        ("application_id", application_id_s.as_deref()),
-        ("person_id", person_id_s.as_deref()),
-        ("company_id", company_id_s.as_deref()),
-        ("note_type", note_type_str),
-        ("title", title),
-        ("content", content),
+    let fields = vec![
+        ("interaction_id", interaction_id.map(SqlValue::from)),
+        ("job_listing_id", job_listing_id.map(SqlValue::from)),
+        ("application_id", application_id.map(SqlValue::from)),
+        ("person_id", person_id.map(SqlValue::from)),
+        ("company_id", company_id.map(SqlValue::from)),
+        ("note_type", note_type.map(|v| SqlValue::from(v.as_str()))),
+        ("title", title.map(SqlValue::from)),
+        ("content", content.map(SqlValue::from)),
     ];
 
     let (sql, binds) = build_update_sql("note", "id", id, fields);
 
-    let mut query = sqlx::query_as::<_, Note>(&sql);
-    for val in &binds {
-        query = query.bind(val);
-    }
-
-    query.fetch_one(pool).await
+    bind_sql_values(sqlx::query_as::<_, Note>(&sql), &binds)
+        .fetch_one(pool)
+        .await
 }
 
 // ======================================================
 // Delete
 // ======================================================
+/// Moves the note to the trash.
 pub async fn delete_note(pool: &SqlitePool, id: i64) -> Result<i64, Error> {
-    let row = query!(
-        r#"
-        DELETE FROM note
-        WHERE id = ?
-        RETURNING id AS "id!: i64"
-        "#,
-        id
-    )
-    .fetch_one(pool)
-    .await?;
+    trash::move_to_trash(pool, EntityType::Note, id).await
+}
 
-    Ok(row.id)
+// ======================================================
+// List (filtered / sorted / paged)
+// ======================================================
+pub const NOTE_LIST: ListSpec = ListSpec {
+    table: "note",
+    columns: &[
+        col("id", "id", ColumnKind::Integer),
+        col("interactionId", "interaction_id", ColumnKind::Integer),
+        col("jobListingId", "job_listing_id", ColumnKind::Integer),
+        col("applicationId", "application_id", ColumnKind::Integer),
+        col("personId", "person_id", ColumnKind::Integer),
+        col("companyId", "company_id", ColumnKind::Integer),
+        col("noteType", "note_type", ColumnKind::Text),
+        col("title", "title", ColumnKind::Text),
+        col("content", "content", ColumnKind::Text),
+        col("createdAt", "created_at", ColumnKind::DateTime),
+        col("updatedAt", "updated_at", ColumnKind::DateTime),
+        col("archivedAt", "archived_at", ColumnKind::DateTime),
+        col("deletedAt", "deleted_at", ColumnKind::DateTime),
+    ],
+    default_order: "created_at DESC",
+    tag_entity: Some(EntityType::Note),
+    soft_delete: true,
+};
+
+pub async fn list_notes(
+    pool: &SqlitePool,
+    options: &ListOptions,
+) -> Result<ListPage<Note>, ListError> {
+    fetch_page(pool, &NOTE_LIST, options).await
 }
@@ -1,10 +1,15 @@
-use crate::db::models::enums::Role;
-use crate::utils::sql_utils::build_update_sql;
+use crate::db::models::enums::{EntityType, Role};
+use crate::db::models::patch::Patch;
+use crate::db::queries::list_query::{
+    col, fetch_page, ColumnKind, ListError, ListOptions, ListPage, ListSpec,
+};
+use crate::db::queries::trash;
+use crate::utils::sql_utils::{bind_sql_values, build_update_sql, SqlValue};
 use chrono::NaiveDateTime;
-use serde::Serialize;
-use sqlx::{query, query_as, Error, FromRow, SqlitePool};
+use serde::{Deserialize, Serialize};
+use sqlx::{query_as, Error, FromRow, SqlitePool};
 
-#[derive(FromRow, Debug, Serialize)]
+#[derive(FromRow, Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Person {
     pub id: i64,
@@ -17,6 +22,8 @@ pub struct Person {
     pub company_id: Option<i64>,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
+    pub archived_at: Option<NaiveDateTime>,
+    pub deleted_at: Option<NaiveDateTime>,
 }
 
 // ======================================================
@@ -57,7 +64,9 @@ pub async fn create_person(
             linkedin_url,
             company_id,
             created_at AS "created_at!: NaiveDateTime",
-            updated_at AS "updated_at!: NaiveDateTime"
+            updated_at AS "updated_at!: NaiveDateTime",
+            archived_at AS "archived_at: NaiveDateTime",
+            deleted_at AS "deleted_at: NaiveDateTime"
         "#,
         first_name,
         last_name,
@@ -88,9 +97,11 @@ pub async fn get_person_by_id(pool: &SqlitePool, id: i64) -> Result<Person, Erro
             linkedin_url,
             company_id,
             created_at AS "created_at!: NaiveDateTime",
-            updated_at AS "updated_at!: NaiveDateTime"
+            updated_at AS "updated_at!: NaiveDateTime",
+            archived_at AS "archived_at: NaiveDateTime",
+            deleted_at AS "deleted_at: NaiveDateTime"
         FROM person
-        WHERE id = ?
+        WHERE id = ? AND deleted_at IS NULL
         "#,
         id
     )
@@ -115,8 +126,11 @@ pub async fn get_all_persons(pool: &SqlitePool) -> Result<Vec<Person>, Error> {
             linkedin_url,
             company_id,
             created_at AS "created_at!: NaiveDateTime",
-            updated_at AS "updated_at!: NaiveDateTime"
+            updated_at AS "updated_at!: NaiveDateTime",
+            archived_at AS "archived_at: NaiveDateTime",
+            deleted_at AS "deleted_at: NaiveDateTime"
         FROM person
+        WHERE deleted_at IS NULL AND archived_at IS NULL
         ORDER BY created_at DESC
         "#
     )
@@ -127,6 +141,7 @@ pub async fn get_all_persons(pool: &SqlitePool) -> Result<Vec<Person>, Error> {
 // ======================================================
 // Get all by Company ID
 // ======================================================
+/// Archived people included.
 pub async fn get_persons_by_company_id(
     pool: &SqlitePool,
     company_id: i64,
@@ -144,9 +159,11 @@ pub async fn get_persons_by_company_id(
             linkedin_url,
             company_id,
             created_at AS "created_at!: NaiveDateTime",
-            updated_at AS "updated_at!: NaiveDateTime"
+            updated_at AS "updated_at!: NaiveDateTime",
+            archived_at AS "archived_at: NaiveDateTime",
+            deleted_at AS "deleted_at: NaiveDateTime"
         FROM person
-        WHERE company_id = ?
+        WHERE company_id = ? AND deleted_at IS NULL
         ORDER BY created_at DESC
         "#,
         company_id
@@ -163,49 +180,70 @@ pub async fn update_person(
     id: i64,
     first_name: Option<&str>,
     last_name: Option<&str>,
-    email: Option<&str>,
-    phone_number: Option<&str>,
-    role: Option<&Role>,
-    linkedin_url: Option<&str>,
-    company_id: Option<i64>,
+    email: Patch<&str>,
+    phone_number: Patch<&str>,
+    role: Patch<&Role>,
+    linkedin_url: Patch<&str>,
+    company_id: Patch<i64>,
 ) -> Result<Person, Error> {
-    let role_str = role.map(|r| r.as_str());
-    let company_id_s = company_id.map(|v| v.to_string());
-
-    let fields: Vec<(&str, Option<&str>)> = vec![
-        ("first_name", first_name),
-        ("last_name", last_name),
-        ("email", email),
-        ("phone_number", phone_number),
-        ("role", role_str),
-        ("linkedin_url", linkedin_url),
-        ("company_id", company_id_s.as_deref()),
+    let fields = vec![
+        (
+            "first_name",
+            Patch::from_option(first_name).map(SqlValue::from),
+        ),
+        (
+            "last_name",
+            Patch::from_option(last_name).map(SqlValue::from),
+        ),
+        ("email", email.map(SqlValue::from)),
+        ("phone_number", phone_number.map(SqlValue::from)),
+        ("role", role.map(|v| SqlValue::from(v.as_str()))),
+        ("linkedin_url", linkedin_url.map(SqlValue::from)),
+        ("company_id", company_id.map(SqlValue::from)),
     ];
 
     let (sql, binds) = build_update_sql("person", "id", id, fields);
 
-    let mut query = sqlx::query_as::<_, Person>(&sql);
-    for val in &binds {
-        query = query.bind(val);
-    }
-
-    query.fetch_one(pool).await
+    bind_sql_values(sqlx::query_as::<_, Person>(&sql), &binds)
+        .fetch_one(pool)
+        .await
 }
 
 // ======================================================
 // Delete
 // ======================================================
+/// Moves the person to the trash.
 pub async fn delete_person(pool: &SqlitePool, id: i64) -> Result<i64, Error> {
-    let row = query!(
-        r#"
-        DELETE FROM person
-        WHERE id = ?
-        RETURNING id AS "id!: i64"
-        "#,
-        id
-    )
-    .fetch_one(pool)
-    .await?;
+    trash::move_to_trash(pool, EntityType::Person, id).await
+}
 
-    Ok(row.id)
+// ======================================================
+// List (filtered / sorted / paged)
+// ======================================================
+pub const PERSON_LIST: ListSpec = ListSpec {
+    table: "person",
+    columns: &[
+        col("id", "id", ColumnKind::Integer),
+        col("firstName", "first_name", ColumnKind::Text),
+        col("lastName", "last_name", ColumnKind::Text),
+        col("email", "email", ColumnKind::Text),
+        col("phoneNumber", "phone_number", ColumnKind::Text),
+        col("role", "role", ColumnKind::Text),
+        col("linkedinUrl", "linkedin_url", ColumnKind::Text),
+        col("companyId", "company_id", ColumnKind::Integer),
+        col("createdAt", "created_at", ColumnKind::DateTime),
+        col("updatedAt", "updated_at", ColumnKind::DateTime),
+        col("archivedAt", "archived_at", ColumnKind::DateTime),
+        col("deletedAt", "deleted_at", ColumnKind::DateTime),
+    ],
+    default_order: "created_at DESC",
+    tag_entity: Some(EntityType::Person),
+    soft_delete: true,
+};
+
+pub async fn list_persons(
+    pool: &SqlitePool,
+    options: &ListOptions,
+) -> Result<ListPage<Person>, ListError> {
+    fetch_page(pool, &PERSON_LIST, options).await
 }
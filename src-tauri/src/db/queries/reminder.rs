@@ -1,9 +1,15 @@
-use crate::utils::sql_utils::build_update_sql;
-use chrono::{NaiveDate, NaiveDateTime};
-use serde::Serialize;
-use sqlx::{query, query_as, Error, FromRow, SqlitePool};
+use crate::db::models::enums::EntityType;
+use crate::db::models::patch::Patch;
+use crate::db::queries::list_query::{
+    col, fetch_page, ColumnKind, ListError, ListOptions, ListPage, ListSpec,
+};
+use crate::db::queries::trash;
+use crate::utils::sql_utils::{bind_sql_values, build_update_sql, SqlValue};
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use serde::{Deserialize, Serialize};
+use sqlx::{query_as, Error, FromRow, SqliteExecutor, SqlitePool};
 
-#[derive(FromRow, Debug, Serialize)]
+#[derive(FromRow, Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Reminder {
     pub id: i64,
@@ -14,18 +20,30 @@ pub struct Reminder {
     pub company_id: Option<i64>,
     pub person_id: Option<i64>,
     pub reminder_date: NaiveDate,
+    /// Wall-clock time in `timezone`; none means all day
+    pub reminder_time: Option<NaiveTime>,
+    /// IANA name; none means the device's local time
+    pub timezone: Option<String>,
+    /// RRULE subset, see `utils::recurrence`
+    pub recurrence: Option<String>,
     pub title: String,
     pub message: Option<String>,
     pub is_completed: bool,
+    /// When the scheduler announced it (UTC)
+    pub notified_at: Option<NaiveDateTime>,
+    /// Not announced again before this (UTC)
+    pub snoozed_until: Option<NaiveDateTime>,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
+    pub archived_at: Option<NaiveDateTime>,
+    pub deleted_at: Option<NaiveDateTime>,
 }
 
 // ======================================================
 // Create
 // ======================================================
 pub async fn create_reminder(
-    pool: &SqlitePool,
+    executor: impl SqliteExecutor<'_>,
     application_id: Option<i64>,
     interaction_id: Option<i64>,
     note_id: Option<i64>,
@@ -33,13 +51,17 @@ pub async fn create_reminder(
     company_id: Option<i64>,
     person_id: Option<i64>,
     reminder_date: &NaiveDate,
+    reminder_time: Option<&NaiveTime>,
+    timezone: Option<&str>,
+    recurrence: Option<&str>,
     title: &str,
     message: Option<&str>,
     is_completed: bool,
 ) -> Result<Reminder, Error> {
     let reminder_date_str = reminder_date.format("%Y-%m-%d").to_string();
+    let reminder_time_str = reminder_time.map(|t| t.format("%H:%M:%S").to_string());
 
-    let reminder = query_as!(
+    query_as!(
         Reminder,
         r#"
         INSERT INTO reminder (
@@ -50,11 +72,14 @@ pub async fn create_reminder(
             company_id,
             person_id,
             reminder_date,
+            reminder_time,
+            timezone,
+            recurrence,
             title,
             message,
             is_completed
         )
-        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
         RETURNING
             id AS "id!: i64",
             application_id,
@@ -64,11 +89,18 @@ pub async fn create_reminder(
             company_id,
             person_id,
             reminder_date AS "reminder_date!: NaiveDate",
-            title AS "title!: String",
+            reminder_time AS "reminder_time: NaiveTime",
+            timezone,
+            recurrence,
+            title,
             message,
-            is_completed as "is_completed!: bool",
+            is_completed AS "is_completed!: bool",
+            notified_at AS "notified_at: NaiveDateTime",
+            snoozed_until AS "snoozed_until: NaiveDateTime",
             created_at AS "created_at!: NaiveDateTime",
-            updated_at AS "updated_at!: NaiveDateTime"
+            updated_at AS "updated_at!: NaiveDateTime",
+            archived_at AS "archived_at: NaiveDateTime",
+            deleted_at AS "deleted_at: NaiveDateTime"
         "#,
         application_id,
         interaction_id,
@@ -77,14 +109,15 @@ pub async fn create_reminder(
         company_id,
         person_id,
         reminder_date_str,
+        reminder_time_str,
+        timezone,
+        recurrence,
         title,
         message,
         is_completed
     )
-    .fetch_one(pool)
-    .await?;
-
-    Ok(reminder)
+    .fetch_one(executor)
+    .await
 }
 
 // ======================================================
@@ -103,13 +136,20 @@ pub async fn get_reminder_by_id(pool: &SqlitePool, id: i64) -> Result<Reminder,
             company_id,
             person_id,
             reminder_date AS "reminder_date!: NaiveDate",
-            title AS "title!: String",
+            reminder_time AS "reminder_time: NaiveTime",
+            timezone,
+            recurrence,
+            title,
             message,
-            is_completed as "is_completed!: bool",
+            is_completed AS "is_completed!: bool",
+            notified_at AS "notified_at: NaiveDateTime",
+            snoozed_until AS "snoozed_until: NaiveDateTime",
             created_at AS "created_at!: NaiveDateTime",
-            updated_at AS "updated_at!: NaiveDateTime"
+            updated_at AS "updated_at!: NaiveDateTime",
+            archived_at AS "archived_at: NaiveDateTime",
+            deleted_at AS "deleted_at: NaiveDateTime"
         FROM reminder
-        WHERE id = ?
+        WHERE id = ? AND deleted_at IS NULL
         "#,
         id
     )
@@ -133,13 +173,21 @@ pub async fn get_all_reminders(pool: &SqlitePool) -> Result<Vec<Reminder>, Error
             company_id,
             person_id,
             reminder_date AS "reminder_date!: NaiveDate",
-            title AS "title!: String",
+            reminder_time AS "reminder_time: NaiveTime",
+            timezone,
+            recurrence,
+            title,
             message,
-            is_completed as "is_completed!: bool",
+            is_completed AS "is_completed!: bool",
+            notified_at AS "notified_at: NaiveDateTime",
+            snoozed_until AS "snoozed_until: NaiveDateTime",
             created_at AS "created_at!: NaiveDateTime",
-            updated_at AS "updated_at!: NaiveDateTime"
+            updated_at AS "updated_at!: NaiveDateTime",
+            archived_at AS "archived_at: NaiveDateTime",
+            deleted_at AS "deleted_at: NaiveDateTime"
         FROM reminder
-        ORDER BY reminder_date ASC
+        WHERE deleted_at IS NULL AND archived_at IS NULL
+        ORDER BY reminder_date ASC, reminder_time ASC
         "#
     )
     .fetch_all(pool)
@@ -149,35 +197,28 @@ pub async fn get_all_reminders(pool: &SqlitePool) -> Result<Vec<Reminder>, Error
 // ======================================================
 // Get upcoming (uncompleted)
 // ======================================================
+/// Open reminders dated within `from..=to`, plus recurring ones that
+/// started earlier, since their later occurrences may fall inside.
+/// Archived and trashed reminders are left out.
 pub async fn get_upcoming_reminders(
     pool: &SqlitePool,
-    current_date: &NaiveDate,
+    from: &NaiveDate,
+    to: &NaiveDate,
 ) -> Result<Vec<Reminder>, Error> {
-    let current_date_str = current_date.format("%Y-%m-%d").to_string();
-
-    query_as!(
-        Reminder,
+    sqlx::query_as::<_, Reminder>(
         r#"
-        SELECT
-            id AS "id!: i64",
-            application_id,
-            interaction_id,
-            note_id,
-            job_listing_id,
-            company_id,
-            person_id,
-            reminder_date AS "reminder_date!: NaiveDate",
-            title AS "title!: String",
-            message,
-            is_completed as "is_completed!: bool",
-            created_at AS "created_at!: NaiveDateTime",
-            updated_at AS "updated_at!: NaiveDateTime"
+        SELECT *
         FROM reminder
-        WHERE reminder_date >= ? AND is_completed = 0
-        ORDER BY reminder_date ASC
+        WHERE is_completed = 0
+          AND deleted_at IS NULL
+          AND archived_at IS NULL
+          AND reminder_date <= ?
+          AND (reminder_date >= ? OR recurrence IS NOT NULL)
+        ORDER BY reminder_date ASC, reminder_time ASC
         "#,
-        current_date_str
     )
+    .bind(to.format("%Y-%m-%d").to_string())
+    .bind(from.format("%Y-%m-%d").to_string())
     .fetch_all(pool)
     .await
 }
@@ -188,64 +229,327 @@ pub async fn get_upcoming_reminders(
 pub async fn update_reminder(
     pool: &SqlitePool,
     id: i64,
-    application_id: Option<i64>,
-    interaction_id: Option<i64>,
-    note_id: Option<i64>,
-    job_listing_id: Option<i64>,
-    company_id: Option<i64>,
-    person_id: Option<i64>,
+    application_id: Patch<i64>,
+    interaction_id: Patch<i64>,
+    note_id: Patch<i64>,
+    job_listing_id: Patch<i64>,
+    company_id: Patch<i64>,
+    person_id: Patch<i64>,
     reminder_date: Option<&NaiveDate>,
+    reminder_time: Patch<&NaiveTime>,
+    timezone: Patch<&str>,
+    recurrence: Patch<&str>,
     title: Option<&str>,
-    message: Option<&str>,
+    message: Patch<&str>,
     is_completed: Option<bool>,
 ) -> Result<Reminder, Error> {
-    let application_id_s = application_id.map(|v| v.to_string());
-    let interaction_id_s = interaction_id.map(|v| v.to_string());
-    let note_id_s = note_id.map(|v| v.to_string());
-    let job_listing_id_s = job_listing_id.map(|v| v.to_string());
-    let company_id_s = company_id.map(|v| v.to_string());
-    let person_id_s = person_id.map(|v| v.to_string());
-    let is_completed_s = is_completed.map(|v| if v { "1".to_string() } else { "0".to_string() });
-    let reminder_date_s = reminder_date.map(|d| d.format("%Y-%m-%d").to_string());
-    let reminder_date_ref = reminder_date_s.as_deref();
-
-    let fields: Vec<(&str, Option<&str>)> = vec![
-        ("application_id", application_id_s.as_deref()),
-        ("interaction_id", interaction_id_s.as_deref()),
-        ("note_id", note_id_s.as_deref()),
-        ("job_listing_id", job_listing_id_s.as_deref()),
-        ("company_id", company_id_s.as_deref()),
-        ("person_id", person_id_s.as_deref()),
-        ("reminder_date", reminder_date_ref),
-        ("title", title),
-        ("message", message),
-        ("is_completed", is_completed_s.as_deref()),
+    let fields = vec![
+        ("application_id", application_id.map(SqlValue::from)),
+        ("interaction_id", interaction_id.map(SqlValue::from)),
+        ("note_id", note_id.map(SqlValue::from)),
+        ("job_listing_id", job_listing_id.map(SqlValue::from)),
+        ("company_id", company_id.map(SqlValue::from)),
+        ("person_id", person_id.map(SqlValue::from)),
+        (
+            "reminder_date",
+            Patch::from_option(reminder_date).map(SqlValue::from),
+        ),
+        ("reminder_time", reminder_time.map(SqlValue::from)),
+        ("timezone", timezone.map(SqlValue::from)),
+        ("recurrence", recurrence.map(SqlValue::from)),
+        ("title", Patch::from_option(title).map(SqlValue::from)),
+        ("message", message.map(SqlValue::from)),
+        (
+            "is_completed",
+            Patch::from_option(is_completed).map(SqlValue::from),
+        ),
     ];
 
     let (sql, binds) = build_update_sql("reminder", "id", id, fields);
 
-    let mut query = sqlx::query_as::<_, Reminder>(&sql);
-    for val in &binds {
-        query = query.bind(val);
-    }
-
-    query.fetch_one(pool).await
+    bind_sql_values(sqlx::query_as::<_, Reminder>(&sql), &binds)
+        .fetch_one(pool)
+        .await
 }
 
 // ======================================================
 // Delete
 // ======================================================
+/// Moves the reminder to the trash.
 pub async fn delete_reminder(pool: &SqlitePool, id: i64) -> Result<i64, Error> {
-    let row = query!(
+    trash::move_to_trash(pool, EntityType::Reminder, id).await
+}
+
+// ======================================================
+// Notifications
+// ======================================================
+/// Open, unannounced reminders dated on or before `through` that are not
+/// snoozed past `now` (UTC), archived or trashed. Timed ones may not be due yet; the caller
+/// checks their time.
+pub async fn get_notification_candidates(
+    pool: &SqlitePool,
+    through: &NaiveDate,
+    now: &NaiveDateTime,
+) -> Result<Vec<Reminder>, Error> {
+    sqlx::query_as::<_, Reminder>(
         r#"
-        DELETE FROM reminder
-        WHERE id = ?
-        RETURNING id AS "id!: i64"
+        SELECT *
+        FROM reminder
+        WHERE is_completed = 0
+          AND notified_at IS NULL
+          AND deleted_at IS NULL
+          AND archived_at IS NULL
+          AND reminder_date <= ?
+          AND (snoozed_until IS NULL OR snoozed_until <= ?)
+        ORDER BY reminder_date ASC, reminder_time ASC, id ASC
         "#,
+    )
+    .bind(through.format("%Y-%m-%d").to_string())
+    .bind(now)
+    .fetch_all(pool)
+    .await
+}
+
+/// Marks the given reminders as notified and returns the ones that were
+/// still open and unannounced.
+///
+/// Claiming happens in a single statement, so a reminder is never returned
+/// twice, even with several schedulers on the same database.
+pub async fn claim_reminders(
+    pool: &SqlitePool,
+    ids: &[i64],
+    now: &NaiveDateTime,
+) -> Result<Vec<Reminder>, Error> {
+    if ids.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let placeholders = vec!["?"; ids.len()].join(", ");
+    let sql = format!(
+        r#"
+        UPDATE reminder
+        SET notified_at = ?
+        WHERE id IN ({})
+          AND is_completed = 0
+          AND notified_at IS NULL
+        RETURNING *
+        "#,
+        placeholders
+    );
+
+    let mut query = sqlx::query_as::<_, Reminder>(&sql).bind(now);
+    for id in ids {
+        query = query.bind(id);
+    }
+    let mut claimed = query.fetch_all(pool).await?;
+
+    // RETURNING has no ORDER BY
+    claimed.sort_by_key(|r| (r.reminder_date, r.reminder_time, r.id));
+    Ok(claimed)
+}
+
+/// Hides an open reminder until `until` (UTC); it is announced again then.
+pub async fn snooze_reminder(
+    pool: &SqlitePool,
+    id: i64,
+    until: &NaiveDateTime,
+) -> Result<Reminder, Error> {
+    query_as!(
+        Reminder,
+        r#"
+        UPDATE reminder
+        SET snoozed_until = ?, notified_at = NULL, updated_at = CURRENT_TIMESTAMP
+        WHERE id = ? AND is_completed = 0 AND deleted_at IS NULL
+        RETURNING
+            id AS "id!: i64",
+            application_id,
+            interaction_id,
+            note_id,
+            job_listing_id,
+            company_id,
+            person_id,
+            reminder_date AS "reminder_date!: NaiveDate",
+            reminder_time AS "reminder_time: NaiveTime",
+            timezone,
+            recurrence,
+            title,
+            message,
+            is_completed AS "is_completed!: bool",
+            notified_at AS "notified_at: NaiveDateTime",
+            snoozed_until AS "snoozed_until: NaiveDateTime",
+            created_at AS "created_at!: NaiveDateTime",
+            updated_at AS "updated_at!: NaiveDateTime",
+            archived_at AS "archived_at: NaiveDateTime",
+            deleted_at AS "deleted_at: NaiveDateTime"
+        "#,
+        until,
         id
     )
     .fetch_one(pool)
+    .await
+}
+
+pub async fn complete_reminder(pool: &SqlitePool, id: i64) -> Result<Reminder, Error> {
+    query_as!(
+        Reminder,
+        r#"
+        UPDATE reminder
+        SET is_completed = 1, snoozed_until = NULL, updated_at = CURRENT_TIMESTAMP
+        WHERE id = ? AND deleted_at IS NULL
+        RETURNING
+            id AS "id!: i64",
+            application_id,
+            interaction_id,
+            note_id,
+            job_listing_id,
+            company_id,
+            person_id,
+            reminder_date AS "reminder_date!: NaiveDate",
+            reminder_time AS "reminder_time: NaiveTime",
+            timezone,
+            recurrence,
+            title,
+            message,
+            is_completed AS "is_completed!: bool",
+            notified_at AS "notified_at: NaiveDateTime",
+            snoozed_until AS "snoozed_until: NaiveDateTime",
+            created_at AS "created_at!: NaiveDateTime",
+            updated_at AS "updated_at!: NaiveDateTime",
+            archived_at AS "archived_at: NaiveDateTime",
+            deleted_at AS "deleted_at: NaiveDateTime"
+        "#,
+        id
+    )
+    .fetch_one(pool)
+    .await
+}
+
+// ======================================================
+// Recurrence
+// ======================================================
+/// Moves the recurrence of a completed reminder to a new open occurrence
+/// on `next_date` governed by `next_rule`, copying its links and tags.
+///
+/// Returns `None` if the reminder is not a completed recurring one, e.g.
+/// because it was rolled over already.
+pub async fn roll_over_reminder(
+    pool: &SqlitePool,
+    id: i64,
+    next_date: &NaiveDate,
+    next_rule: &str,
+) -> Result<Option<Reminder>, Error> {
+    let mut tx = pool.begin().await?;
+
+    let taken = sqlx::query_scalar::<_, i64>(
+        r#"
+        UPDATE reminder
+        SET recurrence = NULL
+        WHERE id = ? AND is_completed = 1 AND recurrence IS NOT NULL
+        RETURNING id
+        "#,
+    )
+    .bind(id)
+    .fetch_optional(&mut *tx)
+    .await?;
+    if taken.is_none() {
+        return Ok(None);
+    }
+
+    let next = sqlx::query_as::<_, Reminder>(
+        r#"
+        INSERT INTO reminder (
+            application_id,
+            interaction_id,
+            note_id,
+            job_listing_id,
+            company_id,
+            person_id,
+            reminder_date,
+            reminder_time,
+            timezone,
+            recurrence,
+            title,
+            message,
+            is_completed
+        )
+        SELECT
+            application_id,
+            interaction_id,
+            note_id,
+            job_listing_id,
+            company_id,
+            person_id,
+            ?,
+            reminder_time,
+            timezone,
+            ?,
+            title,
+            message,
+            0
+        FROM reminder
+        WHERE id = ?
+        RETURNING *
+        "#,
+    )
+    .bind(next_date.format("%Y-%m-%d").to_string())
+    .bind(next_rule)
+    .bind(id)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO entity_tag (tag_id, entity_type, entity_id)
+        SELECT tag_id, entity_type, ?
+        FROM entity_tag
+        WHERE entity_type = ? AND entity_id = ?
+        "#,
+    )
+    .bind(next.id)
+    .bind(EntityType::Reminder.as_str())
+    .bind(id)
+    .execute(&mut *tx)
     .await?;
 
-    Ok(row.id)
+    tx.commit().await?;
+    Ok(Some(next))
+}
+
+// ======================================================
+// List (filtered / sorted / paged)
+// ======================================================
+pub const REMINDER_LIST: ListSpec = ListSpec {
+    table: "reminder",
+    columns: &[
+        col("id", "id", ColumnKind::Integer),
+        col("applicationId", "application_id", ColumnKind::Integer),
+        col("interactionId", "interaction_id", ColumnKind::Integer),
+        col("noteId", "note_id", ColumnKind::Integer),
+        col("jobListingId", "job_listing_id", ColumnKind::Integer),
+        col("companyId", "company_id", ColumnKind::Integer),
+        col("personId", "person_id", ColumnKind::Integer),
+        col("reminderDate", "reminder_date", ColumnKind::Date),
+        col("reminderTime", "reminder_time", ColumnKind::Time),
+        col("timezone", "timezone", ColumnKind::Text),
+        col("recurrence", "recurrence", ColumnKind::Text),
+        col("title", "title", ColumnKind::Text),
+        col("message", "message", ColumnKind::Text),
+        col("isCompleted", "is_completed", ColumnKind::Bool),
+        col("notifiedAt", "notified_at", ColumnKind::DateTime),
+        col("snoozedUntil", "snoozed_until", ColumnKind::DateTime),
+        col("createdAt", "created_at", ColumnKind::DateTime),
+        col("updatedAt", "updated_at", ColumnKind::DateTime),
+        col("archivedAt", "archived_at", ColumnKind::DateTime),
+        col("deletedAt", "deleted_at", ColumnKind::DateTime),
+    ],
+    default_order: "reminder_date ASC, reminder_time ASC",
+    tag_entity: Some(EntityType::Reminder),
+    soft_delete: true,
+};
+
+pub async fn list_reminders(
+    pool: &SqlitePool,
+    options: &ListOptions,
+) -> Result<ListPage<Reminder>, ListError> {
+    fetch_page(pool, &REMINDER_LIST, options).await
 }
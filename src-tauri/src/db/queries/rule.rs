@@ -0,0 +1,306 @@
+use crate::db::models::enums::{EntityType, Stage};
+use crate::db::models::patch::Patch;
+use crate::db::models::rule::{RuleAction, RuleTrigger};
+use crate::utils::sql_utils::{bind_sql_values, build_update_sql, SqlValue};
+use chrono::{NaiveDate, NaiveDateTime};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use sqlx::sqlite::SqliteRow;
+use sqlx::{Error, FromRow, Row, SqliteExecutor, SqlitePool};
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AutomationRule {
+    pub id: i64,
+    pub name: String,
+    pub enabled: bool,
+    pub trigger: RuleTrigger,
+    pub action: RuleAction,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+/// Trigger and action are JSON columns.
+impl<'r> FromRow<'r, SqliteRow> for AutomationRule {
+    fn from_row(row: &'r SqliteRow) -> Result<Self, Error> {
+        Ok(AutomationRule {
+            id: row.try_get("id")?,
+            name: row.try_get("name")?,
+            enabled: row.try_get("enabled")?,
+            trigger: json_column(row, "rule_trigger")?,
+            action: json_column(row, "rule_action")?,
+            created_at: row.try_get("created_at")?,
+            updated_at: row.try_get("updated_at")?,
+        })
+    }
+}
+
+fn json_column<T: DeserializeOwned>(row: &SqliteRow, column: &str) -> Result<T, Error> {
+    let text: String = row.try_get(column)?;
+    serde_json::from_str(&text).map_err(|e| Error::ColumnDecode {
+        index: column.to_string(),
+        source: Box::new(e),
+    })
+}
+
+pub(crate) fn to_json<T: Serialize>(value: &T) -> String {
+    // Plain enums of strings and numbers always serialize
+    serde_json::to_string(value).unwrap_or_default()
+}
+
+/// One firing of a rule and the record it created or changed.
+#[derive(FromRow, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleRun {
+    pub id: i64,
+    pub rule_id: i64,
+    pub application_id: Option<i64>,
+    pub anchor: Option<String>,
+    pub entity_type: Option<EntityType>,
+    pub entity_id: Option<i64>,
+    pub fired_at: NaiveDateTime,
+}
+
+/// Names rule texts can refer to.
+#[derive(FromRow, Debug, Default)]
+pub struct ApplicationContext {
+    pub stage: Option<Stage>,
+    pub company: Option<String>,
+    pub job_title: Option<String>,
+}
+
+/// An open application and the date of its latest activity.
+#[derive(FromRow, Debug)]
+pub struct QuietApplication {
+    pub id: i64,
+    pub last_activity: NaiveDate,
+}
+
+// ======================================================
+// Create
+// ======================================================
+pub async fn create_rule(
+    pool: &SqlitePool,
+    name: &str,
+    enabled: bool,
+    trigger: &RuleTrigger,
+    action: &RuleAction,
+) -> Result<AutomationRule, Error> {
+    sqlx::query_as::<_, AutomationRule>(
+        r#"
+        INSERT INTO automation_rule (name, enabled, rule_trigger, rule_action)
+        VALUES (?, ?, ?, ?)
+        RETURNING *
+        "#,
+    )
+    .bind(name)
+    .bind(enabled)
+    .bind(to_json(trigger))
+    .bind(to_json(action))
+    .fetch_one(pool)
+    .await
+}
+
+// ======================================================
+// Get by ID / all / enabled for an event
+// ======================================================
+pub async fn get_rule_by_id(pool: &SqlitePool, id: i64) -> Result<AutomationRule, Error> {
+    sqlx::query_as::<_, AutomationRule>("SELECT * FROM automation_rule WHERE id = ?")
+        .bind(id)
+        .fetch_one(pool)
+        .await
+}
+
+pub async fn list_rules(pool: &SqlitePool) -> Result<Vec<AutomationRule>, Error> {
+    sqlx::query_as::<_, AutomationRule>("SELECT * FROM automation_rule ORDER BY id ASC")
+        .fetch_all(pool)
+        .await
+}
+
+/// Enabled rules whose trigger has the given `event` tag, oldest first.
+pub async fn list_enabled_rules(
+    executor: impl SqliteExecutor<'_>,
+    event: &str,
+) -> Result<Vec<AutomationRule>, Error> {
+    sqlx::query_as::<_, AutomationRule>(
+        r#"
+        SELECT *
+        FROM automation_rule
+        WHERE enabled = 1 AND json_extract(rule_trigger, '$.event') = ?
+        ORDER BY id ASC
+        "#,
+    )
+    .bind(event)
+    .fetch_all(executor)
+    .await
+}
+
+// ======================================================
+// Update
+// ======================================================
+pub async fn update_rule(
+    pool: &SqlitePool,
+    id: i64,
+    name: Option<&str>,
+    enabled: Option<bool>,
+    trigger: Option<&RuleTrigger>,
+    action: Option<&RuleAction>,
+) -> Result<AutomationRule, Error> {
+    let fields = vec![
+        ("name", Patch::from_option(name).map(SqlValue::from)),
+        ("enabled", Patch::from_option(enabled).map(SqlValue::from)),
+        (
+            "rule_trigger",
+            Patch::from_option(trigger).map(|t| SqlValue::from(to_json(t))),
+        ),
+        (
+            "rule_action",
+            Patch::from_option(action).map(|a| SqlValue::from(to_json(a))),
+        ),
+    ];
+
+    let (sql, binds) = build_update_sql("automation_rule", "id", id, fields);
+
+    bind_sql_values(sqlx::query_as::<_, AutomationRule>(&sql), &binds)
+        .fetch_one(pool)
+        .await
+}
+
+// ======================================================
+// Delete
+// ======================================================
+pub async fn delete_rule(pool: &SqlitePool, id: i64) -> Result<i64, Error> {
+    sqlx::query_scalar("DELETE FROM automation_rule WHERE id = ? RETURNING id")
+        .bind(id)
+        .fetch_one(pool)
+        .await
+}
+
+// ======================================================
+// Evaluation helpers
+// ======================================================
+pub async fn get_application_context(
+    executor: impl SqliteExecutor<'_>,
+    application_id: i64,
+) -> Result<ApplicationContext, Error> {
+    sqlx::query_as::<_, ApplicationContext>(
+        r#"
+        SELECT a.stage AS stage, c.name AS company, jl.title AS job_title
+        FROM application a
+        LEFT JOIN job_listing jl ON jl.id = a.job_listing_id
+        LEFT JOIN company c ON c.id = jl.company_id
+        WHERE a.id = ?
+        "#,
+    )
+    .bind(application_id)
+    .fetch_one(executor)
+    .await
+}
+
+/// Open applications whose latest interaction (or, without any, the
+/// applied date) is on or before `cutoff`. Archived and trashed
+/// applications are left out.
+pub async fn list_quiet_applications(
+    executor: impl SqliteExecutor<'_>,
+    cutoff: &NaiveDate,
+) -> Result<Vec<QuietApplication>, Error> {
+    sqlx::query_as::<_, QuietApplication>(
+        r#"
+        SELECT id, last_activity
+        FROM (
+            SELECT
+                a.id AS id,
+                MAX(a.applied_date, COALESCE(MAX(i.interaction_date), a.applied_date)) AS last_activity
+            FROM application a
+            LEFT JOIN interaction i ON i.application_id = a.id AND i.deleted_at IS NULL
+            WHERE (a.stage IS NULL OR a.stage NOT IN ('accepted', 'rejected', 'withdrawn', 'ghosted'))
+              AND a.deleted_at IS NULL
+              AND a.archived_at IS NULL
+            GROUP BY a.id
+        )
+        WHERE last_activity <= ?
+        ORDER BY id ASC
+        "#,
+    )
+    .bind(cutoff.format("%Y-%m-%d").to_string())
+    .fetch_all(executor)
+    .await
+}
+
+// ======================================================
+// Runs
+// ======================================================
+/// Records a firing; returns `false` if this rule already fired for the
+/// same application and anchor.
+pub async fn record_run(
+    executor: impl SqliteExecutor<'_>,
+    rule_id: i64,
+    application_id: Option<i64>,
+    anchor: Option<&str>,
+    entity: Option<(EntityType, i64)>,
+) -> Result<bool, Error> {
+    let result = sqlx::query(
+        r#"
+        INSERT INTO automation_rule_run (rule_id, application_id, anchor, entity_type, entity_id)
+        VALUES (?, ?, ?, ?, ?)
+        ON CONFLICT (rule_id, application_id, anchor) DO NOTHING
+        "#,
+    )
+    .bind(rule_id)
+    .bind(application_id)
+    .bind(anchor)
+    .bind(entity.map(|(t, _)| t.as_str()))
+    .bind(entity.map(|(_, id)| id))
+    .execute(executor)
+    .await?;
+
+    Ok(result.rows_affected() == 1)
+}
+
+pub async fn has_run(
+    executor: impl SqliteExecutor<'_>,
+    rule_id: i64,
+    application_id: i64,
+    anchor: &str,
+) -> Result<bool, Error> {
+    sqlx::query_scalar(
+        r#"
+        SELECT EXISTS (
+            SELECT 1 FROM automation_rule_run
+            WHERE rule_id = ? AND application_id = ? AND anchor = ?
+        )
+        "#,
+    )
+    .bind(rule_id)
+    .bind(application_id)
+    .bind(anchor)
+    .fetch_one(executor)
+    .await
+}
+
+pub async fn list_rule_runs(pool: &SqlitePool, rule_id: i64) -> Result<Vec<RuleRun>, Error> {
+    sqlx::query_as::<_, RuleRun>(
+        r#"
+        SELECT id, rule_id, application_id, anchor, entity_type, entity_id, fired_at
+        FROM automation_rule_run
+        WHERE rule_id = ?
+        ORDER BY fired_at DESC, id DESC
+        "#,
+    )
+    .bind(rule_id)
+    .fetch_all(pool)
+    .await
+}
+
+/// Runs of all rules; used by the JSON export.
+pub async fn list_all_rule_runs(pool: &SqlitePool) -> Result<Vec<RuleRun>, Error> {
+    sqlx::query_as::<_, RuleRun>(
+        r#"
+        SELECT id, rule_id, application_id, anchor, entity_type, entity_id, fired_at
+        FROM automation_rule_run
+        ORDER BY id
+        "#,
+    )
+    .fetch_all(pool)
+    .await
+}
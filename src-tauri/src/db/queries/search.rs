@@ -0,0 +1,92 @@
+use crate::db::models::enums::EntityType;
+use serde::Serialize;
+use sqlx::{Error, FromRow, SqlitePool};
+
+#[derive(FromRow, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchHit {
+    pub entity_type: EntityType,
+    pub entity_id: i64,
+    pub snippet: String,
+    pub rank: f64,
+}
+
+/// Turns free text into a safe FTS5 expression.
+///
+/// Every word is quoted so user input can never be parsed as FTS syntax,
+/// and the last word matches as a prefix to support search-as-you-type.
+/// Returns None if the input contains no searchable words.
+pub fn to_fts_query(input: &str) -> Option<String> {
+    let terms: Vec<String> = input
+        .split_whitespace()
+        .map(|t| t.replace('"', ""))
+        .filter(|t| !t.is_empty())
+        .map(|t| format!("\"{}\"", t))
+        .collect();
+
+    if terms.is_empty() {
+        return None;
+    }
+
+    Some(format!("{}*", terms.join(" ")))
+}
+
+// ======================================================
+// Search all entities
+// ======================================================
+pub async fn search_all(
+    pool: &SqlitePool,
+    query: &str,
+    limit: i64,
+) -> Result<Vec<SearchHit>, Error> {
+    let fts_query = match to_fts_query(query) {
+        Some(q) => q,
+        None => return Ok(Vec::new()),
+    };
+
+    sqlx::query_as::<_, SearchHit>(
+        r#"
+        SELECT entity_type, entity_id, snippet, rank FROM (
+            SELECT 'company' AS entity_type, rowid AS entity_id,
+                   snippet(company_fts, -1, '<mark>', '</mark>', '…', 12) AS snippet,
+                   bm25(company_fts) AS rank
+            FROM company_fts WHERE company_fts MATCH ?
+              AND rowid NOT IN (SELECT id FROM company WHERE deleted_at IS NOT NULL)
+            UNION ALL
+            SELECT 'person', rowid,
+                   snippet(person_fts, -1, '<mark>', '</mark>', '…', 12),
+                   bm25(person_fts)
+            FROM person_fts WHERE person_fts MATCH ?
+              AND rowid NOT IN (SELECT id FROM person WHERE deleted_at IS NOT NULL)
+            UNION ALL
+            SELECT 'job_listing', rowid,
+                   snippet(job_listing_fts, -1, '<mark>', '</mark>', '…', 12),
+                   bm25(job_listing_fts)
+            FROM job_listing_fts WHERE job_listing_fts MATCH ?
+              AND rowid NOT IN (SELECT id FROM job_listing WHERE deleted_at IS NOT NULL)
+            UNION ALL
+            SELECT 'interaction', rowid,
+                   snippet(interaction_fts, -1, '<mark>', '</mark>', '…', 12),
+                   bm25(interaction_fts)
+            FROM interaction_fts WHERE interaction_fts MATCH ?
+              AND rowid NOT IN (SELECT id FROM interaction WHERE deleted_at IS NOT NULL)
+            UNION ALL
+            SELECT 'note', rowid,
+                   snippet(note_fts, -1, '<mark>', '</mark>', '…', 12),
+                   bm25(note_fts)
+            FROM note_fts WHERE note_fts MATCH ?
+              AND rowid NOT IN (SELECT id FROM note WHERE deleted_at IS NOT NULL)
+        )
+        ORDER BY rank ASC
+        LIMIT ?
+        "#,
+    )
+    .bind(&fts_query)
+    .bind(&fts_query)
+    .bind(&fts_query)
+    .bind(&fts_query)
+    .bind(&fts_query)
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+}
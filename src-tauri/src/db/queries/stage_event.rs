@@ -0,0 +1,71 @@
+use crate::db::models::enums::Stage;
+use crate::db::queries::list_query::{col, ColumnKind, ListSpec};
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::{Error, FromRow, SqlitePool};
+
+/// A single stage transition. Rows are written by triggers on `application`.
+#[derive(FromRow, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StageEvent {
+    pub id: i64,
+    pub application_id: i64,
+    pub from_stage: Option<Stage>,
+    pub to_stage: Stage,
+    pub changed_at: NaiveDateTime,
+}
+
+pub const STAGE_EVENT_LIST: ListSpec = ListSpec {
+    table: "application_stage_event",
+    columns: &[
+        col("id", "id", ColumnKind::Integer),
+        col("applicationId", "application_id", ColumnKind::Integer),
+        col("fromStage", "from_stage", ColumnKind::Text),
+        col("toStage", "to_stage", ColumnKind::Text),
+        col("changedAt", "changed_at", ColumnKind::DateTime),
+    ],
+    default_order: "changed_at ASC",
+    tag_entity: None,
+    soft_delete: false,
+};
+
+// ======================================================
+// Get timeline for application
+// ======================================================
+pub async fn get_stage_events_for_application(
+    pool: &SqlitePool,
+    application_id: i64,
+) -> Result<Vec<StageEvent>, Error> {
+    sqlx::query_as::<_, StageEvent>(
+        r#"
+        SELECT id, application_id, from_stage, to_stage, changed_at
+        FROM application_stage_event
+        WHERE application_id = ?
+        ORDER BY changed_at ASC, id ASC
+        "#,
+    )
+    .bind(application_id)
+    .fetch_all(pool)
+    .await
+}
+
+// ======================================================
+// Get when the current stage was entered
+// ======================================================
+pub async fn get_current_stage_entered_at(
+    pool: &SqlitePool,
+    application_id: i64,
+) -> Result<Option<NaiveDateTime>, Error> {
+    sqlx::query_scalar(
+        r#"
+        SELECT changed_at
+        FROM application_stage_event
+        WHERE application_id = ?
+        ORDER BY changed_at DESC, id DESC
+        LIMIT 1
+        "#,
+    )
+    .bind(application_id)
+    .fetch_optional(pool)
+    .await
+}
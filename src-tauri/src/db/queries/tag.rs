@@ -0,0 +1,284 @@
+use crate::db::models::enums::EntityType;
+use crate::db::models::patch::Patch;
+use crate::utils::sql_utils::{bind_sql_values, build_update_sql, SqlValue};
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::{Connection, Error, FromRow, SqlitePool};
+use std::collections::HashMap;
+
+#[derive(FromRow, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Tag {
+    pub id: i64,
+    pub name: String,
+    pub color: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+/// A tag with the number of records carrying it.
+#[derive(FromRow, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TagUsage {
+    pub id: i64,
+    pub name: String,
+    pub color: Option<String>,
+    pub usage_count: i64,
+}
+
+/// The short form embedded in entity payloads.
+#[derive(FromRow, Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TagRef {
+    pub id: i64,
+    pub name: String,
+    pub color: Option<String>,
+}
+
+/// One tag link; used by the JSON export.
+#[derive(FromRow, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EntityTag {
+    pub tag_id: i64,
+    pub entity_type: EntityType,
+    pub entity_id: i64,
+    pub created_at: NaiveDateTime,
+}
+
+// ======================================================
+// Create
+// ======================================================
+pub async fn create_tag(pool: &SqlitePool, name: &str, color: Option<&str>) -> Result<Tag, Error> {
+    sqlx::query_as::<_, Tag>("INSERT INTO tag (name, color) VALUES (?, ?) RETURNING *")
+        .bind(name)
+        .bind(color)
+        .fetch_one(pool)
+        .await
+}
+
+// ======================================================
+// Read
+// ======================================================
+pub async fn get_tag_by_id(pool: &SqlitePool, id: i64) -> Result<Tag, Error> {
+    sqlx::query_as::<_, Tag>("SELECT * FROM tag WHERE id = ?")
+        .bind(id)
+        .fetch_one(pool)
+        .await
+}
+
+pub async fn list_tags(pool: &SqlitePool) -> Result<Vec<TagUsage>, Error> {
+    sqlx::query_as::<_, TagUsage>(
+        r#"
+        SELECT t.id, t.name, t.color, COUNT(et.tag_id) AS usage_count
+        FROM tag t
+        LEFT JOIN entity_tag et ON et.tag_id = t.id
+        GROUP BY t.id
+        ORDER BY t.name COLLATE NOCASE ASC
+        "#,
+    )
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn get_tags_for_entity(
+    pool: &SqlitePool,
+    entity_type: EntityType,
+    entity_id: i64,
+) -> Result<Vec<TagRef>, Error> {
+    sqlx::query_as::<_, TagRef>(
+        r#"
+        SELECT t.id, t.name, t.color
+        FROM entity_tag et
+        JOIN tag t ON t.id = et.tag_id
+        WHERE et.entity_type = ? AND et.entity_id = ?
+        ORDER BY t.name COLLATE NOCASE ASC
+        "#,
+    )
+    .bind(entity_type.as_str())
+    .bind(entity_id)
+    .fetch_all(pool)
+    .await
+}
+
+#[derive(FromRow)]
+struct EntityTagRow {
+    entity_id: i64,
+    #[sqlx(flatten)]
+    tag: TagRef,
+}
+
+/// Tags of many records at once, keyed by record ID.
+pub async fn get_tags_for_entities(
+    pool: &SqlitePool,
+    entity_type: EntityType,
+    entity_ids: &[i64],
+) -> Result<HashMap<i64, Vec<TagRef>>, Error> {
+    let mut tags: HashMap<i64, Vec<TagRef>> = HashMap::new();
+    if entity_ids.is_empty() {
+        return Ok(tags);
+    }
+
+    let sql = format!(
+        r#"
+        SELECT et.entity_id, t.id, t.name, t.color
+        FROM entity_tag et
+        JOIN tag t ON t.id = et.tag_id
+        WHERE et.entity_type = ? AND et.entity_id IN ({})
+        ORDER BY t.name COLLATE NOCASE ASC
+        "#,
+        vec!["?"; entity_ids.len()].join(", ")
+    );
+
+    let mut query = sqlx::query_as::<_, EntityTagRow>(&sql).bind(entity_type.as_str());
+    for id in entity_ids {
+        query = query.bind(*id);
+    }
+
+    for row in query.fetch_all(pool).await? {
+        tags.entry(row.entity_id).or_default().push(row.tag);
+    }
+    Ok(tags)
+}
+
+pub async fn list_all_tags(pool: &SqlitePool) -> Result<Vec<Tag>, Error> {
+    sqlx::query_as::<_, Tag>("SELECT * FROM tag ORDER BY id")
+        .fetch_all(pool)
+        .await
+}
+
+pub async fn list_entity_tags(pool: &SqlitePool) -> Result<Vec<EntityTag>, Error> {
+    sqlx::query_as::<_, EntityTag>(
+        "SELECT * FROM entity_tag ORDER BY tag_id, entity_type, entity_id",
+    )
+    .fetch_all(pool)
+    .await
+}
+
+// ======================================================
+// Update (rename / color)
+// ======================================================
+pub async fn update_tag(
+    pool: &SqlitePool,
+    id: i64,
+    name: Option<&str>,
+    color: Patch<&str>,
+) -> Result<Tag, Error> {
+    let fields = vec![
+        ("name", Patch::from_option(name).map(SqlValue::from)),
+        ("color", color.map(SqlValue::from)),
+    ];
+
+    let (sql, binds) = build_update_sql("tag", "id", id, fields);
+
+    bind_sql_values(sqlx::query_as::<_, Tag>(&sql), &binds)
+        .fetch_one(pool)
+        .await
+}
+
+// ======================================================
+// Merge
+// ======================================================
+/// Moves all links of `source_ids` to `target_id` and deletes the sources.
+/// Records already carrying the target keep a single link.
+pub async fn merge_tags(
+    pool: &SqlitePool,
+    source_ids: &[i64],
+    target_id: i64,
+) -> Result<Tag, Error> {
+    let mut conn = pool.acquire().await?;
+    let mut tx = conn.begin().await?;
+
+    // Fails with RowNotFound before anything is changed
+    sqlx::query_scalar::<_, i64>("SELECT id FROM tag WHERE id = ?")
+        .bind(target_id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+    for source_id in source_ids.iter().filter(|id| **id != target_id) {
+        sqlx::query(
+            r#"
+            INSERT OR IGNORE INTO entity_tag (tag_id, entity_type, entity_id, created_at)
+            SELECT ?, entity_type, entity_id, created_at
+            FROM entity_tag
+            WHERE tag_id = ?
+            "#,
+        )
+        .bind(target_id)
+        .bind(source_id)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query_scalar::<_, i64>("DELETE FROM tag WHERE id = ? RETURNING id")
+            .bind(source_id)
+            .fetch_one(&mut *tx)
+            .await?;
+    }
+
+    let tag = sqlx::query_as::<_, Tag>("SELECT * FROM tag WHERE id = ?")
+        .bind(target_id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+    Ok(tag)
+}
+
+// ======================================================
+// Delete
+// ======================================================
+pub async fn delete_tag(pool: &SqlitePool, id: i64) -> Result<i64, Error> {
+    sqlx::query_scalar("DELETE FROM tag WHERE id = ? RETURNING id")
+        .bind(id)
+        .fetch_one(pool)
+        .await
+}
+
+// ======================================================
+// Attach / Detach
+// ======================================================
+/// Links a tag to a record. Attaching twice is a no-op.
+/// Fails with RowNotFound if the tag or the record does not exist.
+pub async fn attach_tag(
+    pool: &SqlitePool,
+    tag_id: i64,
+    entity_type: EntityType,
+    entity_id: i64,
+) -> Result<(), Error> {
+    // entity_tag is polymorphic, so check the record by hand
+    sqlx::query_scalar::<_, i64>(&format!(
+        "SELECT id FROM {} WHERE id = ?",
+        entity_type.table_name()
+    ))
+    .bind(entity_id)
+    .fetch_one(pool)
+    .await?;
+    get_tag_by_id(pool, tag_id).await?;
+
+    sqlx::query(
+        "INSERT OR IGNORE INTO entity_tag (tag_id, entity_type, entity_id) VALUES (?, ?, ?)",
+    )
+    .bind(tag_id)
+    .bind(entity_type.as_str())
+    .bind(entity_id)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Returns whether a link was removed.
+pub async fn detach_tag(
+    pool: &SqlitePool,
+    tag_id: i64,
+    entity_type: EntityType,
+    entity_id: i64,
+) -> Result<bool, Error> {
+    let result = sqlx::query(
+        "DELETE FROM entity_tag WHERE tag_id = ? AND entity_type = ? AND entity_id = ?",
+    )
+    .bind(tag_id)
+    .bind(entity_type.as_str())
+    .bind(entity_id)
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected() > 0)
+}
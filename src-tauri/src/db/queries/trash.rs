@@ -0,0 +1,234 @@
+//! Soft delete: deleting a record stamps `deleted_at` instead of removing
+//! the row. Trashed records are left out of every read and can be restored
+//! until the trash is emptied.
+
+use crate::db::models::enums::EntityType;
+use chrono::{NaiveDateTime, SubsecRound, Utc};
+use serde::Serialize;
+use sqlx::{Error, FromRow, SqlitePool};
+
+/// Entity types in the order the trash is emptied: records first, then
+/// what they point to.
+const PURGE_ORDER: [EntityType; 7] = [
+    EntityType::Reminder,
+    EntityType::Note,
+    EntityType::Interaction,
+    EntityType::Application,
+    EntityType::Person,
+    EntityType::JobListing,
+    EntityType::Company,
+];
+
+/// One trashed record.
+#[derive(FromRow, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrashItem {
+    pub entity_type: EntityType,
+    pub id: i64,
+    pub label: String,
+    pub deleted_at: NaiveDateTime,
+}
+
+/// SQL expression naming a record of `entity_type` in the trash listing.
+fn label_sql(entity_type: EntityType) -> &'static str {
+    match entity_type {
+        EntityType::Company => "name",
+        EntityType::Person => "TRIM(first_name || ' ' || last_name)",
+        EntityType::JobListing => "title",
+        EntityType::Application => {
+            "COALESCE((SELECT j.title FROM job_listing j WHERE j.id = job_listing_id), \
+             'Application') || ' (' || applied_date || ')'"
+        }
+        EntityType::Interaction => "COALESCE(subject, interaction_type)",
+        EntityType::Note => "COALESCE(title, 'Note')",
+        EntityType::Reminder => "title",
+    }
+}
+
+fn now() -> NaiveDateTime {
+    // Same precision as CURRENT_TIMESTAMP
+    Utc::now().naive_utc().trunc_subsecs(0)
+}
+
+// ======================================================
+// Move to trash
+// ======================================================
+/// Trashes a live record. A company takes its job listings along, stamped
+/// with the same time so they are restored together.
+pub async fn move_to_trash(
+    pool: &SqlitePool,
+    entity_type: EntityType,
+    id: i64,
+) -> Result<i64, Error> {
+    let deleted_at = now();
+    let mut tx = pool.begin().await?;
+
+    let sql = format!(
+        "UPDATE {} SET deleted_at = ? WHERE id = ? AND deleted_at IS NULL RETURNING id",
+        entity_type.table_name()
+    );
+    let id = sqlx::query_scalar::<_, i64>(&sql)
+        .bind(deleted_at)
+        .bind(id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+    if entity_type == EntityType::Company {
+        sqlx::query(
+            "UPDATE job_listing SET deleted_at = ? WHERE company_id = ? AND deleted_at IS NULL",
+        )
+        .bind(deleted_at)
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    tx.commit().await?;
+    Ok(id)
+}
+
+// ======================================================
+// Restore
+// ======================================================
+/// The trashed record whose restore `entity_type`/`id` would need first;
+/// only job listings depend on their company that way.
+pub async fn trashed_parent(
+    pool: &SqlitePool,
+    entity_type: EntityType,
+    id: i64,
+) -> Result<Option<(EntityType, i64)>, Error> {
+    if entity_type != EntityType::JobListing {
+        return Ok(None);
+    }
+
+    let company_id = sqlx::query_scalar::<_, i64>(
+        r#"
+        SELECT c.id
+        FROM job_listing j
+        JOIN company c ON c.id = j.company_id
+        WHERE j.id = ? AND c.deleted_at IS NOT NULL
+        "#,
+    )
+    .bind(id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(company_id.map(|c| (EntityType::Company, c)))
+}
+
+/// Takes a record out of the trash. A company brings back the job
+/// listings that were trashed along with it.
+pub async fn restore(pool: &SqlitePool, entity_type: EntityType, id: i64) -> Result<i64, Error> {
+    let mut tx = pool.begin().await?;
+
+    if entity_type == EntityType::Company {
+        sqlx::query(
+            r#"
+            UPDATE job_listing
+            SET deleted_at = NULL
+            WHERE company_id = ?
+              AND deleted_at = (SELECT deleted_at FROM company WHERE id = ?)
+            "#,
+        )
+        .bind(id)
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    let sql = format!(
+        "UPDATE {} SET deleted_at = NULL WHERE id = ? AND deleted_at IS NOT NULL RETURNING id",
+        entity_type.table_name()
+    );
+    let id = sqlx::query_scalar::<_, i64>(&sql)
+        .bind(id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+    Ok(id)
+}
+
+// ======================================================
+// Archive
+// ======================================================
+/// Archives or unarchives a live record.
+pub async fn set_archived(
+    pool: &SqlitePool,
+    entity_type: EntityType,
+    id: i64,
+    archived: bool,
+) -> Result<i64, Error> {
+    let sql = format!(
+        "UPDATE {} SET archived_at = ? WHERE id = ? AND deleted_at IS NULL RETURNING id",
+        entity_type.table_name()
+    );
+    sqlx::query_scalar::<_, i64>(&sql)
+        .bind(archived.then(now))
+        .bind(id)
+        .fetch_one(pool)
+        .await
+}
+
+// ======================================================
+// List
+// ======================================================
+/// Trashed records of one or all entity types, most recently deleted first.
+pub async fn list_trash(
+    pool: &SqlitePool,
+    entity_type: Option<EntityType>,
+) -> Result<Vec<TrashItem>, Error> {
+    let selects: Vec<String> = PURGE_ORDER
+        .iter()
+        .filter(|t| entity_type.is_none_or(|e| e == **t))
+        .map(|t| {
+            format!(
+                "SELECT '{}' AS entity_type, id, {} AS label, deleted_at \
+                 FROM {} WHERE deleted_at IS NOT NULL",
+                t.as_str(),
+                label_sql(*t),
+                t.table_name()
+            )
+        })
+        .collect();
+
+    let sql = format!(
+        "SELECT * FROM ({}) ORDER BY deleted_at DESC, entity_type ASC, id DESC",
+        selects.join(" UNION ALL ")
+    );
+    sqlx::query_as::<_, TrashItem>(&sql).fetch_all(pool).await
+}
+
+// ======================================================
+// Empty
+// ======================================================
+/// Deletes trashed records for good, optionally only one entity type and
+/// only those trashed before `before`. Returns the number of records
+/// removed; linked records are updated by the usual foreign key actions.
+pub async fn empty_trash(
+    pool: &SqlitePool,
+    entity_type: Option<EntityType>,
+    before: Option<&NaiveDateTime>,
+) -> Result<u64, Error> {
+    let mut tx = pool.begin().await?;
+
+    let mut removed = 0;
+    for t in PURGE_ORDER
+        .iter()
+        .filter(|t| entity_type.is_none_or(|e| e == **t))
+    {
+        let sql = format!(
+            "DELETE FROM {} WHERE deleted_at IS NOT NULL AND (? IS NULL OR deleted_at < ?)",
+            t.table_name()
+        );
+        removed += sqlx::query(&sql)
+            .bind(before)
+            .bind(before)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+    }
+
+    tx.commit().await?;
+    Ok(removed)
+}
@@ -1 +1,81 @@
-pub const INIT_SQL: &str = include_str!("../../migrations/0001_init.sql");
+/// A single versioned schema migration embedded into the binary.
+pub struct Migration {
+    pub version: i64,
+    pub name: &'static str,
+    pub sql: &'static str,
+}
+
+/// All known migrations in ascending version order.
+/// Every new file under `migrations/` must be appended here.
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        name: "init",
+        sql: include_str!("../../migrations/0001_init.sql"),
+    },
+    Migration {
+        version: 2,
+        name: "search",
+        sql: include_str!("../../migrations/0002_search.sql"),
+    },
+    Migration {
+        version: 3,
+        name: "stage_history",
+        sql: include_str!("../../migrations/0003_stage_history.sql"),
+    },
+    Migration {
+        version: 4,
+        name: "tags",
+        sql: include_str!("../../migrations/0004_tags.sql"),
+    },
+    Migration {
+        version: 5,
+        name: "attachments",
+        sql: include_str!("../../migrations/0005_attachments.sql"),
+    },
+    Migration {
+        version: 6,
+        name: "application_contacts",
+        sql: include_str!("../../migrations/0006_application_contacts.sql"),
+    },
+    Migration {
+        version: 7,
+        name: "reminder_notifications",
+        sql: include_str!("../../migrations/0007_reminder_notifications.sql"),
+    },
+    Migration {
+        version: 8,
+        name: "reminder_schedule",
+        sql: include_str!("../../migrations/0008_reminder_schedule.sql"),
+    },
+    Migration {
+        version: 9,
+        name: "automation_rules",
+        sql: include_str!("../../migrations/0009_automation_rules.sql"),
+    },
+    Migration {
+        version: 10,
+        name: "ghosted_stage",
+        sql: include_str!("../../migrations/0010_ghosted_stage.sql"),
+    },
+    Migration {
+        version: 11,
+        name: "soft_delete",
+        sql: include_str!("../../migrations/0011_soft_delete.sql"),
+    },
+    Migration {
+        version: 12,
+        name: "audit_log",
+        sql: include_str!("../../migrations/0012_audit_log.sql"),
+    },
+    Migration {
+        version: 13,
+        name: "salary_normalization",
+        sql: include_str!("../../migrations/0013_salary_normalization.sql"),
+    },
+    Migration {
+        version: 14,
+        name: "audit_operations",
+        sql: include_str!("../../migrations/0014_audit_operations.sql"),
+    },
+];
@@ -1,13 +1,14 @@
+use crate::db::migrator::run_migrations;
 use sqlx::{Executor, SqlitePool};
 
 pub async fn setup_test_db() -> SqlitePool {
     // Create isolated in-memory SQLite database
     let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
 
-    // Load schema from migration
-    let schema =
-        std::fs::read_to_string("migrations/0001_init.sql").expect("Failed to read schema file");
-    pool.execute(schema.as_str()).await.unwrap();
+    // Apply all migrations up to head
+    run_migrations(&pool)
+        .await
+        .expect("Failed to apply migrations");
 
     // ======================================================
     // Seed minimal valid data for relational dependencies
@@ -0,0 +1,119 @@
+#[cfg(test)]
+mod tests {
+    use crate::db::models::enums::Stage;
+    use crate::db::queries::analytics::DateRange;
+    use crate::db::tests::test_utils::setup_test_db;
+    use crate::services::analytics_service::{build_pipeline_report, median};
+    use chrono::NaiveDate;
+    use sqlx::Executor;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_pipeline_report() {
+        // ======================================================
+        // Setup: three applications in January 2024
+        // ======================================================
+        let pool = setup_test_db().await;
+        pool.execute(
+            r#"
+            INSERT INTO company (id, name, industry) VALUES (2, 'Acme', 'Robotics');
+            INSERT INTO job_listing (id, company_id, title, work_type, seniority_level)
+            VALUES (2, 2, 'Engineer', 'hybrid', 'senior');
+
+            INSERT INTO application (id, job_listing_id, stage, applied_date) VALUES
+                (10, 2, 'applied', '2024-01-01'),
+                (11, 2, 'applied', '2024-01-03'),
+                (12, 1, 'applied', '2024-01-20');
+
+            UPDATE application SET stage = 'screening' WHERE id = 10;
+            UPDATE application SET stage = 'interviewing' WHERE id = 10;
+            UPDATE application SET stage = 'rejected' WHERE id = 11;
+
+            INSERT INTO interaction (interaction_type, interaction_date, application_id) VALUES
+                ('email', '2024-01-05', 10),
+                ('email', '2024-01-13', 11);
+            "#,
+        )
+        .await
+        .unwrap();
+
+        let range = DateRange {
+            from: Some(date("2024-01-01")),
+            to: Some(date("2024-01-31")),
+        };
+        let report = build_pipeline_report(&pool, &range)
+            .await
+            .expect("failed to build report");
+
+        // ======================================================
+        // Counts and funnel
+        // ======================================================
+        assert_eq!(report.total_applications, 3);
+
+        let reached: Vec<(Stage, i64)> = report
+            .funnel
+            .iter()
+            .map(|s| (s.stage.clone(), s.reached))
+            .take(5)
+            .collect();
+        assert_eq!(
+            reached,
+            vec![
+                (Stage::Applied, 3),
+                (Stage::Screening, 1),
+                (Stage::Assessment, 1),
+                (Stage::Interviewing, 1),
+                (Stage::Offered, 0),
+            ]
+        );
+        assert_eq!(report.funnel[0].conversion_rate, None);
+        assert_eq!(report.funnel[1].conversion_rate, Some(1.0 / 3.0));
+
+        // ======================================================
+        // Response times and rates
+        // ======================================================
+        assert_eq!(report.median_days_to_first_interaction, Some(7.0));
+        assert_eq!(report.overall_response_rate, Some(2.0 / 3.0));
+
+        let acme = &report.response_rates.by_company[0];
+        assert_eq!(
+            (acme.key.as_str(), acme.responded, acme.applications),
+            ("Acme", 2, 2)
+        );
+
+        let industries: Vec<&str> = report
+            .response_rates
+            .by_industry
+            .iter()
+            .map(|r| r.key.as_str())
+            .collect();
+        assert_eq!(industries, vec!["Robotics", "unknown"]);
+
+        // ======================================================
+        // Weekly volume (gaps filled with zero)
+        // ======================================================
+        let weeks: Vec<(NaiveDate, i64)> = report
+            .weekly_volume
+            .iter()
+            .map(|w| (w.week_start, w.count))
+            .collect();
+        assert_eq!(
+            weeks,
+            vec![
+                (date("2024-01-01"), 2),
+                (date("2024-01-08"), 0),
+                (date("2024-01-15"), 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_median() {
+        assert_eq!(median(&mut []), None);
+        assert_eq!(median(&mut [5.0, 1.0, 3.0]), Some(3.0));
+        assert_eq!(median(&mut [4.0, 1.0, 3.0, 2.0]), Some(2.5));
+    }
+}
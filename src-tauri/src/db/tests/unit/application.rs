@@ -1,6 +1,7 @@
 #[cfg(test)]
 mod tests {
     use crate::db::models::enums::Stage;
+    use crate::db::models::patch::Patch;
     use crate::db::queries::application::*;
     use crate::db::tests::test_utils::setup_test_db;
     use chrono::NaiveDate;
@@ -49,10 +50,10 @@ mod tests {
         let updated = update_application(
             &pool,
             created.id,
-            Some(1),
+            Patch::Set(1),
             Some(&Stage::Interviewing),
             Some(&new_date),
-            Some("Updated note"),
+            Patch::Set("Updated note"),
         )
         .await
         .expect("failed to update application");
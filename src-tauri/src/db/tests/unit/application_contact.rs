@@ -0,0 +1,89 @@
+#[cfg(test)]
+mod tests {
+    use crate::db::models::enums::ContactRole;
+    use crate::db::queries::application_contact::*;
+    use crate::db::tests::test_utils::setup_test_db;
+    use crate::error::AppError;
+    use crate::services::application_contact_service::*;
+
+    #[tokio::test]
+    async fn test_application_contacts() {
+        // ======================================================
+        // Setup: a second person and a second application
+        // ======================================================
+        let pool = setup_test_db().await;
+        sqlx::query(
+            r#"
+            INSERT INTO person (first_name, last_name) VALUES ('Ada', 'Lovelace');
+            INSERT INTO application (job_listing_id, stage, applied_date)
+            VALUES (1, 'applied', '2020-01-01');
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        // ======================================================
+        // Attach, and re-attach to change the role
+        // ======================================================
+        attach_contact(&pool, 1, 1, &ContactRole::Recruiter)
+            .await
+            .unwrap();
+        attach_contact(&pool, 1, 2, &ContactRole::Referrer)
+            .await
+            .unwrap();
+        attach_contact(&pool, 2, 1, &ContactRole::Interviewer)
+            .await
+            .unwrap();
+        let updated = attach_contact(&pool, 1, 1, &ContactRole::HiringManager)
+            .await
+            .unwrap();
+        assert_eq!(updated.role, ContactRole::HiringManager);
+
+        let err = attach_contact_service(&pool, &1, &99, &ContactRole::Other)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AppError::NotFound { .. }));
+
+        // ======================================================
+        // Both directions
+        // ======================================================
+        let people = get_contacts_for_application_service(&pool, &1)
+            .await
+            .unwrap()
+            .data
+            .unwrap();
+        let people = people.as_array().unwrap();
+        assert_eq!(people.len(), 2);
+        assert_eq!(people[0]["displayLabel"], "Doe, John");
+        assert_eq!(people[0]["contactRole"], "hiring_manager");
+        assert_eq!(people[1]["displayLabel"], "Lovelace, Ada");
+
+        let applications = get_applications_for_contact_service(&pool, &1)
+            .await
+            .unwrap()
+            .data
+            .unwrap();
+        let applications = applications.as_array().unwrap();
+        assert_eq!(applications.len(), 2);
+        // Most recent application first
+        assert_eq!(applications[0]["id"], 1);
+        assert_eq!(applications[1]["contactRole"], "interviewer");
+        assert!(applications[1]["displayLabel"].is_string());
+
+        // ======================================================
+        // Detach and cascades
+        // ======================================================
+        detach_contact_service(&pool, &1, &2).await.unwrap();
+        let err = detach_contact_service(&pool, &1, &2).await.unwrap_err();
+        assert!(matches!(err, AppError::NotFound { .. }));
+
+        sqlx::query("DELETE FROM application WHERE id = 2")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let remaining = list_application_contacts(&pool).await.unwrap();
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].application_id, 1);
+    }
+}
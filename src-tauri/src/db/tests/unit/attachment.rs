@@ -0,0 +1,178 @@
+#[cfg(test)]
+mod tests {
+    use crate::db::models::enums::EntityType;
+    use crate::db::tests::test_utils::setup_test_db;
+    use crate::error::AppError;
+    use crate::services::attachment_service::*;
+    use crate::utils::file_store::{guess_mime_type, FileStore};
+    use std::collections::HashSet;
+    use std::path::{Path, PathBuf};
+    use std::time::Duration;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "jobtrackr-{}-{}-{}",
+            name,
+            std::process::id(),
+            chrono::Utc::now().timestamp_nanos_opt().unwrap()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn stored_files(root: &Path) -> usize {
+        if !root.exists() {
+            return 0;
+        }
+        std::fs::read_dir(root)
+            .unwrap()
+            .map(|d| std::fs::read_dir(d.unwrap().path()).unwrap().count())
+            .sum()
+    }
+
+    #[tokio::test]
+    async fn test_attachments() {
+        // ======================================================
+        // Setup: two copies of one CV and a cover letter
+        // ======================================================
+        let pool = setup_test_db().await;
+        let dir = temp_dir("attachments");
+        let root = dir.join("store");
+        let store = FileStore::new(&root);
+
+        let cv = dir.join("cv-2025.pdf");
+        let cv_copy = dir.join("CV final.PDF");
+        let letter = dir.join("letter.docx");
+        std::fs::write(&cv, b"%PDF-1.4 my cv").unwrap();
+        std::fs::write(&cv_copy, b"%PDF-1.4 my cv").unwrap();
+        std::fs::write(&letter, b"dear hiring manager").unwrap();
+
+        // ======================================================
+        // Add and dedupe
+        // ======================================================
+        let first = add_attachment_service(&pool, &store, &cv, EntityType::Application, &1)
+            .await
+            .unwrap()
+            .data
+            .unwrap();
+        assert_eq!(first["filename"], "cv-2025.pdf");
+        assert_eq!(first["mimeType"], "application/pdf");
+        assert_eq!(first["sizeBytes"], 14);
+        assert_eq!(first["deduplicated"], false);
+        assert_eq!(first["sha256"].as_str().unwrap().len(), 64);
+
+        let second = add_attachment_service(&pool, &store, &cv_copy, EntityType::Company, &1)
+            .await
+            .unwrap()
+            .data
+            .unwrap();
+        assert_eq!(second["id"], first["id"]);
+        assert_eq!(second["deduplicated"], true);
+
+        add_attachment_service(&pool, &store, &letter, EntityType::Application, &1)
+            .await
+            .unwrap();
+        assert_eq!(stored_files(&root), 2);
+
+        // ======================================================
+        // Rejected input
+        // ======================================================
+        let err = add_attachment_service(&pool, &store, &cv, EntityType::Note, &1)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AppError::Validation { .. }));
+        let err = add_attachment_service(&pool, &store, &dir, EntityType::Company, &1)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AppError::Validation { .. }));
+        let err = add_attachment_service(&pool, &store, &cv, EntityType::Company, &99)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AppError::NotFound { .. }));
+
+        // ======================================================
+        // List and open
+        // ======================================================
+        let listed = list_attachments_service(&pool, EntityType::Application, &1)
+            .await
+            .unwrap()
+            .data
+            .unwrap();
+        assert_eq!(listed.as_array().unwrap().len(), 2);
+
+        let cv_id = first["id"].as_i64().unwrap();
+        let mut opened = None;
+        open_attachment_service(&pool, &store, &cv_id, |path| {
+            opened = Some(path.to_path_buf());
+            Ok(())
+        })
+        .await
+        .unwrap();
+        let opened = opened.unwrap();
+        assert!(opened.starts_with(&root));
+        assert_eq!(opened.extension().unwrap(), "pdf");
+        assert_eq!(std::fs::read(&opened).unwrap(), b"%PDF-1.4 my cv");
+
+        // ======================================================
+        // Unlink: the file stays while something links to it
+        // ======================================================
+        unlink_attachment_service(&pool, &store, &cv_id, EntityType::Application, &1)
+            .await
+            .unwrap();
+        assert!(opened.exists());
+
+        let report = unlink_attachment_service(&pool, &store, &cv_id, EntityType::Company, &1)
+            .await
+            .unwrap()
+            .data
+            .unwrap();
+        assert_eq!(report["removedRecords"], 1);
+        assert!(!opened.exists());
+        assert_eq!(stored_files(&root), 1);
+
+        // ======================================================
+        // Deleting the record orphans its files
+        // ======================================================
+        sqlx::query("DELETE FROM application WHERE id = 1")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let report = cleanup_orphans(&pool, &store).await.unwrap();
+        assert_eq!(report.removed_records, 1);
+        assert_eq!(stored_files(&root), 0);
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_file_store_sweep() {
+        let dir = temp_dir("store-sweep");
+        let store = FileStore::new(&dir);
+        let source = dir.join("a.txt");
+        std::fs::write(&source, b"a").unwrap();
+
+        let known = FileStore::storage_path_for(&"ab".repeat(32), "a.txt");
+        let stray = FileStore::storage_path_for(&"cd".repeat(32), "b.txt");
+        store.put(&source, &known).unwrap();
+        store.put(&source, &stray).unwrap();
+
+        // Young files survive
+        let keep: HashSet<String> = [known.clone()].into();
+        assert!(store
+            .sweep(&keep, Duration::from_secs(3600))
+            .unwrap()
+            .is_empty());
+
+        assert_eq!(
+            store.sweep(&keep, Duration::ZERO).unwrap(),
+            vec![stray.clone()]
+        );
+        assert!(store.path_of(&known).exists());
+        assert!(!store.path_of(&stray).exists());
+
+        assert_eq!(guess_mime_type("Offer.PDF"), "application/pdf");
+        assert_eq!(guess_mime_type("notes"), "application/octet-stream");
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,163 @@
+#[cfg(test)]
+mod tests {
+    use crate::db::models::enums::EntityType;
+    use crate::db::models::patch::Patch;
+    use crate::db::queries::audit::{get_history, AuditAction};
+    use crate::db::queries::note::get_note_by_id;
+    use crate::db::tests::test_utils::setup_test_db;
+    use crate::error::AppError;
+    use crate::services::audit_service::*;
+    use crate::services::note_service::*;
+    use crate::services::trash_service::*;
+    use serde_json::json;
+
+    async fn update_title(pool: &sqlx::SqlitePool, title: &str) {
+        update_note_service(
+            pool,
+            &1,
+            Patch::Unchanged,
+            Patch::Unchanged,
+            Patch::Unchanged,
+            Patch::Unchanged,
+            Patch::Unchanged,
+            Patch::Unchanged,
+            Patch::Set(title),
+            Patch::Unchanged,
+        )
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_history_records_changes() {
+        let pool = setup_test_db().await;
+
+        // ======================================================
+        // Create, update, no-op update, delete
+        // ======================================================
+        let created = create_note_service(
+            &pool,
+            None,
+            None,
+            Some(1),
+            None,
+            None,
+            None,
+            Some("Draft"),
+            Some("Body"),
+        )
+        .await
+        .unwrap();
+        let id = created.data.unwrap()["id"].as_i64().unwrap();
+
+        let history = get_history(&pool, EntityType::Note, id).await.unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].action, AuditAction::Create);
+        assert_eq!(history[0].origin.as_deref(), Some("note.Create"));
+        assert_eq!(
+            history[0].changes["title"],
+            json!({ "before": null, "after": "Draft" })
+        );
+        assert!(history[0].changes.get("createdAt").is_none());
+
+        update_title(&pool, "Renamed").await;
+        update_title(&pool, "Renamed").await;
+
+        let history = get_history(&pool, EntityType::Note, 1).await.unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].action, AuditAction::Update);
+        assert_eq!(
+            history[0].changes,
+            json!({ "title": { "before": "Initial Note", "after": "Renamed" } })
+        );
+
+        delete_note_service(&pool, &1).await.unwrap();
+        restore_service(&pool, EntityType::Note, &1).await.unwrap();
+
+        let history = get_history_service(&pool, EntityType::Note, &1)
+            .await
+            .unwrap()
+            .data
+            .unwrap();
+        let actions: Vec<_> = history
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|e| e["action"].clone())
+            .collect();
+        assert_eq!(
+            actions,
+            vec![json!("restore"), json!("delete"), json!("update")]
+        );
+        assert!(history[1]["changes"]["deletedAt"]["after"].is_string());
+    }
+
+    #[tokio::test]
+    async fn test_revert() {
+        let pool = setup_test_db().await;
+
+        update_title(&pool, "Second").await;
+        update_title(&pool, "Third").await;
+        let history = get_history(&pool, EntityType::Note, 1).await.unwrap();
+        let (third, second) = (history[0].id, history[1].id);
+
+        // ======================================================
+        // Back to the version right after the first update
+        // ======================================================
+        let reverted = revert_service(&pool, EntityType::Note, &1, &second)
+            .await
+            .unwrap();
+        assert_eq!(reverted.data.unwrap()["title"], "Second");
+        assert_eq!(
+            get_note_by_id(&pool, 1).await.unwrap().title.as_deref(),
+            Some("Second")
+        );
+
+        // The revert is recorded and can itself be undone
+        let history = get_history(&pool, EntityType::Note, 1).await.unwrap();
+        assert_eq!(history[0].action, AuditAction::Revert);
+        assert_eq!(history[0].origin.as_deref(), Some("audit.Revert"));
+        revert_service(&pool, EntityType::Note, &1, &third)
+            .await
+            .unwrap();
+        assert_eq!(
+            get_note_by_id(&pool, 1).await.unwrap().title.as_deref(),
+            Some("Third")
+        );
+
+        // ======================================================
+        // Errors
+        // ======================================================
+        let err = revert_service(&pool, EntityType::Company, &1, &second)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AppError::Validation { .. }));
+
+        let err = revert_service(&pool, EntityType::Note, &1, &9999)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AppError::NotFound { .. }));
+
+        delete_note_service(&pool, &1).await.unwrap();
+        let err = revert_service(&pool, EntityType::Note, &1, &second)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AppError::Validation { .. }));
+    }
+
+    #[test]
+    fn test_diff() {
+        let before = json!({ "id": 1, "name": "A", "city": null, "updatedAt": "x" });
+        let after = json!({ "id": 1, "name": "B", "city": null, "updatedAt": "y" });
+
+        assert_eq!(
+            diff(Some(&before), Some(&after)),
+            json!({ "name": { "before": "A", "after": "B" } })
+        );
+        assert_eq!(
+            diff(Some(&before), None),
+            json!({ "name": { "before": "A", "after": null } })
+        );
+        assert_eq!(diff(Some(&before), Some(&before)), json!({}));
+    }
+}
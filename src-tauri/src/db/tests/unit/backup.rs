@@ -0,0 +1,116 @@
+#[cfg(test)]
+mod tests {
+    use crate::db::backup::*;
+    use crate::db::connection::init_db;
+    use chrono::{NaiveDate, NaiveDateTime};
+    use sqlx::SqlitePool;
+    use std::path::PathBuf;
+
+    fn temp_db_path(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "jobtrackr-{}-{}-{}",
+            name,
+            std::process::id(),
+            chrono::Utc::now().timestamp_nanos_opt().unwrap()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir.join("jobtrackr.db")
+    }
+
+    fn snapshot(day: (i32, u32, u32), hour: u32, kind: BackupKind) -> BackupFile {
+        let created_at: NaiveDateTime = NaiveDate::from_ymd_opt(day.0, day.1, day.2)
+            .unwrap()
+            .and_hms_opt(hour, 0, 0)
+            .unwrap();
+        BackupFile {
+            file_name: format!("{}-{}", created_at, kind.as_str()),
+            path: PathBuf::new(),
+            kind,
+            created_at,
+            size_bytes: 0,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_and_list_snapshot() {
+        // ======================================================
+        // Setup
+        // ======================================================
+        // VACUUM INTO needs a file-backed database
+        let db_path = temp_db_path("backup");
+        let pool = init_db(&db_path).await.expect("failed to open database");
+        sqlx::query("INSERT INTO company (name) VALUES ('Snapshot Corp')")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        // ======================================================
+        // Create
+        // ======================================================
+        let first = create_snapshot(&pool, &db_path, BackupKind::Manual)
+            .await
+            .expect("failed to create snapshot");
+        let second = create_snapshot(&pool, &db_path, BackupKind::Daily)
+            .await
+            .expect("failed to create second snapshot");
+
+        assert!(first.path.exists());
+        assert!(first.size_bytes > 0);
+        assert_ne!(first.file_name, second.file_name);
+
+        // ======================================================
+        // List
+        // ======================================================
+        let listed = list_snapshots(&db_path).expect("failed to list snapshots");
+        assert_eq!(listed.len(), 2);
+        assert!(listed.iter().any(|b| b.kind == BackupKind::Manual));
+        assert!(listed.iter().any(|b| b.kind == BackupKind::Daily));
+
+        // ======================================================
+        // Snapshot contains the data
+        // ======================================================
+        let copy = SqlitePool::connect(&format!("sqlite://{}", first.path.display()))
+            .await
+            .unwrap();
+        let name: String = sqlx::query_scalar("SELECT name FROM company WHERE id = 1")
+            .fetch_one(&copy)
+            .await
+            .unwrap();
+        assert_eq!(name, "Snapshot Corp");
+        copy.close().await;
+        pool.close().await;
+
+        let _ = std::fs::remove_dir_all(db_path.parent().unwrap());
+    }
+
+    #[test]
+    fn test_select_expired_keeps_daily_and_weekly() {
+        // Newest first, as returned by list_snapshots
+        let backups = vec![
+            snapshot((2026, 3, 20), 12, BackupKind::Daily),
+            snapshot((2026, 3, 20), 9, BackupKind::PreMigration),
+            snapshot((2026, 3, 19), 12, BackupKind::Daily),
+            snapshot((2026, 3, 18), 12, BackupKind::Daily),
+            snapshot((2026, 3, 10), 12, BackupKind::Daily),
+            snapshot((2026, 3, 9), 12, BackupKind::Daily),
+            snapshot((2026, 2, 1), 12, BackupKind::Manual),
+            snapshot((2026, 1, 5), 12, BackupKind::Daily),
+        ];
+
+        let expired: Vec<String> = select_expired(&backups, 2, 2)
+            .into_iter()
+            .map(|b| b.file_name.clone())
+            .collect();
+
+        // Kept: 03-20 12:00 (day + week 12), 03-19 (day), 03-10 (week 11), manual
+        assert_eq!(
+            expired,
+            vec![
+                backups[1].file_name.clone(),
+                backups[3].file_name.clone(),
+                backups[5].file_name.clone(),
+                backups[7].file_name.clone(),
+            ]
+        );
+    }
+}
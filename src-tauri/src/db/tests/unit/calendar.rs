@@ -0,0 +1,115 @@
+#[cfg(test)]
+mod tests {
+    use crate::db::tests::test_utils::setup_test_db;
+    use crate::services::calendar_service::*;
+    use crate::utils::ical_utils::fold_line;
+
+    #[tokio::test]
+    async fn test_build_calendar() {
+        // ======================================================
+        // Setup: one pending reminder, one completed, one interview
+        // ======================================================
+        let pool = setup_test_db().await;
+        sqlx::query(
+            r#"
+            INSERT INTO reminder (application_id, reminder_date, title, is_completed)
+            VALUES (1, '2025-01-01', 'Already done', 1);
+            INSERT INTO interaction (interaction_type, interaction_date, summary, medium, application_id, person_id)
+            VALUES ('interview', '2025-03-04', 'Tech round; bring laptop, charger', 'video', 1, 1);
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        // ======================================================
+        // Events (default)
+        // ======================================================
+        let (ics, counts) = build_calendar(&pool, "default", ReminderComponent::Event)
+            .await
+            .unwrap();
+        assert_eq!(counts.reminders, 1);
+        assert_eq!(counts.interviews, 1);
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+        assert!(ics.ends_with("END:VCALENDAR\r\n"));
+        assert_eq!(ics.matches("BEGIN:VEVENT").count(), 2);
+        assert!(!ics.contains("Already done"));
+
+        assert!(ics.contains("UID:jobtrackr-reminder-1.default@jobtrackr\r\n"));
+        assert!(ics.contains("UID:jobtrackr-interview-2.default@jobtrackr\r\n"));
+        assert!(ics.contains("SUMMARY:Interview with Default Company\r\n"));
+        assert!(ics.contains("DTSTART;VALUE=DATE:20250304\r\nDTEND;VALUE=DATE:20250305\r\n"));
+
+        // Context and escaped body in the description, unfolded for comparison
+        let unfolded = ics.replace("\r\n ", "");
+        assert!(unfolded.contains(
+            "DESCRIPTION:Company: Default Company\\nInterviewer: John Doe\\nJob: Default Job\\nMedium: video\\n\\nTech round\\; bring laptop\\, charger\r\n"
+        ));
+        assert!(ics.split("\r\n").all(|line| line.len() <= 75));
+
+        // Same data, same document
+        let (again, _) = build_calendar(&pool, "default", ReminderComponent::Event)
+            .await
+            .unwrap();
+        assert_eq!(ics, again);
+
+        // ======================================================
+        // To-dos
+        // ======================================================
+        let (ics, _) = build_calendar(&pool, "work", ReminderComponent::Todo)
+            .await
+            .unwrap();
+        assert_eq!(ics.matches("BEGIN:VTODO").count(), 1);
+        assert_eq!(ics.matches("BEGIN:VEVENT").count(), 1);
+        assert!(ics.contains("UID:jobtrackr-reminder-1.work@jobtrackr\r\n"));
+        assert!(ics.contains("STATUS:NEEDS-ACTION\r\n"));
+
+        // ======================================================
+        // Timed, recurring reminders
+        // ======================================================
+        sqlx::query(
+            r#"
+            UPDATE reminder
+            SET reminder_date = '2025-03-03',
+                reminder_time = '14:30:00',
+                timezone = 'Europe/Berlin',
+                recurrence = 'FREQ=WEEKLY;INTERVAL=1;UNTIL=20250331'
+            WHERE id = 1
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let (ics, _) = build_calendar(&pool, "default", ReminderComponent::Event)
+            .await
+            .unwrap();
+        assert!(ics.contains("DTSTART;TZID=Europe/Berlin:"));
+        assert!(ics.contains("T143000\r\nDURATION:PT30M\r\n"));
+        // UNTIL in UTC: the end of March 31 in Berlin (CEST)
+        assert!(ics.contains("RRULE:FREQ=WEEKLY;INTERVAL=1;UNTIL=20250331T215959Z\r\n"));
+        // The zone is defined in the calendar, with its DST changes
+        assert_eq!(ics.matches("BEGIN:VTIMEZONE\r\nTZID:Europe/Berlin\r\n").count(), 1);
+        assert!(ics.contains(
+            "BEGIN:DAYLIGHT\r\nDTSTART:20250330T020000\r\nTZOFFSETFROM:+0100\r\nTZOFFSETTO:+0200\r\nTZNAME:CEST\r\nEND:DAYLIGHT\r\n"
+        ));
+        assert!(ics.contains(
+            "BEGIN:STANDARD\r\nDTSTART:20251026T030000\r\nTZOFFSETFROM:+0200\r\nTZOFFSETTO:+0100\r\nTZNAME:CET\r\nEND:STANDARD\r\n"
+        ));
+
+        let (ics, _) = build_calendar(&pool, "default", ReminderComponent::Todo)
+            .await
+            .unwrap();
+        assert_eq!(ics.matches("DTSTART;TZID=Europe/Berlin:").count(), 1);
+        assert_eq!(ics.matches("DUE;TZID=Europe/Berlin:").count(), 1);
+    }
+
+    #[test]
+    fn test_fold_line_keeps_characters_whole() {
+        let line = format!("SUMMARY:{}", "é".repeat(60));
+        let folded = fold_line(&line);
+
+        assert!(folded.split("\r\n").all(|l| l.len() <= 75));
+        assert_eq!(folded.replace("\r\n ", ""), line);
+    }
+}
@@ -1,6 +1,7 @@
 #[cfg(test)]
 mod tests {
     use crate::db::models::enums::WorkType;
+    use crate::db::models::patch::Patch;
     use crate::db::queries::company::*;
     use crate::db::tests::test_utils::setup_test_db;
 
@@ -55,14 +56,14 @@ mod tests {
             &pool,
             created.id,
             Some("Updated Corp"),
-            Some("Updated Street 9"),
-            Some("99999"),
-            Some("Hamburg"),
-            Some("Germany"),
-            Some(&WorkType::Remote),
-            Some("Consulting"),
-            Some("https://updated.test"),
-            Some("+4900000000"),
+            Patch::Set("Updated Street 9"),
+            Patch::Set("99999"),
+            Patch::Set("Hamburg"),
+            Patch::Set("Germany"),
+            Patch::Set(&WorkType::Remote),
+            Patch::Set("Consulting"),
+            Patch::Set("https://updated.test"),
+            Patch::Set("+4900000000"),
         )
         .await
         .expect("failed to update company");
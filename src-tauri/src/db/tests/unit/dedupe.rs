@@ -0,0 +1,239 @@
+#[cfg(test)]
+mod tests {
+    use crate::db::connection::DbState;
+    use crate::db::queries::company::get_company_by_id;
+    use crate::db::queries::job_listing::get_job_listing_by_id;
+    use crate::db::tests::test_utils::setup_test_db;
+    use crate::error::AppError;
+    use crate::services::dedupe_service::*;
+    use crate::services::undo_service::{redo_service, undo_service, undoable};
+    use crate::utils::similarity::*;
+    use sqlx::SqlitePool;
+
+    async fn insert(pool: &SqlitePool, sql: &str) -> i64 {
+        sqlx::query(sql)
+            .execute(pool)
+            .await
+            .unwrap()
+            .last_insert_rowid()
+    }
+
+    async fn tag_ids(pool: &SqlitePool, company_id: i64) -> Vec<i64> {
+        sqlx::query_scalar(
+            "SELECT tag_id FROM entity_tag WHERE entity_type = 'company' AND entity_id = ? ORDER BY tag_id",
+        )
+        .bind(company_id)
+        .fetch_all(pool)
+        .await
+        .unwrap()
+    }
+
+    #[test]
+    fn test_normalization() {
+        assert_eq!(normalize_company_name("ACME GmbH"), "acme");
+        assert_eq!(normalize_company_name("Acme, Inc."), "acme");
+        assert_eq!(normalize_company_name("acme.com"), "acme");
+        assert_eq!(normalize_company_name("The Company"), "the company");
+        assert_eq!(normalize_person_name("Doe", " jane"), "doe jane");
+
+        assert_eq!(
+            website_domain("https://www.Acme.com/jobs?x=1").as_deref(),
+            Some("acme.com")
+        );
+        assert_eq!(website_domain("localhost"), None);
+        assert_eq!(
+            linkedin_handle("https://www.linkedin.com/in/Jane-Doe/").as_deref(),
+            Some("jane-doe")
+        );
+        assert!(same_phone("+45 12 34 56 78", "0045 12345678"));
+        assert!(same_phone("+45 12345678", "12 34 56 78"));
+        assert!(!same_phone("123", "123"));
+
+        assert_eq!(similarity("acme", "acme"), 1.0);
+        assert_eq!(similarity("acme", "acne"), 0.75);
+        assert_eq!(similarity("", "abc"), 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_find_duplicates() {
+        let pool = setup_test_db().await;
+        let acme = insert(&pool, "INSERT INTO company (name) VALUES ('Acme')").await;
+        let gmbh = insert(&pool, "INSERT INTO company (name) VALUES ('ACME GmbH')").await;
+        let site = insert(
+            &pool,
+            "INSERT INTO company (name, website) VALUES ('Acme Holding', 'https://acme.com')",
+        )
+        .await;
+        insert(&pool, "INSERT INTO company (name) VALUES ('Globex')").await;
+
+        let found = find_duplicate_companies_service(&pool, None)
+            .await
+            .unwrap()
+            .data
+            .unwrap();
+        let pairs = found.as_array().unwrap();
+        assert_eq!(pairs.len(), 3);
+        assert_eq!(pairs[0]["first"]["id"], acme);
+        assert_eq!(pairs[0]["second"]["id"], gmbh);
+        assert_eq!(pairs[0]["reasons"], serde_json::json!(["name"]));
+        assert!(pairs
+            .iter()
+            .any(|p| p["second"]["id"] == site && p["reasons"][0] == "domain"));
+
+        let err = find_duplicate_companies_service(&pool, Some(1.5))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AppError::Validation { .. }));
+
+        // Contact details outweigh names
+        insert(
+            &pool,
+            "INSERT INTO person (first_name, last_name, email) VALUES ('J.', 'Doe', 'JANE@example.com ')",
+        )
+        .await;
+        insert(
+            &pool,
+            "INSERT INTO person (first_name, last_name, email) VALUES ('Jane', 'Doe', 'jane@example.com')",
+        )
+        .await;
+        let found = find_duplicate_persons_service(&pool, Some(0.9))
+            .await
+            .unwrap()
+            .data
+            .unwrap();
+        assert_eq!(found.as_array().unwrap().len(), 1);
+        assert_eq!(found[0]["reasons"], serde_json::json!(["email"]));
+    }
+
+    #[tokio::test]
+    async fn test_merge_companies() {
+        let pool = setup_test_db().await;
+        let db = DbState::default();
+        db.replace(pool.clone());
+
+        let dup = insert(
+            &pool,
+            "INSERT INTO company (name, website) VALUES ('Default Co', 'default.com')",
+        )
+        .await;
+        let listing = insert(
+            &pool,
+            &format!(
+                "INSERT INTO job_listing (company_id, title) VALUES ({}, 'Second Job')",
+                dup
+            ),
+        )
+        .await;
+        insert(
+            &pool,
+            &format!("UPDATE person SET company_id = {} WHERE id = 1", dup),
+        )
+        .await;
+        insert(
+            &pool,
+            &format!(
+                r#"
+                INSERT INTO tag (name) VALUES ('shared'), ('extra');
+                INSERT INTO entity_tag (tag_id, entity_type, entity_id) VALUES
+                    (1, 'company', 1), (1, 'company', {dup}), (2, 'company', {dup});
+                "#
+            ),
+        )
+        .await;
+
+        // ======================================================
+        // Merge: references move, gaps are filled, the duplicate is trashed
+        // ======================================================
+        let merged = undoable(&db, &pool, merge_companies_service(&pool, &1, &dup))
+            .await
+            .unwrap();
+        assert_eq!(merged.data.unwrap()["moved"], 2);
+
+        assert_eq!(
+            get_job_listing_by_id(&pool, listing)
+                .await
+                .unwrap()
+                .company_id,
+            1
+        );
+        let person_company: Option<i64> =
+            sqlx::query_scalar("SELECT company_id FROM person WHERE id = 1")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(person_company, Some(1));
+        let survivor = get_company_by_id(&pool, 1).await.unwrap();
+        assert_eq!(survivor.website.as_deref(), Some("default.com"));
+        assert!(get_company_by_id(&pool, dup).await.is_err());
+        assert_eq!(tag_ids(&pool, 1).await, vec![1, 2]);
+
+        // ======================================================
+        // Undo puts everything back
+        // ======================================================
+        undo_service(&pool, &db, None).await.unwrap();
+        assert_eq!(
+            get_job_listing_by_id(&pool, listing)
+                .await
+                .unwrap()
+                .company_id,
+            dup
+        );
+        assert_eq!(get_company_by_id(&pool, 1).await.unwrap().website, None);
+        assert!(get_company_by_id(&pool, dup).await.is_ok());
+        assert_eq!(tag_ids(&pool, 1).await, vec![1]);
+        assert_eq!(tag_ids(&pool, dup).await, vec![1, 2]);
+
+        redo_service(&pool, &db, None).await.unwrap();
+        assert_eq!(tag_ids(&pool, 1).await, vec![1, 2]);
+
+        // ======================================================
+        // Errors
+        // ======================================================
+        let err = merge_companies_service(&pool, &1, &1).await.unwrap_err();
+        assert!(matches!(err, AppError::Validation { .. }));
+        let err = merge_companies_service(&pool, &1, &9999).await.unwrap_err();
+        assert!(matches!(err, AppError::NotFound { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_undo_person_merge_contacts() {
+        let pool = setup_test_db().await;
+        let db = DbState::default();
+        db.replace(pool.clone());
+
+        let dup = insert(
+            &pool,
+            "INSERT INTO person (first_name, last_name) VALUES ('Jane', 'Doe')",
+        )
+        .await;
+        insert(
+            &pool,
+            &format!(
+                "INSERT INTO application_contact (application_id, person_id, role) VALUES (1, {}, 'recruiter')",
+                dup
+            ),
+        )
+        .await;
+        let contacts = |person_id: i64| {
+            let pool = pool.clone();
+            async move {
+                sqlx::query_as::<_, (i64, String)>(
+                    "SELECT application_id, role FROM application_contact WHERE person_id = ?",
+                )
+                .bind(person_id)
+                .fetch_all(&pool)
+                .await
+                .unwrap()
+            }
+        };
+
+        undoable(&db, &pool, merge_persons_service(&pool, &1, &dup))
+            .await
+            .unwrap();
+        assert_eq!(contacts(1).await, vec![(1, "recruiter".to_string())]);
+
+        undo_service(&pool, &db, None).await.unwrap();
+        assert_eq!(contacts(1).await, vec![]);
+        assert_eq!(contacts(dup).await, vec![(1, "recruiter".to_string())]);
+    }
+}
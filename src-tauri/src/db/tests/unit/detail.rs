@@ -0,0 +1,88 @@
+#[cfg(test)]
+mod tests {
+    use crate::db::tests::test_utils::setup_test_db;
+    use crate::error::AppError;
+    use crate::services::detail_service::*;
+
+    #[tokio::test]
+    async fn test_get_detail() {
+        // ======================================================
+        // Setup: the seeded records are all related to each other
+        // ======================================================
+        let pool = setup_test_db().await;
+        sqlx::query(
+            r#"
+            UPDATE application SET stage = 'screening' WHERE id = 1;
+            INSERT INTO application_contact (application_id, person_id, role)
+            VALUES (1, 1, 'recruiter');
+            INSERT INTO reminder (company_id, reminder_date, title)
+            VALUES (1, '2025-01-01', 'Earlier');
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        // ======================================================
+        // Company
+        // ======================================================
+        let company = get_company_detail_service(&pool, &1)
+            .await
+            .unwrap()
+            .data
+            .unwrap();
+        assert_eq!(company["company"]["displayLabel"], "Default Company");
+        assert_eq!(company["jobListings"][0]["displayLabel"], "Default Job");
+        assert_eq!(company["persons"][0]["displayLabel"], "Doe, John");
+        assert_eq!(company["interactions"].as_array().unwrap().len(), 1);
+        assert_eq!(company["notes"].as_array().unwrap().len(), 1);
+        // Reminders in date order
+        assert_eq!(company["reminders"].as_array().unwrap().len(), 2);
+        assert_eq!(company["reminders"][0]["title"], "Earlier");
+        assert!(company["company"]["tags"].is_array());
+        assert!(company["attachments"].as_array().unwrap().is_empty());
+
+        // ======================================================
+        // Application
+        // ======================================================
+        let application = get_application_detail_service(&pool, &1)
+            .await
+            .unwrap()
+            .data
+            .unwrap();
+        assert_eq!(application["application"]["id"], 1);
+        assert_eq!(application["jobListing"]["title"], "Default Job");
+        assert_eq!(application["company"]["name"], "Default Company");
+        assert_eq!(application["stageHistory"].as_array().unwrap().len(), 2);
+        assert_eq!(application["contacts"][0]["contactRole"], "recruiter");
+        assert_eq!(application["interactions"].as_array().unwrap().len(), 1);
+        assert_eq!(application["notes"].as_array().unwrap().len(), 1);
+        assert_eq!(application["reminders"].as_array().unwrap().len(), 1);
+
+        // ======================================================
+        // Job listing and person
+        // ======================================================
+        let listing = get_job_listing_detail_service(&pool, &1)
+            .await
+            .unwrap()
+            .data
+            .unwrap();
+        assert_eq!(listing["company"]["id"], 1);
+        assert_eq!(listing["applications"][0]["id"], 1);
+
+        let person = get_person_detail_service(&pool, &1)
+            .await
+            .unwrap()
+            .data
+            .unwrap();
+        assert_eq!(person["company"]["id"], 1);
+        assert_eq!(person["applications"][0]["contactRole"], "recruiter");
+        assert_eq!(person["interactions"].as_array().unwrap().len(), 1);
+
+        // ======================================================
+        // Missing record
+        // ======================================================
+        let err = get_company_detail_service(&pool, &99).await.unwrap_err();
+        assert!(matches!(err, AppError::NotFound { .. }));
+    }
+}
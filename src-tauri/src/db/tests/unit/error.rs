@@ -0,0 +1,103 @@
+#[cfg(test)]
+mod tests {
+    use crate::db::tests::test_utils::setup_test_db;
+    use crate::error::*;
+    use crate::services::company_service::*;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_sqlx_errors_are_classified() {
+        // ======================================================
+        // Setup
+        // ======================================================
+        let pool = setup_test_db().await;
+
+        // ======================================================
+        // Constraint violations
+        // ======================================================
+        let check = sqlx::query("UPDATE application SET stage = 'bogus' WHERE id = 1")
+            .execute(&pool)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            AppError::from(check),
+            AppError::ConstraintViolation {
+                constraint: ConstraintKind::Check,
+                ..
+            }
+        ));
+
+        let not_null = sqlx::query("INSERT INTO company (name) VALUES (NULL)")
+            .execute(&pool)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            AppError::from(not_null),
+            AppError::ConstraintViolation {
+                constraint: ConstraintKind::NotNull,
+                ..
+            }
+        ));
+
+        // ======================================================
+        // Unique violations are conflicts
+        // ======================================================
+        let duplicate = sqlx::query("INSERT INTO company (id, name) VALUES (1, 'Duplicate')")
+            .execute(&pool)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            AppError::from(duplicate),
+            AppError::Conflict { .. }
+        ));
+
+        // Deferred to the end of the statement, so it leaves the write pending; keep it last
+        let foreign_key =
+            sqlx::query("INSERT INTO job_listing (company_id, title) VALUES (999, 'x')")
+                .execute(&pool)
+                .await
+                .unwrap_err();
+        assert!(matches!(
+            AppError::from(foreign_key),
+            AppError::ConstraintViolation {
+                constraint: ConstraintKind::ForeignKey,
+                ..
+            }
+        ));
+
+        // ======================================================
+        // Not found (service level, with context)
+        // ======================================================
+        let err = get_company_by_id_service(&pool, &999).await.unwrap_err();
+        assert_eq!(
+            err,
+            AppError::not_found("Failed to retrieve company 999: record not found")
+        );
+        assert_eq!(
+            serde_json::to_value(&err).unwrap(),
+            json!({"kind": "notFound", "message": "Failed to retrieve company 999: record not found"})
+        );
+    }
+
+    #[test]
+    fn test_validation_error_shape() {
+        let err =
+            AppError::invalid_field("appliedDate", "Invalid date format. Expected YYYY-MM-DD.");
+
+        assert_eq!(
+            serde_json::to_value(&err).unwrap(),
+            json!({
+                "kind": "validation",
+                "message": "appliedDate: Invalid date format. Expected YYYY-MM-DD.",
+                "fields": [
+                    {"field": "appliedDate", "message": "Invalid date format. Expected YYYY-MM-DD."}
+                ]
+            })
+        );
+
+        // Legacy string form keeps the old `status` marker
+        let legacy: serde_json::Value = serde_json::from_str(&String::from(err)).unwrap();
+        assert_eq!(legacy["status"], json!("error"));
+        assert_eq!(legacy["kind"], json!("validation"));
+    }
+}
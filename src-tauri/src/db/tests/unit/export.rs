@@ -0,0 +1,246 @@
+#[cfg(test)]
+mod tests {
+    use crate::db::migrator::run_migrations;
+    use crate::db::queries::attachment::Attachment;
+    use crate::db::tests::test_utils::setup_test_db;
+    use crate::services::export_service::*;
+    use crate::utils::file_store::FileStore;
+    use sqlx::SqlitePool;
+
+    async fn count(pool: &SqlitePool, table: &str) -> i64 {
+        sqlx::query_scalar(&format!("SELECT COUNT(*) FROM {}", table))
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_export_restore_and_merge() {
+        // ======================================================
+        // Setup: seeded DB with some stage history
+        // ======================================================
+        let pool = setup_test_db().await;
+        sqlx::query(
+            r#"
+            UPDATE application SET stage = 'screening' WHERE id = 1;
+            UPDATE reminder SET notified_at = '2025-01-01 08:00:00', snoozed_until = '2025-01-02 08:00:00' WHERE id = 1;
+            INSERT INTO tag (name) VALUES ('remote');
+            INSERT INTO entity_tag (tag_id, entity_type, entity_id) VALUES (1, 'company', 1);
+            INSERT INTO attachment (sha256, filename, mime_type, size_bytes, storage_path)
+            VALUES (printf('%064d', 7), 'cv.pdf', 'application/pdf', 14, '00/cv.pdf');
+            INSERT INTO attachment_link (attachment_id, entity_type, entity_id) VALUES (1, 'application', 1);
+            INSERT INTO automation_rule (name, rule_trigger, rule_action)
+            VALUES ('Screen', '{"event":"application_created"}', '{"type":"set_stage","stage":"screening"}');
+            INSERT INTO automation_rule_run (rule_id, application_id, anchor, entity_type, entity_id)
+            VALUES (1, 1, '2025-01-01', 'reminder', 1);
+            INSERT INTO exchange_rate (currency, rate, is_base) VALUES ('EUR', 1, 1), ('USD', 0.9, 0);
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let bundle = build_export(&pool).await.unwrap();
+        assert_eq!(bundle.format, EXPORT_FORMAT);
+        assert_eq!(bundle.data.companies.len(), 1);
+        assert_eq!(bundle.data.reminders.len(), 1);
+        assert!(bundle.data.reminders[0].snoozed_until.is_some());
+        assert_eq!(bundle.data.application_stage_events.len(), 2);
+        assert_eq!(bundle.data.attachments.len(), 1);
+        assert_eq!(bundle.data.attachment_links.len(), 1);
+        assert_eq!(bundle.data.automation_rules.len(), 1);
+        assert_eq!(bundle.data.automation_rule_runs.len(), 1);
+        assert_eq!(bundle.data.exchange_rates.len(), 2);
+        assert!(bundle.data.exchange_rates[0].is_base);
+
+        // Round-trips through its own JSON representation
+        let text = serde_json::to_string(&bundle).unwrap();
+        let bundle: ExportBundle = serde_json::from_str(&text).unwrap();
+
+        // ======================================================
+        // Restore into an empty DB keeps IDs and history
+        // ======================================================
+        let fresh = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        run_migrations(&fresh).await.unwrap();
+        apply_import(&fresh, &bundle, JsonImportMode::Restore)
+            .await
+            .unwrap();
+
+        let restored = build_export(&fresh).await.unwrap();
+        assert_eq!(
+            serde_json::to_value(&restored.data).unwrap(),
+            serde_json::to_value(&bundle.data).unwrap()
+        );
+        // Every restored record has its create entry
+        let created: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM audit_log WHERE action = 'create' AND origin = 'export.ImportJson'",
+        )
+        .fetch_one(&fresh)
+        .await
+        .unwrap();
+        assert_eq!(created, 7);
+
+        // ======================================================
+        // Restore refuses a non-empty DB and changes nothing
+        // ======================================================
+        let err = apply_import(&pool, &bundle, JsonImportMode::Restore)
+            .await
+            .unwrap_err();
+        assert!(err.contains("empty database"));
+        assert_eq!(count(&pool, "company").await, 1);
+
+        // ======================================================
+        // Merge adds copies with remapped references
+        // ======================================================
+        apply_import(&pool, &bundle, JsonImportMode::Merge)
+            .await
+            .unwrap();
+        assert_eq!(count(&pool, "company").await, 2);
+        assert_eq!(count(&pool, "application").await, 2);
+        assert_eq!(count(&pool, "application_stage_event").await, 4);
+        // The tag is reused by name and linked to the copied company
+        assert_eq!(count(&pool, "tag").await, 1);
+        assert_eq!(count(&pool, "entity_tag").await, 2);
+        // So is the attachment, by content
+        assert_eq!(count(&pool, "attachment").await, 1);
+        assert_eq!(count(&pool, "attachment_link").await, 2);
+        // The identical rule is reused; its run follows the copies
+        assert_eq!(count(&pool, "automation_rule").await, 1);
+        let run: (i64, i64, String, i64) = sqlx::query_as(
+            "SELECT rule_id, application_id, entity_type, entity_id FROM automation_rule_run WHERE id = 2",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(run, (1, 2, "reminder".to_string(), 2));
+
+        let (job_company, reminder_note): (i64, i64) = sqlx::query_as(
+            r#"
+            SELECT jl.company_id, r.note_id
+            FROM reminder r
+            JOIN application a ON a.id = r.application_id
+            JOIN job_listing jl ON jl.id = a.job_listing_id
+            WHERE r.id = 2
+            "#,
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(job_company, 2);
+        assert_eq!(reminder_note, 2);
+    }
+
+    #[tokio::test]
+    async fn test_merge_converts_exchange_rates() {
+        let source = setup_test_db().await;
+        sqlx::query(
+            "INSERT INTO exchange_rate (currency, rate, is_base) VALUES ('EUR', 1, 1), ('USD', 0.8, 0), ('GBP', 1.2, 0)",
+        )
+        .execute(&source)
+        .await
+        .unwrap();
+        let bundle = build_export(&source).await.unwrap();
+
+        // The target keeps its base and its own rates; new ones are converted
+        let target = setup_test_db().await;
+        sqlx::query(
+            "INSERT INTO exchange_rate (currency, rate, is_base) VALUES ('USD', 1, 1), ('GBP', 1.4, 0)",
+        )
+        .execute(&target)
+        .await
+        .unwrap();
+        apply_import(&target, &bundle, JsonImportMode::Merge)
+            .await
+            .unwrap();
+
+        let rates: Vec<(String, f64, bool)> =
+            sqlx::query_as("SELECT currency, rate, is_base FROM exchange_rate ORDER BY currency")
+                .fetch_all(&target)
+                .await
+                .unwrap();
+        assert_eq!(
+            rates,
+            vec![
+                ("EUR".to_string(), 1.25, false),
+                ("GBP".to_string(), 1.4, false),
+                ("USD".to_string(), 1.0, true),
+            ]
+        );
+
+        // Without a rate for the target's base nothing can be converted
+        let other = setup_test_db().await;
+        sqlx::query("INSERT INTO exchange_rate (currency, rate, is_base) VALUES ('DKK', 1, 1)")
+            .execute(&other)
+            .await
+            .unwrap();
+        apply_import(&other, &bundle, JsonImportMode::Merge)
+            .await
+            .unwrap();
+        assert_eq!(count(&other, "exchange_rate").await, 1);
+    }
+
+    #[test]
+    fn test_attachment_files_travel_with_export() {
+        let dir = std::env::temp_dir().join(format!(
+            "jobtrackr-export-files-{}-{}",
+            std::process::id(),
+            chrono::Utc::now().timestamp_nanos_opt().unwrap()
+        ));
+        let store = FileStore::new(dir.join("store"));
+        let source = dir.join("cv.pdf");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(&source, b"%PDF-1.4 my cv").unwrap();
+
+        let attachment = |id: i64, storage_path: &str| Attachment {
+            id,
+            sha256: format!("{:064}", id),
+            filename: "cv.pdf".to_string(),
+            mime_type: "application/pdf".to_string(),
+            size_bytes: 14,
+            storage_path: storage_path.to_string(),
+            created_at: chrono::Utc::now().naive_utc(),
+        };
+        store.put(&source, "ab/cv.pdf").unwrap();
+        let attachments = vec![attachment(1, "ab/cv.pdf"), attachment(2, "cd/gone.pdf")];
+
+        // ======================================================
+        // Export copies what the store has
+        // ======================================================
+        let export_path = dir.join("jobtrackr-export-1.json");
+        let files = attachment_dir_for(&export_path);
+        assert_eq!(files, dir.join("jobtrackr-export-1-attachments"));
+        let missing = copy_attachment_files(&attachments, &store, &FileStore::new(&files)).unwrap();
+        assert_eq!(missing, 1);
+        assert!(files.join("ab").join("cv.pdf").is_file());
+
+        // ======================================================
+        // Import into another profile fills its store
+        // ======================================================
+        let other = FileStore::new(dir.join("other-store"));
+        let mut imported = attachments;
+        let missing =
+            collect_attachment_files(&mut imported, &other, Some(&FileStore::new(&files))).unwrap();
+        assert_eq!(missing, 1);
+        assert_eq!(imported.len(), 1);
+        assert_eq!(imported[0].id, 1);
+        assert!(other.path_of("ab/cv.pdf").is_file());
+
+        // Files already in the store need no source
+        let missing = collect_attachment_files(&mut imported, &other, None).unwrap();
+        assert_eq!((missing, imported.len()), (0, 1));
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[tokio::test]
+    async fn test_import_rejects_unknown_format() {
+        let pool = setup_test_db().await;
+        let mut bundle = build_export(&pool).await.unwrap();
+        bundle.format = "something-else".to_string();
+
+        let err = apply_import(&pool, &bundle, JsonImportMode::Merge)
+            .await
+            .unwrap_err();
+        assert!(err.contains("not a JobTrackr export"));
+    }
+}
@@ -0,0 +1,169 @@
+#[cfg(test)]
+mod tests {
+    use crate::db::tests::test_utils::setup_test_db;
+    use crate::services::import_service::*;
+    use std::collections::HashMap;
+
+    fn request(
+        entity: ImportEntity,
+        content: &str,
+        mapping: &[(&str, &str)],
+        dry_run: bool,
+    ) -> ImportRequest {
+        ImportRequest {
+            entity,
+            source: CsvSource {
+                content: Some(content.to_string()),
+                ..Default::default()
+            },
+            mapping: mapping
+                .iter()
+                .map(|(h, f)| (h.to_string(), f.to_string()))
+                .collect::<HashMap<_, _>>(),
+            dry_run,
+        }
+    }
+
+    async fn count(pool: &sqlx::SqlitePool, table: &str) -> i64 {
+        sqlx::query_scalar(&format!("SELECT COUNT(*) FROM {}", table))
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_import_job_listings() {
+        // ======================================================
+        // Setup
+        // ======================================================
+        let pool = setup_test_db().await;
+        let csv = "\
+Company,Position,Type,Level,Min,Max,Cur
+default company,Backend Engineer,Full time,Senior,\"60,000\",80000,eur
+New Startup,Frontend Engineer,remote,mid,,,USD
+New Startup,Data Engineer,on the moon,mid,,,USD
+Default Company,Default Job,remote,,,,
+,,,,,,
+";
+        let mapping = [
+            ("Company", "company"),
+            ("Position", "title"),
+            ("Type", "workType"),
+            ("Level", "seniorityLevel"),
+            ("Min", "salaryMin"),
+            ("Max", "salaryMax"),
+            ("Cur", "currency"),
+        ];
+
+        // ======================================================
+        // Dry run reports but does not persist
+        // ======================================================
+        let report = run_import(
+            &pool,
+            &request(ImportEntity::JobListing, csv, &mapping, true),
+        )
+        .await
+        .expect("dry run failed");
+
+        assert_eq!(report.total_rows, 5);
+        assert_eq!((report.created, report.skipped, report.failed), (2, 2, 1));
+        assert_eq!(report.companies_created, vec!["New Startup".to_string()]);
+        assert_eq!(count(&pool, "job_listing").await, 1);
+        assert_eq!(count(&pool, "company").await, 1);
+        assert_eq!(count(&pool, "audit_log").await, 0);
+
+        let failed = report
+            .rows
+            .iter()
+            .find(|r| r.status == RowStatus::Failed)
+            .unwrap();
+        assert_eq!(failed.line, 4);
+        assert!(failed.message.as_deref().unwrap().contains("workType"));
+
+        // ======================================================
+        // Real run persists valid rows and resolves companies by name
+        // ======================================================
+        let report = run_import(
+            &pool,
+            &request(ImportEntity::JobListing, csv, &mapping, false),
+        )
+        .await
+        .expect("import failed");
+
+        assert_eq!(report.created, 2);
+        assert_eq!(count(&pool, "job_listing").await, 3);
+        assert_eq!(count(&pool, "company").await, 2);
+        // The new listings and the company created for them are in the history
+        let entries: Vec<(String, i64)> = sqlx::query_as(
+            "SELECT entity_type, entity_id FROM audit_log WHERE action = 'create' ORDER BY id",
+        )
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+        assert_eq!(
+            entries,
+            vec![
+                ("job_listing".to_string(), 2),
+                ("company".to_string(), 2),
+                ("job_listing".to_string(), 3),
+            ]
+        );
+
+        let (company_id, work_type, salary_min, currency): (i64, String, i64, String) =
+            sqlx::query_as(
+                "SELECT company_id, work_type, salary_min, currency FROM job_listing WHERE title = 'Backend Engineer'",
+            )
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(
+            (
+                company_id,
+                work_type.as_str(),
+                salary_min,
+                currency.as_str()
+            ),
+            (1, "full_time", 60000, "EUR")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_import_rejects_bad_mapping() {
+        let pool = setup_test_db().await;
+        let csv = "First;Last;Mail\nJane;Roe;jane@example.com\n";
+
+        // Required field missing
+        let mut req = request(ImportEntity::Person, csv, &[("First", "firstName")], false);
+        req.source.delimiter = Some(';');
+        let result = run_import(&pool, &req).await;
+        assert!(result.unwrap_err().contains("lastName"));
+
+        // Unknown header (wrong delimiter)
+        let result = run_import(
+            &pool,
+            &request(
+                ImportEntity::Person,
+                csv,
+                &[("First", "firstName"), ("Last", "lastName")],
+                false,
+            ),
+        )
+        .await;
+        assert!(result.is_err());
+
+        // Custom delimiter works
+        let mut req = request(
+            ImportEntity::Person,
+            csv,
+            &[
+                ("First", "firstName"),
+                ("Last", "lastName"),
+                ("Mail", "email"),
+            ],
+            false,
+        );
+        req.source.delimiter = Some(';');
+        let report = run_import(&pool, &req).await.expect("import failed");
+        assert_eq!(report.created, 1);
+    }
+}
@@ -1,6 +1,7 @@
 #[cfg(test)]
 mod tests {
     use crate::db::models::enums::InteractionType;
+    use crate::db::models::patch::Patch;
     use crate::db::queries::interaction::*;
     use crate::db::tests::test_utils::setup_test_db;
     use chrono::NaiveDate;
@@ -62,12 +63,12 @@ mod tests {
             created.id,
             Some(&InteractionType::Phone),
             Some(&new_date),
-            Some("Phone Call"),
-            Some("Confirmed interview schedule"),
-            Some("in_person"),
-            Some(1),
-            Some(1),
-            Some(1),
+            Patch::Set("Phone Call"),
+            Patch::Set("Confirmed interview schedule"),
+            Patch::Set("in_person"),
+            Patch::Set(1),
+            Patch::Set(1),
+            Patch::Set(1),
         )
         .await
         .expect("failed to update interaction");
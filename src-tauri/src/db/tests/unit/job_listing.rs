@@ -1,6 +1,7 @@
 #[cfg(test)]
 mod tests {
-    use crate::db::models::enums::{Currency, SeniorityLevel, WorkType};
+    use crate::db::models::enums::{Currency, PayPeriod, SeniorityLevel, WorkType};
+    use crate::db::models::patch::Patch;
     use crate::db::queries::job_listing::*;
     use crate::db::tests::test_utils::setup_test_db;
 
@@ -24,6 +25,7 @@ mod tests {
             Some(55000),
             Some(70000),
             Some(&Currency::EUR),
+            Some(&PayPeriod::Yearly),
             Some("Rust + SQLX developer position"),
             Some("https://jobs.example.com/backend"),
         )
@@ -38,6 +40,7 @@ mod tests {
         assert_eq!(created.salary_min, Some(55000));
         assert_eq!(created.salary_max, Some(70000));
         assert_eq!(created.currency, Some(Currency::EUR));
+        assert_eq!(created.pay_period, Some(PayPeriod::Yearly));
         assert_eq!(
             created.description.as_deref(),
             Some("Rust + SQLX developer position")
@@ -66,14 +69,15 @@ mod tests {
             created.id,
             Some(1),
             Some("Senior Backend Engineer"),
-            Some(&WorkType::Hybrid),
-            Some("Engineering"),
-            Some(&SeniorityLevel::Senior),
-            Some(75000),
-            Some(90000),
-            Some(&Currency::USD),
-            Some("Updated description"),
-            Some("https://updated.example.com/job"),
+            Patch::Set(&WorkType::Hybrid),
+            Patch::Set("Engineering"),
+            Patch::Set(&SeniorityLevel::Senior),
+            Patch::Set(75000),
+            Patch::Set(90000),
+            Patch::Set(&Currency::USD),
+            Patch::Set(&PayPeriod::Monthly),
+            Patch::Set("Updated description"),
+            Patch::Set("https://updated.example.com/job"),
         )
         .await
         .expect("failed to update job listing");
@@ -84,6 +88,7 @@ mod tests {
         assert_eq!(updated.salary_min, Some(75000));
         assert_eq!(updated.salary_max, Some(90000));
         assert_eq!(updated.currency, Some(Currency::USD));
+        assert_eq!(updated.pay_period, Some(PayPeriod::Monthly));
         assert_eq!(updated.category.as_deref(), Some("Engineering"));
         assert_eq!(
             updated.url.as_deref(),
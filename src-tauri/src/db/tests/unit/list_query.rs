@@ -0,0 +1,132 @@
+#[cfg(test)]
+mod tests {
+    use crate::db::queries::application::list_applications;
+    use crate::db::queries::company::{list_companies, COMPANY_LIST};
+    use crate::db::queries::list_query::*;
+    use crate::db::tests::test_utils::setup_test_db;
+    use serde_json::json;
+    use sqlx::Executor;
+
+    fn options(value: serde_json::Value) -> ListOptions {
+        serde_json::from_value(value).expect("invalid list options")
+    }
+
+    #[tokio::test]
+    async fn test_list_filter_sort_and_page() {
+        // ======================================================
+        // Setup
+        // ======================================================
+        let pool = setup_test_db().await;
+        pool.execute(
+            r#"
+            INSERT INTO company (name, city, industry) VALUES
+                ('Acme', 'Berlin', 'Robotics'),
+                ('Beta 100%', 'Hamburg', 'Fintech'),
+                ('Gamma', 'Berlin', NULL);
+            INSERT INTO application (job_listing_id, stage, applied_date) VALUES
+                (1, 'interviewing', '2024-03-01'),
+                (1, 'rejected', '2024-04-15');
+            "#,
+        )
+        .await
+        .unwrap();
+
+        // ======================================================
+        // No options: everything, default order
+        // ======================================================
+        let page = list_companies(&pool, &ListOptions::default())
+            .await
+            .expect("failed to list companies");
+        assert_eq!(page.total, 4);
+        assert_eq!(page.items.len(), 4);
+        assert_eq!(page.items[0].name, "Acme");
+
+        // ======================================================
+        // Column filters, sorting and paging
+        // ======================================================
+        let page = list_companies(
+            &pool,
+            &options(json!({
+                "filters": [{ "field": "city", "op": "eq", "value": "Berlin" }],
+                "sort": [{ "field": "name", "direction": "desc" }],
+                "limit": 2,
+                "offset": 1
+            })),
+        )
+        .await
+        .unwrap();
+        assert_eq!(page.total, 3);
+        let names: Vec<&str> = page.items.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["Default Company", "Acme"]);
+        assert!(!page.pagination().has_more);
+
+        // LIKE wildcards in user input are matched literally
+        let page = list_companies(
+            &pool,
+            &options(json!({
+                "filters": [{ "field": "name", "op": "contains", "value": "0%" }]
+            })),
+        )
+        .await
+        .unwrap();
+        assert_eq!(page.total, 1);
+        assert_eq!(page.items[0].name, "Beta 100%");
+
+        let page = list_companies(
+            &pool,
+            &options(json!({ "filters": [{ "field": "industry", "op": "isNull" }] })),
+        )
+        .await
+        .unwrap();
+        assert_eq!(page.total, 2);
+
+        // ======================================================
+        // Enum sets and date ranges
+        // ======================================================
+        let page = list_applications(
+            &pool,
+            &options(json!({
+                "filters": [
+                    { "field": "stage", "op": "in", "value": ["interviewing", "rejected"] },
+                    { "field": "appliedDate", "op": "between", "value": ["2024-01-01", "2024-03-31"] }
+                ]
+            })),
+        )
+        .await
+        .unwrap();
+        assert_eq!(page.total, 1);
+        assert_eq!(
+            page.items[0].applied_date.to_string(),
+            "2024-03-01".to_string()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_list_rejects_invalid_options() {
+        let pool = setup_test_db().await;
+
+        let invalid = [
+            json!({ "filters": [{ "field": "name; DROP TABLE company", "op": "eq", "value": "x" }] }),
+            json!({ "sort": [{ "field": "created_at" }] }),
+            json!({ "filters": [{ "field": "id", "op": "eq", "value": "abc" }] }),
+            json!({ "filters": [{ "field": "createdAt", "op": "contains", "value": "2024" }] }),
+            json!({ "filters": [{ "field": "city", "op": "in", "value": [] }] }),
+            json!({ "limit": 0 }),
+        ];
+
+        for value in invalid {
+            let result = list_companies(&pool, &options(value.clone())).await;
+            assert!(
+                matches!(result, Err(ListError::Invalid(_))),
+                "expected {} to be rejected",
+                value
+            );
+        }
+
+        // Oversized pages are capped
+        let sql = COMPANY_LIST
+            .build(&options(json!({ "limit": 1_000_000 })))
+            .unwrap();
+        assert_eq!(sql.limit, Some(MAX_PAGE_SIZE));
+    }
+}
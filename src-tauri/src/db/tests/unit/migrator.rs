@@ -0,0 +1,96 @@
+#[cfg(test)]
+mod tests {
+    use crate::db::migrator::*;
+    use crate::db::schema::MIGRATIONS;
+    use sqlx::{Executor, SqlitePool};
+
+    async fn applied_versions(pool: &SqlitePool) -> Vec<i64> {
+        sqlx::query_scalar("SELECT version FROM schema_migrations ORDER BY version")
+            .fetch_all(pool)
+            .await
+            .expect("failed to read schema_migrations")
+    }
+
+    #[tokio::test]
+    async fn test_fresh_database_migrates_to_head() {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+
+        let version = run_migrations(&pool).await.expect("migration failed");
+        assert_eq!(version, latest_version());
+
+        let expected: Vec<i64> = MIGRATIONS.iter().map(|m| m.version).collect();
+        assert_eq!(applied_versions(&pool).await, expected);
+
+        // Running again is a no-op
+        let again = run_migrations(&pool).await.expect("second run failed");
+        assert_eq!(again, latest_version());
+        assert!(pending_migrations(&pool).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_upgrade_legacy_0001_database_to_head() {
+        // ======================================================
+        // Setup: database created by the pre-migration init_db
+        // ======================================================
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        pool.execute(MIGRATIONS[0].sql).await.unwrap();
+        pool.execute("INSERT INTO company (name) VALUES ('Legacy Corp')")
+            .await
+            .unwrap();
+
+        // ======================================================
+        // Upgrade
+        // ======================================================
+        let version = run_migrations(&pool).await.expect("upgrade failed");
+        assert_eq!(version, latest_version());
+        assert_eq!(current_version(&pool).await.unwrap(), latest_version());
+
+        let expected: Vec<i64> = MIGRATIONS.iter().map(|m| m.version).collect();
+        assert_eq!(applied_versions(&pool).await, expected);
+
+        // Existing data survives the upgrade
+        let name: String = sqlx::query_scalar("SELECT name FROM company WHERE id = 1")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(name, "Legacy Corp");
+    }
+
+    #[tokio::test]
+    async fn test_modified_migration_is_rejected() {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        run_migrations(&pool).await.unwrap();
+
+        sqlx::query("UPDATE schema_migrations SET checksum = 'tampered' WHERE version = 1")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let result = run_migrations(&pool).await;
+        assert!(matches!(
+            result,
+            Err(MigrationError::ChecksumMismatch { version: 1, .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_newer_database_is_rejected() {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        run_migrations(&pool).await.unwrap();
+
+        let future = latest_version() + 1;
+        sqlx::query(
+            "INSERT INTO schema_migrations (version, name, checksum) VALUES (?, 'future', '')",
+        )
+        .bind(future)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let result = run_migrations(&pool).await;
+        assert!(matches!(
+            result,
+            Err(MigrationError::DatabaseAhead { database_version, .. }) if database_version == future
+        ));
+    }
+}
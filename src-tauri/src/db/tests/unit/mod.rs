@@ -1,7 +1,32 @@
+mod analytics;
 mod application;
+mod application_contact;
+mod attachment;
+mod audit;
+mod backup;
+mod calendar;
 mod company;
+mod dedupe;
+mod detail;
+mod error;
+mod export;
+mod import;
 mod interactions;
 mod job_listing;
+mod list_query;
+mod migrator;
 mod note;
+mod patch;
 mod person;
 mod reminder;
+mod reminder_recurrence;
+mod reminder_scheduler;
+mod rule;
+mod salary;
+mod search;
+mod settings;
+mod stage_event;
+mod stale_application;
+mod tag;
+mod trash;
+mod undo;
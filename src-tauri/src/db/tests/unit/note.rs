@@ -1,6 +1,7 @@
 #[cfg(test)]
 mod tests {
     use crate::db::models::enums::NoteType;
+    use crate::db::models::patch::Patch;
     use crate::db::queries::note::*;
     use crate::db::tests::test_utils::setup_test_db;
 
@@ -55,14 +56,14 @@ mod tests {
         let updated = update_note(
             &pool,
             created.id,
-            Some(1),
-            Some(1),
-            Some(1),
-            Some(1),
-            Some(1),
-            Some(&NoteType::Feedback),
-            Some("Updated title"),
-            Some("Updated content"),
+            Patch::Set(1),
+            Patch::Set(1),
+            Patch::Set(1),
+            Patch::Set(1),
+            Patch::Set(1),
+            Patch::Set(&NoteType::Feedback),
+            Patch::Set("Updated title"),
+            Patch::Set("Updated content"),
         )
         .await
         .expect("failed to update note");
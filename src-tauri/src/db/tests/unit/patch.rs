@@ -0,0 +1,90 @@
+#[cfg(test)]
+mod tests {
+    use crate::commands::person_commands::PersonCommand;
+    use crate::db::models::patch::Patch;
+    use crate::db::queries::job_listing::*;
+    use crate::db::queries::person::*;
+    use crate::db::tests::test_utils::setup_test_db;
+
+    #[test]
+    fn test_patch_deserialization() {
+        let command: PersonCommand = serde_json::from_str(
+            r#"{"action": "Update", "payload": {"id": 1, "email": null, "phoneNumber": "123"}}"#,
+        )
+        .unwrap();
+
+        match command {
+            PersonCommand::Update {
+                first_name,
+                email,
+                phone_number,
+                company_id,
+                ..
+            } => {
+                assert_eq!(first_name, None);
+                assert_eq!(email, Patch::Clear);
+                assert_eq!(phone_number, Patch::Set("123".to_string()));
+                assert_eq!(company_id, Patch::Unchanged);
+            }
+            _ => panic!("expected Update"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_update_clears_nullable_fields() {
+        // ======================================================
+        // Setup
+        // ======================================================
+        let pool = setup_test_db().await;
+        sqlx::query("UPDATE job_listing SET salary_min = 50000, salary_max = 70000 WHERE id = 1")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        // ======================================================
+        // Clear one column, set another, leave the rest
+        // ======================================================
+        let updated = update_job_listing(
+            &pool,
+            1,
+            None,
+            None,
+            Patch::Unchanged,
+            Patch::Set("Engineering"),
+            Patch::Unchanged,
+            Patch::Unchanged,
+            Patch::Clear,
+            Patch::Unchanged,
+            Patch::Unchanged,
+            Patch::Unchanged,
+            Patch::Unchanged,
+        )
+        .await
+        .expect("failed to update job listing");
+
+        assert_eq!(updated.salary_min, Some(50000));
+        assert_eq!(updated.salary_max, None);
+        assert_eq!(updated.category.as_deref(), Some("Engineering"));
+        assert_eq!(updated.title, "Default Job");
+
+        // ======================================================
+        // Unlink a person from their company
+        // ======================================================
+        let person = update_person(
+            &pool,
+            1,
+            None,
+            None,
+            Patch::Unchanged,
+            Patch::Unchanged,
+            Patch::Unchanged,
+            Patch::Unchanged,
+            Patch::Clear,
+        )
+        .await
+        .expect("failed to update person");
+
+        assert_eq!(person.company_id, None);
+        assert_eq!(person.email.as_deref(), Some("john@example.com"));
+    }
+}
@@ -1,6 +1,7 @@
 #[cfg(test)]
 mod tests {
     use crate::db::models::enums::Role;
+    use crate::db::models::patch::Patch;
     use crate::db::queries::person::*;
     use crate::db::tests::test_utils::setup_test_db;
 
@@ -57,11 +58,11 @@ mod tests {
             created.id,
             Some("Alice-Marie"),
             Some("Johnson"),
-            Some("alice@updated.com"),
-            Some("+4999999999"),
-            Some(&Role::HR),
-            Some("https://linkedin.com/in/alice-marie"),
-            Some(1),
+            Patch::Set("alice@updated.com"),
+            Patch::Set("+4999999999"),
+            Patch::Set(&Role::HR),
+            Patch::Set("https://linkedin.com/in/alice-marie"),
+            Patch::Set(1),
         )
         .await
         .expect("failed to update person");
@@ -1,5 +1,6 @@
 #[cfg(test)]
 mod tests {
+    use crate::db::models::patch::Patch;
     use crate::db::queries::reminder::*;
     use crate::db::tests::test_utils::setup_test_db;
     use chrono::NaiveDate;
@@ -25,6 +26,9 @@ mod tests {
             Some(1), // company_id
             Some(1), // person_id
             &reminder_date,
+            None, // reminder_time
+            None, // timezone
+            None, // recurrence
             "Follow up interview",
             Some("Reach out to recruiter about next steps"),
             false,
@@ -67,7 +71,8 @@ mod tests {
         // Get upcoming (should include this reminder)
         // ======================================================
         let today = NaiveDate::from_ymd_opt(2025, 10, 25).unwrap();
-        let upcoming = get_upcoming_reminders(&pool, &today)
+        let window_end = NaiveDate::from_ymd_opt(2025, 12, 31).unwrap();
+        let upcoming = get_upcoming_reminders(&pool, &today, &window_end)
             .await
             .expect("failed to get upcoming reminders");
 
@@ -83,15 +88,18 @@ mod tests {
         let updated = update_reminder(
             &pool,
             created.id,
-            Some(1),
-            Some(1),
-            Some(1),
-            Some(1),
-            Some(1),
-            Some(1),
+            Patch::Set(1),
+            Patch::Set(1),
+            Patch::Set(1),
+            Patch::Set(1),
+            Patch::Set(1),
+            Patch::Set(1),
             Some(&new_date),
+            Patch::Unchanged,
+            Patch::Unchanged,
+            Patch::Unchanged,
             Some("Final Interview"),
-            Some("Confirm meeting slot"),
+            Patch::Set("Confirm meeting slot"),
             Some(true),
         )
         .await
@@ -105,7 +113,7 @@ mod tests {
         // ======================================================
         // Get upcoming (should NOT include updated reminder since completed)
         // ======================================================
-        let upcoming_after = get_upcoming_reminders(&pool, &today)
+        let upcoming_after = get_upcoming_reminders(&pool, &today, &window_end)
             .await
             .expect("failed to get upcoming reminders after update");
 
@@ -0,0 +1,248 @@
+#[cfg(test)]
+mod tests {
+    use crate::db::models::enums::EntityType;
+    use crate::db::queries::{reminder, tag};
+    use crate::db::tests::test_utils::setup_test_db;
+    use crate::error::AppError;
+    use crate::services::reminder_scheduler::collect_due_reminders;
+    use crate::services::reminder_service::*;
+    use crate::utils::recurrence::*;
+    use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    fn at(s: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    #[test]
+    fn test_recurrence_rules() {
+        // ======================================================
+        // Parsing: canonical form, errors
+        // ======================================================
+        let rule: Recurrence = "rrule:freq=daily;until=2025-03-05".parse().unwrap();
+        assert_eq!(rule.to_string(), "FREQ=DAILY;INTERVAL=1;UNTIL=20250305");
+        assert_eq!(
+            rule.occurrences(date("2025-03-01")).count(),
+            5,
+            "UNTIL is inclusive"
+        );
+
+        for bad in [
+            "",
+            "FREQ=YEARLY",
+            "FREQ=WEEKLY;BYDAY=MO",
+            "FREQ=WEEKLY;INTERVAL=0",
+            "FREQ=WEEKLY;COUNT=2;UNTIL=20250301",
+            "FREQ=DAILY;UNTIL=tomorrow",
+        ] {
+            assert!(bad.parse::<Recurrence>().is_err(), "{:?} should fail", bad);
+        }
+
+        // ======================================================
+        // Monthly rules skip months without the day
+        // ======================================================
+        let monthly: Recurrence = "FREQ=MONTHLY;COUNT=3".parse().unwrap();
+        let dates: Vec<NaiveDate> = monthly.occurrences(date("2025-01-31")).collect();
+        assert_eq!(
+            dates,
+            [date("2025-01-31"), date("2025-03-31"), date("2025-05-31")]
+        );
+
+        // ======================================================
+        // Advancing counts down and stops at the end
+        // ======================================================
+        let (next, rest) = monthly.advance(date("2025-01-31")).unwrap();
+        assert_eq!(next, date("2025-03-31"));
+        assert_eq!(rest.end, RecurrenceEnd::Count(2));
+        let (_, last) = rest.advance(next).unwrap();
+        assert!(last.advance(date("2025-05-31")).is_none());
+
+        // ======================================================
+        // Windows
+        // ======================================================
+        let biweekly: Recurrence = "FREQ=WEEKLY;INTERVAL=2".parse().unwrap();
+        assert_eq!(
+            biweekly.occurrences_between(
+                date("2025-01-06"),
+                date("2025-02-01"),
+                date("2025-02-28")
+            ),
+            [date("2025-02-03"), date("2025-02-17")]
+        );
+    }
+
+    #[test]
+    fn test_wall_clock_to_utc() {
+        let berlin = parse_timezone("Europe/Berlin").unwrap();
+        let half_past_two = NaiveTime::from_hms_opt(14, 30, 0).unwrap();
+
+        // CET in winter, CEST in summer
+        assert_eq!(
+            wall_clock_to_utc(&berlin, date("2025-03-03"), half_past_two),
+            at("2025-03-03 13:30")
+        );
+        assert_eq!(
+            wall_clock_to_utc(&berlin, date("2025-07-01"), half_past_two),
+            at("2025-07-01 12:30")
+        );
+
+        // 02:30 does not exist on the night clocks go forward
+        let skipped = NaiveTime::from_hms_opt(2, 30, 0).unwrap();
+        assert_eq!(
+            wall_clock_to_utc(&berlin, date("2025-03-30"), skipped),
+            at("2025-03-30 01:30")
+        );
+
+        assert!(parse_timezone("Mars/Olympus_Mons").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_recurring_reminder_series() {
+        // ======================================================
+        // Setup: weekly Monday 09:00 Berlin, three times, tagged
+        // ======================================================
+        let pool = setup_test_db().await;
+        let monday = date("2025-03-03");
+        let nine = NaiveTime::from_hms_opt(9, 0, 0).unwrap();
+
+        let err = create_reminder_service(
+            &pool,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            &monday,
+            Some(&nine),
+            Some("Berlin"),
+            None,
+            "Check the board",
+            None,
+            false,
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(err, AppError::Validation { .. }));
+
+        let created = create_reminder_service(
+            &pool,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            &monday,
+            Some(&nine),
+            Some(" Europe/Berlin "),
+            Some("freq=weekly;count=3"),
+            "Check the board",
+            None,
+            false,
+        )
+        .await
+        .unwrap();
+        let data = created.data.unwrap();
+        let id = data["id"].as_i64().unwrap();
+        assert_eq!(data["reminderTime"], "09:00:00");
+        assert_eq!(data["timezone"], "Europe/Berlin");
+        assert_eq!(data["recurrence"], "FREQ=WEEKLY;INTERVAL=1;COUNT=3");
+
+        let weekly = tag::create_tag(&pool, "weekly", None).await.unwrap();
+        tag::attach_tag(&pool, weekly.id, EntityType::Reminder, id)
+            .await
+            .unwrap();
+
+        // ======================================================
+        // Upcoming: every occurrence within the window
+        // ======================================================
+        let upcoming =
+            get_upcoming_reminders_service(&pool, &date("2025-03-01"), &date("2025-03-31"))
+                .await
+                .unwrap()
+                .data
+                .unwrap();
+        let items = upcoming.as_array().unwrap();
+        let dates: Vec<&str> = items
+            .iter()
+            .map(|i| i["occurrenceDate"].as_str().unwrap())
+            .collect();
+        assert_eq!(dates, ["2025-03-03", "2025-03-10", "2025-03-17"]);
+        assert_eq!(items[0]["dueAt"], "2025-03-03T08:00:00");
+        assert!(items.iter().all(|i| i["id"] == id));
+
+        let err = get_upcoming_reminders_service(&pool, &date("2025-03-31"), &date("2025-03-01"))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AppError::Validation { .. }));
+
+        // ======================================================
+        // Due at 09:00 Berlin, not at midnight
+        // ======================================================
+        let due = collect_due_reminders(&pool, &monday, &at("2025-03-03 07:59"))
+            .await
+            .unwrap();
+        assert!(due.is_empty());
+        let due = collect_due_reminders(&pool, &monday, &at("2025-03-03 08:00"))
+            .await
+            .unwrap();
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].reminder_time, Some(nine));
+
+        // ======================================================
+        // Completing creates the next occurrence once
+        // ======================================================
+        let done = mark_reminder_done_service(&pool, &id).await.unwrap();
+        let data = done.data.unwrap();
+        assert_eq!(data["recurrence"], serde_json::Value::Null);
+        let next = &data["nextOccurrence"];
+        let next_id = next["id"].as_i64().unwrap();
+        assert_eq!(next["reminderDate"], "2025-03-10");
+        assert_eq!(next["reminderTime"], "09:00:00");
+        assert_eq!(next["recurrence"], "FREQ=WEEKLY;INTERVAL=1;COUNT=2");
+        assert_eq!(next["isCompleted"], false);
+        assert_eq!(next["tags"][0]["name"], "weekly");
+
+        let again = mark_reminder_done_service(&pool, &id).await.unwrap();
+        assert!(again.data.unwrap().get("nextOccurrence").is_none());
+
+        // Completing through an update continues the series as well
+        let updated = update_reminder_service(
+            &pool,
+            &next_id,
+            Default::default(),
+            Default::default(),
+            Default::default(),
+            Default::default(),
+            Default::default(),
+            Default::default(),
+            None,
+            Default::default(),
+            Default::default(),
+            Default::default(),
+            None,
+            Default::default(),
+            Some(true),
+        )
+        .await
+        .unwrap();
+        let last = &updated.data.unwrap()["nextOccurrence"];
+        assert_eq!(last["reminderDate"], "2025-03-17");
+        assert_eq!(last["recurrence"], "FREQ=WEEKLY;INTERVAL=1;COUNT=1");
+
+        // The last one ends the series
+        let last_id = last["id"].as_i64().unwrap();
+        let done = mark_reminder_done_service(&pool, &last_id).await.unwrap();
+        assert!(done.data.unwrap().get("nextOccurrence").is_none());
+
+        let open =
+            reminder::get_upcoming_reminders(&pool, &date("2025-03-01"), &date("2025-12-31"))
+                .await
+                .unwrap();
+        assert!(open.iter().all(|r| r.title != "Check the board"));
+    }
+}
@@ -0,0 +1,119 @@
+#[cfg(test)]
+mod tests {
+    use crate::db::queries::reminder::*;
+    use crate::db::tests::test_utils::setup_test_db;
+    use crate::error::AppError;
+    use crate::services::reminder_scheduler::*;
+    use crate::services::reminder_service::{mark_reminder_done_service, snooze_reminder_service};
+    use chrono::{Duration, NaiveDate, NaiveDateTime};
+
+    fn at(s: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_due_reminders_are_claimed_once() {
+        // ======================================================
+        // Setup: overdue, due, future and completed reminders
+        // ======================================================
+        let pool = setup_test_db().await;
+        sqlx::query(
+            r#"
+            DELETE FROM reminder;
+            INSERT INTO reminder (id, reminder_date, title, message, is_completed) VALUES
+                (10, '2025-03-01', 'Follow up', NULL, 0),
+                (11, '2025-03-03', 'Call back', 'Ask about the offer', 0),
+                (12, '2025-03-04', 'Tomorrow', NULL, 0),
+                (13, '2025-03-01', 'Done already', NULL, 1);
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let today = NaiveDate::from_ymd_opt(2025, 3, 3).unwrap();
+        let now = at("2025-03-03 08:00");
+
+        // ======================================================
+        // Claim: due ones once, in date order
+        // ======================================================
+        let due = collect_due_reminders(&pool, &today, &now).await.unwrap();
+        let ids: Vec<i64> = due.iter().map(|r| r.id).collect();
+        assert_eq!(ids, [10, 11]);
+        assert!(due[0].overdue);
+        assert!(!due[1].overdue);
+
+        assert!(collect_due_reminders(&pool, &today, &now)
+            .await
+            .unwrap()
+            .is_empty());
+
+        // ======================================================
+        // Snooze: announced again once the snooze is over
+        // ======================================================
+        snooze_reminder(&pool, 11, &(now + Duration::minutes(30)))
+            .await
+            .unwrap();
+        let later = now + Duration::minutes(10);
+        assert!(collect_due_reminders(&pool, &today, &later)
+            .await
+            .unwrap()
+            .is_empty());
+        let after = now + Duration::minutes(31);
+        let due = collect_due_reminders(&pool, &today, &after).await.unwrap();
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].id, 11);
+
+        // ======================================================
+        // Rescheduling re-arms, completing stops it
+        // ======================================================
+        sqlx::query("UPDATE reminder SET reminder_date = '2025-03-02' WHERE id = 10")
+            .execute(&pool)
+            .await
+            .unwrap();
+        mark_reminder_done_service(&pool, &11).await.unwrap();
+        let due = collect_due_reminders(&pool, &today, &after).await.unwrap();
+        let ids: Vec<i64> = due.iter().map(|r| r.id).collect();
+        assert_eq!(ids, [10]);
+
+        // Completed reminders cannot be snoozed
+        let err = snooze_reminder_service(&pool, &11, 10).await.unwrap_err();
+        assert!(matches!(err, AppError::NotFound { .. }));
+        let err = snooze_reminder_service(&pool, &10, 0).await.unwrap_err();
+        assert!(matches!(err, AppError::Validation { .. }));
+    }
+
+    #[test]
+    fn test_notification_texts() {
+        let reminder = |id: i64, overdue: bool, message: Option<&str>| DueReminder {
+            id,
+            title: format!("Reminder {}", id),
+            message: message.map(str::to_string),
+            reminder_date: NaiveDate::from_ymd_opt(2025, 3, 3).unwrap(),
+            reminder_time: None,
+            timezone: None,
+            display_label: format!("Reminder {}", id),
+            overdue,
+        };
+
+        let texts =
+            notification_texts(&[reminder(1, true, None), reminder(2, false, Some("Call"))]);
+        assert_eq!(
+            texts,
+            [
+                (
+                    "Overdue: Reminder 1".to_string(),
+                    "Due 2025-03-03".to_string()
+                ),
+                ("Reminder 2".to_string(), "Call".to_string()),
+            ]
+        );
+
+        // Many at once collapse into a summary
+        let many: Vec<DueReminder> = (1..=5).map(|id| reminder(id, id <= 2, None)).collect();
+        let texts = notification_texts(&many);
+        assert_eq!(texts.len(), 1);
+        assert_eq!(texts[0].0, "5 reminders are due");
+        assert!(texts[0].1.starts_with("2 of them overdue"));
+    }
+}
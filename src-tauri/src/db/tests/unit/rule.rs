@@ -0,0 +1,375 @@
+#[cfg(test)]
+mod tests {
+    use crate::db::models::enums::{InteractionType, Stage};
+    use crate::db::models::patch::Patch;
+    use crate::db::models::rule::{RuleAction, RuleTrigger};
+    use crate::db::queries::{application, note, reminder, rule};
+    use crate::db::tests::test_utils::setup_test_db;
+    use crate::error::AppError;
+    use crate::services::application_service::{
+        create_application_service, update_application_service,
+    };
+    use crate::services::interaction_service::create_interaction_service;
+    use crate::services::rule_service::*;
+    use chrono::{Duration, Local, NaiveDate};
+    use serde_json::json;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn test_rule_json_format() {
+        let trigger: RuleTrigger =
+            serde_json::from_value(json!({"event": "stage_changed", "stage": "interviewing"}))
+                .unwrap();
+        assert_eq!(
+            trigger,
+            RuleTrigger::StageChanged {
+                stage: Some(Stage::Interviewing)
+            }
+        );
+
+        let any: RuleTrigger =
+            serde_json::from_value(json!({"event": "interaction_logged"})).unwrap();
+        assert_eq!(
+            any,
+            RuleTrigger::InteractionLogged {
+                interaction_type: None
+            }
+        );
+
+        let action: RuleAction = serde_json::from_value(json!({
+            "type": "create_reminder",
+            "title": "Follow up with {company}",
+            "daysAfter": 7
+        }))
+        .unwrap();
+        assert_eq!(
+            serde_json::to_value(&action).unwrap()["daysAfter"],
+            json!(7)
+        );
+
+        assert!(serde_json::from_value::<RuleTrigger>(json!({"event": "tuesday"})).is_err());
+        assert!(serde_json::from_value::<RuleAction>(json!({"type": "set_stage"})).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_rule_crud_and_validation() {
+        let pool = setup_test_db().await;
+        let trigger = RuleTrigger::NoInteraction { days: 0 };
+        let action = RuleAction::SetStage {
+            stage: Stage::OnHold,
+        };
+
+        let err = create_rule_service(&pool, "Quiet", true, &trigger, &action)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AppError::Validation { .. }));
+
+        let err = create_rule_service(
+            &pool,
+            "Late",
+            true,
+            &RuleTrigger::ApplicationCreated,
+            &RuleAction::CreateReminder {
+                title: " ".into(),
+                message: None,
+                days_after: 7,
+            },
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(err, AppError::Validation { .. }));
+
+        let created = create_rule_service(
+            &pool,
+            "  Quiet  ",
+            true,
+            &RuleTrigger::NoInteraction { days: 30 },
+            &action,
+        )
+        .await
+        .unwrap();
+        let data = created.data.unwrap();
+        let id = data["id"].as_i64().unwrap();
+        assert_eq!(data["name"], "Quiet");
+        assert_eq!(
+            data["trigger"],
+            json!({"event": "no_interaction", "days": 30})
+        );
+        assert_eq!(
+            data["action"],
+            json!({"type": "set_stage", "stage": "on_hold"})
+        );
+
+        let updated = update_rule_service(&pool, &id, None, Some(false), None, None)
+            .await
+            .unwrap();
+        let data = updated.data.unwrap();
+        assert_eq!(data["enabled"], false);
+        assert_eq!(data["name"], "Quiet");
+
+        delete_rule_service(&pool, &id).await.unwrap();
+        assert!(get_rule_by_id_service(&pool, &id).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_event_rules_fire_with_the_change() {
+        // ======================================================
+        // Setup: one rule per event, plus a disabled one
+        // ======================================================
+        let pool = setup_test_db().await;
+        let today = Local::now().date_naive();
+
+        let follow_up = rule::create_rule(
+            &pool,
+            "Follow up",
+            true,
+            &RuleTrigger::ApplicationCreated,
+            &RuleAction::CreateReminder {
+                title: "Follow up with {company}".into(),
+                message: Some("About {job}".into()),
+                days_after: 7,
+            },
+        )
+        .await
+        .unwrap();
+        rule::create_rule(
+            &pool,
+            "Disabled",
+            false,
+            &RuleTrigger::ApplicationCreated,
+            &RuleAction::SetStage {
+                stage: Stage::Rejected,
+            },
+        )
+        .await
+        .unwrap();
+        rule::create_rule(
+            &pool,
+            "Prep",
+            true,
+            &RuleTrigger::StageChanged {
+                stage: Some(Stage::Interviewing),
+            },
+            &RuleAction::CreateNote {
+                title: "Prepare for {company}".into(),
+                content: None,
+                note_type: None,
+            },
+        )
+        .await
+        .unwrap();
+        rule::create_rule(
+            &pool,
+            "Offer",
+            true,
+            &RuleTrigger::InteractionLogged {
+                interaction_type: Some(InteractionType::OfferDiscussion),
+            },
+            &RuleAction::SetStage {
+                stage: Stage::Offered,
+            },
+        )
+        .await
+        .unwrap();
+
+        // ======================================================
+        // Application created: reminder with filled-in texts
+        // ======================================================
+        let created =
+            create_application_service(&pool, Some(1), Some(&Stage::Applied), &today, None)
+                .await
+                .unwrap();
+        let data = created.data.unwrap();
+        let app_id = data["id"].as_i64().unwrap();
+        assert_eq!(data["stage"], "applied");
+
+        let automations = data["automations"].as_array().unwrap();
+        assert_eq!(automations.len(), 1);
+        assert_eq!(automations[0]["ruleId"], follow_up.id);
+        assert_eq!(automations[0]["entityType"], "reminder");
+
+        let reminder_id = automations[0]["entityId"].as_i64().unwrap();
+        let created_reminder = reminder::get_reminder_by_id(&pool, reminder_id)
+            .await
+            .unwrap();
+        assert_eq!(created_reminder.title, "Follow up with Default Company");
+        assert_eq!(
+            created_reminder.message.as_deref(),
+            Some("About Default Job")
+        );
+        assert_eq!(created_reminder.reminder_date, today + Duration::days(7));
+        assert_eq!(created_reminder.application_id, Some(app_id));
+
+        // ======================================================
+        // Stage changes: only into the rule's stage, only on change
+        // ======================================================
+        let updated = update_application_service(
+            &pool,
+            &app_id,
+            Patch::Unchanged,
+            Some(&Stage::Screening),
+            None,
+            Patch::Unchanged,
+        )
+        .await
+        .unwrap();
+        assert!(updated.data.unwrap().get("automations").is_none());
+
+        for expected in [1, 0] {
+            let updated = update_application_service(
+                &pool,
+                &app_id,
+                Patch::Unchanged,
+                Some(&Stage::Interviewing),
+                None,
+                Patch::Unchanged,
+            )
+            .await
+            .unwrap();
+            let data = updated.data.unwrap();
+            let fired = data
+                .get("automations")
+                .map_or(0, |a| a.as_array().unwrap().len());
+            assert_eq!(fired, expected);
+        }
+
+        let notes = note::get_notes_by_application_id(&pool, app_id)
+            .await
+            .unwrap();
+        assert_eq!(notes.len(), 1);
+        assert_eq!(
+            notes[0].title.as_deref(),
+            Some("Prepare for Default Company")
+        );
+
+        // ======================================================
+        // Interaction logged: only of the rule's type
+        // ======================================================
+        for interaction_type in [InteractionType::Email, InteractionType::OfferDiscussion] {
+            create_interaction_service(
+                &pool,
+                &interaction_type,
+                &today,
+                None,
+                Some("Call"),
+                None,
+                Some(app_id),
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+        }
+
+        let record = application::get_application_by_id(&pool, app_id)
+            .await
+            .unwrap();
+        assert_eq!(record.stage, Some(Stage::Offered));
+
+        let runs = rule::list_rule_runs(&pool, follow_up.id).await.unwrap();
+        assert_eq!(runs.len(), 1);
+        assert_eq!(runs[0].entity_id, Some(reminder_id));
+    }
+
+    #[tokio::test]
+    async fn test_inactivity_rules_fire_once_per_quiet_period() {
+        // ======================================================
+        // Setup: an application quiet since January
+        // ======================================================
+        let pool = setup_test_db().await;
+        let quiet = application::create_application(
+            &pool,
+            Some(1),
+            Some(&Stage::Applied),
+            &date("2025-01-01"),
+            None,
+        )
+        .await
+        .unwrap();
+
+        let nudge = rule::create_rule(
+            &pool,
+            "Nudge",
+            true,
+            &RuleTrigger::NoInteraction { days: 14 },
+            &RuleAction::CreateReminder {
+                title: "Nudge {company}".into(),
+                message: None,
+                days_after: 0,
+            },
+        )
+        .await
+        .unwrap();
+
+        // ======================================================
+        // Fires for the quiet application only, and only once
+        // ======================================================
+        let first = run_inactivity_rules_service(&pool, &date("2025-02-01"))
+            .await
+            .unwrap()
+            .data
+            .unwrap();
+        let fired = first.as_array().unwrap();
+        assert_eq!(fired.len(), 1);
+        assert_eq!(fired[0]["applicationId"], quiet.id);
+
+        let created = reminder::get_reminder_by_id(&pool, fired[0]["entityId"].as_i64().unwrap())
+            .await
+            .unwrap();
+        assert_eq!(created.reminder_date, date("2025-02-01"));
+
+        let again = run_inactivity_rules_service(&pool, &date("2025-02-05"))
+            .await
+            .unwrap();
+        assert_eq!(again.data.unwrap(), json!([]));
+
+        // ======================================================
+        // A new interaction starts a new quiet period
+        // ======================================================
+        create_interaction_service(
+            &pool,
+            &InteractionType::Email,
+            &date("2025-02-03"),
+            None,
+            None,
+            None,
+            Some(quiet.id),
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+        let early = run_inactivity_rules_service(&pool, &date("2025-02-10"))
+            .await
+            .unwrap();
+        assert_eq!(early.data.unwrap(), json!([]));
+
+        let later = run_inactivity_rules_service(&pool, &date("2025-02-20"))
+            .await
+            .unwrap();
+        assert_eq!(later.data.unwrap().as_array().unwrap().len(), 1);
+
+        // Closed applications are left alone
+        application::update_application(
+            &pool,
+            quiet.id,
+            Patch::Unchanged,
+            Some(&Stage::Rejected),
+            None,
+            Patch::Unchanged,
+        )
+        .await
+        .unwrap();
+        let closed = run_inactivity_rules_service(&pool, &date("2025-06-01"))
+            .await
+            .unwrap();
+        assert_eq!(closed.data.unwrap(), json!([]));
+
+        let runs = get_rule_runs_service(&pool, &nudge.id).await.unwrap();
+        assert_eq!(runs.data.unwrap().as_array().unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,335 @@
+#[cfg(test)]
+mod tests {
+    use crate::db::migrator::{checksum, current_version, run_migrations};
+    use crate::db::models::enums::{Currency, PayPeriod};
+    use crate::db::queries::job_listing::JOB_LISTING_LIST;
+    use crate::db::queries::list_query::ListOptions;
+    use crate::db::schema::MIGRATIONS;
+    use crate::db::tests::test_utils::setup_test_db;
+    use crate::error::AppError;
+    use crate::services::exchange_rate_service::*;
+    use crate::services::job_listing_service::{
+        get_all_job_listings_service, get_job_listing_by_id_service,
+    };
+    use serde_json::json;
+    use sqlx::sqlite::SqlitePoolOptions;
+    use sqlx::{Executor, SqlitePool};
+
+    async fn insert(pool: &SqlitePool, sql: &str) -> i64 {
+        sqlx::query(sql)
+            .execute(pool)
+            .await
+            .unwrap()
+            .last_insert_rowid()
+    }
+
+    #[test]
+    fn test_currency_codes() {
+        assert_eq!("eur".parse::<Currency>(), Ok(Currency::EUR));
+        assert_eq!(" chf ".parse::<Currency>().unwrap().as_str(), "CHF");
+        assert_eq!("other".parse::<Currency>(), Ok(Currency::XXX));
+        assert!("EURO".parse::<Currency>().is_err());
+        assert!("ABC".parse::<Currency>().is_err());
+
+        assert_eq!(json!(Currency::DKK), json!("DKK"));
+        assert_eq!(
+            serde_json::from_value::<Currency>(json!("sek")).unwrap(),
+            "SEK".parse().unwrap()
+        );
+        assert!(serde_json::from_value::<Currency>(json!("XYZ")).is_err());
+
+        assert_eq!(annual_salary(5000, Some(PayPeriod::Monthly), 1.0), 60000.0);
+        assert_eq!(annual_salary(40, Some(PayPeriod::Hourly), 0.5), 41600.0);
+        assert_eq!(annual_salary(300, Some(PayPeriod::Daily), 1.0), 78000.0);
+        assert_eq!(annual_salary(50000, None, 1.1), 55000.0);
+    }
+
+    #[tokio::test]
+    async fn test_exchange_rates() {
+        let pool = setup_test_db().await;
+        let usd = Currency::USD;
+        let eur = Currency::EUR;
+
+        // ======================================================
+        // Rates need a base currency
+        // ======================================================
+        let err = set_exchange_rate_service(&pool, &usd, 0.9)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AppError::Validation { .. }));
+
+        set_base_currency_service(&pool, &eur).await.unwrap();
+        set_exchange_rate_service(&pool, &usd, 0.8).await.unwrap();
+        set_exchange_rate_service(&pool, &usd, 0.9).await.unwrap();
+        set_exchange_rate_service(&pool, &Currency::GBP, 1.2)
+            .await
+            .unwrap();
+
+        let listed = list_exchange_rates_service(&pool).await.unwrap();
+        let data = listed.data.unwrap();
+        assert_eq!(data["baseCurrency"], "EUR");
+        assert_eq!(data["rates"].as_array().unwrap().len(), 3);
+        assert_eq!(data["rates"][2]["currency"], "USD");
+        assert_eq!(data["rates"][2]["rate"], 0.9);
+
+        // ======================================================
+        // Switching the base rescales every rate
+        // ======================================================
+        let switched = set_base_currency_service(&pool, &usd).await.unwrap();
+        let data = switched.data.unwrap();
+        assert_eq!(data["rates"][0]["currency"], "USD");
+        assert_eq!(data["rates"][0]["rate"], 1.0);
+        let rate = |code: &str| {
+            data["rates"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .find(|r| r["currency"] == code)
+                .unwrap()["rate"]
+                .as_f64()
+                .unwrap()
+        };
+        assert!((rate("EUR") - 1.0 / 0.9).abs() < 1e-9);
+        assert!((rate("GBP") - 1.2 / 0.9).abs() < 1e-9);
+
+        // ======================================================
+        // Errors
+        // ======================================================
+        let err = set_exchange_rate_service(&pool, &usd, 2.0)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AppError::Validation { .. }));
+        let err = set_exchange_rate_service(&pool, &eur, -1.0)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AppError::Validation { .. }));
+        let err = delete_exchange_rate_service(&pool, &usd).await.unwrap_err();
+        assert!(matches!(err, AppError::Validation { .. }));
+        let err = set_base_currency_service(&pool, &Currency::DKK)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AppError::NotFound { .. }));
+
+        delete_exchange_rate_service(&pool, &eur).await.unwrap();
+        let err = delete_exchange_rate_service(&pool, &eur).await.unwrap_err();
+        assert!(matches!(err, AppError::NotFound { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_annual_salary_sql_matches_rust() {
+        // The list spec's CASE is a literal; it has to agree with
+        // `PayPeriod::per_year` for every period
+        let pool = setup_test_db().await;
+        set_base_currency_service(&pool, &Currency::EUR)
+            .await
+            .unwrap();
+        set_exchange_rate_service(&pool, &Currency::USD, 0.9)
+            .await
+            .unwrap();
+        let expression = JOB_LISTING_LIST
+            .columns
+            .iter()
+            .find(|c| c.field == "annualSalaryMin")
+            .unwrap()
+            .column;
+
+        // Stops compiling when a period is added without listing it below
+        let _: fn(PayPeriod) = |p| match p {
+            PayPeriod::Hourly | PayPeriod::Daily | PayPeriod::Monthly | PayPeriod::Yearly => {}
+        };
+        for period in [
+            None,
+            Some(PayPeriod::Hourly),
+            Some(PayPeriod::Daily),
+            Some(PayPeriod::Monthly),
+            Some(PayPeriod::Yearly),
+        ] {
+            let id = sqlx::query(
+                "INSERT INTO job_listing (company_id, title, salary_min, currency, pay_period)
+                 VALUES (1, 'Parity', 37, 'USD', ?)",
+            )
+            .bind(period.map(|p| p.as_str()))
+            .execute(&pool)
+            .await
+            .unwrap()
+            .last_insert_rowid();
+
+            let in_sql: Option<f64> = sqlx::query_scalar(&format!(
+                "SELECT {} FROM job_listing WHERE id = ?",
+                expression
+            ))
+            .bind(id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+            assert_eq!(
+                in_sql,
+                Some(annual_salary(37, period, 0.9)),
+                "pay period {:?}",
+                period
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_compare_listings_by_annual_salary() {
+        let pool = setup_test_db().await;
+        set_base_currency_service(&pool, &Currency::EUR)
+            .await
+            .unwrap();
+        set_exchange_rate_service(&pool, &Currency::USD, 0.9)
+            .await
+            .unwrap();
+
+        let monthly = insert(
+            &pool,
+            "INSERT INTO job_listing (company_id, title, salary_min, salary_max, currency, pay_period)
+             VALUES (1, 'Monthly', 4000, 5000, 'EUR', 'monthly')",
+        )
+        .await;
+        let hourly = insert(
+            &pool,
+            "INSERT INTO job_listing (company_id, title, salary_min, salary_max, currency, pay_period)
+             VALUES (1, 'Hourly', 30, 40, 'USD', 'hourly')",
+        )
+        .await;
+        // No rate for GBP: cannot be compared
+        let unconverted = insert(
+            &pool,
+            "INSERT INTO job_listing (company_id, title, salary_min, salary_max, currency)
+             VALUES (1, 'Yearly', 90000, 100000, 'GBP')",
+        )
+        .await;
+
+        // ======================================================
+        // Payloads carry the normalized amounts
+        // ======================================================
+        let fetched = get_job_listing_by_id_service(&pool, &hourly)
+            .await
+            .unwrap()
+            .data
+            .unwrap();
+        assert_eq!(fetched["payPeriod"], "hourly");
+        assert_eq!(fetched["annualSalaryMin"], 56160.0);
+        assert_eq!(fetched["annualSalaryMax"], 74880.0);
+        assert_eq!(fetched["baseCurrency"], "EUR");
+
+        let fetched = get_job_listing_by_id_service(&pool, &unconverted)
+            .await
+            .unwrap()
+            .data
+            .unwrap();
+        assert_eq!(fetched["annualSalaryMax"], json!(null));
+
+        // ======================================================
+        // Sort and filter on them
+        // ======================================================
+        let options: ListOptions = serde_json::from_value(json!({
+            "filters": [{ "field": "annualSalaryMax", "op": "isNotNull" }],
+            "sort": [{ "field": "annualSalaryMax", "direction": "desc" }]
+        }))
+        .unwrap();
+        let page = get_all_job_listings_service(&pool, &options)
+            .await
+            .unwrap()
+            .data
+            .unwrap();
+        let ids: Vec<i64> = page
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|l| l["id"].as_i64().unwrap())
+            .collect();
+        assert_eq!(ids, vec![hourly, monthly]);
+
+        let options: ListOptions = serde_json::from_value(json!({
+            "filters": [{ "field": "annualSalaryMin", "op": "gte", "value": 50000 }]
+        }))
+        .unwrap();
+        let page = get_all_job_listings_service(&pool, &options)
+            .await
+            .unwrap()
+            .data
+            .unwrap();
+        assert_eq!(page.as_array().unwrap().len(), 1);
+        assert_eq!(page[0]["id"], hourly);
+    }
+
+    #[tokio::test]
+    async fn test_upgrade_keeps_job_listings() {
+        // ======================================================
+        // Setup: a database at the version before pay periods
+        // ======================================================
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        pool.execute("PRAGMA foreign_keys = OFF").await.unwrap();
+        let previous = MIGRATIONS
+            .iter()
+            .position(|m| m.name == "salary_normalization")
+            .unwrap();
+        for migration in &MIGRATIONS[..previous] {
+            pool.execute(migration.sql).await.unwrap();
+        }
+        current_version(&pool).await.unwrap();
+        for migration in &MIGRATIONS[1..previous] {
+            sqlx::query("INSERT INTO schema_migrations (version, name, checksum) VALUES (?, ?, ?)")
+                .bind(migration.version)
+                .bind(migration.name)
+                .bind(checksum(migration.sql))
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+        pool.execute(
+            r#"
+            INSERT INTO company (name) VALUES ('Legacy Corp');
+            INSERT INTO job_listing (company_id, title, currency) VALUES (1, 'Gone', 'EUR');
+            INSERT INTO job_listing (company_id, title, currency) VALUES (1, 'Legacy Job', 'other');
+            DELETE FROM job_listing WHERE title = 'Gone';
+            INSERT INTO application (job_listing_id) VALUES (2);
+            "#,
+        )
+        .await
+        .unwrap();
+
+        // ======================================================
+        // Upgrade
+        // ======================================================
+        run_migrations(&pool).await.expect("upgrade failed");
+
+        let currency: String = sqlx::query_scalar("SELECT currency FROM job_listing WHERE id = 2")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(currency, "XXX");
+
+        // Still searchable, ids not reused, application still linked
+        let found: i64 = sqlx::query_scalar(
+            "SELECT rowid FROM job_listing_fts WHERE job_listing_fts MATCH 'legacy'",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(found, 2);
+        let next = insert(
+            &pool,
+            "INSERT INTO job_listing (company_id, title) VALUES (1, 'New')",
+        )
+        .await;
+        assert_eq!(next, 3);
+
+        pool.execute("PRAGMA foreign_keys = ON").await.unwrap();
+        pool.execute("DELETE FROM job_listing WHERE id = 2")
+            .await
+            .unwrap();
+        let linked: Option<i64> =
+            sqlx::query_scalar("SELECT job_listing_id FROM application WHERE id = 1")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(linked, None);
+    }
+}
@@ -0,0 +1,69 @@
+#[cfg(test)]
+mod tests {
+    use crate::db::models::enums::EntityType;
+    use crate::db::queries::search::*;
+    use crate::db::tests::test_utils::setup_test_db;
+    use sqlx::Executor;
+
+    #[tokio::test]
+    async fn test_search_across_entities() {
+        // ======================================================
+        // Setup
+        // ======================================================
+        let pool = setup_test_db().await;
+
+        // ======================================================
+        // Seeded rows are indexed
+        // ======================================================
+        let hits = search_all(&pool, "intro", 10)
+            .await
+            .expect("failed to search");
+
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].entity_type, EntityType::Interaction);
+        assert_eq!(hits[0].entity_id, 1);
+        assert!(hits[0].snippet.contains("<mark>Intro</mark>"));
+
+        let hits = search_all(&pool, "default", 10)
+            .await
+            .expect("failed to search");
+        let types: Vec<EntityType> = hits.iter().map(|h| h.entity_type).collect();
+
+        assert!(types.contains(&EntityType::Company));
+        assert!(types.contains(&EntityType::JobListing));
+
+        // ======================================================
+        // Index follows updates and deletes
+        // ======================================================
+        pool.execute("UPDATE company SET name = 'Acme Robotics' WHERE id = 1")
+            .await
+            .unwrap();
+
+        let hits = search_all(&pool, "acme", 10).await.unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].entity_type, EntityType::Company);
+
+        pool.execute("DELETE FROM note WHERE id = 1").await.unwrap();
+
+        let hits = search_all(&pool, "initial note", 10).await.unwrap();
+        assert!(hits.iter().all(|h| h.entity_type != EntityType::Note));
+    }
+
+    #[tokio::test]
+    async fn test_search_query_sanitization() {
+        let pool = setup_test_db().await;
+
+        assert_eq!(to_fts_query("   "), None);
+        assert_eq!(
+            to_fts_query("rust dev").as_deref(),
+            Some("\"rust\" \"dev\"*")
+        );
+
+        // FTS syntax characters must not cause query errors
+        for input in ["\"", "AND OR", "foo:bar", "(john", "* -"] {
+            search_all(&pool, input, 10)
+                .await
+                .unwrap_or_else(|e| panic!("search for {:?} failed: {}", input, e));
+        }
+    }
+}
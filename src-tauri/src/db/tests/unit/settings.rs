@@ -0,0 +1,213 @@
+#[cfg(test)]
+mod tests {
+    use crate::settings::*;
+    use serde_json::json;
+    use std::path::{Path, PathBuf};
+    use std::sync::{Mutex, MutexGuard};
+
+    // resolve_from reads HOME and the JOBTRACKR_* variables, which are
+    // process-wide, so these tests must not run concurrently.
+    static ENV_LOCK: Mutex<()> = Mutex::new(());
+
+    /// Points HOME at a fresh temp dir and clears the JOBTRACKR_* variables;
+    /// restores the previous environment on drop.
+    struct TestEnv {
+        home: PathBuf,
+        old_home: Option<String>,
+        _lock: MutexGuard<'static, ()>,
+    }
+
+    impl TestEnv {
+        fn new(name: &str) -> Self {
+            let lock = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+            let home = std::env::temp_dir().join(format!(
+                "jobtrackr-settings-{}-{}-{}",
+                name,
+                std::process::id(),
+                chrono::Utc::now().timestamp_nanos_opt().unwrap()
+            ));
+            std::fs::create_dir_all(&home).unwrap();
+
+            let old_home = std::env::var("HOME").ok();
+            std::env::set_var("HOME", &home);
+            std::env::remove_var("JOBTRACKR_DATA_DIR");
+            std::env::remove_var("JOBTRACKR_PROFILE");
+
+            TestEnv {
+                home,
+                old_home,
+                _lock: lock,
+            }
+        }
+
+        fn default_dir(&self) -> PathBuf {
+            self.home.join(".JobTrackr")
+        }
+    }
+
+    impl Drop for TestEnv {
+        fn drop(&mut self) {
+            match &self.old_home {
+                Some(home) => std::env::set_var("HOME", home),
+                None => std::env::remove_var("HOME"),
+            }
+            std::env::remove_var("JOBTRACKR_DATA_DIR");
+            std::env::remove_var("JOBTRACKR_PROFILE");
+        }
+    }
+
+    fn write_settings(dir: &Path, value: serde_json::Value) {
+        std::fs::create_dir_all(dir).unwrap();
+        std::fs::write(dir.join("settings.json"), value.to_string()).unwrap();
+    }
+
+    fn args(list: &[&str]) -> Vec<String> {
+        std::iter::once("jobtrackr")
+            .chain(list.iter().copied())
+            .map(str::to_string)
+            .collect()
+    }
+
+    fn portable_dir() -> PathBuf {
+        std::env::current_exe()
+            .unwrap()
+            .parent()
+            .unwrap()
+            .join("JobTrackrData")
+    }
+
+    #[test]
+    fn test_data_dir_precedence() {
+        let env = TestEnv::new("data-dir");
+        let from_file = env.home.join("from-file");
+        let from_env = env.home.join("from-env");
+        let from_flag = env.home.join("from-flag");
+
+        // ======================================================
+        // Nothing set: ~/.JobTrackr
+        // ======================================================
+        let settings = AppSettings::resolve_from(&args(&[]));
+        assert_eq!(settings.data_dir, env.default_dir());
+
+        // ======================================================
+        // dataDir in ~/.JobTrackr/settings.json
+        // ======================================================
+        write_settings(&env.default_dir(), json!({ "dataDir": from_file }));
+        let settings = AppSettings::resolve_from(&args(&[]));
+        assert_eq!(settings.data_dir, from_file);
+
+        // ======================================================
+        // JOBTRACKR_DATA_DIR beats the settings file
+        // ======================================================
+        std::env::set_var("JOBTRACKR_DATA_DIR", &from_env);
+        let settings = AppSettings::resolve_from(&args(&[]));
+        assert_eq!(settings.data_dir, from_env);
+
+        // ======================================================
+        // --portable beats the environment
+        // ======================================================
+        let settings = AppSettings::resolve_from(&args(&["--portable"]));
+        assert_eq!(settings.data_dir, portable_dir());
+
+        // ======================================================
+        // --data-dir beats everything, in both spellings
+        // ======================================================
+        let flag = from_flag.to_str().unwrap();
+        let settings = AppSettings::resolve_from(&args(&["--portable", "--data-dir", flag]));
+        assert_eq!(settings.data_dir, from_flag);
+
+        let joined = format!("--data-dir={}", flag);
+        let settings = AppSettings::resolve_from(&args(&["--portable", &joined]));
+        assert_eq!(settings.data_dir, from_flag);
+    }
+
+    #[test]
+    fn test_profile_precedence() {
+        let env = TestEnv::new("profile");
+
+        // ======================================================
+        // Nothing set: the default profile
+        // ======================================================
+        let settings = AppSettings::resolve_from(&args(&[]));
+        assert_eq!(settings.active_profile, DEFAULT_PROFILE);
+
+        // ======================================================
+        // activeProfile in the data directory's settings.json
+        // ======================================================
+        write_settings(&env.default_dir(), json!({ "activeProfile": "stored" }));
+        let settings = AppSettings::resolve_from(&args(&[]));
+        assert_eq!(settings.active_profile, "stored");
+
+        // ======================================================
+        // JOBTRACKR_PROFILE beats the settings file
+        // ======================================================
+        std::env::set_var("JOBTRACKR_PROFILE", "from env");
+        let settings = AppSettings::resolve_from(&args(&[]));
+        assert_eq!(settings.active_profile, "from env");
+
+        // ======================================================
+        // --profile beats the environment
+        // ======================================================
+        let settings = AppSettings::resolve_from(&args(&["--profile", "cli"]));
+        assert_eq!(settings.active_profile, "cli");
+    }
+
+    #[test]
+    fn test_invalid_profile_falls_back_to_default() {
+        let env = TestEnv::new("invalid-profile");
+
+        // ======================================================
+        // Invalid stored name
+        // ======================================================
+        write_settings(&env.default_dir(), json!({ "activeProfile": "../escape" }));
+        let settings = AppSettings::resolve_from(&args(&[]));
+        assert_eq!(settings.active_profile, DEFAULT_PROFILE);
+
+        // ======================================================
+        // Invalid CLI name: not silently replaced by a lower level
+        // ======================================================
+        write_settings(&env.default_dir(), json!({ "activeProfile": "stored" }));
+        let settings = AppSettings::resolve_from(&args(&["--profile", "a/b"]));
+        assert_eq!(settings.active_profile, DEFAULT_PROFILE);
+
+        let settings = AppSettings::resolve_from(&args(&["--profile", ".."]));
+        assert_eq!(settings.active_profile, DEFAULT_PROFILE);
+    }
+
+    #[test]
+    fn test_validate_profile_name() {
+        for name in [
+            "work",
+            "Job Hunt 2025",
+            "v1.2",
+            "side_project-b",
+            "Bewerbung",
+        ] {
+            assert!(
+                validate_profile_name(name).is_ok(),
+                "{:?} should be valid",
+                name
+            );
+        }
+
+        let too_long = "a".repeat(65);
+        for name in [
+            "",
+            "   ",
+            "a/b",
+            "a\\b",
+            "..",
+            ".hidden",
+            " padded",
+            "padded ",
+            "semi;colon",
+            too_long.as_str(),
+        ] {
+            assert!(
+                validate_profile_name(name).is_err(),
+                "{:?} should be rejected",
+                name
+            );
+        }
+    }
+}
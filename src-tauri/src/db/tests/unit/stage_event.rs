@@ -0,0 +1,91 @@
+#[cfg(test)]
+mod tests {
+    use crate::db::models::enums::Stage;
+    use crate::db::models::patch::Patch;
+    use crate::db::queries::application::{create_application, update_application};
+    use crate::db::queries::stage_event::*;
+    use crate::db::tests::test_utils::setup_test_db;
+    use chrono::NaiveDate;
+
+    #[tokio::test]
+    async fn test_stage_transitions_are_recorded() {
+        // ======================================================
+        // Setup
+        // ======================================================
+        let pool = setup_test_db().await;
+        let applied = NaiveDate::from_ymd_opt(2024, 1, 10).unwrap();
+
+        let created = create_application(&pool, Some(1), Some(&Stage::Applied), &applied, None)
+            .await
+            .expect("failed to create application");
+
+        // ======================================================
+        // Create records the initial stage, back-dated to applied_date
+        // ======================================================
+        let events = get_stage_events_for_application(&pool, created.id)
+            .await
+            .expect("failed to load timeline");
+
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].from_stage, None);
+        assert_eq!(events[0].to_stage, Stage::Applied);
+        assert_eq!(events[0].changed_at.date(), applied);
+
+        // ======================================================
+        // Stage changes append, other updates do not
+        // ======================================================
+        update_application(
+            &pool,
+            created.id,
+            Patch::Unchanged,
+            Some(&Stage::Screening),
+            None,
+            Patch::Unchanged,
+        )
+        .await
+        .unwrap();
+        update_application(
+            &pool,
+            created.id,
+            Patch::Unchanged,
+            Some(&Stage::Screening),
+            None,
+            Patch::Set("same stage"),
+        )
+        .await
+        .unwrap();
+        update_application(
+            &pool,
+            created.id,
+            Patch::Unchanged,
+            Some(&Stage::Rejected),
+            None,
+            Patch::Unchanged,
+        )
+        .await
+        .unwrap();
+
+        let events = get_stage_events_for_application(&pool, created.id)
+            .await
+            .unwrap();
+        let path: Vec<(Option<Stage>, Stage)> = events
+            .into_iter()
+            .map(|e| (e.from_stage, e.to_stage))
+            .collect();
+
+        assert_eq!(
+            path,
+            vec![
+                (None, Stage::Applied),
+                (Some(Stage::Applied), Stage::Screening),
+                (Some(Stage::Screening), Stage::Rejected),
+            ]
+        );
+
+        let entered = get_current_stage_entered_at(&pool, created.id)
+            .await
+            .unwrap()
+            .expect("current stage should have an entry");
+        assert!(entered.date() > applied);
+    }
+}
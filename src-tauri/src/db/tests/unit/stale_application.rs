@@ -0,0 +1,160 @@
+#[cfg(test)]
+mod tests {
+    use crate::db::models::enums::{EntityType, InteractionType, Stage};
+    use crate::db::models::patch::Patch;
+    use crate::db::queries::application::{
+        create_application, delete_application, get_application_by_id, update_application,
+    };
+    use crate::db::queries::interaction::create_interaction;
+    use crate::db::queries::stage_event::get_stage_events_for_application;
+    use crate::db::queries::{tag, trash};
+    use crate::db::tests::test_utils::setup_test_db;
+    use crate::error::AppError;
+    use crate::services::application_service::*;
+    use chrono::NaiveDate;
+    use sqlx::SqlitePool;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    async fn applied_on(pool: &SqlitePool, applied: &str, stage: Stage) -> i64 {
+        create_application(pool, Some(1), Some(&stage), &date(applied), None)
+            .await
+            .expect("failed to create application")
+            .id
+    }
+
+    #[tokio::test]
+    async fn test_stale_applications_and_ghosting() {
+        // ======================================================
+        // Setup: all applied on Jan 1st, today is Feb 10th
+        // ======================================================
+        let pool = setup_test_db().await;
+        let today = date("2025-02-10");
+
+        let silent = applied_on(&pool, "2025-01-01", Stage::Applied).await;
+        let answered = applied_on(&pool, "2025-01-01", Stage::Applied).await;
+        let moved = applied_on(&pool, "2025-01-01", Stage::Applied).await;
+        let closed = applied_on(&pool, "2025-01-01", Stage::Rejected).await;
+
+        create_interaction(
+            &pool,
+            &InteractionType::Email,
+            &date("2025-01-25"),
+            None,
+            None,
+            None,
+            Some(answered),
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+        // Stage changes count as activity (recorded now)
+        update_application(
+            &pool,
+            moved,
+            Patch::Unchanged,
+            Some(&Stage::Screening),
+            None,
+            Patch::Unchanged,
+        )
+        .await
+        .unwrap();
+
+        // ======================================================
+        // ListStale: only the silent, open application
+        // ======================================================
+        let stale = list_stale_applications_service(&pool, 30, &today)
+            .await
+            .unwrap()
+            .data
+            .unwrap();
+        let items = stale.as_array().unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0]["id"], silent);
+        assert_eq!(items[0]["lastActivity"], "2025-01-01");
+        assert_eq!(items[0]["daysInactive"], 40);
+
+        let wider = list_stale_applications_service(&pool, 10, &today)
+            .await
+            .unwrap()
+            .data
+            .unwrap();
+        let ids: Vec<i64> = wider
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|i| i["id"].as_i64().unwrap())
+            .collect();
+        assert_eq!(ids, [silent, answered]);
+        assert!(!ids.contains(&closed));
+
+        let err = list_stale_applications_service(&pool, 0, &today)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AppError::Validation { .. }));
+
+        // ======================================================
+        // GhostStale: restricted to ids, then the whole batch
+        // ======================================================
+        let none = ghost_stale_applications_service(&pool, 30, &today, Some(&[answered]))
+            .await
+            .unwrap();
+        assert_eq!(none.data.unwrap().as_array().unwrap().len(), 0);
+
+        let ghosted = ghost_stale_applications_service(&pool, 30, &today, None)
+            .await
+            .unwrap()
+            .data
+            .unwrap();
+        assert_eq!(ghosted.as_array().unwrap().len(), 1);
+        assert_eq!(ghosted[0]["stage"], "ghosted");
+
+        let record = get_application_by_id(&pool, silent).await.unwrap();
+        assert_eq!(record.stage, Some(Stage::Ghosted));
+
+        let events = get_stage_events_for_application(&pool, silent)
+            .await
+            .unwrap();
+        let last = events.last().unwrap();
+        assert_eq!(last.from_stage, Some(Stage::Applied));
+        assert_eq!(last.to_stage, Stage::Ghosted);
+
+        // Ghosted applications are closed
+        let stale = list_stale_applications_service(&pool, 30, &today)
+            .await
+            .unwrap();
+        assert_eq!(stale.data.unwrap().as_array().unwrap().len(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_application_rebuild_keeps_triggers() {
+        let pool = setup_test_db().await;
+        let id = applied_on(&pool, "2025-01-01", Stage::Applied).await;
+
+        // Initial stage event, back-dated to the applied date
+        let events = get_stage_events_for_application(&pool, id).await.unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].changed_at.date(), date("2025-01-01"));
+
+        // Tag links go with the application once it is deleted for good
+        let label = tag::create_tag(&pool, "label", None).await.unwrap();
+        tag::attach_tag(&pool, label.id, EntityType::Application, id)
+            .await
+            .unwrap();
+        delete_application(&pool, id).await.unwrap();
+        trash::empty_trash(&pool, Some(EntityType::Application), None)
+            .await
+            .unwrap();
+        let tags = tag::get_tags_for_entity(&pool, EntityType::Application, id)
+            .await
+            .unwrap();
+        assert!(tags.is_empty());
+
+        // Ids are not reused
+        let next = applied_on(&pool, "2025-01-02", Stage::Applied).await;
+        assert!(next > id);
+    }
+}
@@ -0,0 +1,140 @@
+#[cfg(test)]
+mod tests {
+    use crate::db::models::enums::EntityType;
+    use crate::db::queries::company::list_companies;
+    use crate::db::queries::list_query::{ListOptions, TagMatch};
+    use crate::db::queries::tag::*;
+    use crate::db::tests::test_utils::setup_test_db;
+    use crate::error::AppError;
+    use crate::services::company_service::get_company_by_id_service;
+    use crate::services::tag_service::*;
+
+    #[tokio::test]
+    async fn test_tags() {
+        let pool = setup_test_db().await;
+        sqlx::query("INSERT INTO company (name) VALUES ('Second'), ('Third')")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        // ======================================================
+        // Create and validate
+        // ======================================================
+        let remote = create_tag(&pool, "remote", Some("#00aa00")).await.unwrap();
+        let dream = create_tag(&pool, "dream job", None).await.unwrap();
+        let fintech = create_tag(&pool, "fintech", None).await.unwrap();
+
+        assert_eq!(normalize_tag_name("  urgent ").unwrap(), "urgent");
+        assert!(matches!(
+            normalize_tag_name("   "),
+            Err(AppError::Validation { .. })
+        ));
+        assert_eq!(normalize_tag_color("#AABBCC").unwrap(), "#aabbcc");
+        assert!(normalize_tag_color("red").is_err());
+        assert!(create_tag_service(&pool, "x", Some("#12345"))
+            .await
+            .is_err());
+
+        // ======================================================
+        // Attach (idempotent) and detach
+        // ======================================================
+        for (tag_id, company_id) in [
+            (remote.id, 1),
+            (remote.id, 1),
+            (dream.id, 1),
+            (remote.id, 2),
+        ] {
+            attach_tag(&pool, tag_id, EntityType::Company, company_id)
+                .await
+                .unwrap();
+        }
+        attach_tag(&pool, fintech.id, EntityType::JobListing, 1)
+            .await
+            .unwrap();
+        assert!(matches!(
+            attach_tag(&pool, remote.id, EntityType::Company, 99).await,
+            Err(sqlx::Error::RowNotFound)
+        ));
+
+        let tags = get_tags_for_entity(&pool, EntityType::Company, 1)
+            .await
+            .unwrap();
+        let names: Vec<&str> = tags.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, ["dream job", "remote"]);
+
+        assert!(detach_tag(&pool, fintech.id, EntityType::JobListing, 1)
+            .await
+            .unwrap());
+        assert!(!detach_tag(&pool, fintech.id, EntityType::JobListing, 1)
+            .await
+            .unwrap());
+
+        let usage = list_tags(&pool).await.unwrap();
+        let remote_usage = usage.iter().find(|t| t.id == remote.id).unwrap();
+        assert_eq!(remote_usage.usage_count, 2);
+
+        // ======================================================
+        // Payloads carry their tags
+        // ======================================================
+        let response = get_company_by_id_service(&pool, &1).await.unwrap();
+        let data = response.data.unwrap();
+        assert_eq!(data["tags"][0]["name"], "dream job");
+        assert_eq!(data["tags"][1]["color"], "#00aa00");
+
+        // ======================================================
+        // Filter lists by tag
+        // ======================================================
+        let by_tags = |tags: Vec<i64>, tag_match: TagMatch| ListOptions {
+            tags,
+            tag_match,
+            ..Default::default()
+        };
+
+        let page = list_companies(&pool, &by_tags(vec![remote.id, dream.id], TagMatch::All))
+            .await
+            .unwrap();
+        assert_eq!(page.total, 1);
+        assert_eq!(page.items[0].id, 1);
+
+        let page = list_companies(&pool, &by_tags(vec![remote.id, dream.id], TagMatch::Any))
+            .await
+            .unwrap();
+        assert_eq!(page.total, 2);
+
+        // Duplicate IDs do not make "all" impossible
+        let page = list_companies(&pool, &by_tags(vec![remote.id, remote.id], TagMatch::All))
+            .await
+            .unwrap();
+        assert_eq!(page.total, 2);
+
+        // ======================================================
+        // Merge: links move, duplicates collapse, sources vanish
+        // ======================================================
+        let merged = merge_tags(&pool, &[dream.id, fintech.id], remote.id)
+            .await
+            .unwrap();
+        assert_eq!(merged.id, remote.id);
+        assert!(get_tag_by_id(&pool, dream.id).await.is_err());
+        let tags = get_tags_for_entity(&pool, EntityType::Company, 1)
+            .await
+            .unwrap();
+        assert_eq!(tags.len(), 1);
+        assert_eq!(tags[0].id, remote.id);
+
+        // ======================================================
+        // Deleting a record removes its links
+        // ======================================================
+        sqlx::query("DELETE FROM company WHERE id = 2")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let usage = list_tags(&pool).await.unwrap();
+        assert_eq!(usage[0].usage_count, 1);
+
+        // ======================================================
+        // Names are unique regardless of case
+        // ======================================================
+        let err = create_tag_service(&pool, "REMOTE", None).await.unwrap_err();
+        assert!(matches!(err, AppError::Conflict { .. }));
+    }
+}
@@ -0,0 +1,158 @@
+#[cfg(test)]
+mod tests {
+    use crate::db::models::enums::EntityType;
+    use crate::db::queries::application::{get_application_by_id, list_applications};
+    use crate::db::queries::company::{delete_company, get_company_by_id};
+    use crate::db::queries::job_listing::{get_job_listing_by_id, list_job_listings};
+    use crate::db::queries::list_query::{Inclusion, ListOptions};
+    use crate::db::queries::search::search_all;
+    use crate::db::queries::trash;
+    use crate::db::tests::test_utils::setup_test_db;
+    use crate::error::AppError;
+    use crate::services::trash_service::*;
+
+    #[tokio::test]
+    async fn test_trash_and_restore() {
+        let pool = setup_test_db().await;
+
+        // ======================================================
+        // Deleting a company trashes it with its job listings
+        // ======================================================
+        delete_company(&pool, 1).await.unwrap();
+
+        assert!(get_company_by_id(&pool, 1).await.is_err());
+        assert!(get_job_listing_by_id(&pool, 1).await.is_err());
+        let page = list_job_listings(&pool, &ListOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(page.total, 0);
+        assert!(search_all(&pool, "Default", 10).await.unwrap().is_empty());
+
+        // Nothing else was touched
+        let application = get_application_by_id(&pool, 1).await.unwrap();
+        assert_eq!(application.job_listing_id, Some(1));
+
+        let items = trash::list_trash(&pool, None).await.unwrap();
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].entity_type, EntityType::Company);
+        assert_eq!(items[0].label, "Default Company");
+        assert_eq!(items[1].entity_type, EntityType::JobListing);
+        assert_eq!(items[1].deleted_at, items[0].deleted_at);
+
+        // Deleting twice is not possible
+        assert!(delete_company(&pool, 1).await.is_err());
+
+        // ======================================================
+        // Restore: the listing needs its company back first
+        // ======================================================
+        let err = restore_service(&pool, EntityType::JobListing, &1)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AppError::ConstraintViolation { .. }));
+
+        restore_service(&pool, EntityType::Company, &1)
+            .await
+            .unwrap();
+        assert!(get_job_listing_by_id(&pool, 1).await.is_ok());
+        assert!(trash::list_trash(&pool, None).await.unwrap().is_empty());
+
+        let err = restore_service(&pool, EntityType::Company, &1)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AppError::NotFound { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_archive() {
+        let pool = setup_test_db().await;
+        let only = |archived| ListOptions {
+            archived,
+            ..Default::default()
+        };
+
+        set_archived_service(&pool, EntityType::Application, &1, true)
+            .await
+            .unwrap();
+
+        // Hidden from lists, still readable by id
+        let page = list_applications(&pool, &ListOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(page.total, 0);
+        let page = list_applications(&pool, &only(Inclusion::Only))
+            .await
+            .unwrap();
+        assert_eq!(page.items[0].id, 1);
+        assert!(page.items[0].archived_at.is_some());
+        assert!(get_application_by_id(&pool, 1).await.is_ok());
+
+        set_archived_service(&pool, EntityType::Application, &1, false)
+            .await
+            .unwrap();
+        let page = list_applications(&pool, &ListOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(page.total, 1);
+
+        // Trashed records cannot be archived
+        trash::move_to_trash(&pool, EntityType::Application, 1)
+            .await
+            .unwrap();
+        let err = set_archived_service(&pool, EntityType::Application, &1, true)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AppError::NotFound { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_empty_trash() {
+        let pool = setup_test_db().await;
+        trash::move_to_trash(&pool, EntityType::Application, 1)
+            .await
+            .unwrap();
+        trash::move_to_trash(&pool, EntityType::Note, 1)
+            .await
+            .unwrap();
+
+        // Nothing is old enough yet
+        let kept = empty_trash_service(&pool, None, Some(30)).await.unwrap();
+        assert_eq!(kept.data.unwrap()["removed"], 0);
+
+        let err = empty_trash_service(&pool, None, Some(-1))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AppError::Validation { .. }));
+
+        let emptied = empty_trash_service(&pool, Some(EntityType::Application), None)
+            .await
+            .unwrap();
+        assert_eq!(emptied.data.unwrap()["removed"], 1);
+
+        // Gone for good; links to it are cleared as before
+        let listed = trash::list_trash(&pool, None).await.unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].entity_type, EntityType::Note);
+        assert!(trash::restore(&pool, EntityType::Application, 1)
+            .await
+            .is_err());
+
+        let linked: Option<i64> =
+            sqlx::query_scalar("SELECT application_id FROM reminder WHERE id = 1")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(linked, None);
+
+        // Exports still carry trashed records
+        let all = ListOptions {
+            archived: Inclusion::Include,
+            trashed: Inclusion::Include,
+            ..Default::default()
+        };
+        let notes = crate::db::queries::note::list_notes(&pool, &all)
+            .await
+            .unwrap();
+        assert_eq!(notes.total, 1);
+        assert!(notes.items[0].deleted_at.is_some());
+    }
+}
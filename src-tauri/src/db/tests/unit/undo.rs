@@ -0,0 +1,218 @@
+#[cfg(test)]
+mod tests {
+    use crate::db::connection::DbState;
+    use crate::db::models::patch::Patch;
+    use crate::db::queries::application::get_application_by_id;
+    use crate::db::queries::job_listing::get_job_listing_by_id;
+    use crate::db::queries::note::get_note_by_id;
+    use crate::db::tests::test_utils::setup_test_db;
+    use crate::error::AppError;
+    use crate::services::application_service::delete_application_service;
+    use crate::services::company_service::delete_company_service;
+    use crate::services::note_service::*;
+    use crate::services::undo_service::*;
+
+    async fn update_title(db: &DbState, pool: &sqlx::SqlitePool, title: &str) {
+        undoable(
+            db,
+            pool,
+            update_note_service(
+                pool,
+                &1,
+                Patch::Unchanged,
+                Patch::Unchanged,
+                Patch::Unchanged,
+                Patch::Unchanged,
+                Patch::Unchanged,
+                Patch::Unchanged,
+                Patch::Set(title),
+                Patch::Unchanged,
+            ),
+        )
+        .await
+        .unwrap();
+    }
+
+    fn counts(db: &DbState) -> (usize, usize) {
+        let stack = db.undo_stack();
+        (stack.undo_count(), stack.redo_count())
+    }
+
+    #[tokio::test]
+    async fn test_undo_and_redo() {
+        let pool = setup_test_db().await;
+        let db = DbState::default();
+        db.replace(pool.clone());
+
+        // ======================================================
+        // Three changes
+        // ======================================================
+        let created = undoable(
+            &db,
+            &pool,
+            create_note_service(
+                &pool,
+                None,
+                None,
+                Some(1),
+                None,
+                None,
+                None,
+                Some("Scratch"),
+                None,
+            ),
+        )
+        .await
+        .unwrap();
+        let new_id = created.data.unwrap()["id"].as_i64().unwrap();
+        update_title(&db, &pool, "Renamed").await;
+        undoable(&db, &pool, delete_application_service(&pool, &1))
+            .await
+            .unwrap();
+
+        // Failed calls are not tracked
+        assert!(undoable(&db, &pool, delete_application_service(&pool, &1))
+            .await
+            .is_err());
+        assert_eq!(counts(&db), (3, 0));
+
+        // ======================================================
+        // Undo: the deleted row comes back with its id and links
+        // ======================================================
+        undo_service(&pool, &db, None).await.unwrap();
+        let application = get_application_by_id(&pool, 1).await.unwrap();
+        assert_eq!(application.job_listing_id, Some(1));
+        let linked: Option<i64> =
+            sqlx::query_scalar("SELECT application_id FROM reminder WHERE id = 1")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(linked, Some(1));
+
+        let undone = undo_service(&pool, &db, Some(5)).await.unwrap();
+        let data = undone.data.unwrap();
+        assert_eq!(data["operations"].as_array().unwrap().len(), 2);
+        assert_eq!(data["canRedo"], 3);
+        assert_eq!(
+            get_note_by_id(&pool, 1).await.unwrap().title.as_deref(),
+            Some("Initial Note")
+        );
+        assert!(get_note_by_id(&pool, new_id).await.is_err());
+
+        // ======================================================
+        // Redo, and a new change drops what is left to redo
+        // ======================================================
+        redo_service(&pool, &db, Some(2)).await.unwrap();
+        assert_eq!(
+            get_note_by_id(&pool, new_id)
+                .await
+                .unwrap()
+                .title
+                .as_deref(),
+            Some("Scratch")
+        );
+        assert_eq!(
+            get_note_by_id(&pool, 1).await.unwrap().title.as_deref(),
+            Some("Renamed")
+        );
+        assert_eq!(counts(&db), (2, 1));
+
+        update_title(&db, &pool, "Again").await;
+        assert_eq!(counts(&db), (3, 0));
+        let nothing = redo_service(&pool, &db, None).await.unwrap();
+        assert_eq!(nothing.data.unwrap()["operations"], serde_json::json!([]));
+
+        let err = undo_service(&pool, &db, Some(0)).await.unwrap_err();
+        assert!(matches!(err, AppError::Validation { .. }));
+
+        // A different database starts a new history
+        db.replace(pool.clone());
+        assert_eq!(counts(&db), (0, 0));
+    }
+
+    #[tokio::test]
+    async fn test_undo_company_delete() {
+        let pool = setup_test_db().await;
+        let db = DbState::default();
+        db.replace(pool.clone());
+
+        undoable(&db, &pool, delete_company_service(&pool, &1))
+            .await
+            .unwrap();
+        assert!(get_job_listing_by_id(&pool, 1).await.is_err());
+
+        // Its job listings went to the trash with it and come back with it
+        undo_service(&pool, &db, None).await.unwrap();
+        assert!(get_job_listing_by_id(&pool, 1).await.is_ok());
+
+        redo_service(&pool, &db, None).await.unwrap();
+        assert!(get_job_listing_by_id(&pool, 1).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_undo_leaves_concurrent_changes() {
+        let pool = setup_test_db().await;
+        let db = DbState::default();
+        db.replace(pool.clone());
+
+        // Something else (e.g. the scheduler) records a change while the
+        // command runs
+        let background = pool.clone();
+        undoable(&db, &pool, async {
+            let created = tokio::spawn(async move {
+                create_note_service(
+                    &background,
+                    None,
+                    None,
+                    Some(1),
+                    None,
+                    None,
+                    None,
+                    Some("Background"),
+                    None,
+                )
+                .await
+                .unwrap()
+            })
+            .await
+            .unwrap();
+            update_note_service(
+                &pool,
+                &1,
+                Patch::Unchanged,
+                Patch::Unchanged,
+                Patch::Unchanged,
+                Patch::Unchanged,
+                Patch::Unchanged,
+                Patch::Unchanged,
+                Patch::Set("Mine"),
+                Patch::Unchanged,
+            )
+            .await?;
+            Ok(created)
+        })
+        .await
+        .unwrap();
+        assert_eq!(counts(&db), (1, 0));
+
+        let undone = undo_service(&pool, &db, None).await.unwrap();
+        assert_eq!(
+            undone.data.unwrap()["operations"][0]
+                .as_array()
+                .unwrap()
+                .len(),
+            1
+        );
+        assert_eq!(
+            get_note_by_id(&pool, 1).await.unwrap().title.as_deref(),
+            Some("Initial Note")
+        );
+        let kept: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM note WHERE title = 'Background' AND deleted_at IS NULL",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(kept, 1);
+    }
+}
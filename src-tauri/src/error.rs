@@ -0,0 +1,167 @@
+use crate::db::queries::list_query::ListError;
+use serde::Serialize;
+use sqlx::error::ErrorKind;
+use std::fmt;
+
+/// A single invalid input field.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldError {
+    pub field: String,
+    pub message: String,
+}
+
+/// Which database constraint rejected a write.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ConstraintKind {
+    ForeignKey,
+    Check,
+    NotNull,
+    Other,
+}
+
+/// Error returned to the frontend.
+///
+/// Serialized with a `kind` tag so callers can branch on the failure
+/// instead of parsing messages, e.g.
+/// `{"kind": "notFound", "message": "Failed to retrieve company 7: record not found"}`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum AppError {
+    NotFound {
+        message: String,
+    },
+    Validation {
+        message: String,
+        fields: Vec<FieldError>,
+    },
+    /// A unique constraint was violated.
+    Conflict {
+        message: String,
+    },
+    ConstraintViolation {
+        message: String,
+        constraint: ConstraintKind,
+    },
+    Database {
+        message: String,
+    },
+    Io {
+        message: String,
+    },
+}
+
+impl AppError {
+    pub fn not_found(message: impl Into<String>) -> Self {
+        AppError::NotFound {
+            message: message.into(),
+        }
+    }
+
+    /// Validation failure of a single input field.
+    pub fn invalid_field(field: impl Into<String>, message: impl Into<String>) -> Self {
+        let field = field.into();
+        let message = message.into();
+        AppError::Validation {
+            message: format!("{}: {}", field, message),
+            fields: vec![FieldError { field, message }],
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        match self {
+            AppError::NotFound { message }
+            | AppError::Validation { message, .. }
+            | AppError::Conflict { message }
+            | AppError::ConstraintViolation { message, .. }
+            | AppError::Database { message }
+            | AppError::Io { message } => message,
+        }
+    }
+
+    fn message_mut(&mut self) -> &mut String {
+        match self {
+            AppError::NotFound { message }
+            | AppError::Validation { message, .. }
+            | AppError::Conflict { message }
+            | AppError::ConstraintViolation { message, .. }
+            | AppError::Database { message }
+            | AppError::Io { message } => message,
+        }
+    }
+
+    /// Prefixes the message with what was being attempted,
+    /// e.g. "Failed to update company 3: record not found".
+    pub fn context(mut self, action: impl fmt::Display) -> Self {
+        let message = self.message_mut();
+        *message = format!("{}: {}", action, message);
+        self
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+impl std::error::Error for AppError {}
+
+impl From<sqlx::Error> for AppError {
+    fn from(e: sqlx::Error) -> Self {
+        match e {
+            sqlx::Error::RowNotFound => AppError::not_found("record not found"),
+            sqlx::Error::Database(db) => {
+                let message = db.message().to_string();
+                let constraint = match db.kind() {
+                    ErrorKind::UniqueViolation => return AppError::Conflict { message },
+                    ErrorKind::ForeignKeyViolation => ConstraintKind::ForeignKey,
+                    ErrorKind::CheckViolation => ConstraintKind::Check,
+                    ErrorKind::NotNullViolation => ConstraintKind::NotNull,
+                    _ if message.contains("constraint failed") => ConstraintKind::Other,
+                    _ => return AppError::Database { message },
+                };
+                AppError::ConstraintViolation {
+                    message,
+                    constraint,
+                }
+            }
+            sqlx::Error::Io(e) => e.into(),
+            other => AppError::Database {
+                message: other.to_string(),
+            },
+        }
+    }
+}
+
+impl From<std::io::Error> for AppError {
+    fn from(e: std::io::Error) -> Self {
+        AppError::Io {
+            message: e.to_string(),
+        }
+    }
+}
+
+impl From<ListError> for AppError {
+    fn from(e: ListError) -> Self {
+        match e {
+            ListError::Invalid(_) => AppError::Validation {
+                message: e.to_string(),
+                fields: Vec::new(),
+            },
+            ListError::Database(e) => e.into(),
+        }
+    }
+}
+
+/// Lets commands that still return `JsonResult` use helpers returning `AppError`.
+impl From<AppError> for String {
+    fn from(e: AppError) -> Self {
+        let mut value = serde_json::to_value(&e).unwrap_or_default();
+        if let serde_json::Value::Object(ref mut obj) = value {
+            obj.insert("status".to_string(), "error".into());
+        }
+        value.to_string()
+    }
+}
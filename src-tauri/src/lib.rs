@@ -1,28 +1,43 @@
 pub mod commands;
 pub mod db;
+pub mod error;
 pub mod logger;
 pub mod services;
+pub mod settings;
 pub mod utils;
 
 use crate::commands::*;
-use crate::db::connection::init_db;
+use crate::db::connection::{init_db, DbState};
 use crate::logger::*;
+use crate::services::attachment_service::sweep_attachment_store;
+use crate::services::backup_service::run_daily_backup;
+use crate::services::calendar_service::refresh_calendar_feed;
+use crate::services::reminder_scheduler::spawn_reminder_scheduler;
+use crate::settings::AppSettings;
+use std::sync::Mutex;
 use tauri::Manager;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 #[tokio::main]
 pub async fn run() {
+    // Resolve data directory and active profile from CLI / env / settings.json
+    let settings = AppSettings::resolve();
+
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
-        .setup(|app| {
+        .plugin(tauri_plugin_notification::init())
+        .manage(DbState::default())
+        .manage(Mutex::new(settings.clone()))
+        .setup(move |app| {
             // Clone to produce an owned handle with 'static lifetime
             let app_handle = app.app_handle().clone();
 
             // Run async init in background to avoid blocking UI
             tauri::async_runtime::spawn(async move {
                 // Logger (blocking)
-                if let Err(e) = tokio::task::spawn_blocking(|| {
-                    logger::init();
+                let log_dir = settings.active_log_dir();
+                if let Err(e) = tokio::task::spawn_blocking(move || {
+                    logger::init(&log_dir);
                     info!("Logger initialized.");
                 })
                 .await
@@ -31,11 +46,30 @@ pub async fn run() {
                     return;
                 }
 
+                info!(
+                    "Using data directory {} (profile '{}')",
+                    settings.data_dir.display(),
+                    settings.active_profile
+                );
+
                 // Database (async)
-                match init_db().await {
+                let db_path = settings.active_db_path();
+                match init_db(&db_path).await {
                     Ok(pool) => {
                         info!("Database initialized and ready.");
-                        app_handle.manage(pool);
+                        app_handle.state::<DbState>().replace(pool.clone());
+
+                        // Daily snapshot + rotation (non-blocking for commands)
+                        run_daily_backup(&pool, &db_path, &settings.backup).await;
+
+                        // Keep the subscribable calendar feed current
+                        refresh_calendar_feed(&pool, &settings).await;
+
+                        // Drop files no record refers to anymore
+                        sweep_attachment_store(&pool, &settings).await;
+
+                        // Announce due reminders from now on
+                        spawn_reminder_scheduler(app_handle.clone());
                     }
                     Err(e) => {
                         error!("Database initialization failed: {:?}", e);
@@ -46,13 +80,27 @@ pub async fn run() {
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
+            handle_analytics_command,
             handle_application_command,
+            handle_application_contact_command,
+            handle_attachment_command,
+            handle_audit_command,
+            handle_backup_command,
             handle_company_command,
+            handle_exchange_rate_command,
+            handle_export_command,
+            handle_import_command,
             handle_interaction_command,
             handle_job_listing_command,
             handle_note_command,
             handle_person_command,
+            handle_profile_command,
             handle_reminder_command,
+            handle_rule_command,
+            handle_search_command,
+            handle_tag_command,
+            handle_trash_command,
+            handle_undo_command,
         ])
         .run(tauri::generate_context!())
         .expect("Error while running JobTrackr application");
@@ -1,36 +1,61 @@
 // src/logger.rs
 use chrono::Local;
-use std::{fs, fs::OpenOptions, sync::Mutex};
+use once_cell::sync::Lazy;
+use std::{
+    fs,
+    fs::{File, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
 use tracing_subscriber::fmt;
 
-pub fn init() {
-    let home = dirs::home_dir().unwrap_or_else(|| {
-        eprintln!("FATAL: cannot determine home directory");
-        std::process::exit(1);
-    });
+/// Log file shared by the global subscriber; swapped when the profile changes.
+static LOG_FILE: Lazy<Mutex<Option<File>>> = Lazy::new(|| Mutex::new(None));
 
-    let month = Local::now().format("%Y-%m").to_string();
-    let dir = home.join(".JobTrackr").join("logs").join(month);
-    if let Err(e) = fs::create_dir_all(&dir) {
-        eprintln!("FATAL: cannot create log directory {:?}: {}", dir, e);
-        std::process::exit(1);
+struct LogWriter;
+
+impl Write for LogWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match LOG_FILE.lock().unwrap().as_mut() {
+            Some(file) => file.write(buf),
+            None => Ok(buf.len()),
+        }
     }
 
+    fn flush(&mut self) -> io::Result<()> {
+        match LOG_FILE.lock().unwrap().as_mut() {
+            Some(file) => file.flush(),
+            None => Ok(()),
+        }
+    }
+}
+
+fn open_log_file(log_dir: &Path) -> io::Result<(File, PathBuf)> {
+    let month = Local::now().format("%Y-%m").to_string();
+    let dir = log_dir.join(month);
+    fs::create_dir_all(&dir)?;
+
     let file_path = dir.join(format!("jobtrackr.{}.log", Local::now().format("%Y-%m-%d")));
     let file = OpenOptions::new()
         .create(true)
         .append(true)
-        .open(&file_path)
-        .unwrap_or_else(|e| {
-            eprintln!("FATAL: cannot open log file {:?}: {}", file_path, e);
-            std::process::exit(1);
-        });
+        .open(&file_path)?;
+
+    Ok((file, file_path))
+}
+
+pub fn init(log_dir: &Path) {
+    let (file, file_path) = open_log_file(log_dir).unwrap_or_else(|e| {
+        eprintln!("FATAL: cannot open log file in {:?}: {}", log_dir, e);
+        std::process::exit(1);
+    });
+    *LOG_FILE.lock().unwrap() = Some(file);
 
-    let writer = Mutex::new(file);
     let timer = fmt::time::ChronoLocal::new("%Y-%m-%d %H:%M:%S%.3f".into());
 
     let subscriber = fmt()
-        .with_writer(writer)
+        .with_writer(|| LogWriter)
         .with_timer(timer)
         .with_target(false)
         .with_ansi(false)
@@ -41,4 +66,13 @@ pub fn init() {
     tracing::info!("Logger initialized at {:?}", file_path);
 }
 
+/// Points the running logger at another directory (used when switching profiles).
+pub fn redirect(log_dir: &Path) -> io::Result<()> {
+    let (file, file_path) = open_log_file(log_dir)?;
+    tracing::info!("Switching log output to {:?}", file_path);
+    *LOG_FILE.lock().unwrap() = Some(file);
+    tracing::info!("Logger redirected to {:?}", file_path);
+    Ok(())
+}
+
 pub use tracing::{debug, error, info, warn};
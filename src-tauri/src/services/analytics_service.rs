@@ -0,0 +1,236 @@
+use crate::db::models::enums::Stage;
+use crate::db::queries::analytics::{self, ApplicationOutcome, DateRange, StageCount, WeeklyCount};
+use crate::logger::*;
+use crate::services::service_types::JsonResult;
+use chrono::{Duration, NaiveDate};
+use serde::Serialize;
+use serde_json::json;
+use sqlx::SqlitePool;
+use std::collections::{BTreeMap, HashMap};
+
+/// The "happy path" stages in order. Reaching a later stage implies having
+/// passed the earlier ones, even if a stage was skipped.
+pub const PIPELINE: [Stage; 7] = [
+    Stage::Applied,
+    Stage::Screening,
+    Stage::Assessment,
+    Stage::Interviewing,
+    Stage::Offered,
+    Stage::Negotiation,
+    Stage::Accepted,
+];
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FunnelStep {
+    pub stage: Stage,
+    pub reached: i64,
+    /// Share of applications in the previous step that reached this one
+    pub conversion_rate: Option<f64>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResponseRate {
+    pub key: String,
+    pub applications: i64,
+    pub responded: i64,
+    pub rate: f64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResponseRates {
+    pub by_company: Vec<ResponseRate>,
+    pub by_industry: Vec<ResponseRate>,
+    pub by_work_type: Vec<ResponseRate>,
+    pub by_seniority_level: Vec<ResponseRate>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PipelineReport {
+    pub range: DateRange,
+    pub total_applications: i64,
+    pub by_stage: Vec<StageCount>,
+    pub funnel: Vec<FunnelStep>,
+    pub median_days_to_first_interaction: Option<f64>,
+    pub overall_response_rate: Option<f64>,
+    pub response_rates: ResponseRates,
+    pub weekly_volume: Vec<WeeklyCount>,
+}
+
+const UNKNOWN_KEY: &str = "unknown";
+
+// ======================================================
+// Helper: Median of a list of values
+// ======================================================
+pub fn median(values: &mut [f64]) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_by(|a, b| a.total_cmp(b));
+    let mid = values.len() / 2;
+    if values.len().is_multiple_of(2) {
+        Some((values[mid - 1] + values[mid]) / 2.0)
+    } else {
+        Some(values[mid])
+    }
+}
+
+fn ratio(part: i64, whole: i64) -> Option<f64> {
+    (whole > 0).then(|| part as f64 / whole as f64)
+}
+
+// ======================================================
+// Helper: Funnel from the stages each application entered
+// ======================================================
+fn build_funnel(visits: &[analytics::StageVisit]) -> Vec<FunnelStep> {
+    // Furthest pipeline step per application
+    let mut furthest: HashMap<i64, usize> = HashMap::new();
+    for visit in visits {
+        if let Some(step) = PIPELINE.iter().position(|s| *s == visit.stage) {
+            let entry = furthest.entry(visit.application_id).or_insert(step);
+            *entry = (*entry).max(step);
+        }
+    }
+
+    let mut funnel: Vec<FunnelStep> = Vec::with_capacity(PIPELINE.len());
+    for (step, stage) in PIPELINE.iter().enumerate() {
+        let reached = furthest.values().filter(|f| **f >= step).count() as i64;
+        let conversion_rate = funnel.last().and_then(|prev| ratio(reached, prev.reached));
+        funnel.push(FunnelStep {
+            stage: stage.clone(),
+            reached,
+            conversion_rate,
+        });
+    }
+    funnel
+}
+
+// ======================================================
+// Helper: Response rate grouped by an arbitrary key
+// ======================================================
+fn group_response_rates<F>(outcomes: &[ApplicationOutcome], key: F) -> Vec<ResponseRate>
+where
+    F: Fn(&ApplicationOutcome) -> Option<String>,
+{
+    let mut groups: BTreeMap<String, (i64, i64)> = BTreeMap::new();
+    for outcome in outcomes {
+        let k = key(outcome)
+            .filter(|k| !k.trim().is_empty())
+            .unwrap_or_else(|| UNKNOWN_KEY.to_string());
+        let entry = groups.entry(k).or_default();
+        entry.0 += 1;
+        if outcome.responded {
+            entry.1 += 1;
+        }
+    }
+
+    let mut rates: Vec<ResponseRate> = groups
+        .into_iter()
+        .map(|(key, (applications, responded))| ResponseRate {
+            key,
+            applications,
+            responded,
+            rate: ratio(responded, applications).unwrap_or(0.0),
+        })
+        .collect();
+    rates.sort_by_key(|r| std::cmp::Reverse(r.applications));
+    rates
+}
+
+// ======================================================
+// Helper: Fill weeks without applications with zero
+// ======================================================
+fn fill_weeks(weeks: Vec<WeeklyCount>) -> Vec<WeeklyCount> {
+    let (Some(first), Some(last)) = (weeks.first(), weeks.last()) else {
+        return weeks;
+    };
+
+    let counts: HashMap<NaiveDate, i64> = weeks.iter().map(|w| (w.week_start, w.count)).collect();
+    let mut filled = Vec::new();
+    let mut week = first.week_start;
+    while week <= last.week_start {
+        filled.push(WeeklyCount {
+            week_start: week,
+            count: counts.get(&week).copied().unwrap_or(0),
+        });
+        week += Duration::weeks(1);
+    }
+    filled
+}
+
+// ======================================================
+// Build Pipeline Report
+// ======================================================
+pub async fn build_pipeline_report(
+    pool: &SqlitePool,
+    range: &DateRange,
+) -> Result<PipelineReport, sqlx::Error> {
+    let by_stage = analytics::count_by_stage(pool, range).await?;
+    let visits = analytics::get_stage_visits(pool, range).await?;
+    let outcomes = analytics::get_application_outcomes(pool, range).await?;
+    let weekly = analytics::count_by_week(pool, range).await?;
+
+    let total_applications = outcomes.len() as i64;
+    let responded = outcomes.iter().filter(|o| o.responded).count() as i64;
+
+    let mut days: Vec<f64> = outcomes
+        .iter()
+        .filter_map(|o| o.days_to_first_interaction)
+        .collect();
+
+    Ok(PipelineReport {
+        range: *range,
+        total_applications,
+        by_stage,
+        funnel: build_funnel(&visits),
+        median_days_to_first_interaction: median(&mut days),
+        overall_response_rate: ratio(responded, total_applications),
+        response_rates: ResponseRates {
+            by_company: group_response_rates(&outcomes, |o| o.company.clone()),
+            by_industry: group_response_rates(&outcomes, |o| o.industry.clone()),
+            by_work_type: group_response_rates(&outcomes, |o| {
+                o.work_type.as_ref().map(|w| w.as_str().to_string())
+            }),
+            by_seniority_level: group_response_rates(&outcomes, |o| {
+                o.seniority_level.as_ref().map(|s| s.as_str().to_string())
+            }),
+        },
+        weekly_volume: fill_weeks(weekly),
+    })
+}
+
+// ======================================================
+// Get Pipeline Report
+// ======================================================
+pub async fn get_pipeline_report_service(pool: &SqlitePool, range: &DateRange) -> JsonResult {
+    info!(
+        "Computing pipeline analytics (from: {:?}, to: {:?})",
+        range.from, range.to
+    );
+
+    match build_pipeline_report(pool, range).await {
+        Ok(report) => {
+            info!(
+                "Pipeline analytics computed ({} applications).",
+                report.total_applications
+            );
+            let json = json!({
+                "status": "success",
+                "message": "Pipeline analytics computed successfully.",
+                "data": report
+            });
+            Ok(json.to_string())
+        }
+        Err(e) => {
+            error!("Error computing pipeline analytics: {}", e);
+            let json = json!({
+                "status": "error",
+                "message": format!("Failed to compute pipeline analytics: {}", e)
+            });
+            Err(json.to_string())
+        }
+    }
+}
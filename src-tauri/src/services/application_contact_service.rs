@@ -0,0 +1,171 @@
+use crate::db::models::enums::ContactRole;
+use crate::db::queries::application_contact;
+use crate::error::AppError;
+use crate::logger::*;
+use crate::services::application_service::build_application_payload;
+use crate::services::person_service::format_person_label;
+use crate::services::service_types::{ApiResponse, ServiceResult};
+use crate::services::service_utils::add_display_label;
+use serde_json::{json, Value};
+use sqlx::SqlitePool;
+
+// ======================================================
+// Attach Contact
+// ======================================================
+pub async fn attach_contact_service(
+    pool: &SqlitePool,
+    application_id: &i64,
+    person_id: &i64,
+    role: &ContactRole,
+) -> ServiceResult {
+    info!(
+        "Adding person {} to application {} as {}",
+        person_id,
+        application_id,
+        role.as_str()
+    );
+
+    match application_contact::attach_contact(pool, *application_id, *person_id, role).await {
+        Ok(record) => {
+            info!(
+                "Person {} is now {} on application {}.",
+                person_id,
+                role.as_str(),
+                application_id
+            );
+
+            Ok(ApiResponse::success(
+                format!(
+                    "Person {} added to application {}.",
+                    person_id, application_id
+                ),
+                json!(record),
+            ))
+        }
+        Err(e) => {
+            error!(
+                "Error adding person {} to application {}: {}",
+                person_id, application_id, e
+            );
+            Err(AppError::from(e).context(format!(
+                "Failed to add person {} to application {}",
+                person_id, application_id
+            )))
+        }
+    }
+}
+
+// ======================================================
+// Detach Contact
+// ======================================================
+pub async fn detach_contact_service(
+    pool: &SqlitePool,
+    application_id: &i64,
+    person_id: &i64,
+) -> ServiceResult {
+    info!(
+        "Removing person {} from application {}",
+        person_id, application_id
+    );
+
+    match application_contact::detach_contact(pool, *application_id, *person_id).await {
+        Ok(true) => Ok(ApiResponse::message(format!(
+            "Person {} removed from application {}.",
+            person_id, application_id
+        ))),
+        Ok(false) => Err(AppError::not_found(format!(
+            "Person {} is not a contact on application {}",
+            person_id, application_id
+        ))),
+        Err(e) => {
+            error!(
+                "Error removing person {} from application {}: {}",
+                person_id, application_id, e
+            );
+            Err(AppError::from(e).context(format!(
+                "Failed to remove person {} from application {}",
+                person_id, application_id
+            )))
+        }
+    }
+}
+
+// ======================================================
+// People on an Application
+// ======================================================
+/// Person payloads (with `displayLabel`) plus their `contactRole`.
+pub async fn get_contacts_for_application_service(
+    pool: &SqlitePool,
+    application_id: &i64,
+) -> ServiceResult {
+    info!("Retrieving contacts of application {}", application_id);
+
+    match application_contact::get_contacts_by_application_id(pool, *application_id).await {
+        Ok(contacts) => {
+            let data: Value = contacts
+                .iter()
+                .map(|c| {
+                    let p = &c.person;
+                    let mut data = add_display_label(
+                        p,
+                        Some(format_person_label(&p.last_name, &p.first_name, p.id)),
+                    );
+                    data["contactRole"] = json!(c.contact_role);
+                    data
+                })
+                .collect();
+
+            Ok(ApiResponse::success(
+                format!("Contacts of application {} retrieved.", application_id),
+                data,
+            ))
+        }
+        Err(e) => {
+            error!(
+                "Error retrieving contacts of application {}: {}",
+                application_id, e
+            );
+            Err(AppError::from(e).context(format!(
+                "Failed to retrieve contacts of application {}",
+                application_id
+            )))
+        }
+    }
+}
+
+// ======================================================
+// Applications of a Person
+// ======================================================
+/// Application payloads plus the person's `contactRole` in each.
+pub async fn get_applications_for_contact_service(
+    pool: &SqlitePool,
+    person_id: &i64,
+) -> ServiceResult {
+    info!("Retrieving applications involving person {}", person_id);
+
+    match application_contact::get_applications_by_contact_id(pool, *person_id).await {
+        Ok(applications) => {
+            let mut data = Vec::with_capacity(applications.len());
+            for a in &applications {
+                let mut payload = build_application_payload(pool, &a.application).await;
+                payload["contactRole"] = json!(a.contact_role);
+                data.push(payload);
+            }
+
+            Ok(ApiResponse::success(
+                format!("Applications involving person {} retrieved.", person_id),
+                Value::from(data),
+            ))
+        }
+        Err(e) => {
+            error!(
+                "Error retrieving applications of person {}: {}",
+                person_id, e
+            );
+            Err(AppError::from(e).context(format!(
+                "Failed to retrieve applications of person {}",
+                person_id
+            )))
+        }
+    }
+}
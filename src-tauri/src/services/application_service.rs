@@ -1,16 +1,27 @@
-use crate::db::models::enums::Stage;
-use crate::db::queries::application;
+use crate::db::models::enums::{EntityType, Stage};
+use crate::db::models::patch::Patch;
+use crate::db::queries::application::{self, Application, StaleApplication};
+use crate::db::queries::audit::AuditAction;
+use crate::db::queries::list_query::ListOptions;
+use crate::db::queries::stage_event;
+use crate::error::AppError;
 use crate::logger::*;
-use crate::services::service_types::JsonResult;
-use crate::services::service_utils::add_display_label;
-use chrono::NaiveDate;
-use serde_json::json;
+use crate::services::audit_service::{record_change, record_rule_outcomes, snapshot};
+use crate::services::rule_engine::{add_outcomes, fire_rules, RuleEvent, RuleOutcome};
+use crate::services::service_types::{ApiResponse, ServiceResult};
+use crate::services::service_utils::{add_display_label, add_tags};
+use chrono::{Duration, Local, NaiveDate, NaiveDateTime, Utc};
+use serde_json::{json, Value};
 use sqlx::{query_scalar, SqlitePool};
 
+/// Days without activity after which an application counts as stale.
+pub const DEFAULT_STALE_DAYS: i64 = 30;
+pub const MAX_STALE_DAYS: i64 = 365;
+
 // ======================================================
 // Helper: Retrieve display label for application
 // ======================================================
-async fn fetch_joblisting_label(
+pub async fn fetch_joblisting_label(
     pool: &SqlitePool,
     job_listing_id: Option<i64>,
     fallback_id: i64,
@@ -29,6 +40,120 @@ async fn fetch_joblisting_label(
     }
 }
 
+// ======================================================
+// Helper: Build application payload with derived fields
+// ======================================================
+/// Adds `displayLabel`, `daysInStage` (whole days since the current
+/// stage was entered) and `tags` to an application record.
+pub async fn build_application_payload(pool: &SqlitePool, record: &Application) -> Value {
+    let display_label = fetch_joblisting_label(pool, record.job_listing_id, record.id).await;
+    let mut data = add_display_label(record, Some(display_label));
+
+    let days_in_stage = match stage_event::get_current_stage_entered_at(pool, record.id).await {
+        Ok(Some(entered_at)) => Some(days_since(entered_at)),
+        Ok(None) => None,
+        Err(e) => {
+            warn!(
+                "Could not load stage history for application {}: {}",
+                record.id, e
+            );
+            None
+        }
+    };
+
+    if let Value::Object(ref mut obj) = data {
+        obj.insert("daysInStage".to_string(), json!(days_in_stage));
+    }
+
+    add_tags(pool, EntityType::Application, &mut data).await;
+    data
+}
+
+fn days_since(moment: NaiveDateTime) -> i64 {
+    (Utc::now().naive_utc() - moment).num_days().max(0)
+}
+
+// ======================================================
+// Helper: Write and run automation rules in one transaction
+// ======================================================
+async fn create_with_rules(
+    pool: &SqlitePool,
+    job_listing_id: Option<i64>,
+    stage: Option<&Stage>,
+    applied_date: &NaiveDate,
+    application_notes: Option<&str>,
+) -> Result<(Application, Vec<RuleOutcome>), sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    let record = application::create_application(
+        &mut *tx,
+        job_listing_id,
+        stage,
+        applied_date,
+        application_notes,
+    )
+    .await?;
+
+    let today = Local::now().date_naive();
+    let outcomes = fire_rules(&mut tx, &RuleEvent::ApplicationCreated(&record), today).await?;
+
+    // A rule may have moved the stage on
+    let record = if outcomes.is_empty() {
+        record
+    } else {
+        application::get_application_by_id(&mut *tx, record.id).await?
+    };
+
+    tx.commit().await?;
+    Ok((record, outcomes))
+}
+
+/// Runs `stage_changed` rules only when the stage actually changes.
+async fn update_with_rules(
+    pool: &SqlitePool,
+    id: i64,
+    job_listing_id: Patch<i64>,
+    stage: Option<&Stage>,
+    applied_date: Option<&NaiveDate>,
+    application_notes: Patch<&str>,
+) -> Result<(Application, Vec<RuleOutcome>), sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    let previous = match stage {
+        Some(_) => {
+            application::get_application_by_id(&mut *tx, id)
+                .await?
+                .stage
+        }
+        None => None,
+    };
+
+    let record = application::update_application(
+        &mut *tx,
+        id,
+        job_listing_id,
+        stage,
+        applied_date,
+        application_notes,
+    )
+    .await?;
+
+    let mut outcomes = Vec::new();
+    if stage.is_some() && previous.as_ref() != stage {
+        let today = Local::now().date_naive();
+        outcomes = fire_rules(&mut tx, &RuleEvent::StageChanged(&record), today).await?;
+    }
+
+    let record = if outcomes.is_empty() {
+        record
+    } else {
+        application::get_application_by_id(&mut *tx, id).await?
+    };
+
+    tx.commit().await?;
+    Ok((record, outcomes))
+}
+
 // ======================================================
 // Create Application
 // ======================================================
@@ -38,50 +163,46 @@ pub async fn create_application_service(
     stage: Option<&Stage>,
     applied_date: &NaiveDate,
     application_notes: Option<&str>,
-) -> JsonResult {
+) -> ServiceResult {
     info!(
         "Creating application for job_listing_id: {:?}",
         job_listing_id
     );
 
-    let result = application::create_application(
-        pool,
-        job_listing_id,
-        stage,
-        applied_date,
-        application_notes,
-    )
-    .await;
+    let result =
+        create_with_rules(pool, job_listing_id, stage, applied_date, application_notes).await;
 
     match result {
-        Ok(record) => {
+        Ok((record, outcomes)) => {
             info!("Application created successfully. ID: {}", record.id);
+            record_change(
+                pool,
+                EntityType::Application,
+                record.id,
+                AuditAction::Create,
+                None,
+                "application.Create",
+            )
+            .await;
+            record_rule_outcomes(pool, &outcomes, Some((EntityType::Application, record.id))).await;
 
-            let display_label =
-                fetch_joblisting_label(pool, record.job_listing_id, record.id).await;
-            let data = add_display_label(&record, Some(display_label));
+            let mut data = build_application_payload(pool, &record).await;
+            add_outcomes(&mut data, &outcomes);
 
-            let json = json!({
-                "status": "success",
-                "message": format!(
+            Ok(ApiResponse::success(
+                format!(
                     "Application created successfully (job_listing_id: {:?}).",
                     job_listing_id
                 ),
-                "data": data
-            });
-
-            Ok(json.to_string())
+                data,
+            ))
         }
         Err(e) => {
             error!("Database error creating application: {}", e);
-            let json = json!({
-                "status": "error",
-                "message": format!(
-                    "Failed to create application (job_listing_id: {:?}): {}",
-                    job_listing_id, e
-                )
-            });
-            Err(json.to_string())
+            Err(AppError::from(e).context(format!(
+                "Failed to create application (job_listing_id: {:?})",
+                job_listing_id
+            )))
         }
     }
 }
@@ -89,7 +210,7 @@ pub async fn create_application_service(
 // ======================================================
 // Get Application by ID
 // ======================================================
-pub async fn get_application_by_id_service(pool: &SqlitePool, id: &i64) -> JsonResult {
+pub async fn get_application_by_id_service(pool: &SqlitePool, id: &i64) -> ServiceResult {
     info!("Retrieving application by ID: {}", id);
 
     let result = application::get_application_by_id(pool, *id).await;
@@ -98,25 +219,16 @@ pub async fn get_application_by_id_service(pool: &SqlitePool, id: &i64) -> JsonR
         Ok(record) => {
             info!("Application retrieved successfully. ID: {}", id);
 
-            let display_label =
-                fetch_joblisting_label(pool, record.job_listing_id, record.id).await;
-            let data = add_display_label(&record, Some(display_label));
-
-            let json = json!({
-                "status": "success",
-                "message": format!("Application {} retrieved successfully.", id),
-                "data": data
-            });
+            let data = build_application_payload(pool, &record).await;
 
-            Ok(json.to_string())
+            Ok(ApiResponse::success(
+                format!("Application {} retrieved successfully.", id),
+                data,
+            ))
         }
         Err(e) => {
             error!("Database error retrieving application: {}", e);
-            let json = json!({
-                "status": "error",
-                "message": format!("Failed to retrieve application {}: {}", id, e)
-            });
-            Err(json.to_string())
+            Err(AppError::from(e).context(format!("Failed to retrieve application {}", id)))
         }
     }
 }
@@ -124,39 +236,36 @@ pub async fn get_application_by_id_service(pool: &SqlitePool, id: &i64) -> JsonR
 // ======================================================
 // Get All Applications
 // ======================================================
-pub async fn get_all_applications_service(pool: &SqlitePool) -> JsonResult {
+pub async fn get_all_applications_service(
+    pool: &SqlitePool,
+    options: &ListOptions,
+) -> ServiceResult {
     info!("Retrieving all applications");
 
-    let result = application::get_all_applications(pool).await;
+    let result = application::list_applications(pool, options).await;
 
     match result {
-        Ok(records) => {
+        Ok(page) => {
             info!(
-                "Applications retrieved successfully ({} total).",
-                records.len()
+                "Applications retrieved successfully ({} of {} total).",
+                page.items.len(),
+                page.total
             );
 
-            let mut enriched = Vec::with_capacity(records.len());
-            for r in records {
-                let display_label = fetch_joblisting_label(pool, r.job_listing_id, r.id).await;
-                enriched.push(add_display_label(&r, Some(display_label)));
+            let mut enriched = Vec::with_capacity(page.items.len());
+            for r in &page.items {
+                enriched.push(build_application_payload(pool, r).await);
             }
 
-            let json = json!({
-                "status": "success",
-                "message": "All applications retrieved successfully.",
-                "data": enriched
-            });
-
-            Ok(json.to_string())
+            Ok(ApiResponse::success(
+                "All applications retrieved successfully.",
+                Value::from(enriched),
+            )
+            .with_pagination(page.pagination()))
         }
         Err(e) => {
             error!("Database error retrieving applications: {}", e);
-            let json = json!({
-                "status": "error",
-                "message": format!("Error retrieving applications: {}", e)
-            });
-            Err(json.to_string())
+            Err(AppError::from(e).context("Error retrieving applications"))
         }
     }
 }
@@ -167,14 +276,15 @@ pub async fn get_all_applications_service(pool: &SqlitePool) -> JsonResult {
 pub async fn update_application_service(
     pool: &SqlitePool,
     id: &i64,
-    job_listing_id: Option<i64>,
+    job_listing_id: Patch<i64>,
     stage: Option<&Stage>,
     applied_date: Option<&NaiveDate>,
-    application_notes: Option<&str>,
-) -> JsonResult {
+    application_notes: Patch<&str>,
+) -> ServiceResult {
     info!("Updating application with ID: {}", id);
 
-    let result = application::update_application(
+    let before = snapshot(pool, EntityType::Application, *id).await;
+    let result = update_with_rules(
         pool,
         *id,
         job_listing_id,
@@ -185,28 +295,30 @@ pub async fn update_application_service(
     .await;
 
     match result {
-        Ok(record) => {
+        Ok((record, outcomes)) => {
             info!("Application updated successfully. ID: {}", id);
+            record_change(
+                pool,
+                EntityType::Application,
+                *id,
+                AuditAction::Update,
+                before,
+                "application.Update",
+            )
+            .await;
+            record_rule_outcomes(pool, &outcomes, Some((EntityType::Application, *id))).await;
 
-            let display_label =
-                fetch_joblisting_label(pool, record.job_listing_id, record.id).await;
-            let data = add_display_label(&record, Some(display_label));
-
-            let json = json!({
-                "status": "success",
-                "message": format!("Application {} updated successfully.", id),
-                "data": data
-            });
+            let mut data = build_application_payload(pool, &record).await;
+            add_outcomes(&mut data, &outcomes);
 
-            Ok(json.to_string())
+            Ok(ApiResponse::success(
+                format!("Application {} updated successfully.", id),
+                data,
+            ))
         }
         Err(e) => {
             error!("Database error updating application: {}", e);
-            let json = json!({
-                "status": "error",
-                "message": format!("Failed to update application {}: {}", id, e)
-            });
-            Err(json.to_string())
+            Err(AppError::from(e).context(format!("Failed to update application {}", id)))
         }
     }
 }
@@ -214,27 +326,233 @@ pub async fn update_application_service(
 // ======================================================
 // Delete Application
 // ======================================================
-pub async fn delete_application_service(pool: &SqlitePool, id: &i64) -> JsonResult {
+pub async fn delete_application_service(pool: &SqlitePool, id: &i64) -> ServiceResult {
     info!("Deleting application with ID: {}", id);
 
+    let before = snapshot(pool, EntityType::Application, *id).await;
     let result = application::delete_application(pool, *id).await;
 
     match result {
         Ok(_) => {
             info!("Application deleted successfully. ID: {}", id);
-            let json = json!({
-                "status": "success",
-                "message": format!("Application {} deleted successfully.", id)
-            });
-            Ok(json.to_string())
+            record_change(
+                pool,
+                EntityType::Application,
+                *id,
+                AuditAction::Delete,
+                before,
+                "application.Delete",
+            )
+            .await;
+            Ok(ApiResponse::message(format!(
+                "Application {} deleted successfully.",
+                id
+            )))
         }
         Err(e) => {
             error!("Database error deleting application: {}", e);
-            let json = json!({
-                "status": "error",
-                "message": format!("Failed to delete application {}: {}", id, e)
-            });
-            Err(json.to_string())
+            Err(AppError::from(e).context(format!("Failed to delete application {}", id)))
+        }
+    }
+}
+
+// ======================================================
+// Get Application Timeline
+// ======================================================
+pub async fn get_application_timeline_service(pool: &SqlitePool, id: &i64) -> ServiceResult {
+    info!("Retrieving stage timeline for application ID: {}", id);
+
+    if let Err(e) = application::get_application_by_id(pool, *id).await {
+        error!("Database error retrieving application: {}", e);
+        return Err(AppError::from(e).context(format!("Failed to retrieve application {}", id)));
+    }
+
+    let result = stage_event::get_stage_events_for_application(pool, *id).await;
+
+    match result {
+        Ok(events) => {
+            info!(
+                "Timeline retrieved successfully for application {} ({} events).",
+                id,
+                events.len()
+            );
+
+            // Each entry lasts until the next transition; the last one is still open
+            let data: Value = events
+                .iter()
+                .enumerate()
+                .map(|(i, event)| {
+                    let left_at = events.get(i + 1).map(|next| next.changed_at);
+                    let days = match left_at {
+                        Some(end) => (end - event.changed_at).num_days().max(0),
+                        None => days_since(event.changed_at),
+                    };
+
+                    let mut entry = serde_json::to_value(event).unwrap();
+                    if let Value::Object(ref mut obj) = entry {
+                        obj.insert("leftAt".to_string(), json!(left_at));
+                        obj.insert("daysInStage".to_string(), json!(days));
+                    }
+                    entry
+                })
+                .collect();
+
+            Ok(ApiResponse::success(
+                format!("Timeline for application {} retrieved successfully.", id),
+                data,
+            ))
+        }
+        Err(e) => {
+            error!("Database error retrieving application timeline: {}", e);
+            Err(AppError::from(e).context(format!(
+                "Failed to retrieve timeline for application {}",
+                id
+            )))
+        }
+    }
+}
+
+// ======================================================
+// Stale Applications
+// ======================================================
+fn stale_cutoff(days: i64, today: &NaiveDate) -> Result<NaiveDate, AppError> {
+    if !(1..=MAX_STALE_DAYS).contains(&days) {
+        return Err(AppError::invalid_field(
+            "days",
+            format!("must be between 1 and {}", MAX_STALE_DAYS),
+        ));
+    }
+    Ok(*today - Duration::days(days))
+}
+
+async fn build_stale_payload(
+    pool: &SqlitePool,
+    stale: &StaleApplication,
+    today: &NaiveDate,
+) -> Value {
+    let mut data = build_application_payload(pool, &stale.application).await;
+    if let Value::Object(ref mut obj) = data {
+        obj.insert("lastActivity".to_string(), json!(stale.last_activity));
+        obj.insert(
+            "daysInactive".to_string(),
+            json!((*today - stale.last_activity).num_days()),
+        );
+    }
+    data
+}
+
+/// Open applications without an interaction or stage change for `days` days.
+pub async fn list_stale_applications_service(
+    pool: &SqlitePool,
+    days: i64,
+    today: &NaiveDate,
+) -> ServiceResult {
+    info!("Retrieving applications inactive for {} days", days);
+
+    let cutoff = stale_cutoff(days, today)?;
+
+    match application::list_stale_applications(pool, &cutoff).await {
+        Ok(stale) => {
+            info!(
+                "Stale applications retrieved successfully ({}).",
+                stale.len()
+            );
+
+            let mut enriched = Vec::with_capacity(stale.len());
+            for s in &stale {
+                enriched.push(build_stale_payload(pool, s, today).await);
+            }
+
+            Ok(ApiResponse::success(
+                format!("{} stale application(s) found.", stale.len()),
+                Value::from(enriched),
+            ))
+        }
+        Err(e) => {
+            error!("Database error retrieving stale applications: {}", e);
+            Err(AppError::from(e).context("Failed to retrieve stale applications"))
+        }
+    }
+}
+
+/// Moves stale applications (only those in `ids`, if given) to `ghosted`
+/// in one transaction. The stage history records each move and
+/// `stage_changed` rules run as for any other stage change.
+pub async fn ghost_stale_applications_service(
+    pool: &SqlitePool,
+    days: i64,
+    today: &NaiveDate,
+    ids: Option<&[i64]>,
+) -> ServiceResult {
+    info!("Ghosting applications inactive for {} days", days);
+
+    let cutoff = stale_cutoff(days, today)?;
+
+    let result = async {
+        let mut tx = pool.begin().await?;
+
+        let stale = application::list_stale_applications(&mut *tx, &cutoff).await?;
+        let mut ghosted = Vec::new();
+        for s in stale
+            .iter()
+            .filter(|s| ids.is_none_or(|ids| ids.contains(&s.application.id)))
+        {
+            let record = application::update_application(
+                &mut *tx,
+                s.application.id,
+                Patch::Unchanged,
+                Some(&Stage::Ghosted),
+                None,
+                Patch::Unchanged,
+            )
+            .await?;
+            let outcomes = fire_rules(&mut tx, &RuleEvent::StageChanged(&record), *today).await?;
+            ghosted.push((record.id, s.application.stage.clone(), outcomes));
+        }
+
+        tx.commit().await?;
+        Ok::<_, sqlx::Error>(ghosted)
+    }
+    .await;
+
+    match result {
+        Ok(ghosted) => {
+            info!("{} application(s) moved to ghosted.", ghosted.len());
+
+            let mut enriched = Vec::with_capacity(ghosted.len());
+            for (id, stage, outcomes) in &ghosted {
+                // The stage was the only change; put the old one back for the diff
+                let mut before = snapshot(pool, EntityType::Application, *id).await;
+                if let Some(Value::Object(fields)) = &mut before {
+                    fields.insert("stage".to_string(), json!(stage));
+                }
+                record_change(
+                    pool,
+                    EntityType::Application,
+                    *id,
+                    AuditAction::Update,
+                    before,
+                    "application.GhostStale",
+                )
+                .await;
+                record_rule_outcomes(pool, outcomes, Some((EntityType::Application, *id))).await;
+
+                // Re-read: a rule may have changed the record again
+                if let Ok(record) = application::get_application_by_id(pool, *id).await {
+                    let mut data = build_application_payload(pool, &record).await;
+                    add_outcomes(&mut data, outcomes);
+                    enriched.push(data);
+                }
+            }
+
+            Ok(ApiResponse::success(
+                format!("{} stale application(s) moved to ghosted.", ghosted.len()),
+                Value::from(enriched),
+            ))
+        }
+        Err(e) => {
+            error!("Database error ghosting stale applications: {}", e);
+            Err(AppError::from(e).context("Failed to move stale applications to ghosted"))
         }
     }
 }
@@ -0,0 +1,351 @@
+use crate::db::models::enums::EntityType;
+use crate::db::queries::attachment;
+use crate::error::AppError;
+use crate::logger::*;
+use crate::services::service_types::{ApiResponse, ServiceResult};
+use crate::settings::AppSettings;
+use crate::utils::file_store::{guess_mime_type, hash_file, FileStore};
+use serde::Serialize;
+use serde_json::json;
+use sqlx::SqlitePool;
+use std::collections::HashSet;
+use std::path::Path;
+use std::time::Duration;
+
+/// Records that can carry attachments.
+pub const ATTACHABLE_TYPES: [EntityType; 4] = [
+    EntityType::Application,
+    EntityType::JobListing,
+    EntityType::Company,
+    EntityType::Person,
+];
+
+/// Unreferenced files younger than this are left alone by the sweep,
+/// as their record may still be on its way into the database.
+const ORPHAN_MIN_AGE: Duration = Duration::from_secs(10 * 60);
+
+#[derive(Serialize, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct CleanupReport {
+    pub removed_records: usize,
+    pub removed_files: usize,
+}
+
+// ======================================================
+// Helpers
+// ======================================================
+fn ensure_attachable(entity_type: EntityType) -> Result<(), AppError> {
+    if ATTACHABLE_TYPES.contains(&entity_type) {
+        Ok(())
+    } else {
+        Err(AppError::invalid_field(
+            "entityType",
+            format!("{} records cannot have attachments", entity_type.as_str()),
+        ))
+    }
+}
+
+pub fn attachment_store(settings: &AppSettings) -> FileStore {
+    FileStore::new(settings.active_attachment_dir())
+}
+
+/// Removes attachments no record links to, then any stored file without
+/// an attachment row.
+pub async fn cleanup_orphans(
+    pool: &SqlitePool,
+    store: &FileStore,
+) -> Result<CleanupReport, AppError> {
+    let mut report = CleanupReport::default();
+
+    for storage_path in attachment::delete_unlinked_attachments(pool).await? {
+        store.remove(&storage_path)?;
+        report.removed_records += 1;
+        report.removed_files += 1;
+    }
+
+    let known: HashSet<String> = attachment::list_storage_paths(pool)
+        .await?
+        .into_iter()
+        .collect();
+    report.removed_files += store.sweep(&known, ORPHAN_MIN_AGE)?.len();
+
+    if report != CleanupReport::default() {
+        info!(
+            "Attachment cleanup removed {} records and {} files.",
+            report.removed_records, report.removed_files
+        );
+    }
+    Ok(report)
+}
+
+/// Startup sweep; failures are only logged.
+pub async fn sweep_attachment_store(pool: &SqlitePool, settings: &AppSettings) {
+    if let Err(e) = cleanup_orphans(pool, &attachment_store(settings)).await {
+        warn!("Could not clean up attachments: {}", e);
+    }
+}
+
+// ======================================================
+// Add Attachment
+// ======================================================
+/// Copies the file into the store (once per distinct content) and links
+/// it to the record.
+pub async fn add_attachment_service(
+    pool: &SqlitePool,
+    store: &FileStore,
+    source: &Path,
+    entity_type: EntityType,
+    entity_id: &i64,
+) -> ServiceResult {
+    info!(
+        "Attaching {} to {} {}",
+        source.display(),
+        entity_type.as_str(),
+        entity_id
+    );
+
+    ensure_attachable(entity_type)?;
+    let filename = match source.file_name() {
+        Some(name) if source.is_file() => name.to_string_lossy().into_owned(),
+        _ => {
+            return Err(AppError::invalid_field(
+                "path",
+                format!("'{}' is not a file", source.display()),
+            ))
+        }
+    };
+
+    let context = format!(
+        "Failed to attach '{}' to {} {}",
+        filename,
+        entity_type.as_str(),
+        entity_id
+    );
+
+    attachment::ensure_entity_exists(pool, entity_type, *entity_id)
+        .await
+        .map_err(|e| AppError::from(e).context(&context))?;
+
+    let (sha256, size_bytes) =
+        hash_file(source).map_err(|e| AppError::from(e).context(&context))?;
+    let existing = attachment::find_attachment_by_sha256(pool, &sha256)
+        .await
+        .map_err(|e| AppError::from(e).context(&context))?;
+    let deduplicated = existing.is_some();
+
+    let storage_path = existing
+        .as_ref()
+        .map(|a| a.storage_path.clone())
+        .unwrap_or_else(|| FileStore::storage_path_for(&sha256, &filename));
+    // Also restores the file if it went missing from the store
+    store
+        .put(source, &storage_path)
+        .map_err(|e| AppError::from(e).context(&context))?;
+
+    let record = match existing {
+        Some(record) => record,
+        None => attachment::create_attachment(
+            pool,
+            &sha256,
+            &filename,
+            guess_mime_type(&filename),
+            size_bytes as i64,
+            &storage_path,
+        )
+        .await
+        .map_err(|e| AppError::from(e).context(&context))?,
+    };
+
+    match attachment::link_attachment(pool, record.id, entity_type, *entity_id).await {
+        Ok(()) => {
+            info!(
+                "Attachment {} linked to {} {} (deduplicated: {}).",
+                record.id,
+                entity_type.as_str(),
+                entity_id,
+                deduplicated
+            );
+
+            let mut data = json!(record);
+            data["deduplicated"] = json!(deduplicated);
+
+            Ok(ApiResponse::success(
+                format!("'{}' attached successfully.", filename),
+                data,
+            ))
+        }
+        Err(e) => {
+            error!("Error linking attachment {}: {}", record.id, e);
+            Err(AppError::from(e).context(context))
+        }
+    }
+}
+
+// ======================================================
+// List Attachments
+// ======================================================
+pub async fn list_attachments_service(
+    pool: &SqlitePool,
+    entity_type: EntityType,
+    entity_id: &i64,
+) -> ServiceResult {
+    info!(
+        "Retrieving attachments of {} {}",
+        entity_type.as_str(),
+        entity_id
+    );
+
+    ensure_attachable(entity_type)?;
+
+    match attachment::list_attachments_for_entity(pool, entity_type, *entity_id).await {
+        Ok(records) => {
+            info!("Attachments retrieved successfully ({}).", records.len());
+
+            Ok(ApiResponse::success(
+                "Attachments retrieved successfully.",
+                json!(records),
+            ))
+        }
+        Err(e) => {
+            error!(
+                "Error retrieving attachments of {} {}: {}",
+                entity_type.as_str(),
+                entity_id,
+                e
+            );
+            Err(AppError::from(e).context(format!(
+                "Failed to retrieve attachments of {} {}",
+                entity_type.as_str(),
+                entity_id
+            )))
+        }
+    }
+}
+
+// ======================================================
+// Open Attachment
+// ======================================================
+/// Resolves the stored file and hands it to `open`, which launches the
+/// default application.
+pub async fn open_attachment_service(
+    pool: &SqlitePool,
+    store: &FileStore,
+    id: &i64,
+    open: impl FnOnce(&Path) -> Result<(), String>,
+) -> ServiceResult {
+    info!("Opening attachment {}", id);
+
+    let record = attachment::get_attachment_by_id(pool, *id)
+        .await
+        .map_err(|e| AppError::from(e).context(format!("Failed to open attachment {}", id)))?;
+
+    let path = store.path_of(&record.storage_path);
+    if !path.is_file() {
+        error!(
+            "Stored file of attachment {} is missing: {}",
+            id,
+            path.display()
+        );
+        return Err(AppError::not_found(format!(
+            "The file of attachment {} is missing from the store",
+            id
+        )));
+    }
+
+    match open(&path) {
+        Ok(()) => Ok(ApiResponse::message(format!(
+            "Opened '{}'.",
+            record.filename
+        ))),
+        Err(e) => {
+            error!("Error opening {}: {}", path.display(), e);
+            Err(AppError::Io {
+                message: format!("Failed to open '{}': {}", record.filename, e),
+            })
+        }
+    }
+}
+
+// ======================================================
+// Unlink / Delete Attachment
+// ======================================================
+/// Removes one link; the file goes once nothing links to it anymore.
+pub async fn unlink_attachment_service(
+    pool: &SqlitePool,
+    store: &FileStore,
+    id: &i64,
+    entity_type: EntityType,
+    entity_id: &i64,
+) -> ServiceResult {
+    info!(
+        "Unlinking attachment {} from {} {}",
+        id,
+        entity_type.as_str(),
+        entity_id
+    );
+
+    match attachment::unlink_attachment(pool, *id, entity_type, *entity_id).await {
+        Ok(true) => {
+            let report = cleanup_orphans(pool, store).await?;
+
+            Ok(ApiResponse::success(
+                format!("Attachment {} removed.", id),
+                json!(report),
+            ))
+        }
+        Ok(false) => Err(AppError::not_found(format!(
+            "Attachment {} is not linked to {} {}",
+            id,
+            entity_type.as_str(),
+            entity_id
+        ))),
+        Err(e) => {
+            error!("Error unlinking attachment {}: {}", id, e);
+            Err(AppError::from(e).context(format!("Failed to remove attachment {}", id)))
+        }
+    }
+}
+
+/// Deletes the attachment from every record and removes its file.
+pub async fn delete_attachment_service(
+    pool: &SqlitePool,
+    store: &FileStore,
+    id: &i64,
+) -> ServiceResult {
+    info!("Deleting attachment with ID: {}", id);
+
+    match attachment::delete_attachment(pool, *id).await {
+        Ok(storage_path) => {
+            store.remove(&storage_path)?;
+            let mut report = cleanup_orphans(pool, store).await?;
+            report.removed_records += 1;
+            report.removed_files += 1;
+
+            info!("Attachment deleted successfully. ID: {}", id);
+            Ok(ApiResponse::success(
+                format!("Attachment {} deleted successfully.", id),
+                json!(report),
+            ))
+        }
+        Err(e) => {
+            error!("Error deleting attachment {}: {}", id, e);
+            Err(AppError::from(e).context(format!("Failed to delete attachment {}", id)))
+        }
+    }
+}
+
+// ======================================================
+// Cleanup
+// ======================================================
+pub async fn cleanup_attachments_service(pool: &SqlitePool, store: &FileStore) -> ServiceResult {
+    info!("Cleaning up orphaned attachments");
+
+    let report = cleanup_orphans(pool, store)
+        .await
+        .map_err(|e| e.context("Failed to clean up attachments"))?;
+
+    Ok(ApiResponse::success(
+        "Attachments cleaned up.",
+        json!(report),
+    ))
+}
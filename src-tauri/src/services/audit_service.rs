@@ -0,0 +1,415 @@
+//! Change history. Services record every create, update and delete here,
+//! field by field, and a record can be put back the way it was after any
+//! recorded change.
+
+use crate::db::models::enums::EntityType;
+use crate::db::models::patch::Patch;
+use crate::db::queries::audit::{self, AuditAction, AuditEntry};
+use crate::db::queries::dedupe;
+use crate::db::queries::list_query::{
+    to_sql_value, Filter, FilterOp, Inclusion, ListError, ListOptions, ListPage, ListSpec,
+};
+use crate::db::queries::{
+    application, company, interaction, job_listing, note, person, reminder, stage_event,
+};
+use crate::error::AppError;
+use crate::logger::*;
+use crate::services::rule_engine::RuleOutcome;
+use crate::services::service_types::{ApiResponse, ServiceResult};
+use crate::utils::sql_utils::{bind_sql_values, build_update_sql};
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use sqlx::SqlitePool;
+use std::collections::HashSet;
+
+/// Bookkeeping fields left out of diffs.
+const UNTRACKED: [&str; 3] = ["id", "createdAt", "updatedAt"];
+
+/// Fields a revert leaves alone; the trash and the archive have their own
+/// commands.
+const NOT_REVERTED: [&str; 2] = ["archivedAt", "deletedAt"];
+
+fn list_spec(entity_type: EntityType) -> &'static ListSpec {
+    match entity_type {
+        EntityType::Company => &company::COMPANY_LIST,
+        EntityType::Person => &person::PERSON_LIST,
+        EntityType::JobListing => &job_listing::JOB_LISTING_LIST,
+        EntityType::Application => &application::APPLICATION_LIST,
+        EntityType::Interaction => &interaction::INTERACTION_LIST,
+        EntityType::Note => &note::NOTE_LIST,
+        EntityType::Reminder => &reminder::REMINDER_LIST,
+    }
+}
+
+// ======================================================
+// Snapshots and diffs
+// ======================================================
+fn first_item<T: Serialize>(
+    page: Result<ListPage<T>, ListError>,
+) -> Result<Option<Value>, ListError> {
+    Ok(page?.items.first().map(|r| json!(r)))
+}
+
+async fn fetch_snapshot(
+    pool: &SqlitePool,
+    entity_type: EntityType,
+    id: i64,
+) -> Result<Option<Value>, ListError> {
+    let options = ListOptions {
+        filters: vec![Filter {
+            field: "id".to_string(),
+            op: FilterOp::Eq,
+            value: json!(id),
+        }],
+        archived: Inclusion::Include,
+        trashed: Inclusion::Include,
+        ..Default::default()
+    };
+
+    match entity_type {
+        EntityType::Company => first_item(company::list_companies(pool, &options).await),
+        EntityType::Person => first_item(person::list_persons(pool, &options).await),
+        EntityType::JobListing => first_item(job_listing::list_job_listings(pool, &options).await),
+        EntityType::Application => first_item(application::list_applications(pool, &options).await),
+        EntityType::Interaction => first_item(interaction::list_interactions(pool, &options).await),
+        EntityType::Note => first_item(note::list_notes(pool, &options).await),
+        EntityType::Reminder => first_item(reminder::list_reminders(pool, &options).await),
+    }
+}
+
+/// The record as stored (payload field names), archived or trashed
+/// included; `None` if it does not exist or could not be read.
+pub async fn snapshot(pool: &SqlitePool, entity_type: EntityType, id: i64) -> Option<Value> {
+    match fetch_snapshot(pool, entity_type, id).await {
+        Ok(record) => record,
+        Err(e) => {
+            warn!(
+                "Could not read {} {} for the audit log: {}",
+                entity_type.as_str(),
+                id,
+                e
+            );
+            None
+        }
+    }
+}
+
+/// `{field: {"before": .., "after": ..}}` for every field that differs.
+/// A missing side (no record yet, or no more) counts as all `null`.
+pub fn diff(before: Option<&Value>, after: Option<&Value>) -> Value {
+    let empty = Map::new();
+    let fields = |v: Option<&Value>| v.and_then(Value::as_object).unwrap_or(&empty).clone();
+    let (before, after) = (fields(before), fields(after));
+
+    let mut changes = Map::new();
+    for name in before.keys().chain(after.keys()) {
+        if UNTRACKED.contains(&name.as_str()) || changes.contains_key(name) {
+            continue;
+        }
+        let old = before.get(name).unwrap_or(&Value::Null);
+        let new = after.get(name).unwrap_or(&Value::Null);
+        if old != new {
+            changes.insert(name.clone(), json!({ "before": old, "after": new }));
+        }
+    }
+    Value::Object(changes)
+}
+
+// ======================================================
+// Recording
+// ======================================================
+/// Records a change to one record. `before` is its snapshot from before the
+/// change; the state after it is read here. Updates that changed nothing
+/// are skipped. Failures are only logged: the change itself went through.
+pub async fn record_change(
+    pool: &SqlitePool,
+    entity_type: EntityType,
+    id: i64,
+    action: AuditAction,
+    before: Option<Value>,
+    origin: &str,
+) {
+    let after = snapshot(pool, entity_type, id).await;
+    let changes = diff(before.as_ref(), after.as_ref());
+    if action == AuditAction::Update && changes.as_object().is_some_and(Map::is_empty) {
+        return;
+    }
+
+    if let Err(e) = audit::record_entry(pool, entity_type, id, action, &changes, Some(origin)).await
+    {
+        warn!(
+            "Could not record {:?} of {} {} in the audit log: {}",
+            action,
+            entity_type.as_str(),
+            id,
+            e
+        );
+    }
+}
+
+/// Records a change to the links of a company or person (see
+/// [`dedupe::get_links`]) as an update; `before` is their state from before
+/// the change. Like [`record_change`], failures are only logged.
+pub async fn record_link_change(
+    pool: &SqlitePool,
+    entity_type: EntityType,
+    id: i64,
+    before: Map<String, Value>,
+    origin: &str,
+) {
+    let after = match dedupe::get_links(pool, entity_type, id).await {
+        Ok(links) => links,
+        Err(e) => {
+            warn!(
+                "Could not read the links of {} {} for the audit log: {}",
+                entity_type.as_str(),
+                id,
+                e
+            );
+            return;
+        }
+    };
+    let changes = diff(Some(&Value::Object(before)), Some(&Value::Object(after)));
+    if changes.as_object().is_some_and(Map::is_empty) {
+        return;
+    }
+
+    let action = AuditAction::Update;
+    if let Err(e) = audit::record_entry(pool, entity_type, id, action, &changes, Some(origin)).await
+    {
+        warn!(
+            "Could not record the links of {} {} in the audit log: {}",
+            entity_type.as_str(),
+            id,
+            e
+        );
+    }
+}
+
+/// Records what automation rules did. `logged` is the record the calling
+/// service records itself, so its entry already covers the rule's changes.
+pub async fn record_rule_outcomes(
+    pool: &SqlitePool,
+    outcomes: &[RuleOutcome],
+    logged: Option<(EntityType, i64)>,
+) {
+    let mut seen = HashSet::new();
+    for outcome in outcomes {
+        let key = (outcome.entity_type, outcome.entity_id);
+        if logged == Some(key) || !seen.insert(key) {
+            continue;
+        }
+        let origin = format!("rule.{}", outcome.rule_id);
+
+        if outcome.entity_type == EntityType::Application {
+            // Rules only change the stage; the stage history has the old one
+            let mut before = snapshot(pool, EntityType::Application, outcome.entity_id).await;
+            let events = stage_event::get_stage_events_for_application(pool, outcome.entity_id)
+                .await
+                .unwrap_or_default();
+            if let (Some(Value::Object(fields)), Some(last)) = (&mut before, events.last()) {
+                fields.insert("stage".to_string(), json!(last.from_stage));
+            }
+            record_change(
+                pool,
+                EntityType::Application,
+                outcome.entity_id,
+                AuditAction::Update,
+                before,
+                &origin,
+            )
+            .await;
+        } else {
+            record_change(
+                pool,
+                outcome.entity_type,
+                outcome.entity_id,
+                AuditAction::Create,
+                None,
+                &origin,
+            )
+            .await;
+        }
+    }
+}
+
+// ======================================================
+// Get History
+// ======================================================
+pub async fn get_history_service(
+    pool: &SqlitePool,
+    entity_type: EntityType,
+    id: &i64,
+) -> ServiceResult {
+    info!("Retrieving history of {} {}", entity_type.as_str(), id);
+
+    match audit::get_history(pool, entity_type, *id).await {
+        Ok(entries) => {
+            info!(
+                "History of {} {} retrieved successfully ({}).",
+                entity_type.as_str(),
+                id,
+                entries.len()
+            );
+            Ok(ApiResponse::success(
+                format!("{} change(s) recorded.", entries.len()),
+                json!(entries),
+            ))
+        }
+        Err(e) => {
+            error!(
+                "Error retrieving history of {} {}: {}",
+                entity_type.as_str(),
+                id,
+                e
+            );
+            Err(AppError::from(e).context(format!(
+                "Failed to retrieve history of {} {}",
+                entity_type.as_str(),
+                id
+            )))
+        }
+    }
+}
+
+// ======================================================
+// Revert
+// ======================================================
+/// Field values as they were right after entry `audit_id`, for every field
+/// changed since: walks back from the newest entry, undoing each change.
+fn version_after(history: &[AuditEntry], audit_id: i64) -> Map<String, Value> {
+    let mut fields = Map::new();
+    // History is newest first
+    for entry in history.iter().take_while(|e| e.id > audit_id) {
+        if let Value::Object(changes) = &entry.changes {
+            for (name, change) in changes {
+                fields.insert(name.clone(), change["before"].clone());
+            }
+        }
+    }
+    fields
+}
+
+/// Writes `fields` (payload names) to the record, skipping those that
+/// already match `current` or are not (writable) columns. Returns how many changed.
+pub(crate) async fn write_fields(
+    pool: &SqlitePool,
+    entity_type: EntityType,
+    id: i64,
+    current: &Value,
+    fields: Map<String, Value>,
+) -> Result<usize, AppError> {
+    let spec = list_spec(entity_type);
+
+    let mut patches = Vec::new();
+    for (name, value) in fields {
+        if current.get(&name) == Some(&value) || NOT_REVERTED.contains(&name.as_str()) {
+            continue;
+        }
+        let Some(def) = spec.columns.iter().find(|c| c.field == name && !c.computed) else {
+            continue;
+        };
+        let patch = if value.is_null() {
+            Patch::Clear
+        } else {
+            Patch::Set(to_sql_value(def, &value)?)
+        };
+        patches.push((def.column, patch));
+    }
+
+    let changed = patches.len();
+    if changed > 0 {
+        let (sql, binds) = build_update_sql(spec.table, "id", id, patches);
+        bind_sql_values(sqlx::query_as::<_, (i64,)>(&sql), &binds)
+            .fetch_one(pool)
+            .await?;
+    }
+    Ok(changed)
+}
+
+/// Puts a live record back the way it was right after audit entry
+/// `audit_id`. The revert is recorded like any other change, so it can be
+/// reverted too.
+pub async fn revert_service(
+    pool: &SqlitePool,
+    entity_type: EntityType,
+    id: &i64,
+    audit_id: &i64,
+) -> ServiceResult {
+    info!(
+        "Reverting {} {} to audit entry {}",
+        entity_type.as_str(),
+        id,
+        audit_id
+    );
+
+    let result: Result<usize, AppError> = async {
+        let entry = audit::get_entry(pool, *audit_id).await?;
+        if entry.entity_type != entity_type || entry.entity_id != *id {
+            return Err(AppError::invalid_field(
+                "auditId",
+                format!("is not an entry of {} {}", entity_type.as_str(), id),
+            ));
+        }
+
+        let current = snapshot(pool, entity_type, *id)
+            .await
+            .ok_or_else(|| AppError::not_found("record not found"))?;
+        if !current["deletedAt"].is_null() {
+            return Err(AppError::invalid_field(
+                "id",
+                "is in the trash; restore it first",
+            ));
+        }
+
+        let history = audit::get_history(pool, entity_type, *id).await?;
+        let fields = version_after(&history, *audit_id);
+
+        let changed = write_fields(pool, entity_type, *id, &current, fields).await?;
+        if changed > 0 {
+            record_change(
+                pool,
+                entity_type,
+                *id,
+                AuditAction::Revert,
+                Some(current),
+                "audit.Revert",
+            )
+            .await;
+        }
+        Ok(changed)
+    }
+    .await;
+
+    match result {
+        Ok(changed) => {
+            info!(
+                "{} {} reverted ({} field(s)).",
+                entity_type.as_str(),
+                id,
+                changed
+            );
+            let record = snapshot(pool, entity_type, *id).await;
+            Ok(ApiResponse::success(
+                format!(
+                    "{} {} reverted to entry {} ({} field(s) changed).",
+                    entity_type.as_str(),
+                    id,
+                    audit_id,
+                    changed
+                ),
+                json!(record),
+            ))
+        }
+        Err(e) => {
+            error!(
+                "Error reverting {} {} to audit entry {}: {}",
+                entity_type.as_str(),
+                id,
+                audit_id,
+                e
+            );
+            Err(e.context(format!("Failed to revert {} {}", entity_type.as_str(), id)))
+        }
+    }
+}
@@ -0,0 +1,293 @@
+use crate::db::backup::{create_snapshot, list_snapshots, select_expired, BackupFile, BackupKind};
+use crate::db::connection::{init_db, DbState};
+use crate::logger::*;
+use crate::services::service_types::JsonResult;
+use crate::settings::{AppSettings, BackupPolicy};
+use chrono::Local;
+use serde_json::json;
+use sqlx::{sqlite::SqlitePoolOptions, SqlitePool};
+use std::{fs, path::Path, sync::Mutex};
+
+// ======================================================
+// Helper: Build error response
+// ======================================================
+fn error_json(message: String) -> String {
+    json!({
+        "status": "error",
+        "message": message
+    })
+    .to_string()
+}
+
+// ======================================================
+// Helper: Delete snapshots outside the retention policy
+// ======================================================
+fn rotate_backups(db_path: &Path, policy: &BackupPolicy) -> Vec<String> {
+    let backups = match list_snapshots(db_path) {
+        Ok(b) => b,
+        Err(e) => {
+            warn!("Could not list backups for rotation: {}", e);
+            return Vec::new();
+        }
+    };
+
+    let mut removed = Vec::new();
+    for backup in select_expired(&backups, policy.keep_daily, policy.keep_weekly) {
+        match fs::remove_file(&backup.path) {
+            Ok(_) => {
+                info!("Rotated out backup {}", backup.file_name);
+                removed.push(backup.file_name.clone());
+            }
+            Err(e) => warn!("Failed to remove backup {}: {}", backup.file_name, e),
+        }
+    }
+    removed
+}
+
+// ======================================================
+// Helper: Check that a snapshot is a readable JobTrackr database
+// ======================================================
+async fn verify_snapshot(path: &Path) -> Result<(), String> {
+    let url = format!("sqlite://{}?mode=ro", path.display());
+    let pool = SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect(&url)
+        .await
+        .map_err(|e| format!("cannot open backup: {}", e))?;
+
+    let integrity: Result<String, sqlx::Error> = sqlx::query_scalar("PRAGMA integrity_check")
+        .fetch_one(&pool)
+        .await;
+    let tables: Result<i64, sqlx::Error> = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = 'company'",
+    )
+    .fetch_one(&pool)
+    .await;
+    pool.close().await;
+
+    let status = integrity.map_err(|e| format!("cannot read backup: {}", e))?;
+    let tables = tables.map_err(|e| format!("cannot read backup: {}", e))?;
+
+    if status != "ok" {
+        Err(format!("integrity check failed: {}", status))
+    } else if tables != 1 {
+        Err("file is not a JobTrackr database".to_string())
+    } else {
+        Ok(())
+    }
+}
+
+// ======================================================
+// Scheduled daily backup (startup)
+// ======================================================
+/// Takes the day's automatic snapshot if none exists yet and applies rotation.
+pub async fn run_daily_backup(pool: &SqlitePool, db_path: &Path, policy: &BackupPolicy) {
+    let today = Local::now().date_naive();
+    let existing = list_snapshots(db_path).unwrap_or_default();
+    let has_today = existing
+        .iter()
+        .any(|b| b.kind == BackupKind::Daily && b.created_at.date() == today);
+
+    if has_today {
+        info!("Daily backup already present for {}.", today);
+    } else {
+        match create_snapshot(pool, db_path, BackupKind::Daily).await {
+            Ok(backup) => info!("Daily backup written to {}", backup.path.display()),
+            Err(e) => error!("Daily backup failed: {}", e),
+        }
+    }
+
+    rotate_backups(db_path, policy);
+}
+
+// ======================================================
+// List Backups
+// ======================================================
+pub fn list_backups_service(settings: &Mutex<AppSettings>) -> JsonResult {
+    info!("Listing backups");
+
+    let current = settings.lock().unwrap().clone();
+
+    match list_snapshots(&current.active_db_path()) {
+        Ok(backups) => {
+            info!("Backups listed successfully ({} total).", backups.len());
+            let json = json!({
+                "status": "success",
+                "message": "All backups retrieved successfully.",
+                "data": {
+                    "policy": current.backup,
+                    "backups": backups
+                }
+            });
+            Ok(json.to_string())
+        }
+        Err(e) => {
+            error!("Error listing backups: {}", e);
+            Err(error_json(format!("Failed to list backups: {}", e)))
+        }
+    }
+}
+
+// ======================================================
+// Create Backup
+// ======================================================
+pub async fn create_backup_service(pool: &SqlitePool, settings: &Mutex<AppSettings>) -> JsonResult {
+    info!("Creating manual backup");
+
+    let current = settings.lock().unwrap().clone();
+
+    match create_snapshot(pool, &current.active_db_path(), BackupKind::Manual).await {
+        Ok(backup) => {
+            info!("Backup created successfully at {}", backup.path.display());
+            let json = json!({
+                "status": "success",
+                "message": format!("Backup '{}' created successfully.", backup.file_name),
+                "data": backup
+            });
+            Ok(json.to_string())
+        }
+        Err(e) => {
+            error!("Error creating backup: {}", e);
+            Err(error_json(format!("Failed to create backup: {}", e)))
+        }
+    }
+}
+
+// ======================================================
+// Restore Backup
+// ======================================================
+pub async fn restore_backup_service(
+    db: &DbState,
+    settings: &Mutex<AppSettings>,
+    file_name: &str,
+) -> JsonResult {
+    info!("Restoring backup '{}'", file_name);
+
+    let current = settings.lock().unwrap().clone();
+    let db_path = current.active_db_path();
+
+    // Only accept files we listed ourselves — never arbitrary paths
+    let backup: BackupFile = match list_snapshots(&db_path)
+        .unwrap_or_default()
+        .into_iter()
+        .find(|b| b.file_name == file_name)
+    {
+        Some(b) => b,
+        None => {
+            warn!("Backup '{}' not found", file_name);
+            return Err(error_json(format!("Backup '{}' not found.", file_name)));
+        }
+    };
+
+    if let Err(e) = verify_snapshot(&backup.path).await {
+        error!("Backup '{}' failed verification: {}", file_name, e);
+        return Err(error_json(format!(
+            "Backup '{}' cannot be restored: {}",
+            file_name, e
+        )));
+    }
+
+    // 1. Keep a safety copy of the current state, then release the database file
+    let safety = match db.pool() {
+        Some(pool) => match create_snapshot(&pool, &db_path, BackupKind::PreRestore).await {
+            Ok(b) => Some(b),
+            Err(e) => {
+                error!("Pre-restore backup failed: {}", e);
+                return Err(error_json(format!(
+                    "Failed to back up current data before restoring: {}",
+                    e
+                )));
+            }
+        },
+        None => None,
+    };
+    if let Some(pool) = db.take() {
+        pool.close().await;
+    }
+
+    // 2. Replace the database file atomically and drop stale journal files
+    let staging = db_path.with_extension("db.restore");
+    let swap = fs::copy(&backup.path, &staging).and_then(|_| fs::rename(&staging, &db_path));
+    for suffix in ["-wal", "-shm", "-journal"] {
+        let _ = fs::remove_file(format!("{}{}", db_path.display(), suffix));
+    }
+
+    // 3. Re-open (this also migrates older snapshots up to the current schema)
+    let reopened = match swap {
+        Ok(_) => init_db(&db_path).await.map_err(|e| e.to_string()),
+        Err(e) => Err(e.to_string()),
+    };
+
+    match reopened {
+        Ok(pool) => {
+            db.replace(pool);
+            info!("Backup '{}' restored successfully.", file_name);
+            let json = json!({
+                "status": "success",
+                "message": format!("Backup '{}' restored successfully.", file_name),
+                "data": {
+                    "restored": backup,
+                    "safetyBackup": safety
+                }
+            });
+            Ok(json.to_string())
+        }
+        Err(e) => {
+            error!("Error restoring backup '{}': {}", file_name, e);
+
+            // Roll back to the safety copy so the app keeps working
+            if let Some(safety) = &safety {
+                let _ = fs::copy(&safety.path, &db_path);
+            }
+            match init_db(&db_path).await {
+                Ok(pool) => {
+                    db.replace(pool);
+                }
+                Err(reopen) => error!("Failed to re-open database after restore error: {}", reopen),
+            }
+
+            Err(error_json(format!(
+                "Failed to restore backup '{}': {}",
+                file_name, e
+            )))
+        }
+    }
+}
+
+// ======================================================
+// Update Retention Policy
+// ======================================================
+pub fn set_backup_policy_service(
+    settings: &Mutex<AppSettings>,
+    keep_daily: usize,
+    keep_weekly: usize,
+) -> JsonResult {
+    info!(
+        "Updating backup policy (daily: {}, weekly: {})",
+        keep_daily, keep_weekly
+    );
+
+    let mut next = settings.lock().unwrap().clone();
+    next.backup = BackupPolicy {
+        keep_daily,
+        keep_weekly,
+    };
+
+    if let Err(e) = next.persist_backup_policy() {
+        error!("Error saving backup policy: {}", e);
+        return Err(error_json(format!("Failed to save backup policy: {}", e)));
+    }
+    *settings.lock().unwrap() = next.clone();
+
+    let removed = rotate_backups(&next.active_db_path(), &next.backup);
+
+    let json = json!({
+        "status": "success",
+        "message": "Backup policy updated successfully.",
+        "data": {
+            "policy": next.backup,
+            "removed": removed
+        }
+    });
+    Ok(json.to_string())
+}
@@ -0,0 +1,332 @@
+use crate::db::queries::calendar::{
+    get_interviews, get_pending_reminders, CalendarInterview, CalendarReminder,
+};
+use crate::logger::*;
+use crate::services::export_service::write_file;
+use crate::services::service_types::JsonResult;
+use crate::settings::AppSettings;
+use crate::utils::ical_utils::IcsBuilder;
+use crate::utils::recurrence::{parse_timezone, wall_clock_to_utc, Recurrence, RecurrenceEnd};
+use chrono::{Datelike, NaiveDate, NaiveTime};
+use chrono_tz::Tz;
+use serde::Deserialize;
+use serde_json::json;
+use sqlx::SqlitePool;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+const PROD_ID: &str = "-//JobTrackr//JobTrackr Calendar//EN";
+
+/// Length of timed reminder events.
+const TIMED_REMINDER_DURATION: &str = "PT30M";
+
+/// Time zone definitions reach this many years past the last reminder
+/// in the zone, which recurring reminders keep running into.
+const TIMEZONE_YEARS_AHEAD: i32 = 10;
+
+/// How pending reminders appear in the calendar.
+///
+/// Events show up in every calendar app; to-dos are richer but many
+/// subscribed-calendar clients ignore them.
+#[derive(Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ReminderComponent {
+    #[default]
+    Event,
+    Todo,
+}
+
+#[derive(Debug, Default)]
+pub struct CalendarCounts {
+    pub reminders: usize,
+    pub interviews: usize,
+}
+
+// ======================================================
+// Helper: Stable identifiers
+// ======================================================
+/// UIDs only depend on the record, so re-exports update entries in place.
+fn uid(kind: &str, id: i64, profile: &str) -> String {
+    format!("jobtrackr-{}-{}.{}@jobtrackr", kind, id, profile)
+}
+
+fn describe(context: &[(&str, Option<&str>)], body: Option<&str>) -> String {
+    let mut lines: Vec<String> = context
+        .iter()
+        .filter_map(|(label, value)| value.map(|v| format!("{}: {}", label, v)))
+        .collect();
+
+    if let Some(body) = body.filter(|b| !b.trim().is_empty()) {
+        if !lines.is_empty() {
+            lines.push(String::new());
+        }
+        lines.push(body.to_string());
+    }
+
+    lines.join("\n")
+}
+
+fn write_reminder(
+    ics: &mut IcsBuilder,
+    r: &CalendarReminder,
+    profile: &str,
+    component: ReminderComponent,
+) {
+    let name = match component {
+        ReminderComponent::Event => "VEVENT",
+        ReminderComponent::Todo => "VTODO",
+    };
+    let description = describe(
+        &[
+            ("Company", r.company.as_deref()),
+            ("Contact", r.person.as_deref()),
+            ("Job", r.job_title.as_deref()),
+        ],
+        r.message.as_deref(),
+    );
+
+    ics.begin(name);
+    ics.raw("UID", &uid("reminder", r.id, profile));
+    ics.utc("DTSTAMP", r.updated_at);
+    ics.utc("LAST-MODIFIED", r.updated_at);
+    ics.text("SUMMARY", &r.title);
+    if !description.is_empty() {
+        ics.text("DESCRIPTION", &description);
+    }
+    // Unknown zones are written as floating times
+    let tzid = r
+        .timezone
+        .as_deref()
+        .filter(|name| parse_timezone(name).is_ok());
+    let recurrence = r
+        .recurrence
+        .as_deref()
+        .and_then(|rule| rule.parse::<Recurrence>().ok());
+    match (component, r.reminder_time) {
+        (ReminderComponent::Event, Some(time)) => {
+            ics.local("DTSTART", r.reminder_date.and_time(time), tzid);
+            ics.raw("DURATION", TIMED_REMINDER_DURATION);
+            ics.raw("TRANSP", "TRANSPARENT");
+        }
+        (ReminderComponent::Event, None) => {
+            ics.date("DTSTART", r.reminder_date);
+            ics.date(
+                "DTEND",
+                r.reminder_date.succ_opt().unwrap_or(r.reminder_date),
+            );
+            ics.raw("TRANSP", "TRANSPARENT");
+        }
+        (ReminderComponent::Todo, time) => {
+            // Recurring to-dos need a DTSTART for the rule to apply to
+            let write = |ics: &mut IcsBuilder, name: &str| match time {
+                Some(time) => ics.local(name, r.reminder_date.and_time(time), tzid),
+                None => ics.date(name, r.reminder_date),
+            };
+            if recurrence.is_some() {
+                write(ics, "DTSTART");
+            }
+            write(ics, "DUE");
+            ics.raw("STATUS", "NEEDS-ACTION");
+        }
+    }
+    if let Some(rule) = recurrence {
+        ics.raw("RRULE", &rrule(&rule, r.reminder_time.is_some(), tzid));
+    }
+    ics.raw("CATEGORIES", "JobTrackr,Reminder");
+    ics.end(name);
+}
+
+/// UNTIL must match the value type of DTSTART: a date for all-day
+/// reminders, a UTC time when a zone is given and floating otherwise.
+fn rrule(rule: &Recurrence, timed: bool, tzid: Option<&str>) -> String {
+    let (RecurrenceEnd::Until(until), true) = (rule.end, timed) else {
+        return rule.to_string();
+    };
+
+    let open_ended = Recurrence {
+        end: RecurrenceEnd::Never,
+        ..*rule
+    };
+    let last_second = NaiveTime::from_hms_opt(23, 59, 59).unwrap_or_default();
+    let until = match tzid.and_then(|name| parse_timezone(name).ok()) {
+        Some(tz) => wall_clock_to_utc(&tz, until, last_second)
+            .format("%Y%m%dT%H%M%SZ")
+            .to_string(),
+        None => until
+            .and_time(last_second)
+            .format("%Y%m%dT%H%M%S")
+            .to_string(),
+    };
+    format!("{};UNTIL={}", open_ended, until)
+}
+
+fn write_interview(ics: &mut IcsBuilder, i: &CalendarInterview, profile: &str) {
+    let summary = match (i.subject.as_deref(), i.company.as_deref()) {
+        (Some(subject), _) if !subject.trim().is_empty() => subject.to_string(),
+        (_, Some(company)) => format!("Interview with {}", company),
+        _ => "Interview".to_string(),
+    };
+    let description = describe(
+        &[
+            ("Company", i.company.as_deref()),
+            ("Interviewer", i.person.as_deref()),
+            ("Job", i.job_title.as_deref()),
+            ("Medium", i.medium.as_deref()),
+        ],
+        i.summary.as_deref(),
+    );
+
+    ics.begin("VEVENT");
+    ics.raw("UID", &uid("interview", i.id, profile));
+    ics.utc("DTSTAMP", i.updated_at);
+    ics.utc("LAST-MODIFIED", i.updated_at);
+    ics.text("SUMMARY", &summary);
+    if !description.is_empty() {
+        ics.text("DESCRIPTION", &description);
+    }
+    ics.date("DTSTART", i.interaction_date);
+    ics.date(
+        "DTEND",
+        i.interaction_date.succ_opt().unwrap_or(i.interaction_date),
+    );
+    ics.raw("CATEGORIES", "JobTrackr,Interview");
+    ics.end("VEVENT");
+}
+
+/// Zones that timed reminders are written in, with the dates they use.
+fn reminder_zones(reminders: &[CalendarReminder]) -> BTreeMap<String, (Tz, NaiveDate, NaiveDate)> {
+    let mut zones: BTreeMap<String, (Tz, NaiveDate, NaiveDate)> = BTreeMap::new();
+    for r in reminders.iter().filter(|r| r.reminder_time.is_some()) {
+        let Some(tz) = r
+            .timezone
+            .as_deref()
+            .and_then(|name| parse_timezone(name).ok())
+        else {
+            continue;
+        };
+        zones
+            .entry(tz.name().to_string())
+            .and_modify(|(_, from, to)| {
+                *from = (*from).min(r.reminder_date);
+                *to = (*to).max(r.reminder_date);
+            })
+            .or_insert((tz, r.reminder_date, r.reminder_date));
+    }
+    zones
+}
+
+// ======================================================
+// Build Calendar
+// ======================================================
+/// Renders pending reminders and interview interactions as an iCalendar document.
+pub async fn build_calendar(
+    pool: &SqlitePool,
+    profile: &str,
+    reminders_as: ReminderComponent,
+) -> Result<(String, CalendarCounts), sqlx::Error> {
+    let reminders = get_pending_reminders(pool).await?;
+    let interviews = get_interviews(pool).await?;
+
+    let mut ics = IcsBuilder::calendar(PROD_ID, &format!("JobTrackr ({})", profile));
+    for (tz, from, to) in reminder_zones(&reminders).into_values() {
+        let from = from.pred_opt().unwrap_or(from);
+        let to = to.with_year(to.year() + TIMEZONE_YEARS_AHEAD).unwrap_or(to);
+        ics.timezone(tz, from, to);
+    }
+    for r in &reminders {
+        write_reminder(&mut ics, r, profile, reminders_as);
+    }
+    for i in &interviews {
+        write_interview(&mut ics, i, profile);
+    }
+
+    let counts = CalendarCounts {
+        reminders: reminders.len(),
+        interviews: interviews.len(),
+    };
+    Ok((ics.finish(), counts))
+}
+
+async fn write_calendar(
+    pool: &SqlitePool,
+    profile: &str,
+    target: &Path,
+    reminders_as: ReminderComponent,
+) -> Result<CalendarCounts, String> {
+    let (text, counts) = build_calendar(pool, profile, reminders_as)
+        .await
+        .map_err(|e| e.to_string())?;
+    write_file(target, &text)?;
+    Ok(counts)
+}
+
+// ======================================================
+// Refresh Calendar Feed
+// ======================================================
+/// Regenerates the subscribable feed in the profile directory.
+/// Failures are logged only; the feed is a convenience, not a source of truth.
+pub async fn refresh_calendar_feed(pool: &SqlitePool, settings: &AppSettings) {
+    let target = settings.active_calendar_path();
+    match write_calendar(
+        pool,
+        &settings.active_profile,
+        &target,
+        ReminderComponent::Event,
+    )
+    .await
+    {
+        Ok(_) => info!("Calendar feed refreshed at {}", target.display()),
+        Err(e) => warn!(
+            "Failed to refresh calendar feed {}: {}",
+            target.display(),
+            e
+        ),
+    }
+}
+
+// ======================================================
+// Export ICS
+// ======================================================
+/// Without a path the subscribable feed in the profile directory is regenerated.
+pub async fn export_ics_service(
+    pool: &SqlitePool,
+    settings: &Mutex<AppSettings>,
+    path: Option<&str>,
+    reminders_as: ReminderComponent,
+) -> JsonResult {
+    info!("Exporting calendar ({:?} reminders)", reminders_as);
+
+    let (profile, target) = {
+        let settings = settings.lock().unwrap();
+        let target = match path {
+            Some(p) => PathBuf::from(p),
+            None => settings.active_calendar_path(),
+        };
+        (settings.active_profile.clone(), target)
+    };
+
+    match write_calendar(pool, &profile, &target, reminders_as).await {
+        Ok(counts) => {
+            info!("Calendar exported to {}", target.display());
+            let json = json!({
+                "status": "success",
+                "message": format!("Calendar exported to {}.", target.display()),
+                "data": {
+                    "path": target,
+                    "reminders": counts.reminders,
+                    "interviews": counts.interviews
+                }
+            });
+            Ok(json.to_string())
+        }
+        Err(e) => {
+            error!("Error exporting calendar to {}: {}", target.display(), e);
+            let json = json!({
+                "status": "error",
+                "message": format!("Failed to export calendar: {}", e)
+            });
+            Err(json.to_string())
+        }
+    }
+}
@@ -1,9 +1,14 @@
-use crate::db::models::enums::WorkType;
+use crate::db::models::enums::{EntityType, WorkType};
+use crate::db::models::patch::Patch;
+use crate::db::queries::audit::AuditAction;
 use crate::db::queries::company;
+use crate::db::queries::list_query::ListOptions;
+use crate::error::AppError;
 use crate::logger::*;
-use crate::services::service_types::JsonResult;
-use crate::services::service_utils::add_display_label;
-use serde_json::{json, Value};
+use crate::services::audit_service::{record_change, snapshot};
+use crate::services::service_types::{ApiResponse, ServiceResult};
+use crate::services::service_utils::{add_display_label, add_tags, add_tags_to_all};
+use serde_json::Value;
 use sqlx::SqlitePool;
 
 // ======================================================
@@ -20,7 +25,7 @@ pub async fn create_company_service(
     industry: Option<&str>,
     website: Option<&str>,
     phone_number: Option<&str>,
-) -> JsonResult {
+) -> ServiceResult {
     info!("Creating company: {}", name);
 
     let result = company::create_company(
@@ -40,24 +45,27 @@ pub async fn create_company_service(
     match result {
         Ok(record) => {
             info!("Company created successfully. ID: {}", record.id);
-
-            let data = add_display_label(&record, Some(record.name.as_str()));
-
-            let json = json!({
-                "status": "success",
-                "message": format!("Company '{}' created successfully.", record.name),
-                "data": data
-            });
-
-            Ok(json.to_string())
+            record_change(
+                pool,
+                EntityType::Company,
+                record.id,
+                AuditAction::Create,
+                None,
+                "company.Create",
+            )
+            .await;
+
+            let mut data = add_display_label(&record, Some(record.name.as_str()));
+            add_tags(pool, EntityType::Company, &mut data).await;
+
+            Ok(ApiResponse::success(
+                format!("Company '{}' created successfully.", record.name),
+                data,
+            ))
         }
         Err(e) => {
             error!("Error creating company '{}': {}", name, e);
-            let json = json!({
-                "status": "error",
-                "message": format!("Failed to create company '{}': {}", name, e)
-            });
-            Err(json.to_string())
+            Err(AppError::from(e).context(format!("Failed to create company '{}'", name)))
         }
     }
 }
@@ -65,7 +73,7 @@ pub async fn create_company_service(
 // ======================================================
 // Get Company by ID
 // ======================================================
-pub async fn get_company_by_id_service(pool: &SqlitePool, id: &i64) -> JsonResult {
+pub async fn get_company_by_id_service(pool: &SqlitePool, id: &i64) -> ServiceResult {
     info!("Retrieving company by ID: {}", id);
 
     let result = company::get_company_by_id(pool, *id).await;
@@ -74,23 +82,17 @@ pub async fn get_company_by_id_service(pool: &SqlitePool, id: &i64) -> JsonResul
         Ok(record) => {
             info!("Company retrieved successfully. ID: {}", id);
 
-            let data = add_display_label(&record, Some(record.name.as_str()));
+            let mut data = add_display_label(&record, Some(record.name.as_str()));
+            add_tags(pool, EntityType::Company, &mut data).await;
 
-            let json = json!({
-                "status": "success",
-                "message": format!("Company {} retrieved successfully.", id),
-                "data": data
-            });
-
-            Ok(json.to_string())
+            Ok(ApiResponse::success(
+                format!("Company {} retrieved successfully.", id),
+                data,
+            ))
         }
         Err(e) => {
             error!("Error retrieving company {}: {}", id, e);
-            let json = json!({
-                "status": "error",
-                "message": format!("Failed to retrieve company {}: {}", id, e)
-            });
-            Err(json.to_string())
+            Err(AppError::from(e).context(format!("Failed to retrieve company {}", id)))
         }
     }
 }
@@ -98,38 +100,35 @@ pub async fn get_company_by_id_service(pool: &SqlitePool, id: &i64) -> JsonResul
 // ======================================================
 // Get All Companies
 // ======================================================
-pub async fn get_all_companies_service(pool: &SqlitePool) -> JsonResult {
+pub async fn get_all_companies_service(pool: &SqlitePool, options: &ListOptions) -> ServiceResult {
     info!("Retrieving all companies");
 
-    let result = company::get_all_companies(pool).await;
+    let result = company::list_companies(pool, options).await;
 
     match result {
-        Ok(records) => {
+        Ok(page) => {
             info!(
-                "Companies retrieved successfully ({} total).",
-                records.len()
+                "Companies retrieved successfully ({} of {} total).",
+                page.items.len(),
+                page.total
             );
 
-            let data: Vec<Value> = records
-                .into_iter()
-                .map(|r| add_display_label(&r, Some(r.name.as_str())))
+            let mut items: Vec<Value> = page
+                .items
+                .iter()
+                .map(|r| add_display_label(r, Some(r.name.as_str())))
                 .collect();
+            add_tags_to_all(pool, EntityType::Company, &mut items).await;
+            let data = Value::from(items);
 
-            let json = json!({
-                "status": "success",
-                "message": "All companies retrieved successfully.",
-                "data": data
-            });
-
-            Ok(json.to_string())
+            Ok(
+                ApiResponse::success("All companies retrieved successfully.", data)
+                    .with_pagination(page.pagination()),
+            )
         }
         Err(e) => {
             error!("Error retrieving companies: {}", e);
-            let json = json!({
-                "status": "error",
-                "message": format!("Failed to retrieve companies: {}", e)
-            });
-            Err(json.to_string())
+            Err(AppError::from(e).context("Failed to retrieve companies"))
         }
     }
 }
@@ -141,17 +140,19 @@ pub async fn update_company_service(
     pool: &SqlitePool,
     id: &i64,
     name: Option<&str>,
-    street_address: Option<&str>,
-    zip_code: Option<&str>,
-    city: Option<&str>,
-    country: Option<&str>,
-    default_work_type: Option<&WorkType>,
-    industry: Option<&str>,
-    website: Option<&str>,
-    phone_number: Option<&str>,
-) -> JsonResult {
+    street_address: Patch<&str>,
+    zip_code: Patch<&str>,
+    city: Patch<&str>,
+    country: Patch<&str>,
+    default_work_type: Patch<&WorkType>,
+    industry: Patch<&str>,
+    website: Patch<&str>,
+    phone_number: Patch<&str>,
+) -> ServiceResult {
     info!("Updating company with ID: {}", id);
 
+    let before = snapshot(pool, EntityType::Company, *id).await;
+
     let result = company::update_company(
         pool,
         *id,
@@ -170,24 +171,27 @@ pub async fn update_company_service(
     match result {
         Ok(record) => {
             info!("Company updated successfully. ID: {}", id);
-
-            let data = add_display_label(&record, Some(record.name.as_str()));
-
-            let json = json!({
-                "status": "success",
-                "message": format!("Company {} updated successfully.", id),
-                "data": data
-            });
-
-            Ok(json.to_string())
+            record_change(
+                pool,
+                EntityType::Company,
+                *id,
+                AuditAction::Update,
+                before,
+                "company.Update",
+            )
+            .await;
+
+            let mut data = add_display_label(&record, Some(record.name.as_str()));
+            add_tags(pool, EntityType::Company, &mut data).await;
+
+            Ok(ApiResponse::success(
+                format!("Company {} updated successfully.", id),
+                data,
+            ))
         }
         Err(e) => {
             error!("Error updating company {}: {}", id, e);
-            let json = json!({
-                "status": "error",
-                "message": format!("Failed to update company {}: {}", id, e)
-            });
-            Err(json.to_string())
+            Err(AppError::from(e).context(format!("Failed to update company {}", id)))
         }
     }
 }
@@ -195,31 +199,35 @@ pub async fn update_company_service(
 // ======================================================
 // Delete Company
 // ======================================================
-pub async fn delete_company_service(pool: &SqlitePool, id: &i64) -> JsonResult {
+pub async fn delete_company_service(pool: &SqlitePool, id: &i64) -> ServiceResult {
     info!("Deleting company with ID: {}", id);
 
+    let before = snapshot(pool, EntityType::Company, *id).await;
+
     let result = company::delete_company(pool, *id).await;
 
     match result {
         Ok(_) => {
             info!("Company deleted successfully. ID: {}", id);
-
-            let json = json!({
-                "status": "success",
-                "message": format!("Company {} deleted successfully.", id)
-            });
-
-            Ok(json.to_string())
+            record_change(
+                pool,
+                EntityType::Company,
+                *id,
+                AuditAction::Delete,
+                before,
+                "company.Delete",
+            )
+            .await;
+
+            Ok(ApiResponse::message(format!(
+                "Company {} deleted successfully.",
+                id
+            )))
         }
         Err(e) => {
             error!("Error deleting company {}: {}", id, e);
 
-            let json = json!({
-                "status": "error",
-                "message": format!("Failed to delete company {}: {}", id, e)
-            });
-
-            Err(json.to_string())
+            Err(AppError::from(e).context(format!("Failed to delete company {}", id)))
         }
     }
 }
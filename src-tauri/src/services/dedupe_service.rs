@@ -0,0 +1,398 @@
+//! Finds companies and persons that are probably the same and merges them.
+//! Candidates are compared pairwise; each matching signal (name, website
+//! domain, email, phone, LinkedIn profile) adds to a score between 0 and 1.
+
+use crate::db::models::enums::EntityType;
+use crate::db::queries::audit::AuditAction;
+use crate::db::queries::company::Company;
+use crate::db::queries::dedupe;
+use crate::db::queries::person::Person;
+use crate::error::AppError;
+use crate::logger::*;
+use crate::services::audit_service::{record_change, record_link_change, snapshot};
+use crate::services::service_types::{ApiResponse, ServiceResult};
+use crate::utils::similarity::*;
+use serde::Serialize;
+use serde_json::{json, Value};
+use sqlx::SqlitePool;
+
+/// Pairs scoring lower are not reported unless asked for.
+pub const DEFAULT_MIN_SCORE: f64 = 0.6;
+
+/// Names at least this similar count as a (weaker) match.
+const SIMILAR_NAME: f64 = 0.8;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateRecord {
+    pub id: i64,
+    pub label: String,
+}
+
+/// Two records that are probably the same.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicatePair {
+    pub entity_type: EntityType,
+    pub first: DuplicateRecord,
+    pub second: DuplicateRecord,
+    /// 0..1; signals combine, so two weak matches beat one
+    pub score: f64,
+    /// Matching signals: `name`, `similarName`, `domain`, `email`, `phone`, `linkedin`
+    pub reasons: Vec<&'static str>,
+}
+
+// ======================================================
+// Scoring
+// ======================================================
+/// Combines independent signals: each one closes part of the remaining gap
+/// to 1.
+fn combine(signals: &[(&'static str, f64)]) -> f64 {
+    let missing: f64 = signals.iter().map(|(_, s)| 1.0 - s).product();
+    ((1.0 - missing) * 100.0).round() / 100.0
+}
+
+fn name_signal(a: &str, b: &str, equal: f64, similar: f64) -> Option<(&'static str, f64)> {
+    if a.is_empty() || b.is_empty() {
+        return None;
+    }
+    if a == b {
+        return Some(("name", equal));
+    }
+    let ratio = similarity(a, b);
+    (ratio >= SIMILAR_NAME).then_some(("similarName", similar * ratio))
+}
+
+struct CompanyKey {
+    id: i64,
+    label: String,
+    name: String,
+    domain: Option<String>,
+    phone: Option<String>,
+}
+
+impl From<&Company> for CompanyKey {
+    fn from(c: &Company) -> Self {
+        CompanyKey {
+            id: c.id,
+            label: c.name.clone(),
+            name: normalize_company_name(&c.name),
+            domain: c.website.as_deref().and_then(website_domain),
+            phone: c.phone_number.clone(),
+        }
+    }
+}
+
+/// Why two companies look alike, with the weight of each signal.
+fn company_signals(a: &CompanyKey, b: &CompanyKey) -> Vec<(&'static str, f64)> {
+    let mut signals = Vec::new();
+    signals.extend(name_signal(&a.name, &b.name, 0.9, 0.7));
+
+    match (&a.domain, &b.domain) {
+        (Some(x), Some(y)) if x == y => signals.push(("domain", 0.95)),
+        // "Acme" and a company whose website is acme.com
+        (x, y)
+            if x.as_deref().map(domain_stem) == Some(b.name.as_str())
+                || y.as_deref().map(domain_stem) == Some(a.name.as_str()) =>
+        {
+            signals.push(("domain", 0.8))
+        }
+        _ => {}
+    }
+
+    if let (Some(x), Some(y)) = (&a.phone, &b.phone) {
+        if same_phone(x, y) {
+            signals.push(("phone", 0.7));
+        }
+    }
+    signals
+}
+
+struct PersonKey {
+    id: i64,
+    label: String,
+    name: String,
+    email: Option<String>,
+    phone: Option<String>,
+    linkedin: Option<String>,
+}
+
+impl From<&Person> for PersonKey {
+    fn from(p: &Person) -> Self {
+        PersonKey {
+            id: p.id,
+            label: format!("{} {}", p.first_name, p.last_name)
+                .trim()
+                .to_string(),
+            name: normalize_person_name(&p.first_name, &p.last_name),
+            email: p.email.as_deref().and_then(normalize_email),
+            phone: p.phone_number.clone(),
+            linkedin: p.linkedin_url.as_deref().and_then(linkedin_handle),
+        }
+    }
+}
+
+/// Why two persons look alike. Names are shared by strangers, so they
+/// weigh less than contact details.
+fn person_signals(a: &PersonKey, b: &PersonKey) -> Vec<(&'static str, f64)> {
+    let mut signals = Vec::new();
+    signals.extend(name_signal(&a.name, &b.name, 0.6, 0.5));
+
+    if a.email.is_some() && a.email == b.email {
+        signals.push(("email", 0.95));
+    }
+    if a.linkedin.is_some() && a.linkedin == b.linkedin {
+        signals.push(("linkedin", 0.95));
+    }
+    if let (Some(x), Some(y)) = (&a.phone, &b.phone) {
+        if same_phone(x, y) {
+            signals.push(("phone", 0.8));
+        }
+    }
+    signals
+}
+
+/// Every pair scoring at least `min_score`, best first.
+fn find_pairs<K>(
+    entity_type: EntityType,
+    keys: &[K],
+    signals: impl Fn(&K, &K) -> Vec<(&'static str, f64)>,
+    record: impl Fn(&K) -> DuplicateRecord,
+    min_score: f64,
+) -> Vec<DuplicatePair> {
+    let mut pairs = Vec::new();
+    for (i, a) in keys.iter().enumerate() {
+        for b in &keys[i + 1..] {
+            let found = signals(a, b);
+            let score = combine(&found);
+            if !found.is_empty() && score >= min_score {
+                pairs.push(DuplicatePair {
+                    entity_type,
+                    first: record(a),
+                    second: record(b),
+                    score,
+                    reasons: found.iter().map(|(reason, _)| *reason).collect(),
+                });
+            }
+        }
+    }
+    pairs.sort_by(|a, b| b.score.total_cmp(&a.score));
+    pairs
+}
+
+fn check_min_score(min_score: Option<f64>) -> Result<f64, AppError> {
+    let min_score = min_score.unwrap_or(DEFAULT_MIN_SCORE);
+    if !(0.0..=1.0).contains(&min_score) {
+        return Err(AppError::invalid_field(
+            "minScore",
+            "must be between 0 and 1",
+        ));
+    }
+    Ok(min_score)
+}
+
+// ======================================================
+// Find Duplicates
+// ======================================================
+pub async fn find_duplicate_companies_service(
+    pool: &SqlitePool,
+    min_score: Option<f64>,
+) -> ServiceResult {
+    info!("Looking for duplicate companies");
+    let min_score = check_min_score(min_score)?;
+
+    match dedupe::list_company_candidates(pool).await {
+        Ok(companies) => {
+            let keys: Vec<CompanyKey> = companies.iter().map(CompanyKey::from).collect();
+            let pairs = find_pairs(
+                EntityType::Company,
+                &keys,
+                company_signals,
+                |k| DuplicateRecord {
+                    id: k.id,
+                    label: k.label.clone(),
+                },
+                min_score,
+            );
+            info!("{} possible duplicate companies found.", pairs.len());
+            Ok(ApiResponse::success(
+                format!("{} possible duplicate(s) found.", pairs.len()),
+                json!(pairs),
+            ))
+        }
+        Err(e) => {
+            error!("Error looking for duplicate companies: {}", e);
+            Err(AppError::from(e).context("Failed to look for duplicate companies"))
+        }
+    }
+}
+
+pub async fn find_duplicate_persons_service(
+    pool: &SqlitePool,
+    min_score: Option<f64>,
+) -> ServiceResult {
+    info!("Looking for duplicate persons");
+    let min_score = check_min_score(min_score)?;
+
+    match dedupe::list_person_candidates(pool).await {
+        Ok(persons) => {
+            let keys: Vec<PersonKey> = persons.iter().map(PersonKey::from).collect();
+            let pairs = find_pairs(
+                EntityType::Person,
+                &keys,
+                person_signals,
+                |k| DuplicateRecord {
+                    id: k.id,
+                    label: k.label.clone(),
+                },
+                min_score,
+            );
+            info!("{} possible duplicate persons found.", pairs.len());
+            Ok(ApiResponse::success(
+                format!("{} possible duplicate(s) found.", pairs.len()),
+                json!(pairs),
+            ))
+        }
+        Err(e) => {
+            error!("Error looking for duplicate persons: {}", e);
+            Err(AppError::from(e).context("Failed to look for duplicate persons"))
+        }
+    }
+}
+
+// ======================================================
+// Merge
+// ======================================================
+/// Payload name of a reference column.
+fn field_name(column: &str) -> &'static str {
+    match column {
+        "company_id" => "companyId",
+        _ => "personId",
+    }
+}
+
+async fn merge_service(
+    pool: &SqlitePool,
+    entity_type: EntityType,
+    survivor_id: i64,
+    duplicate_id: i64,
+) -> ServiceResult {
+    let name = entity_type.as_str();
+    info!("Merging {} {} into {}", name, duplicate_id, survivor_id);
+
+    if survivor_id == duplicate_id {
+        return Err(AppError::invalid_field(
+            "duplicateId",
+            "must differ from survivorId",
+        ));
+    }
+
+    let origin = match entity_type {
+        EntityType::Company => "company.Merge",
+        _ => "person.Merge",
+    };
+    let survivor_before = snapshot(pool, entity_type, survivor_id).await;
+    let duplicate_before = snapshot(pool, entity_type, duplicate_id).await;
+    let links_before = dedupe::get_links(pool, entity_type, survivor_id).await;
+
+    match dedupe::merge_records(pool, entity_type, survivor_id, duplicate_id).await {
+        Ok(moved) => {
+            info!(
+                "{} {} merged into {} ({} reference(s) moved).",
+                name,
+                duplicate_id,
+                survivor_id,
+                moved.len()
+            );
+
+            // Logged like ordinary edits so the merge can be undone
+            for m in &moved {
+                let mut before = snapshot(pool, m.entity_type, m.id).await;
+                if let Some(Value::Object(fields)) = &mut before {
+                    fields.insert(field_name(m.column).to_string(), json!(duplicate_id));
+                }
+                record_change(
+                    pool,
+                    m.entity_type,
+                    m.id,
+                    AuditAction::Update,
+                    before,
+                    origin,
+                )
+                .await;
+            }
+            record_change(
+                pool,
+                entity_type,
+                survivor_id,
+                AuditAction::Update,
+                survivor_before,
+                origin,
+            )
+            .await;
+            match links_before {
+                Ok(links) => {
+                    record_link_change(pool, entity_type, survivor_id, links, origin).await
+                }
+                Err(e) => warn!(
+                    "Could not record the links of {} {}: {}",
+                    name, survivor_id, e
+                ),
+            }
+            record_change(
+                pool,
+                entity_type,
+                duplicate_id,
+                AuditAction::Delete,
+                duplicate_before,
+                origin,
+            )
+            .await;
+
+            let survivor = snapshot(pool, entity_type, survivor_id).await;
+            Ok(ApiResponse::success(
+                format!(
+                    "{} {} merged into {} successfully.",
+                    name, duplicate_id, survivor_id
+                ),
+                json!({ "survivor": survivor, "moved": moved.len() }),
+            ))
+        }
+        Err(e) => {
+            error!(
+                "Error merging {} {} into {}: {}",
+                name, duplicate_id, survivor_id, e
+            );
+            Err(AppError::from(e).context(format!(
+                "Failed to merge {} {} into {}",
+                name, duplicate_id, survivor_id
+            )))
+        }
+    }
+}
+
+/// Merges company `duplicate_id` into `survivor_id`: job listings,
+/// persons, interactions, notes and reminders move to the survivor, which
+/// also gets the duplicate's tags and attachments and keeps its own values,
+/// taking the duplicate's where it has none. The duplicate goes to the
+/// trash. The whole merge can be undone.
+pub async fn merge_companies_service(
+    pool: &SqlitePool,
+    survivor_id: &i64,
+    duplicate_id: &i64,
+) -> ServiceResult {
+    merge_service(pool, EntityType::Company, *survivor_id, *duplicate_id).await
+}
+
+/// Merges person `duplicate_id` into `survivor_id`: interactions, notes and
+/// reminders move to the survivor, which also gets the duplicate's
+/// application contacts, tags and attachments and keeps its own values,
+/// taking the duplicate's where it has none. The duplicate goes to the
+/// trash. The whole merge can be undone.
+pub async fn merge_persons_service(
+    pool: &SqlitePool,
+    survivor_id: &i64,
+    duplicate_id: &i64,
+) -> ServiceResult {
+    merge_service(pool, EntityType::Person, *survivor_id, *duplicate_id).await
+}
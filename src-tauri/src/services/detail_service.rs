@@ -0,0 +1,318 @@
+use crate::db::models::enums::EntityType;
+use crate::db::queries::list_query::{
+    Filter, FilterOp, Inclusion, ListOptions, SortDirection, SortSpec,
+};
+use crate::db::queries::{
+    application, application_contact, attachment, company, interaction, job_listing, note, person,
+    reminder, stage_event,
+};
+use crate::error::AppError;
+use crate::logger::*;
+use crate::services::application_service::build_application_payload;
+use crate::services::exchange_rate_service::add_annual_salaries;
+use crate::services::person_service::format_person_label;
+use crate::services::reminder_service::format_reminder_label;
+use crate::services::service_types::{ApiResponse, ServiceResult};
+use crate::services::service_utils::{add_display_label, add_tags, add_tags_to_all};
+use serde::Serialize;
+use serde_json::{json, Value};
+use sqlx::SqlitePool;
+
+// ======================================================
+// Helpers
+// ======================================================
+/// Everything pointing at one record through `field`, in the given order.
+/// Archived records are included; trashed ones are not.
+fn related_to(field: &str, id: i64, sort: &str, direction: SortDirection) -> ListOptions {
+    ListOptions {
+        filters: vec![Filter {
+            field: field.to_string(),
+            op: FilterOp::Eq,
+            value: json!(id),
+        }],
+        sort: vec![SortSpec {
+            field: sort.to_string(),
+            direction,
+        }],
+        archived: Inclusion::Include,
+        ..Default::default()
+    }
+}
+
+/// Same shape as the entity's own `ListAll` items.
+async fn payloads<T: Serialize>(
+    pool: &SqlitePool,
+    entity_type: EntityType,
+    records: &[T],
+    label: impl Fn(&T) -> Option<String>,
+) -> Value {
+    let mut items: Vec<Value> = records
+        .iter()
+        .map(|r| add_display_label(r, label(r)))
+        .collect();
+    add_tags_to_all(pool, entity_type, &mut items).await;
+    Value::from(items)
+}
+
+async fn person_payloads(pool: &SqlitePool, records: &[person::Person]) -> Value {
+    payloads(pool, EntityType::Person, records, |p| {
+        Some(format_person_label(&p.last_name, &p.first_name, p.id))
+    })
+    .await
+}
+
+async fn job_listing_payloads(pool: &SqlitePool, records: &[job_listing::JobListing]) -> Value {
+    let mut items = payloads(pool, EntityType::JobListing, records, |j| {
+        Some(j.title.clone())
+    })
+    .await;
+    if let Value::Array(ref mut items) = items {
+        add_annual_salaries(pool, items).await;
+    }
+    items
+}
+
+async fn interaction_payloads(pool: &SqlitePool, records: &[interaction::Interaction]) -> Value {
+    payloads(pool, EntityType::Interaction, records, |i| {
+        i.summary.clone()
+    })
+    .await
+}
+
+async fn note_payloads(pool: &SqlitePool, records: &[note::Note]) -> Value {
+    payloads(pool, EntityType::Note, records, |n| n.title.clone()).await
+}
+
+async fn reminder_payloads(pool: &SqlitePool, records: &[reminder::Reminder]) -> Value {
+    payloads(pool, EntityType::Reminder, records, |r| {
+        Some(format_reminder_label(&r.title, r.id))
+    })
+    .await
+}
+
+async fn company_payload(pool: &SqlitePool, record: &company::Company) -> Value {
+    let mut data = add_display_label(record, Some(record.name.as_str()));
+    add_tags(pool, EntityType::Company, &mut data).await;
+    data
+}
+
+async fn notes_of(pool: &SqlitePool, field: &str, id: i64) -> Result<Value, AppError> {
+    let page = note::list_notes(
+        pool,
+        &related_to(field, id, "createdAt", SortDirection::Desc),
+    )
+    .await?;
+    Ok(note_payloads(pool, &page.items).await)
+}
+
+async fn reminders_of(pool: &SqlitePool, field: &str, id: i64) -> Result<Value, AppError> {
+    let options = related_to(field, id, "reminderDate", SortDirection::Asc);
+    let page = reminder::list_reminders(pool, &options).await?;
+    Ok(reminder_payloads(pool, &page.items).await)
+}
+
+async fn attachments_of(
+    pool: &SqlitePool,
+    entity_type: EntityType,
+    id: i64,
+) -> Result<Value, AppError> {
+    Ok(json!(
+        attachment::list_attachments_for_entity(pool, entity_type, id).await?
+    ))
+}
+
+// ======================================================
+// Company Detail
+// ======================================================
+/// A company with its job listings, people, interactions, notes,
+/// reminders and attachments.
+pub async fn get_company_detail_service(pool: &SqlitePool, id: &i64) -> ServiceResult {
+    info!("Retrieving details of company {}", id);
+
+    let result: Result<Value, AppError> = async {
+        let record = company::get_company_by_id(pool, *id).await?;
+        let job_listings = job_listing::get_job_listings_by_company_id(pool, *id).await?;
+        let persons = person::get_persons_by_company_id(pool, *id).await?;
+        let interactions = interaction::get_interactions_by_company_id(pool, *id).await?;
+
+        Ok(json!({
+            "company": company_payload(pool, &record).await,
+            "jobListings": job_listing_payloads(pool, &job_listings).await,
+            "persons": person_payloads(pool, &persons).await,
+            "interactions": interaction_payloads(pool, &interactions).await,
+            "notes": notes_of(pool, "companyId", *id).await?,
+            "reminders": reminders_of(pool, "companyId", *id).await?,
+            "attachments": attachments_of(pool, EntityType::Company, *id).await?
+        }))
+    }
+    .await;
+
+    match result {
+        Ok(data) => Ok(ApiResponse::success(
+            format!("Details of company {} retrieved successfully.", id),
+            data,
+        )),
+        Err(e) => {
+            error!("Error retrieving details of company {}: {}", id, e);
+            Err(e.context(format!("Failed to retrieve details of company {}", id)))
+        }
+    }
+}
+
+// ======================================================
+// Job Listing Detail
+// ======================================================
+/// A job listing with its company, applications, notes, reminders and
+/// attachments.
+pub async fn get_job_listing_detail_service(pool: &SqlitePool, id: &i64) -> ServiceResult {
+    info!("Retrieving details of job listing {}", id);
+
+    let result: Result<Value, AppError> = async {
+        let record = job_listing::get_job_listing_by_id(pool, *id).await?;
+        let company = company::get_company_by_id(pool, record.company_id).await?;
+        let applications = application::get_applications_by_job_listing_id(pool, *id).await?;
+
+        let mut application_items = Vec::with_capacity(applications.len());
+        for a in &applications {
+            application_items.push(build_application_payload(pool, a).await);
+        }
+
+        Ok(json!({
+            "jobListing": job_listing_payloads(pool, std::slice::from_ref(&record)).await[0],
+            "company": company_payload(pool, &company).await,
+            "applications": application_items,
+            "notes": notes_of(pool, "jobListingId", *id).await?,
+            "reminders": reminders_of(pool, "jobListingId", *id).await?,
+            "attachments": attachments_of(pool, EntityType::JobListing, *id).await?
+        }))
+    }
+    .await;
+
+    match result {
+        Ok(data) => Ok(ApiResponse::success(
+            format!("Details of job listing {} retrieved successfully.", id),
+            data,
+        )),
+        Err(e) => {
+            error!("Error retrieving details of job listing {}: {}", id, e);
+            Err(e.context(format!("Failed to retrieve details of job listing {}", id)))
+        }
+    }
+}
+
+// ======================================================
+// Person Detail
+// ======================================================
+/// A person with their company, the applications they are involved in,
+/// interactions, notes, reminders and attachments.
+pub async fn get_person_detail_service(pool: &SqlitePool, id: &i64) -> ServiceResult {
+    info!("Retrieving details of person {}", id);
+
+    let result: Result<Value, AppError> = async {
+        let record = person::get_person_by_id(pool, *id).await?;
+        let company = match record.company_id {
+            Some(company_id) => Some(company::get_company_by_id(pool, company_id).await?),
+            None => None,
+        };
+        let applications = application_contact::get_applications_by_contact_id(pool, *id).await?;
+        let interactions = interaction::get_interactions_by_person_id(pool, *id).await?;
+
+        let mut application_items = Vec::with_capacity(applications.len());
+        for a in &applications {
+            let mut payload = build_application_payload(pool, &a.application).await;
+            payload["contactRole"] = json!(a.contact_role);
+            application_items.push(payload);
+        }
+
+        let company = match &company {
+            Some(c) => company_payload(pool, c).await,
+            None => Value::Null,
+        };
+
+        Ok(json!({
+            "person": person_payloads(pool, std::slice::from_ref(&record)).await[0],
+            "company": company,
+            "applications": application_items,
+            "interactions": interaction_payloads(pool, &interactions).await,
+            "notes": notes_of(pool, "personId", *id).await?,
+            "reminders": reminders_of(pool, "personId", *id).await?,
+            "attachments": attachments_of(pool, EntityType::Person, *id).await?
+        }))
+    }
+    .await;
+
+    match result {
+        Ok(data) => Ok(ApiResponse::success(
+            format!("Details of person {} retrieved successfully.", id),
+            data,
+        )),
+        Err(e) => {
+            error!("Error retrieving details of person {}: {}", id, e);
+            Err(e.context(format!("Failed to retrieve details of person {}", id)))
+        }
+    }
+}
+
+// ======================================================
+// Application Detail
+// ======================================================
+/// An application with its job listing and company (null without a
+/// listing), stage history, contacts, interactions, notes, reminders and
+/// attachments.
+pub async fn get_application_detail_service(pool: &SqlitePool, id: &i64) -> ServiceResult {
+    info!("Retrieving details of application {}", id);
+
+    let result: Result<Value, AppError> = async {
+        let record = application::get_application_by_id(pool, *id).await?;
+        let (listing, company) = match record.job_listing_id {
+            Some(listing_id) => {
+                let listing = job_listing::get_job_listing_by_id(pool, listing_id).await?;
+                let company = company::get_company_by_id(pool, listing.company_id).await?;
+                (
+                    job_listing_payloads(pool, std::slice::from_ref(&listing)).await[0].take(),
+                    company_payload(pool, &company).await,
+                )
+            }
+            None => (Value::Null, Value::Null),
+        };
+        let stage_history = stage_event::get_stage_events_for_application(pool, *id).await?;
+        let contacts = application_contact::get_contacts_by_application_id(pool, *id).await?;
+        let interactions = interaction::get_interactions_by_application_id(pool, *id).await?;
+        let notes = note::get_notes_by_application_id(pool, *id).await?;
+
+        let people: Vec<&person::Person> = contacts.iter().map(|c| &c.person).collect();
+        let mut contact_items = payloads(pool, EntityType::Person, &people, |p| {
+            Some(format_person_label(&p.last_name, &p.first_name, p.id))
+        })
+        .await;
+        if let Value::Array(items) = &mut contact_items {
+            for (item, contact) in items.iter_mut().zip(&contacts) {
+                item["contactRole"] = json!(contact.contact_role);
+            }
+        }
+
+        Ok(json!({
+            "application": build_application_payload(pool, &record).await,
+            "jobListing": listing,
+            "company": company,
+            "stageHistory": stage_history,
+            "contacts": contact_items,
+            "interactions": interaction_payloads(pool, &interactions).await,
+            "notes": note_payloads(pool, &notes).await,
+            "reminders": reminders_of(pool, "applicationId", *id).await?,
+            "attachments": attachments_of(pool, EntityType::Application, *id).await?
+        }))
+    }
+    .await;
+
+    match result {
+        Ok(data) => Ok(ApiResponse::success(
+            format!("Details of application {} retrieved successfully.", id),
+            data,
+        )),
+        Err(e) => {
+            error!("Error retrieving details of application {}: {}", id, e);
+            Err(e.context(format!("Failed to retrieve details of application {}", id)))
+        }
+    }
+}
@@ -0,0 +1,193 @@
+//! User-maintained exchange rates, and the yearly salaries in the base
+//! currency they make possible.
+
+use crate::db::models::enums::{Currency, PayPeriod};
+use crate::db::queries::exchange_rate;
+use crate::error::AppError;
+use crate::logger::*;
+use crate::services::service_types::{ApiResponse, ServiceResult};
+use serde_json::{json, Value};
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+
+// ======================================================
+// Annual salary
+// ======================================================
+/// `amount` per `pay_period` as a yearly amount in the base currency,
+/// rounded to whole units. A missing pay period counts as yearly.
+pub fn annual_salary(amount: i64, pay_period: Option<PayPeriod>, rate: f64) -> f64 {
+    let per_year = pay_period.map_or(1, |p| p.per_year());
+    (amount as f64 * per_year as f64 * rate).round()
+}
+
+/// Adds `annualSalaryMin`, `annualSalaryMax` and `baseCurrency` to job
+/// listing payloads. The amounts are `null` when the listing's currency has
+/// no exchange rate.
+pub async fn add_annual_salaries(pool: &SqlitePool, items: &mut [Value]) {
+    let rates = match exchange_rate::list_rates(pool).await {
+        Ok(rates) => rates,
+        Err(e) => {
+            warn!("Could not load exchange rates: {}", e);
+            Vec::new()
+        }
+    };
+    let base = rates.iter().find(|r| r.is_base).map(|r| r.currency);
+    let rates: HashMap<&str, f64> = rates
+        .iter()
+        .map(|r| (r.currency.as_str(), r.rate))
+        .collect();
+
+    for item in items.iter_mut() {
+        let rate = item
+            .get("currency")
+            .and_then(Value::as_str)
+            .and_then(|c| rates.get(c).copied());
+        let pay_period: Option<PayPeriod> = item
+            .get("payPeriod")
+            .and_then(|p| serde_json::from_value(p.clone()).ok());
+        let annual = |field: &str| -> Option<f64> {
+            let amount = item.get(field)?.as_i64()?;
+            Some(annual_salary(amount, pay_period, rate?))
+        };
+        let (min, max) = (annual("salaryMin"), annual("salaryMax"));
+
+        if let Value::Object(ref mut obj) = item {
+            obj.insert("annualSalaryMin".to_string(), json!(min));
+            obj.insert("annualSalaryMax".to_string(), json!(max));
+            obj.insert("baseCurrency".to_string(), json!(base));
+        }
+    }
+}
+
+// ======================================================
+// Helpers
+// ======================================================
+async fn rates_payload(pool: &SqlitePool) -> Result<Value, AppError> {
+    let rates = exchange_rate::list_rates(pool).await?;
+    let base = rates.iter().find(|r| r.is_base).map(|r| r.currency);
+    Ok(json!({ "baseCurrency": base, "rates": rates }))
+}
+
+// ======================================================
+// List
+// ======================================================
+pub async fn list_exchange_rates_service(pool: &SqlitePool) -> ServiceResult {
+    info!("Retrieving exchange rates");
+
+    match rates_payload(pool).await {
+        Ok(data) => Ok(ApiResponse::success(
+            "Exchange rates retrieved successfully.",
+            data,
+        )),
+        Err(e) => {
+            error!("Error retrieving exchange rates: {}", e);
+            Err(e.context("Failed to retrieve exchange rates"))
+        }
+    }
+}
+
+// ======================================================
+// Set Rate
+// ======================================================
+/// Sets how much one unit of `currency` is worth in the base currency.
+pub async fn set_exchange_rate_service(
+    pool: &SqlitePool,
+    currency: &Currency,
+    rate: f64,
+) -> ServiceResult {
+    info!("Setting exchange rate for {} to {}", currency, rate);
+
+    if !rate.is_finite() || rate <= 0.0 {
+        return Err(AppError::invalid_field("rate", "must be a positive number"));
+    }
+    match exchange_rate::get_base_currency(pool).await? {
+        None => {
+            return Err(AppError::invalid_field(
+                "currency",
+                "choose a base currency first",
+            ))
+        }
+        Some(base) if base == *currency && rate != 1.0 => {
+            return Err(AppError::invalid_field(
+                "rate",
+                "the base currency always has rate 1",
+            ))
+        }
+        Some(_) => {}
+    }
+
+    match exchange_rate::set_rate(pool, currency, rate).await {
+        Ok(record) => {
+            info!("Exchange rate for {} set successfully.", currency);
+            Ok(ApiResponse::success(
+                format!("Exchange rate for {} set successfully.", currency),
+                json!(record),
+            ))
+        }
+        Err(e) => {
+            error!("Error setting exchange rate for {}: {}", currency, e);
+            Err(AppError::from(e).context(format!("Failed to set exchange rate for {}", currency)))
+        }
+    }
+}
+
+// ======================================================
+// Delete Rate
+// ======================================================
+pub async fn delete_exchange_rate_service(pool: &SqlitePool, currency: &Currency) -> ServiceResult {
+    info!("Deleting exchange rate for {}", currency);
+
+    if exchange_rate::get_base_currency(pool).await? == Some(*currency) {
+        return Err(AppError::invalid_field(
+            "currency",
+            "the base currency cannot be removed",
+        ));
+    }
+
+    match exchange_rate::delete_rate(pool, currency).await {
+        Ok(0) => Err(AppError::not_found(format!(
+            "No exchange rate for {}",
+            currency
+        ))),
+        Ok(_) => {
+            info!("Exchange rate for {} deleted successfully.", currency);
+            Ok(ApiResponse::message(format!(
+                "Exchange rate for {} deleted successfully.",
+                currency
+            )))
+        }
+        Err(e) => {
+            error!("Error deleting exchange rate for {}: {}", currency, e);
+            Err(AppError::from(e)
+                .context(format!("Failed to delete exchange rate for {}", currency)))
+        }
+    }
+}
+
+// ======================================================
+// Set Base Currency
+// ======================================================
+/// Makes `currency` the one salaries are compared in. Existing rates are
+/// converted, so switching needs a rate for `currency` (except for the very
+/// first base).
+pub async fn set_base_currency_service(pool: &SqlitePool, currency: &Currency) -> ServiceResult {
+    info!("Setting base currency to {}", currency);
+
+    match exchange_rate::set_base_currency(pool, currency).await {
+        Ok(rates) => {
+            info!("Base currency set to {}.", currency);
+            Ok(ApiResponse::success(
+                format!("Base currency set to {}.", currency),
+                json!({ "baseCurrency": currency, "rates": rates }),
+            ))
+        }
+        Err(sqlx::Error::RowNotFound) => Err(AppError::not_found(format!(
+            "No exchange rate for {}; add one before making it the base currency",
+            currency
+        ))),
+        Err(e) => {
+            error!("Error setting base currency to {}: {}", currency, e);
+            Err(AppError::from(e).context(format!("Failed to set base currency to {}", currency)))
+        }
+    }
+}
@@ -0,0 +1,703 @@
+use crate::db::migrator::latest_version;
+use crate::db::models::enums::EntityType;
+use crate::db::queries::application::{self, Application, APPLICATION_LIST};
+use crate::db::queries::application_contact::{self, ApplicationContact};
+use crate::db::queries::attachment::{self, Attachment, AttachmentLink};
+use crate::db::queries::audit::AuditAction;
+use crate::db::queries::company::{self, Company, COMPANY_LIST};
+use crate::db::queries::dump;
+use crate::db::queries::exchange_rate::{self, ExchangeRate};
+use crate::db::queries::interaction::{self, Interaction, INTERACTION_LIST};
+use crate::db::queries::job_listing::{self, JobListing, JOB_LISTING_LIST};
+use crate::db::queries::list_query::{
+    fetch_page, Inclusion, ListError, ListOptions, ListSpec, SortDirection, SortSpec,
+};
+use crate::db::queries::note::{self, Note, NOTE_LIST};
+use crate::db::queries::person::{self, Person, PERSON_LIST};
+use crate::db::queries::reminder::{self, Reminder, REMINDER_LIST};
+use crate::db::queries::rule::{self, AutomationRule, RuleRun};
+use crate::db::queries::stage_event::{StageEvent, STAGE_EVENT_LIST};
+use crate::db::queries::tag::{self, EntityTag, Tag};
+use crate::logger::*;
+use crate::services::attachment_service::attachment_store;
+use crate::services::audit_service::record_change;
+use crate::services::service_types::JsonResult;
+use crate::settings::AppSettings;
+use crate::utils::file_store::FileStore;
+use chrono::{Local, NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sqlx::{SqliteConnection, SqlitePool};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Identifies a JobTrackr JSON dump.
+pub const EXPORT_FORMAT: &str = "jobtrackr-export";
+
+/// Bumped whenever the layout of `ExportBundle` changes incompatibly.
+pub const EXPORT_FORMAT_VERSION: i64 = 1;
+
+/// Self-describing JSON dump of the whole database.
+///
+/// Records use the same camelCase shape as the command payloads and keep
+/// their original IDs, so relationships are expressed by the `*Id` fields.
+/// Attached files are not embedded; they are copied next to the dump (see
+/// [`attachment_dir_for`]).
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportBundle {
+    pub format: String,
+    pub format_version: i64,
+    pub schema_version: i64,
+    pub app_version: String,
+    pub exported_at: NaiveDateTime,
+    pub data: ExportData,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ExportData {
+    pub companies: Vec<Company>,
+    pub persons: Vec<Person>,
+    pub job_listings: Vec<JobListing>,
+    pub applications: Vec<Application>,
+    pub interactions: Vec<Interaction>,
+    pub notes: Vec<Note>,
+    pub reminders: Vec<Reminder>,
+    pub application_stage_events: Vec<StageEvent>,
+    pub tags: Vec<Tag>,
+    pub entity_tags: Vec<EntityTag>,
+    pub application_contacts: Vec<ApplicationContact>,
+    pub attachments: Vec<Attachment>,
+    pub attachment_links: Vec<AttachmentLink>,
+    pub automation_rules: Vec<AutomationRule>,
+    /// What each rule already did, so restored rules do not fire again
+    pub automation_rule_runs: Vec<RuleRun>,
+    /// Base currency included (`isBase`)
+    pub exchange_rates: Vec<ExchangeRate>,
+}
+
+impl ExportData {
+    pub fn counts(&self) -> Value {
+        json!({
+            "companies": self.companies.len(),
+            "persons": self.persons.len(),
+            "jobListings": self.job_listings.len(),
+            "applications": self.applications.len(),
+            "interactions": self.interactions.len(),
+            "notes": self.notes.len(),
+            "reminders": self.reminders.len(),
+            "applicationStageEvents": self.application_stage_events.len(),
+            "tags": self.tags.len(),
+            "entityTags": self.entity_tags.len(),
+            "applicationContacts": self.application_contacts.len(),
+            "attachments": self.attachments.len(),
+            "attachmentLinks": self.attachment_links.len(),
+            "automationRules": self.automation_rules.len(),
+            "automationRuleRuns": self.automation_rule_runs.len(),
+            "exchangeRates": self.exchange_rates.len()
+        })
+    }
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum JsonImportMode {
+    /// Only into an empty database; original IDs are preserved
+    Restore,
+    /// Into any database; records get new IDs and references are remapped
+    Merge,
+}
+
+// ======================================================
+// Helper: Build error response
+// ======================================================
+fn error_json(message: String) -> String {
+    json!({
+        "status": "error",
+        "message": message
+    })
+    .to_string()
+}
+
+// ======================================================
+// Build Export
+// ======================================================
+pub async fn build_export(pool: &SqlitePool) -> Result<ExportBundle, ListError> {
+    // Everything, archived and trashed too, in insertion order
+    let all = ListOptions {
+        sort: vec![SortSpec {
+            field: "id".to_string(),
+            direction: SortDirection::Asc,
+        }],
+        archived: Inclusion::Include,
+        trashed: Inclusion::Include,
+        ..Default::default()
+    };
+
+    let data = ExportData {
+        companies: company::list_companies(pool, &all).await?.items,
+        persons: person::list_persons(pool, &all).await?.items,
+        job_listings: job_listing::list_job_listings(pool, &all).await?.items,
+        applications: application::list_applications(pool, &all).await?.items,
+        interactions: interaction::list_interactions(pool, &all).await?.items,
+        notes: note::list_notes(pool, &all).await?.items,
+        reminders: reminder::list_reminders(pool, &all).await?.items,
+        application_stage_events: fetch_page(pool, &STAGE_EVENT_LIST, &all).await?.items,
+        tags: tag::list_all_tags(pool).await?,
+        entity_tags: tag::list_entity_tags(pool).await?,
+        application_contacts: application_contact::list_application_contacts(pool).await?,
+        attachments: attachment::list_all_attachments(pool).await?,
+        attachment_links: attachment::list_attachment_links(pool).await?,
+        automation_rules: rule::list_rules(pool).await?,
+        automation_rule_runs: rule::list_all_rule_runs(pool).await?,
+        exchange_rates: exchange_rate::list_rates(pool).await?,
+    };
+
+    Ok(ExportBundle {
+        format: EXPORT_FORMAT.to_string(),
+        format_version: EXPORT_FORMAT_VERSION,
+        schema_version: latest_version(),
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        exported_at: Utc::now().naive_utc(),
+        data,
+    })
+}
+
+// ======================================================
+// Helper: Reference remapping
+// ======================================================
+type IdMap = HashMap<i64, i64>;
+
+/// Points `field` at the new ID; references to records missing from the
+/// dump are cleared rather than left dangling.
+fn remap(record: &mut Value, field: &str, ids: &IdMap) {
+    if let Some(slot) = record.get_mut(field) {
+        if let Some(old) = slot.as_i64() {
+            *slot = ids.get(&old).map_or(Value::Null, |new| json!(new));
+        }
+    }
+}
+
+async fn insert_all<T: Serialize>(
+    conn: &mut SqliteConnection,
+    spec: &ListSpec,
+    records: &[T],
+    keep_ids: bool,
+    references: &[(&str, &IdMap)],
+) -> Result<IdMap, String> {
+    let mut ids = IdMap::with_capacity(records.len());
+
+    for record in records {
+        let mut value = serde_json::to_value(record).map_err(|e| e.to_string())?;
+        let old_id = value.get("id").and_then(Value::as_i64).unwrap_or_default();
+
+        for (field, map) in references {
+            remap(&mut value, field, map);
+        }
+
+        let new_id = dump::insert_record(conn, spec, &value, keep_ids)
+            .await
+            .map_err(|e| format!("{} {}: {}", spec.table, old_id, e))?;
+        ids.insert(old_id, new_id);
+    }
+
+    Ok(ids)
+}
+
+// ======================================================
+// Apply Import
+// ======================================================
+/// Writes a bundle in a single transaction; any failure leaves the DB untouched.
+/// Once committed, every record written gets a create entry in the audit log.
+pub async fn apply_import(
+    pool: &SqlitePool,
+    bundle: &ExportBundle,
+    mode: JsonImportMode,
+) -> Result<(), String> {
+    if bundle.format != EXPORT_FORMAT {
+        return Err(format!(
+            "not a JobTrackr export (format '{}')",
+            bundle.format
+        ));
+    }
+    if bundle.format_version > EXPORT_FORMAT_VERSION {
+        return Err(format!(
+            "export format version {} is newer than supported version {}",
+            bundle.format_version, EXPORT_FORMAT_VERSION
+        ));
+    }
+
+    let data = &bundle.data;
+    let keep_ids = mode == JsonImportMode::Restore;
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    if mode == JsonImportMode::Restore {
+        for spec in [
+            &COMPANY_LIST,
+            &PERSON_LIST,
+            &JOB_LISTING_LIST,
+            &APPLICATION_LIST,
+            &INTERACTION_LIST,
+            &NOTE_LIST,
+            &REMINDER_LIST,
+        ] {
+            let rows = dump::count_rows(&mut tx, spec)
+                .await
+                .map_err(|e| e.to_string())?;
+            if rows > 0 {
+                return Err(format!(
+                    "restore requires an empty database, but '{}' has {} rows; use merge instead",
+                    spec.table, rows
+                ));
+            }
+        }
+    }
+
+    // Parents before children
+    let companies = insert_all(&mut tx, &COMPANY_LIST, &data.companies, keep_ids, &[]).await?;
+    let persons = insert_all(
+        &mut tx,
+        &PERSON_LIST,
+        &data.persons,
+        keep_ids,
+        &[("companyId", &companies)],
+    )
+    .await?;
+    let job_listings = insert_all(
+        &mut tx,
+        &JOB_LISTING_LIST,
+        &data.job_listings,
+        keep_ids,
+        &[("companyId", &companies)],
+    )
+    .await?;
+    let applications = insert_all(
+        &mut tx,
+        &APPLICATION_LIST,
+        &data.applications,
+        keep_ids,
+        &[("jobListingId", &job_listings)],
+    )
+    .await?;
+    let interactions = insert_all(
+        &mut tx,
+        &INTERACTION_LIST,
+        &data.interactions,
+        keep_ids,
+        &[
+            ("applicationId", &applications),
+            ("personId", &persons),
+            ("companyId", &companies),
+        ],
+    )
+    .await?;
+    let notes = insert_all(
+        &mut tx,
+        &NOTE_LIST,
+        &data.notes,
+        keep_ids,
+        &[
+            ("interactionId", &interactions),
+            ("jobListingId", &job_listings),
+            ("applicationId", &applications),
+            ("personId", &persons),
+            ("companyId", &companies),
+        ],
+    )
+    .await?;
+    let reminders = insert_all(
+        &mut tx,
+        &REMINDER_LIST,
+        &data.reminders,
+        keep_ids,
+        &[
+            ("applicationId", &applications),
+            ("interactionId", &interactions),
+            ("noteId", &notes),
+            ("jobListingId", &job_listings),
+            ("companyId", &companies),
+            ("personId", &persons),
+        ],
+    )
+    .await?;
+
+    // Inserting applications wrote a fresh initial stage event;
+    // replace it with the exported history where we have one
+    let with_history: HashSet<i64> = data
+        .application_stage_events
+        .iter()
+        .map(|e| e.application_id)
+        .collect();
+    for old_id in &with_history {
+        if let Some(new_id) = applications.get(old_id) {
+            dump::delete_stage_events(&mut tx, *new_id)
+                .await
+                .map_err(|e| e.to_string())?;
+        }
+    }
+    let events: Vec<&StageEvent> = data
+        .application_stage_events
+        .iter()
+        .filter(|e| applications.contains_key(&e.application_id))
+        .collect();
+    insert_all(
+        &mut tx,
+        &STAGE_EVENT_LIST,
+        &events,
+        keep_ids,
+        &[("applicationId", &applications)],
+    )
+    .await?;
+
+    let entities = |entity_type: EntityType| match entity_type {
+        EntityType::Company => &companies,
+        EntityType::Person => &persons,
+        EntityType::JobListing => &job_listings,
+        EntityType::Application => &applications,
+        EntityType::Interaction => &interactions,
+        EntityType::Note => &notes,
+        EntityType::Reminder => &reminders,
+    };
+
+    // Tags are matched by name, so merging never duplicates them
+    let mut tags = IdMap::with_capacity(data.tags.len());
+    for t in &data.tags {
+        let new_id = dump::find_or_insert_tag(&mut tx, t, keep_ids)
+            .await
+            .map_err(|e| format!("tag {}: {}", t.id, e))?;
+        tags.insert(t.id, new_id);
+    }
+    for link in &data.entity_tags {
+        // Links to records missing from the dump are dropped
+        let (Some(tag_id), Some(entity_id)) = (
+            tags.get(&link.tag_id),
+            entities(link.entity_type).get(&link.entity_id),
+        ) else {
+            continue;
+        };
+        dump::insert_entity_tag(
+            &mut tx,
+            *tag_id,
+            link.entity_type,
+            *entity_id,
+            link.created_at,
+        )
+        .await
+        .map_err(|e| e.to_string())?;
+    }
+
+    for contact in &data.application_contacts {
+        let (Some(application_id), Some(person_id)) = (
+            applications.get(&contact.application_id),
+            persons.get(&contact.person_id),
+        ) else {
+            continue;
+        };
+        dump::insert_application_contact(&mut tx, *application_id, *person_id, contact)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+
+    // Attachments are matched by content, like the file store
+    let mut attachments = IdMap::with_capacity(data.attachments.len());
+    for a in &data.attachments {
+        let new_id = dump::find_or_insert_attachment(&mut tx, a, keep_ids)
+            .await
+            .map_err(|e| format!("attachment {}: {}", a.id, e))?;
+        attachments.insert(a.id, new_id);
+    }
+    for link in &data.attachment_links {
+        let (Some(attachment_id), Some(entity_id)) = (
+            attachments.get(&link.attachment_id),
+            entities(link.entity_type).get(&link.entity_id),
+        ) else {
+            continue;
+        };
+        dump::insert_attachment_link(
+            &mut tx,
+            *attachment_id,
+            link.entity_type,
+            *entity_id,
+            link.created_at,
+        )
+        .await
+        .map_err(|e| e.to_string())?;
+    }
+
+    // Identical rules are reused, like tags
+    let mut rules = IdMap::with_capacity(data.automation_rules.len());
+    for r in &data.automation_rules {
+        let new_id = dump::find_or_insert_rule(&mut tx, r, keep_ids)
+            .await
+            .map_err(|e| format!("automation rule {}: {}", r.id, e))?;
+        rules.insert(r.id, new_id);
+    }
+    for run in &data.automation_rule_runs {
+        // Runs for applications missing from the dump are dropped; a
+        // missing record only loses the reference
+        let Some(rule_id) = rules.get(&run.rule_id) else {
+            continue;
+        };
+        let application_id = match run.application_id {
+            Some(old) => match applications.get(&old) {
+                Some(new) => Some(*new),
+                None => continue,
+            },
+            None => None,
+        };
+        let entity = run
+            .entity_type
+            .zip(run.entity_id)
+            .and_then(|(t, old)| entities(t).get(&old).map(|new| (t, *new)));
+        dump::insert_rule_run(&mut tx, run, *rule_id, application_id, entity, keep_ids)
+            .await
+            .map_err(|e| format!("automation rule run {}: {}", run.id, e))?;
+    }
+
+    // Rates already set win; see `dump::insert_exchange_rates`
+    let added = dump::insert_exchange_rates(&mut tx, &data.exchange_rates)
+        .await
+        .map_err(|e| format!("exchange rates: {}", e))?;
+    if added < data.exchange_rates.len() as u64 {
+        warn!(
+            "{} of {} exchange rates were not imported; the database already has them or cannot convert them",
+            data.exchange_rates.len() as u64 - added,
+            data.exchange_rates.len()
+        );
+    }
+
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    for (entity_type, ids) in [
+        (EntityType::Company, &companies),
+        (EntityType::Person, &persons),
+        (EntityType::JobListing, &job_listings),
+        (EntityType::Application, &applications),
+        (EntityType::Interaction, &interactions),
+        (EntityType::Note, &notes),
+        (EntityType::Reminder, &reminders),
+    ] {
+        let mut new_ids: Vec<i64> = ids.values().copied().collect();
+        new_ids.sort_unstable();
+        for id in new_ids {
+            record_change(
+                pool,
+                entity_type,
+                id,
+                AuditAction::Create,
+                None,
+                "export.ImportJson",
+            )
+            .await;
+        }
+    }
+    Ok(())
+}
+
+// ======================================================
+// Helper: Default export location
+// ======================================================
+fn default_export_path(settings: &AppSettings) -> PathBuf {
+    let stamp = Local::now().format("%Y%m%d-%H%M%S");
+    settings
+        .active_export_dir()
+        .join(format!("jobtrackr-export-{}.json", stamp))
+}
+
+// ======================================================
+// Helper: Attachment files
+// ======================================================
+/// Folder the attached files of an export are copied to, next to the
+/// dump: `jobtrackr-export-….json` gets `jobtrackr-export-…-attachments`.
+/// It uses the same layout as the attachment store.
+pub fn attachment_dir_for(export_path: &Path) -> PathBuf {
+    let stem = export_path
+        .file_stem()
+        .unwrap_or_default()
+        .to_string_lossy();
+    export_path.with_file_name(format!("{}-attachments", stem))
+}
+
+/// Copies the files of `attachments` from `store` to `target`; returns
+/// how many could not be found in the store.
+pub fn copy_attachment_files(
+    attachments: &[Attachment],
+    store: &FileStore,
+    target: &FileStore,
+) -> Result<usize, String> {
+    let mut missing = 0;
+    for a in attachments {
+        let source = store.path_of(&a.storage_path);
+        if !source.is_file() {
+            warn!("File of attachment {} is missing from the store", a.id);
+            missing += 1;
+            continue;
+        }
+        target
+            .put(&source, &a.storage_path)
+            .map_err(|e| format!("cannot copy '{}': {}", a.filename, e))?;
+    }
+    Ok(missing)
+}
+
+/// Brings the files of `attachments` into `store`, copying them from
+/// `source` where the store lacks them. Attachments whose file is in
+/// neither are dropped, taking their links with them on import; returns
+/// how many were.
+pub fn collect_attachment_files(
+    attachments: &mut Vec<Attachment>,
+    store: &FileStore,
+    source: Option<&FileStore>,
+) -> Result<usize, String> {
+    let before = attachments.len();
+    let mut kept = Vec::with_capacity(before);
+    for a in attachments.drain(..) {
+        if store.path_of(&a.storage_path).is_file() {
+            kept.push(a);
+            continue;
+        }
+        match source.map(|s| s.path_of(&a.storage_path)) {
+            Some(file) if file.is_file() => {
+                store
+                    .put(&file, &a.storage_path)
+                    .map_err(|e| format!("cannot copy '{}': {}", a.filename, e))?;
+                kept.push(a);
+            }
+            _ => warn!(
+                "No file for attachment {} ('{}'); skipped",
+                a.id, a.filename
+            ),
+        }
+    }
+    *attachments = kept;
+    Ok(before - attachments.len())
+}
+
+// ======================================================
+// Export JSON
+// ======================================================
+pub async fn export_json_service(
+    pool: &SqlitePool,
+    settings: &Mutex<AppSettings>,
+    path: Option<&str>,
+) -> JsonResult {
+    info!("Exporting database to JSON");
+
+    let (target, store) = {
+        let settings = settings.lock().unwrap();
+        let target = match path {
+            Some(p) => PathBuf::from(p),
+            None => default_export_path(&settings),
+        };
+        (target, attachment_store(&settings))
+    };
+
+    let bundle = match build_export(pool).await {
+        Ok(b) => b,
+        Err(e) => {
+            error!("Error reading data for export: {}", e);
+            return Err(error_json(format!("Failed to export data: {}", e)));
+        }
+    };
+
+    let files = attachment_dir_for(&target);
+    let written = serde_json::to_string_pretty(&bundle)
+        .map_err(|e| e.to_string())
+        .and_then(|text| write_file(&target, &text))
+        .and_then(|_| {
+            copy_attachment_files(&bundle.data.attachments, &store, &FileStore::new(&files))
+        });
+
+    match written {
+        Ok(missing_files) => {
+            info!("Database exported to {}", target.display());
+            let json = json!({
+                "status": "success",
+                "message": format!("Data exported to {}.", target.display()),
+                "data": {
+                    "path": target,
+                    "attachmentDir": (!bundle.data.attachments.is_empty()).then_some(files),
+                    "missingFiles": missing_files,
+                    "counts": bundle.data.counts()
+                }
+            });
+            Ok(json.to_string())
+        }
+        Err(e) => {
+            error!("Error writing export {}: {}", target.display(), e);
+            Err(error_json(format!("Failed to write export: {}", e)))
+        }
+    }
+}
+
+/// Writes `content`, creating missing parent directories.
+pub fn write_file(path: &Path, content: &str) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    fs::write(path, content).map_err(|e| e.to_string())
+}
+
+// ======================================================
+// Import JSON
+// ======================================================
+/// Attached files are taken from the folder next to `path` (see
+/// [`attachment_dir_for`]) if the attachment store lacks them.
+pub async fn import_json_service(
+    pool: &SqlitePool,
+    store: &FileStore,
+    path: Option<&str>,
+    content: Option<&str>,
+    mode: JsonImportMode,
+) -> JsonResult {
+    info!("Importing JSON export ({:?})", mode);
+
+    let text = match (content, path) {
+        (Some(c), _) => Ok(c.to_string()),
+        (None, Some(p)) => fs::read_to_string(p).map_err(|e| format!("cannot read '{}': {}", p, e)),
+        (None, None) => Err("either 'content' or 'path' is required".to_string()),
+    };
+
+    let mut bundle = match text.and_then(|t| {
+        serde_json::from_str::<ExportBundle>(&t).map_err(|e| format!("invalid export file: {}", e))
+    }) {
+        Ok(b) => b,
+        Err(e) => {
+            error!("Error reading JSON export: {}", e);
+            return Err(error_json(format!("Failed to import data: {}", e)));
+        }
+    };
+
+    let source = path.map(|p| FileStore::new(attachment_dir_for(Path::new(p))));
+    let missing_files =
+        match collect_attachment_files(&mut bundle.data.attachments, store, source.as_ref()) {
+            Ok(n) => n,
+            Err(e) => {
+                error!("Error copying attachment files: {}", e);
+                return Err(error_json(format!("Failed to import data: {}", e)));
+            }
+        };
+
+    match apply_import(pool, &bundle, mode).await {
+        Ok(_) => {
+            info!("JSON export imported successfully.");
+            let json = json!({
+                "status": "success",
+                "message": "Data imported successfully.",
+                "data": {
+                    "mode": format!("{:?}", mode).to_lowercase(),
+                    "missingFiles": missing_files,
+                    "counts": bundle.data.counts()
+                }
+            });
+            Ok(json.to_string())
+        }
+        Err(e) => {
+            error!("Error importing JSON export: {}", e);
+            Err(error_json(format!("Failed to import data: {}", e)))
+        }
+    }
+}
@@ -0,0 +1,649 @@
+use crate::db::models::enums::{Currency, EntityType, PayPeriod, Role, SeniorityLevel, WorkType};
+use crate::db::queries::audit::AuditAction;
+use crate::db::queries::import;
+use crate::db::queries::list_query::SqlValue;
+use crate::logger::*;
+use crate::services::audit_service::record_change;
+use crate::services::service_types::JsonResult;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sqlx::{Connection, SqliteConnection, SqlitePool};
+use std::collections::HashMap;
+use std::fs;
+
+/// Number of data rows returned by a preview.
+const PREVIEW_ROWS: usize = 5;
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ImportEntity {
+    Company,
+    JobListing,
+    Person,
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum FieldKind {
+    Text,
+    Integer,
+    WorkType,
+    SeniorityLevel,
+    Currency,
+    PayPeriod,
+    Role,
+    /// Company name, resolved to `company_id` (created if missing)
+    CompanyName,
+}
+
+/// A target field a CSV column can be mapped to.
+#[derive(Serialize, Debug, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportField {
+    pub field: &'static str,
+    #[serde(skip)]
+    pub column: &'static str,
+    pub kind: FieldKind,
+    pub required: bool,
+}
+
+const fn field(
+    field: &'static str,
+    column: &'static str,
+    kind: FieldKind,
+    required: bool,
+) -> ImportField {
+    ImportField {
+        field,
+        column,
+        kind,
+        required,
+    }
+}
+
+const COMPANY_FIELDS: &[ImportField] = &[
+    field("name", "name", FieldKind::Text, true),
+    field("streetAddress", "street_address", FieldKind::Text, false),
+    field("zipCode", "zip_code", FieldKind::Text, false),
+    field("city", "city", FieldKind::Text, false),
+    field("country", "country", FieldKind::Text, false),
+    field(
+        "defaultWorkType",
+        "default_work_type",
+        FieldKind::WorkType,
+        false,
+    ),
+    field("industry", "industry", FieldKind::Text, false),
+    field("website", "website", FieldKind::Text, false),
+    field("phoneNumber", "phone_number", FieldKind::Text, false),
+];
+
+const JOB_LISTING_FIELDS: &[ImportField] = &[
+    field("company", "company_id", FieldKind::CompanyName, true),
+    field("title", "title", FieldKind::Text, true),
+    field("workType", "work_type", FieldKind::WorkType, false),
+    field("category", "category", FieldKind::Text, false),
+    field(
+        "seniorityLevel",
+        "seniority_level",
+        FieldKind::SeniorityLevel,
+        false,
+    ),
+    field("salaryMin", "salary_min", FieldKind::Integer, false),
+    field("salaryMax", "salary_max", FieldKind::Integer, false),
+    field("currency", "currency", FieldKind::Currency, false),
+    field("payPeriod", "pay_period", FieldKind::PayPeriod, false),
+    field("description", "description", FieldKind::Text, false),
+    field("url", "url", FieldKind::Text, false),
+];
+
+const PERSON_FIELDS: &[ImportField] = &[
+    field("firstName", "first_name", FieldKind::Text, true),
+    field("lastName", "last_name", FieldKind::Text, true),
+    field("email", "email", FieldKind::Text, false),
+    field("phoneNumber", "phone_number", FieldKind::Text, false),
+    field("role", "role", FieldKind::Role, false),
+    field("linkedinUrl", "linkedin_url", FieldKind::Text, false),
+    field("company", "company_id", FieldKind::CompanyName, false),
+];
+
+impl ImportEntity {
+    pub fn fields(&self) -> &'static [ImportField] {
+        match self {
+            ImportEntity::Company => COMPANY_FIELDS,
+            ImportEntity::JobListing => JOB_LISTING_FIELDS,
+            ImportEntity::Person => PERSON_FIELDS,
+        }
+    }
+
+    fn table(&self) -> &'static str {
+        match self {
+            ImportEntity::Company => "company",
+            ImportEntity::JobListing => "job_listing",
+            ImportEntity::Person => "person",
+        }
+    }
+
+    fn entity_type(&self) -> EntityType {
+        match self {
+            ImportEntity::Company => EntityType::Company,
+            ImportEntity::JobListing => EntityType::JobListing,
+            ImportEntity::Person => EntityType::Person,
+        }
+    }
+}
+
+/// Where the CSV comes from: inline text or a file path chosen by the user.
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct CsvSource {
+    pub content: Option<String>,
+    pub path: Option<String>,
+    pub delimiter: Option<char>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportRequest {
+    pub entity: ImportEntity,
+    #[serde(flatten)]
+    pub source: CsvSource,
+    /// CSV header → target field name
+    pub mapping: HashMap<String, String>,
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum RowStatus {
+    Created,
+    Skipped,
+    Failed,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RowReport {
+    /// Line number in the CSV file (the header is line 1)
+    pub line: u64,
+    pub status: RowStatus,
+    pub id: Option<i64>,
+    pub message: Option<String>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportReport {
+    pub entity: ImportEntity,
+    pub dry_run: bool,
+    pub total_rows: usize,
+    pub created: usize,
+    pub skipped: usize,
+    pub failed: usize,
+    pub companies_created: Vec<String>,
+    pub rows: Vec<RowReport>,
+}
+
+// ======================================================
+// Helper: Build error response
+// ======================================================
+fn error_json(message: String) -> String {
+    json!({
+        "status": "error",
+        "message": message
+    })
+    .to_string()
+}
+
+// ======================================================
+// Helper: Read CSV text from the source
+// ======================================================
+fn read_source(source: &CsvSource) -> Result<String, String> {
+    match (&source.content, &source.path) {
+        (Some(content), _) => Ok(content.clone()),
+        (None, Some(path)) => {
+            fs::read_to_string(path).map_err(|e| format!("cannot read '{}': {}", path, e))
+        }
+        (None, None) => Err("either 'content' or 'path' is required".to_string()),
+    }
+}
+
+fn csv_reader<'a>(source: &CsvSource, content: &'a str) -> Result<csv::Reader<&'a [u8]>, String> {
+    let delimiter = source.delimiter.unwrap_or(',');
+    if !delimiter.is_ascii() {
+        return Err("delimiter must be a single ASCII character".to_string());
+    }
+
+    // Excel likes to prepend a byte order mark
+    let content = content.trim_start_matches('\u{feff}');
+
+    Ok(csv::ReaderBuilder::new()
+        .delimiter(delimiter as u8)
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_reader(content.as_bytes()))
+}
+
+// ======================================================
+// Helper: Value conversion
+// ======================================================
+/// Parses an enum cell leniently: "Full time", "full-time" and "FULL_TIME"
+/// all resolve to `full_time`.
+fn parse_enum<T: DeserializeOwned>(raw: &str) -> Result<String, String> {
+    let normalized = raw.trim().to_lowercase().replace([' ', '-'], "_");
+    let candidates = [
+        raw.trim().to_string(),
+        normalized,
+        raw.trim().to_uppercase(),
+    ];
+
+    let mut last_error = String::new();
+    for candidate in candidates {
+        match serde_json::from_value::<T>(Value::String(candidate.clone())) {
+            Ok(_) => return Ok(candidate),
+            Err(e) => last_error = e.to_string(),
+        }
+    }
+    Err(last_error)
+}
+
+fn convert_cell(kind: FieldKind, raw: &str) -> Result<SqlValue, String> {
+    match kind {
+        FieldKind::Text | FieldKind::CompanyName => Ok(SqlValue::Text(raw.to_string())),
+        FieldKind::Integer => {
+            let cleaned: String = raw
+                .chars()
+                .filter(|c| !matches!(c, ',' | '_' | ' '))
+                .collect();
+            cleaned
+                .parse::<i64>()
+                .map(SqlValue::Integer)
+                .map_err(|_| format!("'{}' is not a whole number", raw))
+        }
+        FieldKind::WorkType => parse_enum::<WorkType>(raw).map(SqlValue::Text),
+        FieldKind::SeniorityLevel => parse_enum::<SeniorityLevel>(raw).map(SqlValue::Text),
+        FieldKind::Currency => raw.parse::<Currency>().map(|c| SqlValue::from(c.as_str())),
+        FieldKind::PayPeriod => parse_enum::<PayPeriod>(raw).map(SqlValue::Text),
+        FieldKind::Role => parse_enum::<Role>(raw).map(SqlValue::Text),
+    }
+}
+
+// ======================================================
+// Helper: Validate the column mapping against the header
+// ======================================================
+fn resolve_mapping(
+    entity: ImportEntity,
+    headers: &csv::StringRecord,
+    mapping: &HashMap<String, String>,
+) -> Result<Vec<(usize, &'static ImportField)>, String> {
+    let fields = entity.fields();
+    let mut resolved: Vec<(usize, &'static ImportField)> = Vec::new();
+
+    for (header, target) in mapping {
+        let index = headers
+            .iter()
+            .position(|h| h == header.trim())
+            .ok_or_else(|| format!("column '{}' not found in CSV header", header))?;
+        let def = fields
+            .iter()
+            .find(|f| f.field == target)
+            .ok_or_else(|| format!("unknown field '{}' for {:?}", target, entity))?;
+        if resolved.iter().any(|(_, f)| f.field == def.field) {
+            return Err(format!("field '{}' is mapped more than once", def.field));
+        }
+        resolved.push((index, def));
+    }
+
+    for required in fields.iter().filter(|f| f.required) {
+        if !resolved.iter().any(|(_, f)| f.field == required.field) {
+            return Err(format!("required field '{}' is not mapped", required.field));
+        }
+    }
+
+    resolved.sort_by_key(|(index, _)| *index);
+    Ok(resolved)
+}
+
+// ======================================================
+// Helper: Import a single row inside its own savepoint
+// ======================================================
+struct RowOutcome {
+    status: RowStatus,
+    id: Option<i64>,
+    message: Option<String>,
+    /// Id and name of a company created for the row
+    created_company: Option<(i64, String)>,
+}
+
+impl RowOutcome {
+    fn skipped(id: i64, message: String) -> Self {
+        RowOutcome {
+            status: RowStatus::Skipped,
+            id: Some(id),
+            message: Some(message),
+            created_company: None,
+        }
+    }
+}
+
+async fn import_row(
+    conn: &mut SqliteConnection,
+    entity: ImportEntity,
+    values: Vec<(&'static ImportField, SqlValue)>,
+) -> Result<RowOutcome, sqlx::Error> {
+    let text = |name: &str| {
+        values.iter().find_map(|(f, v)| match v {
+            SqlValue::Text(t) if f.field == name => Some(t.clone()),
+            _ => None,
+        })
+    };
+
+    let mut columns: Vec<(&str, SqlValue)> = Vec::with_capacity(values.len());
+    let mut created_company = None;
+
+    for (def, value) in &values {
+        if def.kind != FieldKind::CompanyName {
+            columns.push((def.column, value.clone()));
+            continue;
+        }
+
+        // Resolve company by name, creating it when unknown
+        let SqlValue::Text(name) = value else {
+            continue;
+        };
+        let company_id = match import::find_company_id_by_name(conn, name).await? {
+            Some(id) => id,
+            None => {
+                let id = import::create_company_by_name(conn, name).await?;
+                created_company = Some((id, name.clone()));
+                id
+            }
+        };
+        columns.push((def.column, SqlValue::Integer(company_id)));
+    }
+
+    // Duplicate detection
+    match entity {
+        ImportEntity::Company => {
+            if let Some(name) = text("name") {
+                if let Some(id) = import::find_company_id_by_name(conn, &name).await? {
+                    return Ok(RowOutcome::skipped(
+                        id,
+                        format!("company '{}' already exists", name),
+                    ));
+                }
+            }
+        }
+        ImportEntity::JobListing => {
+            let company_id = columns.iter().find_map(|(c, v)| match v {
+                SqlValue::Integer(id) if *c == "company_id" => Some(*id),
+                _ => None,
+            });
+            if let (Some(company_id), Some(title)) = (company_id, text("title")) {
+                if let Some(id) = import::find_job_listing_id(conn, company_id, &title).await? {
+                    return Ok(RowOutcome::skipped(
+                        id,
+                        format!("job listing '{}' already exists for this company", title),
+                    ));
+                }
+            }
+        }
+        ImportEntity::Person => {
+            if let Some(email) = text("email") {
+                if let Some(id) = import::find_person_id_by_email(conn, &email).await? {
+                    return Ok(RowOutcome::skipped(
+                        id,
+                        format!("a person with email '{}' already exists", email),
+                    ));
+                }
+            }
+        }
+    }
+
+    let id = import::insert_row(conn, entity.table(), &columns).await?;
+
+    Ok(RowOutcome {
+        status: RowStatus::Created,
+        id: Some(id),
+        message: None,
+        created_company,
+    })
+}
+
+// ======================================================
+// Run Import
+// ======================================================
+/// Imports all rows in one transaction. Each row runs in a savepoint so a bad
+/// row is reported without affecting the others; dry runs roll everything back.
+/// Every record created (companies created on the way included) gets a
+/// create entry in the audit log once the import is committed.
+pub async fn run_import(
+    pool: &SqlitePool,
+    request: &ImportRequest,
+) -> Result<ImportReport, String> {
+    let content = read_source(&request.source)?;
+    let mut reader = csv_reader(&request.source, &content)?;
+    let headers = reader
+        .headers()
+        .map_err(|e| format!("cannot read CSV header: {}", e))?
+        .clone();
+    let mapping = resolve_mapping(request.entity, &headers, &request.mapping)?;
+
+    let mut report = ImportReport {
+        entity: request.entity,
+        dry_run: request.dry_run,
+        total_rows: 0,
+        created: 0,
+        skipped: 0,
+        failed: 0,
+        companies_created: Vec::new(),
+        rows: Vec::new(),
+    };
+
+    let mut inserted: Vec<(EntityType, i64)> = Vec::new();
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    for record in reader.records() {
+        report.total_rows += 1;
+
+        let record = match record {
+            Ok(r) => r,
+            Err(e) => {
+                let line = e.position().map(|p| p.line()).unwrap_or(0);
+                report.failed += 1;
+                report.rows.push(RowReport {
+                    line,
+                    status: RowStatus::Failed,
+                    id: None,
+                    message: Some(format!("malformed row: {}", e)),
+                });
+                continue;
+            }
+        };
+        let line = record.position().map(|p| p.line()).unwrap_or(0);
+
+        if record.iter().all(|cell| cell.is_empty()) {
+            report.skipped += 1;
+            report.rows.push(RowReport {
+                line,
+                status: RowStatus::Skipped,
+                id: None,
+                message: Some("empty row".to_string()),
+            });
+            continue;
+        }
+
+        // Validate and convert mapped cells
+        let mut values = Vec::with_capacity(mapping.len());
+        let mut problems = Vec::new();
+        for (index, def) in &mapping {
+            let raw = record.get(*index).unwrap_or("");
+            if raw.is_empty() {
+                if def.required {
+                    problems.push(format!("'{}' is required", def.field));
+                }
+                continue;
+            }
+            match convert_cell(def.kind, raw) {
+                Ok(value) => values.push((*def, value)),
+                Err(e) => problems.push(format!("'{}': {}", def.field, e)),
+            }
+        }
+
+        if !problems.is_empty() {
+            report.failed += 1;
+            report.rows.push(RowReport {
+                line,
+                status: RowStatus::Failed,
+                id: None,
+                message: Some(problems.join("; ")),
+            });
+            continue;
+        }
+
+        // Savepoint per row
+        let outcome = match tx.begin().await {
+            Ok(mut savepoint) => match import_row(&mut savepoint, request.entity, values).await {
+                Ok(outcome) => savepoint.commit().await.map(|_| outcome),
+                Err(e) => {
+                    let _ = savepoint.rollback().await;
+                    Err(e)
+                }
+            },
+            Err(e) => Err(e),
+        };
+
+        match outcome {
+            Ok(outcome) => {
+                if let Some((id, name)) = outcome.created_company {
+                    inserted.push((EntityType::Company, id));
+                    report.companies_created.push(name);
+                }
+                match outcome.status {
+                    RowStatus::Created => {
+                        report.created += 1;
+                        inserted.extend(outcome.id.map(|id| (request.entity.entity_type(), id)));
+                    }
+                    RowStatus::Skipped => report.skipped += 1,
+                    RowStatus::Failed => report.failed += 1,
+                }
+                report.rows.push(RowReport {
+                    line,
+                    status: outcome.status,
+                    id: outcome.id,
+                    message: outcome.message,
+                });
+            }
+            Err(e) => {
+                report.failed += 1;
+                report.rows.push(RowReport {
+                    line,
+                    status: RowStatus::Failed,
+                    id: None,
+                    message: Some(e.to_string()),
+                });
+            }
+        }
+    }
+
+    if request.dry_run {
+        tx.rollback().await.map_err(|e| e.to_string())?;
+    } else {
+        tx.commit().await.map_err(|e| e.to_string())?;
+        for (entity_type, id) in inserted {
+            record_change(
+                pool,
+                entity_type,
+                id,
+                AuditAction::Create,
+                None,
+                "import.Import",
+            )
+            .await;
+        }
+    }
+
+    Ok(report)
+}
+
+// ======================================================
+// Preview CSV
+// ======================================================
+pub fn preview_csv_service(entity: ImportEntity, source: &CsvSource) -> JsonResult {
+    info!("Previewing CSV for {:?} import", entity);
+
+    let preview = read_source(source).and_then(|content| {
+        let mut reader = csv_reader(source, &content)?;
+        let headers: Vec<String> = reader
+            .headers()
+            .map_err(|e| format!("cannot read CSV header: {}", e))?
+            .iter()
+            .map(str::to_string)
+            .collect();
+        let rows: Vec<Vec<String>> = reader
+            .records()
+            .take(PREVIEW_ROWS)
+            .filter_map(|r| r.ok())
+            .map(|r| r.iter().map(str::to_string).collect())
+            .collect();
+        Ok((headers, rows))
+    });
+
+    match preview {
+        Ok((headers, rows)) => {
+            let json = json!({
+                "status": "success",
+                "message": "CSV preview generated successfully.",
+                "data": {
+                    "headers": headers,
+                    "rows": rows,
+                    "fields": entity.fields()
+                }
+            });
+            Ok(json.to_string())
+        }
+        Err(e) => {
+            error!("Error previewing CSV: {}", e);
+            Err(error_json(format!("Failed to read CSV: {}", e)))
+        }
+    }
+}
+
+// ======================================================
+// Import CSV
+// ======================================================
+pub async fn import_csv_service(pool: &SqlitePool, request: &ImportRequest) -> JsonResult {
+    info!(
+        "Importing CSV as {:?} (dry run: {})",
+        request.entity, request.dry_run
+    );
+
+    match run_import(pool, request).await {
+        Ok(report) => {
+            info!(
+                "CSV import finished: {} created, {} skipped, {} failed{}.",
+                report.created,
+                report.skipped,
+                report.failed,
+                if report.dry_run { " (dry run)" } else { "" }
+            );
+            let message = if report.dry_run {
+                "Dry run completed; no changes were saved."
+            } else {
+                "CSV import completed."
+            };
+            let json = json!({
+                "status": "success",
+                "message": message,
+                "data": report
+            });
+            Ok(json.to_string())
+        }
+        Err(e) => {
+            error!("Error importing CSV: {}", e);
+            Err(error_json(format!("Failed to import CSV: {}", e)))
+        }
+    }
+}
@@ -1,10 +1,16 @@
-use crate::db::models::enums::InteractionType;
+use crate::db::models::enums::{EntityType, InteractionType};
+use crate::db::models::patch::Patch;
+use crate::db::queries::audit::AuditAction;
 use crate::db::queries::interaction;
+use crate::db::queries::list_query::ListOptions;
+use crate::error::AppError;
 use crate::logger::*;
-use crate::services::service_types::JsonResult;
-use crate::services::service_utils::add_display_label;
-use chrono::NaiveDate;
-use serde_json::{json, Value};
+use crate::services::audit_service::{record_change, record_rule_outcomes, snapshot};
+use crate::services::rule_engine::{add_outcomes, fire_rules, RuleEvent};
+use crate::services::service_types::{ApiResponse, ServiceResult};
+use crate::services::service_utils::{add_display_label, add_tags, add_tags_to_all};
+use chrono::{Local, NaiveDate};
+use serde_json::Value;
 use sqlx::SqlitePool;
 
 // ======================================================
@@ -20,46 +26,61 @@ pub async fn create_interaction_service(
     application_id: Option<i64>,
     person_id: Option<i64>,
     company_id: Option<i64>,
-) -> JsonResult {
+) -> ServiceResult {
     info!(
         "Creating interaction (application_id={:?}, person_id={:?}, company_id={:?})",
         application_id, person_id, company_id
     );
 
-    let result = interaction::create_interaction(
-        pool,
-        interaction_type,
-        interaction_date,
-        subject,
-        summary,
-        medium,
-        application_id,
-        person_id,
-        company_id,
-    )
+    let result = async {
+        let mut tx = pool.begin().await?;
+        let record = interaction::create_interaction(
+            &mut *tx,
+            interaction_type,
+            interaction_date,
+            subject,
+            summary,
+            medium,
+            application_id,
+            person_id,
+            company_id,
+        )
+        .await?;
+
+        let today = Local::now().date_naive();
+        let outcomes = fire_rules(&mut tx, &RuleEvent::InteractionLogged(&record), today).await?;
+
+        tx.commit().await?;
+        Ok::<_, sqlx::Error>((record, outcomes))
+    }
     .await;
 
     match result {
-        Ok(record) => {
+        Ok((record, outcomes)) => {
             info!("Interaction created successfully. ID: {}", record.id);
-
-            let data = add_display_label(&record, record.summary.as_deref());
-
-            let json = json!({
-                "status": "success",
-                "message": "Interaction created successfully.",
-                "data": data
-            });
-
-            Ok(json.to_string())
+            record_change(
+                pool,
+                EntityType::Interaction,
+                record.id,
+                AuditAction::Create,
+                None,
+                "interaction.Create",
+            )
+            .await;
+            record_rule_outcomes(pool, &outcomes, None).await;
+
+            let mut data = add_display_label(&record, record.summary.as_deref());
+            add_tags(pool, EntityType::Interaction, &mut data).await;
+            add_outcomes(&mut data, &outcomes);
+
+            Ok(ApiResponse::success(
+                "Interaction created successfully.",
+                data,
+            ))
         }
         Err(e) => {
             error!("Error creating interaction: {}", e);
-            let json = json!({
-                "status": "error",
-                "message": format!("Failed to create interaction: {}", e)
-            });
-            Err(json.to_string())
+            Err(AppError::from(e).context("Failed to create interaction"))
         }
     }
 }
@@ -67,7 +88,7 @@ pub async fn create_interaction_service(
 // ======================================================
 // Get Interaction by ID
 // ======================================================
-pub async fn get_interaction_by_id_service(pool: &SqlitePool, id: &i64) -> JsonResult {
+pub async fn get_interaction_by_id_service(pool: &SqlitePool, id: &i64) -> ServiceResult {
     info!("Retrieving interaction by ID: {}", id);
 
     let result = interaction::get_interaction_by_id(pool, *id).await;
@@ -76,23 +97,17 @@ pub async fn get_interaction_by_id_service(pool: &SqlitePool, id: &i64) -> JsonR
         Ok(record) => {
             info!("Interaction retrieved successfully. ID: {}", id);
 
-            let data = add_display_label(&record, record.summary.as_deref());
-
-            let json = json!({
-                "status": "success",
-                "message": format!("Interaction {} retrieved successfully.", id),
-                "data": data
-            });
+            let mut data = add_display_label(&record, record.summary.as_deref());
+            add_tags(pool, EntityType::Interaction, &mut data).await;
 
-            Ok(json.to_string())
+            Ok(ApiResponse::success(
+                format!("Interaction {} retrieved successfully.", id),
+                data,
+            ))
         }
         Err(e) => {
             error!("Error retrieving interaction {}: {}", id, e);
-            let json = json!({
-                "status": "error",
-                "message": format!("Failed to retrieve interaction {}: {}", id, e)
-            });
-            Err(json.to_string())
+            Err(AppError::from(e).context(format!("Failed to retrieve interaction {}", id)))
         }
     }
 }
@@ -100,38 +115,38 @@ pub async fn get_interaction_by_id_service(pool: &SqlitePool, id: &i64) -> JsonR
 // ======================================================
 // Get All Interactions
 // ======================================================
-pub async fn get_all_interactions_service(pool: &SqlitePool) -> JsonResult {
+pub async fn get_all_interactions_service(
+    pool: &SqlitePool,
+    options: &ListOptions,
+) -> ServiceResult {
     info!("Retrieving all interactions");
 
-    let result = interaction::get_all_interactions(pool).await;
+    let result = interaction::list_interactions(pool, options).await;
 
     match result {
-        Ok(records) => {
+        Ok(page) => {
             info!(
-                "Interactions retrieved successfully ({} total).",
-                records.len()
+                "Interactions retrieved successfully ({} of {} total).",
+                page.items.len(),
+                page.total
             );
 
-            let data: Vec<Value> = records
-                .into_iter()
-                .map(|r| add_display_label(&r, r.summary.as_deref()))
+            let mut items: Vec<Value> = page
+                .items
+                .iter()
+                .map(|r| add_display_label(r, r.summary.as_deref()))
                 .collect();
+            add_tags_to_all(pool, EntityType::Interaction, &mut items).await;
+            let data = Value::from(items);
 
-            let json = json!({
-                "status": "success",
-                "message": "All interactions retrieved successfully.",
-                "data": data
-            });
-
-            Ok(json.to_string())
+            Ok(
+                ApiResponse::success("All interactions retrieved successfully.", data)
+                    .with_pagination(page.pagination()),
+            )
         }
         Err(e) => {
             error!("Error retrieving interactions: {}", e);
-            let json = json!({
-                "status": "error",
-                "message": format!("Failed to retrieve interactions: {}", e)
-            });
-            Err(json.to_string())
+            Err(AppError::from(e).context("Failed to retrieve interactions"))
         }
     }
 }
@@ -144,15 +159,17 @@ pub async fn update_interaction_service(
     id: &i64,
     interaction_type: Option<&InteractionType>,
     interaction_date: Option<&NaiveDate>,
-    subject: Option<&str>,
-    summary: Option<&str>,
-    medium: Option<&str>,
-    application_id: Option<i64>,
-    person_id: Option<i64>,
-    company_id: Option<i64>,
-) -> JsonResult {
+    subject: Patch<&str>,
+    summary: Patch<&str>,
+    medium: Patch<&str>,
+    application_id: Patch<i64>,
+    person_id: Patch<i64>,
+    company_id: Patch<i64>,
+) -> ServiceResult {
     info!("Updating interaction ID: {}", id);
 
+    let before = snapshot(pool, EntityType::Interaction, *id).await;
+
     let result = interaction::update_interaction(
         pool,
         *id,
@@ -170,24 +187,27 @@ pub async fn update_interaction_service(
     match result {
         Ok(record) => {
             info!("Interaction updated successfully. ID: {}", id);
-
-            let data = add_display_label(&record, record.summary.as_deref());
-
-            let json = json!({
-                "status": "success",
-                "message": format!("Interaction {} updated successfully.", id),
-                "data": data
-            });
-
-            Ok(json.to_string())
+            record_change(
+                pool,
+                EntityType::Interaction,
+                *id,
+                AuditAction::Update,
+                before,
+                "interaction.Update",
+            )
+            .await;
+
+            let mut data = add_display_label(&record, record.summary.as_deref());
+            add_tags(pool, EntityType::Interaction, &mut data).await;
+
+            Ok(ApiResponse::success(
+                format!("Interaction {} updated successfully.", id),
+                data,
+            ))
         }
         Err(e) => {
             error!("Error updating interaction {}: {}", id, e);
-            let json = json!({
-                "status": "error",
-                "message": format!("Failed to update interaction {}: {}", id, e)
-            });
-            Err(json.to_string())
+            Err(AppError::from(e).context(format!("Failed to update interaction {}", id)))
         }
     }
 }
@@ -195,31 +215,35 @@ pub async fn update_interaction_service(
 // ======================================================
 // Delete Interaction
 // ======================================================
-pub async fn delete_interaction_service(pool: &SqlitePool, id: &i64) -> JsonResult {
+pub async fn delete_interaction_service(pool: &SqlitePool, id: &i64) -> ServiceResult {
     info!("Deleting interaction ID: {}", id);
 
+    let before = snapshot(pool, EntityType::Interaction, *id).await;
+
     let result = interaction::delete_interaction(pool, *id).await;
 
     match result {
         Ok(_) => {
             info!("Interaction deleted successfully. ID: {}", id);
-
-            let json = json!({
-                "status": "success",
-                "message": format!("Interaction {} deleted successfully.", id)
-            });
-
-            Ok(json.to_string())
+            record_change(
+                pool,
+                EntityType::Interaction,
+                *id,
+                AuditAction::Delete,
+                before,
+                "interaction.Delete",
+            )
+            .await;
+
+            Ok(ApiResponse::message(format!(
+                "Interaction {} deleted successfully.",
+                id
+            )))
         }
         Err(e) => {
             error!("Error deleting interaction {}: {}", id, e);
 
-            let json = json!({
-                "status": "error",
-                "message": format!("Failed to delete interaction {}: {}", id, e)
-            });
-
-            Err(json.to_string())
+            Err(AppError::from(e).context(format!("Failed to delete interaction {}", id)))
         }
     }
 }
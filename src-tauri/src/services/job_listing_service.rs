@@ -1,9 +1,15 @@
-use crate::db::models::enums::{Currency, SeniorityLevel, WorkType};
+use crate::db::models::enums::{Currency, EntityType, PayPeriod, SeniorityLevel, WorkType};
+use crate::db::models::patch::Patch;
+use crate::db::queries::audit::AuditAction;
 use crate::db::queries::job_listing;
+use crate::db::queries::list_query::ListOptions;
+use crate::error::AppError;
 use crate::logger::*;
-use crate::services::service_types::JsonResult;
-use crate::services::service_utils::add_display_label;
-use serde_json::{json, Value};
+use crate::services::audit_service::{record_change, snapshot};
+use crate::services::exchange_rate_service::add_annual_salaries;
+use crate::services::service_types::{ApiResponse, ServiceResult};
+use crate::services::service_utils::{add_display_label, add_tags, add_tags_to_all};
+use serde_json::Value;
 use sqlx::SqlitePool;
 
 // ======================================================
@@ -19,9 +25,10 @@ pub async fn create_job_listing_service(
     salary_min: Option<i64>,
     salary_max: Option<i64>,
     currency: Option<&Currency>,
+    pay_period: Option<&PayPeriod>,
     description: Option<&str>,
     url: Option<&str>,
-) -> JsonResult {
+) -> ServiceResult {
     info!("Creating job listing '{}'", title);
 
     let result = job_listing::create_job_listing(
@@ -34,6 +41,7 @@ pub async fn create_job_listing_service(
         salary_min,
         salary_max,
         currency,
+        pay_period,
         description,
         url,
     )
@@ -42,24 +50,28 @@ pub async fn create_job_listing_service(
     match result {
         Ok(record) => {
             info!("Job listing created successfully. ID: {}", record.id);
-
-            let data = add_display_label(&record, Some(record.title.as_str()));
-
-            let json = json!({
-                "status": "success",
-                "message": format!("Job listing '{}' created successfully.", record.title),
-                "data": data
-            });
-
-            Ok(json.to_string())
+            record_change(
+                pool,
+                EntityType::JobListing,
+                record.id,
+                AuditAction::Create,
+                None,
+                "job_listing.Create",
+            )
+            .await;
+
+            let mut data = add_display_label(&record, Some(record.title.as_str()));
+            add_tags(pool, EntityType::JobListing, &mut data).await;
+            add_annual_salaries(pool, std::slice::from_mut(&mut data)).await;
+
+            Ok(ApiResponse::success(
+                format!("Job listing '{}' created successfully.", record.title),
+                data,
+            ))
         }
         Err(e) => {
             error!("Error creating job listing '{}': {}", title, e);
-            let json = json!({
-                "status": "error",
-                "message": format!("Failed to create job listing '{}': {}", title, e)
-            });
-            Err(json.to_string())
+            Err(AppError::from(e).context(format!("Failed to create job listing '{}'", title)))
         }
     }
 }
@@ -67,7 +79,7 @@ pub async fn create_job_listing_service(
 // ======================================================
 // Get Job Listing by ID
 // ======================================================
-pub async fn get_job_listing_by_id_service(pool: &SqlitePool, id: &i64) -> JsonResult {
+pub async fn get_job_listing_by_id_service(pool: &SqlitePool, id: &i64) -> ServiceResult {
     info!("Retrieving job listing ID: {}", id);
 
     let result = job_listing::get_job_listing_by_id(pool, *id).await;
@@ -76,23 +88,18 @@ pub async fn get_job_listing_by_id_service(pool: &SqlitePool, id: &i64) -> JsonR
         Ok(record) => {
             info!("Job listing retrieved successfully. ID: {}", id);
 
-            let data = add_display_label(&record, Some(record.title.as_str()));
+            let mut data = add_display_label(&record, Some(record.title.as_str()));
+            add_tags(pool, EntityType::JobListing, &mut data).await;
+            add_annual_salaries(pool, std::slice::from_mut(&mut data)).await;
 
-            let json = json!({
-                "status": "success",
-                "message": format!("Job listing {} retrieved successfully.", id),
-                "data": data
-            });
-
-            Ok(json.to_string())
+            Ok(ApiResponse::success(
+                format!("Job listing {} retrieved successfully.", id),
+                data,
+            ))
         }
         Err(e) => {
             error!("Error retrieving job listing {}: {}", id, e);
-            let json = json!({
-                "status": "error",
-                "message": format!("Failed to retrieve job listing {}: {}", id, e)
-            });
-            Err(json.to_string())
+            Err(AppError::from(e).context(format!("Failed to retrieve job listing {}", id)))
         }
     }
 }
@@ -100,38 +107,39 @@ pub async fn get_job_listing_by_id_service(pool: &SqlitePool, id: &i64) -> JsonR
 // ======================================================
 // Get All Job Listings
 // ======================================================
-pub async fn get_all_job_listings_service(pool: &SqlitePool) -> JsonResult {
+pub async fn get_all_job_listings_service(
+    pool: &SqlitePool,
+    options: &ListOptions,
+) -> ServiceResult {
     info!("Retrieving all job listings");
 
-    let result = job_listing::get_all_job_listings(pool).await;
+    let result = job_listing::list_job_listings(pool, options).await;
 
     match result {
-        Ok(records) => {
+        Ok(page) => {
             info!(
-                "Job listings retrieved successfully ({} total).",
-                records.len()
+                "Job listings retrieved successfully ({} of {} total).",
+                page.items.len(),
+                page.total
             );
 
-            let data: Vec<Value> = records
-                .into_iter()
-                .map(|r| add_display_label(&r, Some(r.title.as_str())))
+            let mut items: Vec<Value> = page
+                .items
+                .iter()
+                .map(|r| add_display_label(r, Some(r.title.as_str())))
                 .collect();
-
-            let json = json!({
-                "status": "success",
-                "message": "All job listings retrieved successfully.",
-                "data": data
-            });
-
-            Ok(json.to_string())
+            add_tags_to_all(pool, EntityType::JobListing, &mut items).await;
+            add_annual_salaries(pool, &mut items).await;
+            let data = Value::from(items);
+
+            Ok(
+                ApiResponse::success("All job listings retrieved successfully.", data)
+                    .with_pagination(page.pagination()),
+            )
         }
         Err(e) => {
             error!("Error retrieving job listings: {}", e);
-            let json = json!({
-                "status": "error",
-                "message": format!("Failed to retrieve job listings: {}", e)
-            });
-            Err(json.to_string())
+            Err(AppError::from(e).context("Failed to retrieve job listings"))
         }
     }
 }
@@ -144,17 +152,20 @@ pub async fn update_job_listing_service(
     id: &i64,
     company_id: Option<i64>,
     title: Option<&str>,
-    work_type: Option<&WorkType>,
-    category: Option<&str>,
-    seniority_level: Option<&SeniorityLevel>,
-    salary_min: Option<i64>,
-    salary_max: Option<i64>,
-    currency: Option<&Currency>,
-    description: Option<&str>,
-    url: Option<&str>,
-) -> JsonResult {
+    work_type: Patch<&WorkType>,
+    category: Patch<&str>,
+    seniority_level: Patch<&SeniorityLevel>,
+    salary_min: Patch<i64>,
+    salary_max: Patch<i64>,
+    currency: Patch<&Currency>,
+    pay_period: Patch<&PayPeriod>,
+    description: Patch<&str>,
+    url: Patch<&str>,
+) -> ServiceResult {
     info!("Updating job listing ID: {}", id);
 
+    let before = snapshot(pool, EntityType::JobListing, *id).await;
+
     let result = job_listing::update_job_listing(
         pool,
         *id,
@@ -166,6 +177,7 @@ pub async fn update_job_listing_service(
         salary_min,
         salary_max,
         currency,
+        pay_period,
         description,
         url,
     )
@@ -174,24 +186,28 @@ pub async fn update_job_listing_service(
     match result {
         Ok(record) => {
             info!("Job listing updated successfully. ID: {}", id);
-
-            let data = add_display_label(&record, Some(record.title.as_str()));
-
-            let json = json!({
-                "status": "success",
-                "message": format!("Job listing {} updated successfully.", id),
-                "data": data
-            });
-
-            Ok(json.to_string())
+            record_change(
+                pool,
+                EntityType::JobListing,
+                *id,
+                AuditAction::Update,
+                before,
+                "job_listing.Update",
+            )
+            .await;
+
+            let mut data = add_display_label(&record, Some(record.title.as_str()));
+            add_tags(pool, EntityType::JobListing, &mut data).await;
+            add_annual_salaries(pool, std::slice::from_mut(&mut data)).await;
+
+            Ok(ApiResponse::success(
+                format!("Job listing {} updated successfully.", id),
+                data,
+            ))
         }
         Err(e) => {
             error!("Error updating job listing {}: {}", id, e);
-            let json = json!({
-                "status": "error",
-                "message": format!("Failed to update job listing {}: {}", id, e)
-            });
-            Err(json.to_string())
+            Err(AppError::from(e).context(format!("Failed to update job listing {}", id)))
         }
     }
 }
@@ -199,27 +215,33 @@ pub async fn update_job_listing_service(
 // ======================================================
 // Delete Job Listing
 // ======================================================
-pub async fn delete_job_listing_service(pool: &SqlitePool, id: &i64) -> JsonResult {
+pub async fn delete_job_listing_service(pool: &SqlitePool, id: &i64) -> ServiceResult {
     info!("Deleting job listing ID: {}", id);
 
+    let before = snapshot(pool, EntityType::JobListing, *id).await;
+
     let result = job_listing::delete_job_listing(pool, *id).await;
 
     match result {
         Ok(_) => {
             info!("Job listing deleted successfully. ID: {}", id);
-            let json = json!({
-                "status": "success",
-                "message": format!("Job listing {} deleted successfully.", id)
-            });
-            Ok(json.to_string())
+            record_change(
+                pool,
+                EntityType::JobListing,
+                *id,
+                AuditAction::Delete,
+                before,
+                "job_listing.Delete",
+            )
+            .await;
+            Ok(ApiResponse::message(format!(
+                "Job listing {} deleted successfully.",
+                id
+            )))
         }
         Err(e) => {
             error!("Error deleting job listing {}: {}", id, e);
-            let json = json!({
-                "status": "error",
-                "message": format!("Failed to delete job listing {}: {}", id, e)
-            });
-            Err(json.to_string())
+            Err(AppError::from(e).context(format!("Failed to delete job listing {}", id)))
         }
     }
 }
@@ -1,9 +1,28 @@
+pub mod analytics_service;
+pub mod application_contact_service;
 pub mod application_service;
+pub mod attachment_service;
+pub mod audit_service;
+pub mod backup_service;
+pub mod calendar_service;
 pub mod company_service;
+pub mod dedupe_service;
+pub mod detail_service;
+pub mod exchange_rate_service;
+pub mod export_service;
+pub mod import_service;
 pub mod interaction_service;
 pub mod job_listing_service;
 pub mod note_service;
 pub mod person_service;
+pub mod profile_service;
+pub mod reminder_scheduler;
 pub mod reminder_service;
+pub mod rule_engine;
+pub mod rule_service;
+pub mod search_service;
 pub mod service_types;
 pub mod service_utils;
+pub mod tag_service;
+pub mod trash_service;
+pub mod undo_service;
@@ -1,9 +1,14 @@
-use crate::db::models::enums::NoteType;
+use crate::db::models::enums::{EntityType, NoteType};
+use crate::db::models::patch::Patch;
+use crate::db::queries::audit::AuditAction;
+use crate::db::queries::list_query::ListOptions;
 use crate::db::queries::note;
+use crate::error::AppError;
 use crate::logger::*;
-use crate::services::service_types::JsonResult;
-use crate::services::service_utils::add_display_label;
-use serde_json::{json, Value};
+use crate::services::audit_service::{record_change, snapshot};
+use crate::services::service_types::{ApiResponse, ServiceResult};
+use crate::services::service_utils::{add_display_label, add_tags, add_tags_to_all};
+use serde_json::Value;
 use sqlx::SqlitePool;
 
 // ======================================================
@@ -19,7 +24,7 @@ pub async fn create_note_service(
     note_type: Option<&NoteType>,
     title: Option<&str>,
     content: Option<&str>,
-) -> JsonResult {
+) -> ServiceResult {
     info!(
         "Creating note (interaction_id={:?}, job_listing_id={:?}, application_id={:?})",
         interaction_id, job_listing_id, application_id
@@ -41,22 +46,23 @@ pub async fn create_note_service(
     match result {
         Ok(record) => {
             info!("Note created successfully. ID: {}", record.id);
-            let data = add_display_label(&record, record.title.as_deref());
-
-            let json = json!({
-                "status": "success",
-                "message": "Note created successfully.",
-                "data": data
-            });
-            Ok(json.to_string())
+            record_change(
+                pool,
+                EntityType::Note,
+                record.id,
+                AuditAction::Create,
+                None,
+                "note.Create",
+            )
+            .await;
+            let mut data = add_display_label(&record, record.title.as_deref());
+            add_tags(pool, EntityType::Note, &mut data).await;
+
+            Ok(ApiResponse::success("Note created successfully.", data))
         }
         Err(e) => {
             error!("Error creating note: {}", e);
-            let json = json!({
-                "status": "error",
-                "message": format!("Failed to create note: {}", e)
-            });
-            Err(json.to_string())
+            Err(AppError::from(e).context("Failed to create note"))
         }
     }
 }
@@ -64,28 +70,23 @@ pub async fn create_note_service(
 // ======================================================
 // Get Note by ID
 // ======================================================
-pub async fn get_note_by_id_service(pool: &SqlitePool, id: &i64) -> JsonResult {
+pub async fn get_note_by_id_service(pool: &SqlitePool, id: &i64) -> ServiceResult {
     info!("Retrieving note ID: {}", id);
 
     let result = note::get_note_by_id(pool, *id).await;
 
     match result {
         Ok(record) => {
-            let data = add_display_label(&record, record.title.as_deref());
-            let json = json!({
-                "status": "success",
-                "message": format!("Note {} retrieved successfully.", id),
-                "data": data
-            });
-            Ok(json.to_string())
+            let mut data = add_display_label(&record, record.title.as_deref());
+            add_tags(pool, EntityType::Note, &mut data).await;
+            Ok(ApiResponse::success(
+                format!("Note {} retrieved successfully.", id),
+                data,
+            ))
         }
         Err(e) => {
             error!("Error retrieving note {}: {}", id, e);
-            let json = json!({
-                "status": "error",
-                "message": format!("Failed to retrieve note {}: {}", id, e)
-            });
-            Err(json.to_string())
+            Err(AppError::from(e).context(format!("Failed to retrieve note {}", id)))
         }
     }
 }
@@ -93,34 +94,35 @@ pub async fn get_note_by_id_service(pool: &SqlitePool, id: &i64) -> JsonResult {
 // ======================================================
 // Get All Notes
 // ======================================================
-pub async fn get_all_notes_service(pool: &SqlitePool) -> JsonResult {
+pub async fn get_all_notes_service(pool: &SqlitePool, options: &ListOptions) -> ServiceResult {
     info!("Retrieving all notes");
 
-    let result = note::get_all_notes(pool).await;
+    let result = note::list_notes(pool, options).await;
 
     match result {
-        Ok(records) => {
-            info!("Notes retrieved successfully ({} total).", records.len());
-
-            let data: Vec<Value> = records
-                .into_iter()
-                .map(|r| add_display_label(&r, r.title.as_deref()))
+        Ok(page) => {
+            info!(
+                "Notes retrieved successfully ({} of {} total).",
+                page.items.len(),
+                page.total
+            );
+
+            let mut items: Vec<Value> = page
+                .items
+                .iter()
+                .map(|r| add_display_label(r, r.title.as_deref()))
                 .collect();
+            add_tags_to_all(pool, EntityType::Note, &mut items).await;
+            let data = Value::from(items);
 
-            let json = json!({
-                "status": "success",
-                "message": "All notes retrieved successfully.",
-                "data": data
-            });
-            Ok(json.to_string())
+            Ok(
+                ApiResponse::success("All notes retrieved successfully.", data)
+                    .with_pagination(page.pagination()),
+            )
         }
         Err(e) => {
             error!("Error retrieving notes: {}", e);
-            let json = json!({
-                "status": "error",
-                "message": format!("Failed to retrieve notes: {}", e)
-            });
-            Err(json.to_string())
+            Err(AppError::from(e).context("Failed to retrieve notes"))
         }
     }
 }
@@ -131,17 +133,19 @@ pub async fn get_all_notes_service(pool: &SqlitePool) -> JsonResult {
 pub async fn update_note_service(
     pool: &SqlitePool,
     id: &i64,
-    interaction_id: Option<i64>,
-    job_listing_id: Option<i64>,
-    application_id: Option<i64>,
-    person_id: Option<i64>,
-    company_id: Option<i64>,
-    note_type: Option<&NoteType>,
-    title: Option<&str>,
-    content: Option<&str>,
-) -> JsonResult {
+    interaction_id: Patch<i64>,
+    job_listing_id: Patch<i64>,
+    application_id: Patch<i64>,
+    person_id: Patch<i64>,
+    company_id: Patch<i64>,
+    note_type: Patch<&NoteType>,
+    title: Patch<&str>,
+    content: Patch<&str>,
+) -> ServiceResult {
     info!("Updating note ID: {}", id);
 
+    let before = snapshot(pool, EntityType::Note, *id).await;
+
     let result = note::update_note(
         pool,
         *id,
@@ -159,21 +163,25 @@ pub async fn update_note_service(
     match result {
         Ok(record) => {
             info!("Note updated successfully. ID: {}", id);
-            let data = add_display_label(&record, record.title.as_deref());
-            let json = json!({
-                "status": "success",
-                "message": format!("Note {} updated successfully.", id),
-                "data": data
-            });
-            Ok(json.to_string())
+            record_change(
+                pool,
+                EntityType::Note,
+                *id,
+                AuditAction::Update,
+                before,
+                "note.Update",
+            )
+            .await;
+            let mut data = add_display_label(&record, record.title.as_deref());
+            add_tags(pool, EntityType::Note, &mut data).await;
+            Ok(ApiResponse::success(
+                format!("Note {} updated successfully.", id),
+                data,
+            ))
         }
         Err(e) => {
             error!("Error updating note {}: {}", id, e);
-            let json = json!({
-                "status": "error",
-                "message": format!("Failed to update note {}: {}", id, e)
-            });
-            Err(json.to_string())
+            Err(AppError::from(e).context(format!("Failed to update note {}", id)))
         }
     }
 }
@@ -181,27 +189,33 @@ pub async fn update_note_service(
 // ======================================================
 // Delete Note
 // ======================================================
-pub async fn delete_note_service(pool: &SqlitePool, id: &i64) -> JsonResult {
+pub async fn delete_note_service(pool: &SqlitePool, id: &i64) -> ServiceResult {
     info!("Deleting note ID: {}", id);
 
+    let before = snapshot(pool, EntityType::Note, *id).await;
+
     let result = note::delete_note(pool, *id).await;
 
     match result {
         Ok(_) => {
             info!("Note deleted successfully. ID: {}", id);
-            let json = json!({
-                "status": "success",
-                "message": format!("Note {} deleted successfully.", id)
-            });
-            Ok(json.to_string())
+            record_change(
+                pool,
+                EntityType::Note,
+                *id,
+                AuditAction::Delete,
+                before,
+                "note.Delete",
+            )
+            .await;
+            Ok(ApiResponse::message(format!(
+                "Note {} deleted successfully.",
+                id
+            )))
         }
         Err(e) => {
             error!("Error deleting note {}: {}", id, e);
-            let json = json!({
-                "status": "error",
-                "message": format!("Failed to delete note {}: {}", id, e)
-            });
-            Err(json.to_string())
+            Err(AppError::from(e).context(format!("Failed to delete note {}", id)))
         }
     }
 }
@@ -1,15 +1,20 @@
-use crate::db::models::enums::Role;
+use crate::db::models::enums::{EntityType, Role};
+use crate::db::models::patch::Patch;
+use crate::db::queries::audit::AuditAction;
+use crate::db::queries::list_query::ListOptions;
 use crate::db::queries::person;
+use crate::error::AppError;
 use crate::logger::*;
-use crate::services::service_types::JsonResult;
-use crate::services::service_utils::add_display_label;
-use serde_json::{json, Value};
+use crate::services::audit_service::{record_change, snapshot};
+use crate::services::service_types::{ApiResponse, ServiceResult};
+use crate::services::service_utils::{add_display_label, add_tags, add_tags_to_all};
+use serde_json::Value;
 use sqlx::SqlitePool;
 
 // ======================================================
 // Helper: Format display label for person
 // ======================================================
-fn format_person_label(last_name: &str, first_name: &str, id: i64) -> String {
+pub fn format_person_label(last_name: &str, first_name: &str, id: i64) -> String {
     let last_trimmed = last_name.trim();
     let first_trimmed = first_name.trim();
 
@@ -36,7 +41,7 @@ pub async fn create_person_service(
     role: Option<&Role>,
     linkedin_url: Option<&str>,
     company_id: Option<i64>,
-) -> JsonResult {
+) -> ServiceResult {
     info!("Creating person: {} {}", first_name, last_name);
 
     let result = person::create_person(
@@ -54,29 +59,38 @@ pub async fn create_person_service(
     match result {
         Ok(record) => {
             info!("Person created successfully. ID: {}", record.id);
+            record_change(
+                pool,
+                EntityType::Person,
+                record.id,
+                AuditAction::Create,
+                None,
+                "person.Create",
+            )
+            .await;
 
             let display_label =
                 format_person_label(&record.last_name, &record.first_name, record.id);
-            let data = add_display_label(&record, Some(display_label));
-
-            let json = json!({
-                "status": "success",
-                "message": format!("Person '{} {}' created successfully.", first_name, last_name),
-                "data": data
-            });
-
-            Ok(json.to_string())
+            let mut data = add_display_label(&record, Some(display_label));
+            add_tags(pool, EntityType::Person, &mut data).await;
+
+            Ok(ApiResponse::success(
+                format!(
+                    "Person '{} {}' created successfully.",
+                    first_name, last_name
+                ),
+                data,
+            ))
         }
         Err(e) => {
             error!(
                 "Error creating person '{} {}': {}",
                 first_name, last_name, e
             );
-            let json = json!({
-                "status": "error",
-                "message": format!("Failed to create person '{} {}': {}", first_name, last_name, e)
-            });
-            Err(json.to_string())
+            Err(AppError::from(e).context(format!(
+                "Failed to create person '{} {}'",
+                first_name, last_name
+            )))
         }
     }
 }
@@ -84,7 +98,7 @@ pub async fn create_person_service(
 // ======================================================
 // Get Person by ID
 // ======================================================
-pub async fn get_person_by_id_service(pool: &SqlitePool, id: &i64) -> JsonResult {
+pub async fn get_person_by_id_service(pool: &SqlitePool, id: &i64) -> ServiceResult {
     info!("Retrieving person ID: {}", id);
 
     let result = person::get_person_by_id(pool, *id).await;
@@ -95,23 +109,17 @@ pub async fn get_person_by_id_service(pool: &SqlitePool, id: &i64) -> JsonResult
 
             let display_label =
                 format_person_label(&record.last_name, &record.first_name, record.id);
-            let data = add_display_label(&record, Some(display_label));
+            let mut data = add_display_label(&record, Some(display_label));
+            add_tags(pool, EntityType::Person, &mut data).await;
 
-            let json = json!({
-                "status": "success",
-                "message": format!("Person {} retrieved successfully.", id),
-                "data": data
-            });
-
-            Ok(json.to_string())
+            Ok(ApiResponse::success(
+                format!("Person {} retrieved successfully.", id),
+                data,
+            ))
         }
         Err(e) => {
             error!("Error retrieving person {}: {}", id, e);
-            let json = json!({
-                "status": "error",
-                "message": format!("Failed to retrieve person {}: {}", id, e)
-            });
-            Err(json.to_string())
+            Err(AppError::from(e).context(format!("Failed to retrieve person {}", id)))
         }
     }
 }
@@ -119,38 +127,38 @@ pub async fn get_person_by_id_service(pool: &SqlitePool, id: &i64) -> JsonResult
 // ======================================================
 // Get All Persons
 // ======================================================
-pub async fn get_all_persons_service(pool: &SqlitePool) -> JsonResult {
+pub async fn get_all_persons_service(pool: &SqlitePool, options: &ListOptions) -> ServiceResult {
     info!("Retrieving all persons");
 
-    let result = person::get_all_persons(pool).await;
+    let result = person::list_persons(pool, options).await;
 
     match result {
-        Ok(records) => {
-            info!("Persons retrieved successfully ({} total).", records.len());
+        Ok(page) => {
+            info!(
+                "Persons retrieved successfully ({} of {} total).",
+                page.items.len(),
+                page.total
+            );
 
-            let data: Vec<Value> = records
-                .into_iter()
+            let mut items: Vec<Value> = page
+                .items
+                .iter()
                 .map(|r| {
                     let display_label = format_person_label(&r.last_name, &r.first_name, r.id);
-                    add_display_label(&r, Some(display_label))
+                    add_display_label(r, Some(display_label))
                 })
                 .collect();
+            add_tags_to_all(pool, EntityType::Person, &mut items).await;
+            let data = Value::from(items);
 
-            let json = json!({
-                "status": "success",
-                "message": "All persons retrieved successfully.",
-                "data": data
-            });
-
-            Ok(json.to_string())
+            Ok(
+                ApiResponse::success("All persons retrieved successfully.", data)
+                    .with_pagination(page.pagination()),
+            )
         }
         Err(e) => {
             error!("Error retrieving persons: {}", e);
-            let json = json!({
-                "status": "error",
-                "message": format!("Failed to retrieve persons: {}", e)
-            });
-            Err(json.to_string())
+            Err(AppError::from(e).context("Failed to retrieve persons"))
         }
     }
 }
@@ -163,14 +171,16 @@ pub async fn update_person_service(
     id: &i64,
     first_name: Option<&str>,
     last_name: Option<&str>,
-    email: Option<&str>,
-    phone_number: Option<&str>,
-    role: Option<&Role>,
-    linkedin_url: Option<&str>,
-    company_id: Option<i64>,
-) -> JsonResult {
+    email: Patch<&str>,
+    phone_number: Patch<&str>,
+    role: Patch<&Role>,
+    linkedin_url: Patch<&str>,
+    company_id: Patch<i64>,
+) -> ServiceResult {
     info!("Updating person ID: {}", id);
 
+    let before = snapshot(pool, EntityType::Person, *id).await;
+
     let result = person::update_person(
         pool,
         *id,
@@ -187,26 +197,29 @@ pub async fn update_person_service(
     match result {
         Ok(record) => {
             info!("Person updated successfully. ID: {}", id);
+            record_change(
+                pool,
+                EntityType::Person,
+                *id,
+                AuditAction::Update,
+                before,
+                "person.Update",
+            )
+            .await;
 
             let display_label =
                 format_person_label(&record.last_name, &record.first_name, record.id);
-            let data = add_display_label(&record, Some(display_label));
-
-            let json = json!({
-                "status": "success",
-                "message": format!("Person {} updated successfully.", id),
-                "data": data
-            });
+            let mut data = add_display_label(&record, Some(display_label));
+            add_tags(pool, EntityType::Person, &mut data).await;
 
-            Ok(json.to_string())
+            Ok(ApiResponse::success(
+                format!("Person {} updated successfully.", id),
+                data,
+            ))
         }
         Err(e) => {
             error!("Error updating person {}: {}", id, e);
-            let json = json!({
-                "status": "error",
-                "message": format!("Failed to update person {}: {}", id, e)
-            });
-            Err(json.to_string())
+            Err(AppError::from(e).context(format!("Failed to update person {}", id)))
         }
     }
 }
@@ -214,31 +227,35 @@ pub async fn update_person_service(
 // ======================================================
 // Delete Person
 // ======================================================
-pub async fn delete_person_service(pool: &SqlitePool, id: &i64) -> JsonResult {
+pub async fn delete_person_service(pool: &SqlitePool, id: &i64) -> ServiceResult {
     info!("Deleting person ID: {}", id);
 
+    let before = snapshot(pool, EntityType::Person, *id).await;
+
     let result = person::delete_person(pool, *id).await;
 
     match result {
         Ok(_) => {
             info!("Person deleted successfully. ID: {}", id);
-
-            let json = json!({
-                "status": "success",
-                "message": format!("Person {} deleted successfully.", id)
-            });
-
-            Ok(json.to_string())
+            record_change(
+                pool,
+                EntityType::Person,
+                *id,
+                AuditAction::Delete,
+                before,
+                "person.Delete",
+            )
+            .await;
+
+            Ok(ApiResponse::message(format!(
+                "Person {} deleted successfully.",
+                id
+            )))
         }
         Err(e) => {
             error!("Error deleting person {}: {}", id, e);
 
-            let json = json!({
-                "status": "error",
-                "message": format!("Failed to delete person {}: {}", id, e)
-            });
-
-            Err(json.to_string())
+            Err(AppError::from(e).context(format!("Failed to delete person {}", id)))
         }
     }
 }
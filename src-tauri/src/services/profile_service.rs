@@ -0,0 +1,183 @@
+use crate::db::connection::{init_db, DbState};
+use crate::logger::{self, *};
+use crate::services::service_types::JsonResult;
+use crate::settings::{validate_profile_name, AppSettings, DEFAULT_PROFILE};
+use serde_json::json;
+use std::{fs, sync::Mutex};
+
+// ======================================================
+// Helper: Build error response
+// ======================================================
+fn error_json(message: String) -> String {
+    json!({
+        "status": "error",
+        "message": message
+    })
+    .to_string()
+}
+
+// ======================================================
+// List Profiles
+// ======================================================
+pub fn list_profiles_service(settings: &Mutex<AppSettings>) -> JsonResult {
+    info!("Listing profiles");
+
+    let current = settings.lock().unwrap().clone();
+    let profiles = current.list_profiles();
+
+    info!("Profiles listed successfully ({} total).", profiles.len());
+
+    let json = json!({
+        "status": "success",
+        "message": "All profiles retrieved successfully.",
+        "data": {
+            "dataDir": current.data_dir,
+            "activeProfile": current.active_profile,
+            "profiles": profiles
+        }
+    });
+
+    Ok(json.to_string())
+}
+
+// ======================================================
+// Create Profile
+// ======================================================
+pub async fn create_profile_service(settings: &Mutex<AppSettings>, name: &str) -> JsonResult {
+    info!("Creating profile '{}'", name);
+
+    validate_profile_name(name).map_err(error_json)?;
+
+    let current = settings.lock().unwrap().clone();
+    if current.profile_exists(name) {
+        warn!("Profile '{}' already exists", name);
+        return Err(error_json(format!("Profile '{}' already exists.", name)));
+    }
+
+    // Opening the database creates the directory and applies all migrations
+    match init_db(&current.db_path(name)).await {
+        Ok(pool) => {
+            pool.close().await;
+            info!("Profile '{}' created successfully.", name);
+
+            let json = json!({
+                "status": "success",
+                "message": format!("Profile '{}' created successfully.", name),
+                "data": {
+                    "name": name,
+                    "active": false,
+                    "dbPath": current.db_path(name)
+                }
+            });
+
+            Ok(json.to_string())
+        }
+        Err(e) => {
+            error!("Error creating profile '{}': {}", name, e);
+            Err(error_json(format!(
+                "Failed to create profile '{}': {}",
+                name, e
+            )))
+        }
+    }
+}
+
+// ======================================================
+// Switch Profile
+// ======================================================
+pub async fn switch_profile_service(
+    db: &DbState,
+    settings: &Mutex<AppSettings>,
+    name: &str,
+) -> JsonResult {
+    info!("Switching to profile '{}'", name);
+
+    let mut next = settings.lock().unwrap().clone();
+    if !next.profile_exists(name) {
+        warn!("Profile '{}' does not exist", name);
+        return Err(error_json(format!("Profile '{}' does not exist.", name)));
+    }
+
+    if next.active_profile != name {
+        let pool = match init_db(&next.db_path(name)).await {
+            Ok(pool) => pool,
+            Err(e) => {
+                error!("Error opening profile '{}': {}", name, e);
+                return Err(error_json(format!(
+                    "Failed to open profile '{}': {}",
+                    name, e
+                )));
+            }
+        };
+
+        // Swap first so new commands hit the new database, then drain the old pool
+        if let Some(old) = db.replace(pool) {
+            old.close().await;
+        }
+
+        next.active_profile = name.to_string();
+        if let Err(e) = logger::redirect(&next.active_log_dir()) {
+            warn!("Failed to redirect logs for profile '{}': {}", name, e);
+        }
+        if let Err(e) = next.persist_active_profile() {
+            warn!("Failed to persist active profile '{}': {}", name, e);
+        }
+        *settings.lock().unwrap() = next.clone();
+    }
+
+    info!("Profile '{}' is now active.", name);
+
+    let json = json!({
+        "status": "success",
+        "message": format!("Switched to profile '{}'.", name),
+        "data": {
+            "name": name,
+            "active": true,
+            "dbPath": next.active_db_path()
+        }
+    });
+
+    Ok(json.to_string())
+}
+
+// ======================================================
+// Delete Profile
+// ======================================================
+pub fn delete_profile_service(settings: &Mutex<AppSettings>, name: &str) -> JsonResult {
+    info!("Deleting profile '{}'", name);
+
+    let current = settings.lock().unwrap().clone();
+    if name == DEFAULT_PROFILE {
+        return Err(error_json(
+            "The default profile cannot be deleted.".to_string(),
+        ));
+    }
+    if name == current.active_profile {
+        return Err(error_json(format!(
+            "Profile '{}' is active. Switch to another profile before deleting it.",
+            name
+        )));
+    }
+    validate_profile_name(name).map_err(error_json)?;
+    if !current.profile_exists(name) {
+        return Err(error_json(format!("Profile '{}' does not exist.", name)));
+    }
+
+    match fs::remove_dir_all(current.profile_dir(name)) {
+        Ok(_) => {
+            info!("Profile '{}' deleted successfully.", name);
+            let json = json!({
+                "status": "success",
+                "message": format!("Profile '{}' deleted successfully.", name)
+            });
+            Ok(json.to_string())
+        }
+        Err(e) => {
+            error!("Error deleting profile '{}': {}", name, e);
+            Err(error_json(format!(
+                "Failed to delete profile '{}': {}",
+                name, e
+            )))
+        }
+    }
+}
@@ -0,0 +1,198 @@
+use crate::db::connection::DbState;
+use crate::db::queries::reminder::{self, Reminder};
+use crate::logger::*;
+use crate::services::calendar_service::refresh_calendar_feed;
+use crate::services::reminder_service::{format_reminder_label, reminder_due_at};
+use crate::services::rule_engine::run_inactivity_rules;
+use crate::settings::AppSettings;
+use chrono::{Duration as DateDuration, Local, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use serde::Serialize;
+use sqlx::SqlitePool;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_notification::NotificationExt;
+
+/// Emitted once per reminder when it becomes due; the payload is a
+/// [`DueReminder`]. The `DueReminders` panel in the app layout lists these
+/// with Snooze and Done buttons.
+pub const REMINDER_DUE_EVENT: &str = "reminder-due";
+
+/// Notification action type carrying Snooze / Done buttons, registered by the
+/// frontend. Only Android and iOS support notification actions; desktop
+/// notifications show title and body only, so there the in-app panel is the
+/// way to snooze or complete a reminder.
+pub const REMINDER_ACTION_TYPE: &str = "reminder-due";
+
+/// How often due reminders are looked up.
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Inactivity rules are checked on every this-many-th check (hourly).
+const INACTIVITY_CHECK_EVERY: u32 = 60;
+
+/// Above this many due reminders in one check, a single summary
+/// notification is shown instead of one each.
+const MAX_SINGLE_NOTIFICATIONS: usize = 3;
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DueReminder {
+    pub id: i64,
+    pub title: String,
+    pub message: Option<String>,
+    pub reminder_date: NaiveDate,
+    pub reminder_time: Option<NaiveTime>,
+    pub timezone: Option<String>,
+    pub display_label: String,
+    /// Due before today
+    pub overdue: bool,
+}
+
+impl DueReminder {
+    fn from_record(record: &Reminder, today: &NaiveDate) -> Self {
+        DueReminder {
+            id: record.id,
+            title: record.title.clone(),
+            message: record.message.clone(),
+            reminder_date: record.reminder_date,
+            reminder_time: record.reminder_time,
+            timezone: record.timezone.clone(),
+            display_label: format_reminder_label(&record.title, record.id),
+            overdue: record.reminder_date < *today,
+        }
+    }
+}
+
+// ======================================================
+// Claim due reminders
+// ======================================================
+/// Claims reminders that are due and were not announced yet: all-day ones
+/// dated by `today` (local date), timed ones whose time has passed `now`
+/// (UTC) in their time zone.
+pub async fn collect_due_reminders(
+    pool: &SqlitePool,
+    today: &NaiveDate,
+    now: &NaiveDateTime,
+) -> Result<Vec<DueReminder>, sqlx::Error> {
+    // Time zones up to a day ahead of ours can make tomorrow's dates due
+    let through = *today + DateDuration::days(2);
+    let candidates = reminder::get_notification_candidates(pool, &through, now).await?;
+
+    let due: Vec<i64> = candidates
+        .iter()
+        .filter(|r| match reminder_due_at(r, r.reminder_date) {
+            Some(at) => at <= *now,
+            None => r.reminder_date <= *today,
+        })
+        .map(|r| r.id)
+        .collect();
+
+    let records = reminder::claim_reminders(pool, &due, now).await?;
+    Ok(records
+        .iter()
+        .map(|r| DueReminder::from_record(r, today))
+        .collect())
+}
+
+/// `(title, body)` of the native notifications for one check.
+pub fn notification_texts(due: &[DueReminder]) -> Vec<(String, String)> {
+    if due.len() > MAX_SINGLE_NOTIFICATIONS {
+        let overdue = due.iter().filter(|r| r.overdue).count();
+        let body = match overdue {
+            0 => "Open JobTrackr to see them.".to_string(),
+            n => format!("{} of them overdue. Open JobTrackr to see them.", n),
+        };
+        return vec![(format!("{} reminders are due", due.len()), body)];
+    }
+
+    due.iter()
+        .map(|r| {
+            let title = if r.overdue {
+                format!("Overdue: {}", r.display_label)
+            } else {
+                r.display_label.clone()
+            };
+            let body = r
+                .message
+                .clone()
+                .filter(|m| !m.trim().is_empty())
+                .unwrap_or_else(|| match r.reminder_time {
+                    Some(time) => format!(
+                        "Due {} {}",
+                        r.reminder_date.format("%Y-%m-%d"),
+                        time.format("%H:%M")
+                    ),
+                    None => format!("Due {}", r.reminder_date.format("%Y-%m-%d")),
+                });
+            (title, body)
+        })
+        .collect()
+}
+
+// ======================================================
+// Background task
+// ======================================================
+/// Checks for due reminders every minute for the lifetime of the app,
+/// and runs `no_interaction` automation rules hourly, starting right away.
+/// The pool is looked up on every check, so profile switches are picked up.
+pub fn spawn_reminder_scheduler(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        info!("Reminder scheduler started.");
+        let mut interval = tokio::time::interval(CHECK_INTERVAL);
+        let mut checks: u32 = 0;
+
+        loop {
+            interval.tick().await;
+
+            let Some(pool) = app.state::<DbState>().pool() else {
+                continue;
+            };
+            let today = Local::now().date_naive();
+            let now = Utc::now().naive_utc();
+
+            if checks.is_multiple_of(INACTIVITY_CHECK_EVERY) {
+                // Reminders these create are announced by the check below
+                match run_inactivity_rules(&pool, today).await {
+                    Ok(outcomes) if outcomes.is_empty() => {}
+                    Ok(_) => {
+                        let settings = app.state::<Mutex<AppSettings>>().lock().unwrap().clone();
+                        refresh_calendar_feed(&pool, &settings).await;
+                    }
+                    Err(e) => warn!("Could not run inactivity rules: {}", e),
+                }
+            }
+            checks = checks.wrapping_add(1);
+
+            match collect_due_reminders(&pool, &today, &now).await {
+                Ok(due) if due.is_empty() => {}
+                Ok(due) => announce(&app, &due),
+                Err(e) => warn!("Could not check for due reminders: {}", e),
+            }
+        }
+    });
+}
+
+fn announce(app: &AppHandle, due: &[DueReminder]) {
+    info!("{} reminder(s) due.", due.len());
+
+    for reminder in due {
+        if let Err(e) = app.emit(REMINDER_DUE_EVENT, reminder) {
+            warn!("Could not emit due reminder {}: {}", reminder.id, e);
+        }
+    }
+
+    let single = due.len() <= MAX_SINGLE_NOTIFICATIONS;
+
+    for ((title, body), reminder) in notification_texts(due).into_iter().zip(due) {
+        let mut builder = app.notification().builder().title(title).body(body);
+        // A summary covers several reminders, so only single ones get actions
+        if single {
+            builder = builder
+                .action_type_id(REMINDER_ACTION_TYPE)
+                .extra("reminderId", reminder.id);
+        }
+        if let Err(e) = builder.show() {
+            warn!("Could not show reminder notification: {}", e);
+        }
+    }
+}
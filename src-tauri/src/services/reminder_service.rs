@@ -1,15 +1,22 @@
-use crate::db::queries::reminder;
+use crate::db::models::enums::EntityType;
+use crate::db::models::patch::Patch;
+use crate::db::queries::audit::AuditAction;
+use crate::db::queries::list_query::ListOptions;
+use crate::db::queries::reminder::{self, Reminder};
+use crate::error::AppError;
 use crate::logger::*;
-use crate::services::service_types::JsonResult;
-use crate::services::service_utils::add_display_label;
-use chrono::NaiveDate;
+use crate::services::audit_service::{record_change, snapshot};
+use crate::services::service_types::{ApiResponse, ServiceResult};
+use crate::services::service_utils::{add_display_label, add_tags, add_tags_to_all};
+use crate::utils::recurrence::{parse_timezone, wall_clock_to_utc, Recurrence};
+use chrono::{Duration, Local, NaiveDate, NaiveDateTime, NaiveTime, Utc};
 use serde_json::{json, Value};
 use sqlx::SqlitePool;
 
 // ======================================================
 // Helper: Format display label for reminder
 // ======================================================
-fn format_reminder_label(title: &str, id: i64) -> String {
+pub fn format_reminder_label(title: &str, id: i64) -> String {
     let t = title.trim();
     if !t.is_empty() {
         t.to_string()
@@ -18,6 +25,83 @@ fn format_reminder_label(title: &str, id: i64) -> String {
     }
 }
 
+async fn reminder_payload(pool: &SqlitePool, record: &Reminder) -> Value {
+    let display_label = Some(format_reminder_label(&record.title, record.id));
+    let mut data = add_display_label(record, display_label);
+    add_tags(pool, EntityType::Reminder, &mut data).await;
+    data
+}
+
+// ======================================================
+// Helper: Schedule
+// ======================================================
+/// Checks an IANA time zone name and returns its canonical spelling.
+pub fn normalize_timezone(name: &str) -> Result<String, AppError> {
+    parse_timezone(name)
+        .map(|tz| tz.name().to_string())
+        .map_err(|e| AppError::invalid_field("timezone", e))
+}
+
+/// Checks an RRULE-style recurrence and returns its canonical form.
+pub fn normalize_recurrence(rule: &str) -> Result<String, AppError> {
+    rule.parse::<Recurrence>()
+        .map(|r| r.to_string())
+        .map_err(|e| AppError::invalid_field("recurrence", e))
+}
+
+/// When a timed reminder is due on `date`, in UTC; `None` for all-day
+/// reminders. Without a (valid) time zone the device's local time is used.
+pub fn reminder_due_at(record: &Reminder, date: NaiveDate) -> Option<NaiveDateTime> {
+    let time = record.reminder_time?;
+    Some(match record.timezone.as_deref().map(parse_timezone) {
+        Some(Ok(tz)) => wall_clock_to_utc(&tz, date, time),
+        _ => wall_clock_to_utc(&Local, date, time),
+    })
+}
+
+/// Once a recurring reminder is completed, creates its next occurrence
+/// and returns its payload. The rule moves to the new occurrence.
+async fn continue_series(
+    pool: &SqlitePool,
+    record: &mut Reminder,
+    origin: &str,
+) -> Result<Option<Value>, AppError> {
+    if !record.is_completed {
+        return Ok(None);
+    }
+    let Some((next_date, next_rule)) = record
+        .recurrence
+        .as_deref()
+        .and_then(|rule| rule.parse::<Recurrence>().ok())
+        .and_then(|rule| rule.advance(record.reminder_date))
+    else {
+        return Ok(None);
+    };
+
+    let next =
+        reminder::roll_over_reminder(pool, record.id, &next_date, &next_rule.to_string()).await?;
+    match next {
+        Some(next) => {
+            info!(
+                "Reminder {} recurs on {} as reminder {}.",
+                record.id, next_date, next.id
+            );
+            record.recurrence = None;
+            record_change(
+                pool,
+                EntityType::Reminder,
+                next.id,
+                AuditAction::Create,
+                None,
+                origin,
+            )
+            .await;
+            Ok(Some(reminder_payload(pool, &next).await))
+        }
+        None => Ok(None),
+    }
+}
+
 // ======================================================
 // Create Reminder
 // ======================================================
@@ -30,12 +114,18 @@ pub async fn create_reminder_service(
     company_id: Option<i64>,
     person_id: Option<i64>,
     reminder_date: &NaiveDate,
+    reminder_time: Option<&NaiveTime>,
+    timezone: Option<&str>,
+    recurrence: Option<&str>,
     title: &str,
     message: Option<&str>,
     is_completed: bool,
-) -> JsonResult {
+) -> ServiceResult {
     info!("Creating reminder: {:?}", title);
 
+    let timezone = timezone.map(normalize_timezone).transpose()?;
+    let recurrence = recurrence.map(normalize_recurrence).transpose()?;
+
     let result = reminder::create_reminder(
         pool,
         application_id,
@@ -45,6 +135,9 @@ pub async fn create_reminder_service(
         company_id,
         person_id,
         reminder_date,
+        reminder_time,
+        timezone.as_deref(),
+        recurrence.as_deref(),
         title,
         message,
         is_completed,
@@ -52,27 +145,32 @@ pub async fn create_reminder_service(
     .await;
 
     match result {
-        Ok(record) => {
+        Ok(mut record) => {
             info!("Reminder created successfully. ID: {}", record.id);
 
-            let display_label = Some(format_reminder_label(&record.title, record.id));
-            let data = add_display_label(&record, display_label);
-
-            let json = json!({
-                "status": "success",
-                "message": format!("Reminder '{}' created successfully.", record.title),
-                "data": data
-            });
+            let next = continue_series(pool, &mut record, "reminder.Create").await?;
+            record_change(
+                pool,
+                EntityType::Reminder,
+                record.id,
+                AuditAction::Create,
+                None,
+                "reminder.Create",
+            )
+            .await;
+            let mut data = reminder_payload(pool, &record).await;
+            if let Some(next) = next {
+                data["nextOccurrence"] = next;
+            }
 
-            Ok(json.to_string())
+            Ok(ApiResponse::success(
+                format!("Reminder '{}' created successfully.", record.title),
+                data,
+            ))
         }
         Err(e) => {
             error!("Error creating reminder: {}", e);
-            let json = json!({
-                "status": "error",
-                "message": format!("Failed to create reminder: {}", e)
-            });
-            Err(json.to_string())
+            Err(AppError::from(e).context("Failed to create reminder"))
         }
     }
 }
@@ -80,7 +178,7 @@ pub async fn create_reminder_service(
 // ======================================================
 // Get Reminder by ID
 // ======================================================
-pub async fn get_reminder_by_id_service(pool: &SqlitePool, id: &i64) -> JsonResult {
+pub async fn get_reminder_by_id_service(pool: &SqlitePool, id: &i64) -> ServiceResult {
     info!("Retrieving reminder ID: {}", id);
 
     let result = reminder::get_reminder_by_id(pool, *id).await;
@@ -90,23 +188,17 @@ pub async fn get_reminder_by_id_service(pool: &SqlitePool, id: &i64) -> JsonResu
             info!("Reminder retrieved successfully. ID: {}", id);
 
             let display_label = Some(format_reminder_label(&record.title, record.id));
-            let data = add_display_label(&record, display_label);
+            let mut data = add_display_label(&record, display_label);
+            add_tags(pool, EntityType::Reminder, &mut data).await;
 
-            let json = json!({
-                "status": "success",
-                "message": format!("Reminder {} retrieved successfully.", id),
-                "data": data
-            });
-
-            Ok(json.to_string())
+            Ok(ApiResponse::success(
+                format!("Reminder {} retrieved successfully.", id),
+                data,
+            ))
         }
         Err(e) => {
             error!("Error retrieving reminder {}: {}", id, e);
-            let json = json!({
-                "status": "error",
-                "message": format!("Failed to retrieve reminder {}: {}", id, e)
-            });
-            Err(json.to_string())
+            Err(AppError::from(e).context(format!("Failed to retrieve reminder {}", id)))
         }
     }
 }
@@ -114,41 +206,38 @@ pub async fn get_reminder_by_id_service(pool: &SqlitePool, id: &i64) -> JsonResu
 // ======================================================
 // Get All Reminders
 // ======================================================
-pub async fn get_all_reminders_service(pool: &SqlitePool) -> JsonResult {
+pub async fn get_all_reminders_service(pool: &SqlitePool, options: &ListOptions) -> ServiceResult {
     info!("Retrieving all reminders");
 
-    let result = reminder::get_all_reminders(pool).await;
+    let result = reminder::list_reminders(pool, options).await;
 
     match result {
-        Ok(records) => {
+        Ok(page) => {
             info!(
-                "Reminders retrieved successfully ({} total).",
-                records.len()
+                "Reminders retrieved successfully ({} of {} total).",
+                page.items.len(),
+                page.total
             );
 
-            let data: Vec<Value> = records
-                .into_iter()
+            let mut items: Vec<Value> = page
+                .items
+                .iter()
                 .map(|r| {
                     let display_label = Some(format_reminder_label(&r.title, r.id));
-                    add_display_label(&r, display_label)
+                    add_display_label(r, display_label)
                 })
                 .collect();
+            add_tags_to_all(pool, EntityType::Reminder, &mut items).await;
+            let data = Value::from(items);
 
-            let json = json!({
-                "status": "success",
-                "message": "All reminders retrieved successfully.",
-                "data": data
-            });
-
-            Ok(json.to_string())
+            Ok(
+                ApiResponse::success("All reminders retrieved successfully.", data)
+                    .with_pagination(page.pagination()),
+            )
         }
         Err(e) => {
             error!("Error retrieving reminders: {}", e);
-            let json = json!({
-                "status": "error",
-                "message": format!("Failed to retrieve reminders: {}", e)
-            });
-            Err(json.to_string())
+            Err(AppError::from(e).context("Failed to retrieve reminders"))
         }
     }
 }
@@ -159,19 +248,27 @@ pub async fn get_all_reminders_service(pool: &SqlitePool) -> JsonResult {
 pub async fn update_reminder_service(
     pool: &SqlitePool,
     id: &i64,
-    application_id: Option<i64>,
-    job_listing_id: Option<i64>,
-    interaction_id: Option<i64>,
-    note_id: Option<i64>,
-    company_id: Option<i64>,
-    person_id: Option<i64>,
+    application_id: Patch<i64>,
+    job_listing_id: Patch<i64>,
+    interaction_id: Patch<i64>,
+    note_id: Patch<i64>,
+    company_id: Patch<i64>,
+    person_id: Patch<i64>,
     reminder_date: Option<&NaiveDate>,
+    reminder_time: Patch<&NaiveTime>,
+    timezone: Patch<&str>,
+    recurrence: Patch<&str>,
     title: Option<&str>,
-    message: Option<&str>,
+    message: Patch<&str>,
     is_completed: Option<bool>,
-) -> JsonResult {
+) -> ServiceResult {
     info!("Updating reminder ID: {}", id);
 
+    let timezone = timezone.try_map(normalize_timezone)?;
+    let recurrence = recurrence.try_map(normalize_recurrence)?;
+
+    let before = snapshot(pool, EntityType::Reminder, *id).await;
+
     let result = reminder::update_reminder(
         pool,
         *id,
@@ -182,6 +279,9 @@ pub async fn update_reminder_service(
         company_id,
         person_id,
         reminder_date,
+        reminder_time,
+        timezone.as_deref(),
+        recurrence.as_deref(),
         title,
         message,
         is_completed,
@@ -189,27 +289,32 @@ pub async fn update_reminder_service(
     .await;
 
     match result {
-        Ok(record) => {
+        Ok(mut record) => {
             info!("Reminder updated successfully. ID: {}", id);
 
-            let display_label = Some(format_reminder_label(&record.title, record.id));
-            let data = add_display_label(&record, display_label);
-
-            let json = json!({
-                "status": "success",
-                "message": format!("Reminder {} updated successfully.", id),
-                "data": data
-            });
+            let next = continue_series(pool, &mut record, "reminder.Update").await?;
+            record_change(
+                pool,
+                EntityType::Reminder,
+                *id,
+                AuditAction::Update,
+                before,
+                "reminder.Update",
+            )
+            .await;
+            let mut data = reminder_payload(pool, &record).await;
+            if let Some(next) = next {
+                data["nextOccurrence"] = next;
+            }
 
-            Ok(json.to_string())
+            Ok(ApiResponse::success(
+                format!("Reminder {} updated successfully.", id),
+                data,
+            ))
         }
         Err(e) => {
             error!("Error updating reminder {}: {}", id, e);
-            let json = json!({
-                "status": "error",
-                "message": format!("Failed to update reminder {}: {}", id, e)
-            });
-            Err(json.to_string())
+            Err(AppError::from(e).context(format!("Failed to update reminder {}", id)))
         }
     }
 }
@@ -217,27 +322,188 @@ pub async fn update_reminder_service(
 // ======================================================
 // Delete Reminder
 // ======================================================
-pub async fn delete_reminder_service(pool: &SqlitePool, id: &i64) -> JsonResult {
+pub async fn delete_reminder_service(pool: &SqlitePool, id: &i64) -> ServiceResult {
     info!("Deleting reminder ID: {}", id);
 
+    let before = snapshot(pool, EntityType::Reminder, *id).await;
+
     let result = reminder::delete_reminder(pool, *id).await;
 
     match result {
         Ok(_) => {
             info!("Reminder deleted successfully. ID: {}", id);
-            let json = json!({
-                "status": "success",
-                "message": format!("Reminder {} deleted successfully.", id)
-            });
-            Ok(json.to_string())
+            record_change(
+                pool,
+                EntityType::Reminder,
+                *id,
+                AuditAction::Delete,
+                before,
+                "reminder.Delete",
+            )
+            .await;
+            Ok(ApiResponse::message(format!(
+                "Reminder {} deleted successfully.",
+                id
+            )))
         }
         Err(e) => {
             error!("Error deleting reminder {}: {}", id, e);
-            let json = json!({
-                "status": "error",
-                "message": format!("Failed to delete reminder {}: {}", id, e)
-            });
-            Err(json.to_string())
+            Err(AppError::from(e).context(format!("Failed to delete reminder {}", id)))
+        }
+    }
+}
+
+// ======================================================
+// Get Upcoming Reminders
+// ======================================================
+/// Length of the upcoming window when none is given.
+pub const DEFAULT_UPCOMING_DAYS: i64 = 30;
+/// Longest window recurrences are expanded over.
+pub const MAX_UPCOMING_DAYS: i64 = 366;
+
+/// Open reminders due within `from..=to`, one item per occurrence:
+/// recurring reminders appear on every date their rule produces in the
+/// window. Items carry `occurrenceDate` and, for timed ones, `dueAt` (UTC).
+pub async fn get_upcoming_reminders_service(
+    pool: &SqlitePool,
+    from: &NaiveDate,
+    to: &NaiveDate,
+) -> ServiceResult {
+    info!("Retrieving upcoming reminders from {} to {}", from, to);
+
+    if to < from {
+        return Err(AppError::invalid_field("to", "must not be before 'from'"));
+    }
+    if (*to - *from).num_days() >= MAX_UPCOMING_DAYS {
+        return Err(AppError::invalid_field(
+            "to",
+            format!("window must be at most {} days", MAX_UPCOMING_DAYS),
+        ));
+    }
+
+    match reminder::get_upcoming_reminders(pool, from, to).await {
+        Ok(records) => {
+            let mut occurrences: Vec<(NaiveDate, &Reminder)> = Vec::new();
+            for record in &records {
+                let rule = record
+                    .recurrence
+                    .as_deref()
+                    .and_then(|rule| rule.parse::<Recurrence>().ok());
+                match rule {
+                    Some(rule) => occurrences.extend(
+                        rule.occurrences_between(record.reminder_date, *from, *to)
+                            .into_iter()
+                            .map(|date| (date, record)),
+                    ),
+                    None if record.reminder_date >= *from => {
+                        occurrences.push((record.reminder_date, record))
+                    }
+                    None => {}
+                }
+            }
+            occurrences.sort_by_key(|(date, r)| (*date, r.reminder_time, r.id));
+
+            info!(
+                "Upcoming reminders retrieved ({} occurrences of {} reminders).",
+                occurrences.len(),
+                records.len()
+            );
+
+            let mut items: Vec<Value> = occurrences
+                .iter()
+                .map(|(date, r)| {
+                    let mut item =
+                        add_display_label(*r, Some(format_reminder_label(&r.title, r.id)));
+                    item["occurrenceDate"] = json!(date);
+                    item["dueAt"] = json!(reminder_due_at(r, *date));
+                    item
+                })
+                .collect();
+            add_tags_to_all(pool, EntityType::Reminder, &mut items).await;
+
+            Ok(ApiResponse::success(
+                "Upcoming reminders retrieved successfully.",
+                Value::from(items),
+            ))
+        }
+        Err(e) => {
+            error!("Error retrieving upcoming reminders: {}", e);
+            Err(AppError::from(e).context("Failed to retrieve upcoming reminders"))
+        }
+    }
+}
+
+// ======================================================
+// Snooze / Mark Done
+// ======================================================
+/// Longest accepted snooze: one week.
+pub const MAX_SNOOZE_MINUTES: i64 = 7 * 24 * 60;
+
+pub async fn snooze_reminder_service(pool: &SqlitePool, id: &i64, minutes: i64) -> ServiceResult {
+    info!("Snoozing reminder {} for {} minutes", id, minutes);
+
+    if !(1..=MAX_SNOOZE_MINUTES).contains(&minutes) {
+        return Err(AppError::invalid_field(
+            "minutes",
+            format!("must be between 1 and {}", MAX_SNOOZE_MINUTES),
+        ));
+    }
+
+    let until = Utc::now().naive_utc() + Duration::minutes(minutes);
+
+    match reminder::snooze_reminder(pool, *id, &until).await {
+        Ok(record) => {
+            info!("Reminder {} snoozed until {} UTC.", id, until);
+
+            let display_label = Some(format_reminder_label(&record.title, record.id));
+            let mut data = add_display_label(&record, display_label);
+            data["snoozedUntil"] = json!(until);
+
+            Ok(ApiResponse::success(
+                format!("Reminder {} snoozed for {} minutes.", id, minutes),
+                data,
+            ))
+        }
+        Err(e) => {
+            error!("Error snoozing reminder {}: {}", id, e);
+            // Completed reminders are not matched either
+            Err(AppError::from(e).context(format!("Failed to snooze open reminder {}", id)))
+        }
+    }
+}
+
+pub async fn mark_reminder_done_service(pool: &SqlitePool, id: &i64) -> ServiceResult {
+    info!("Marking reminder {} as done", id);
+
+    let before = snapshot(pool, EntityType::Reminder, *id).await;
+
+    match reminder::complete_reminder(pool, *id).await {
+        Ok(mut record) => {
+            info!("Reminder {} marked as done.", id);
+
+            let next = continue_series(pool, &mut record, "reminder.MarkDone").await?;
+            record_change(
+                pool,
+                EntityType::Reminder,
+                *id,
+                AuditAction::Update,
+                before,
+                "reminder.MarkDone",
+            )
+            .await;
+            let mut data = reminder_payload(pool, &record).await;
+            if let Some(next) = next {
+                data["nextOccurrence"] = next;
+            }
+
+            Ok(ApiResponse::success(
+                format!("Reminder {} marked as done.", id),
+                data,
+            ))
+        }
+        Err(e) => {
+            error!("Error completing reminder {}: {}", id, e);
+            Err(AppError::from(e).context(format!("Failed to complete reminder {}", id)))
         }
     }
 }
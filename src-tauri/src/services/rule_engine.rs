@@ -0,0 +1,280 @@
+//! Evaluates automation rules.
+//!
+//! Event-driven rules run inside the transaction of the change that
+//! triggered them, so a failing action rolls the change back too.
+//! Changes made by actions do not trigger further rules, so rules
+//! cannot loop.
+
+use crate::db::models::enums::{EntityType, Stage};
+use crate::db::models::patch::Patch;
+use crate::db::models::rule::{RuleAction, RuleTrigger};
+use crate::db::queries::application::{self, Application};
+use crate::db::queries::interaction::Interaction;
+use crate::db::queries::rule::{self, ApplicationContext, AutomationRule};
+use crate::db::queries::{note, reminder};
+use crate::logger::*;
+use crate::services::audit_service::record_rule_outcomes;
+use chrono::{Duration, NaiveDate};
+use serde::Serialize;
+use serde_json::{json, Value};
+use sqlx::{Error, SqliteConnection, SqlitePool};
+
+/// A change rules can react to.
+pub enum RuleEvent<'a> {
+    ApplicationCreated(&'a Application),
+    /// `application` already has its new stage
+    StageChanged(&'a Application),
+    InteractionLogged(&'a Interaction),
+}
+
+/// What one rule did.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleOutcome {
+    pub rule_id: i64,
+    pub rule_name: String,
+    pub action: &'static str,
+    pub application_id: Option<i64>,
+    pub entity_type: EntityType,
+    pub entity_id: i64,
+}
+
+/// The records created notes and reminders are linked to.
+#[derive(Default, Clone, Copy)]
+struct Subject {
+    application_id: Option<i64>,
+    interaction_id: Option<i64>,
+    person_id: Option<i64>,
+    company_id: Option<i64>,
+}
+
+impl RuleEvent<'_> {
+    fn name(&self) -> &'static str {
+        match self {
+            RuleEvent::ApplicationCreated(_) => "application_created",
+            RuleEvent::StageChanged(_) => "stage_changed",
+            RuleEvent::InteractionLogged(_) => "interaction_logged",
+        }
+    }
+
+    fn matches(&self, trigger: &RuleTrigger) -> bool {
+        match (self, trigger) {
+            (RuleEvent::ApplicationCreated(_), RuleTrigger::ApplicationCreated) => true,
+            (RuleEvent::StageChanged(a), RuleTrigger::StageChanged { stage }) => {
+                stage.is_none() || *stage == a.stage
+            }
+            (
+                RuleEvent::InteractionLogged(i),
+                RuleTrigger::InteractionLogged { interaction_type },
+            ) => interaction_type
+                .as_ref()
+                .is_none_or(|t| *t == i.interaction_type),
+            _ => false,
+        }
+    }
+
+    fn subject(&self) -> Subject {
+        match self {
+            RuleEvent::ApplicationCreated(a) | RuleEvent::StageChanged(a) => Subject {
+                application_id: Some(a.id),
+                ..Default::default()
+            },
+            RuleEvent::InteractionLogged(i) => Subject {
+                application_id: i.application_id,
+                interaction_id: Some(i.id),
+                person_id: i.person_id,
+                company_id: i.company_id,
+            },
+        }
+    }
+}
+
+// ======================================================
+// Event-driven rules
+// ======================================================
+/// Runs the enabled rules matching `event` on the transaction's connection.
+pub async fn fire_rules(
+    conn: &mut SqliteConnection,
+    event: &RuleEvent<'_>,
+    today: NaiveDate,
+) -> Result<Vec<RuleOutcome>, Error> {
+    let rules = rule::list_enabled_rules(&mut *conn, event.name()).await?;
+    let subject = event.subject();
+
+    let mut outcomes = Vec::new();
+    for r in rules.iter().filter(|r| event.matches(&r.trigger)) {
+        if let Some(entity) = apply_action(conn, r, subject, today).await? {
+            rule::record_run(&mut *conn, r.id, subject.application_id, None, Some(entity)).await?;
+            outcomes.push(outcome(r, subject, entity));
+        }
+    }
+
+    if !outcomes.is_empty() {
+        info!("{} rule(s) fired on {}.", outcomes.len(), event.name());
+    }
+    Ok(outcomes)
+}
+
+// ======================================================
+// Inactivity rules
+// ======================================================
+/// Fires `no_interaction` rules for open applications that have been quiet
+/// long enough. Each rule fires once per application and quiet period; a
+/// new interaction starts a new period.
+pub async fn run_inactivity_rules(
+    pool: &SqlitePool,
+    today: NaiveDate,
+) -> Result<Vec<RuleOutcome>, Error> {
+    let rules = rule::list_enabled_rules(pool, "no_interaction").await?;
+
+    let mut outcomes = Vec::new();
+    for r in &rules {
+        let RuleTrigger::NoInteraction { days } = r.trigger else {
+            continue;
+        };
+        let cutoff = today - Duration::days(days);
+
+        for quiet in rule::list_quiet_applications(pool, &cutoff).await? {
+            let anchor = quiet.last_activity.format("%Y-%m-%d").to_string();
+            let subject = Subject {
+                application_id: Some(quiet.id),
+                ..Default::default()
+            };
+
+            let mut tx = pool.begin().await?;
+            if rule::has_run(&mut *tx, r.id, quiet.id, &anchor).await? {
+                continue;
+            }
+            let entity = apply_action(&mut tx, r, subject, today).await?;
+            // Recorded even when there was nothing to do, so it is not retried
+            rule::record_run(&mut *tx, r.id, Some(quiet.id), Some(&anchor), entity).await?;
+            tx.commit().await?;
+
+            if let Some(entity) = entity {
+                outcomes.push(outcome(r, subject, entity));
+            }
+        }
+    }
+
+    if !outcomes.is_empty() {
+        info!("{} inactivity rule(s) fired.", outcomes.len());
+        record_rule_outcomes(pool, &outcomes, None).await;
+    }
+    Ok(outcomes)
+}
+
+// ======================================================
+// Actions
+// ======================================================
+/// Performs the rule's action; `None` when there was nothing to do.
+async fn apply_action(
+    conn: &mut SqliteConnection,
+    rule: &AutomationRule,
+    subject: Subject,
+    today: NaiveDate,
+) -> Result<Option<(EntityType, i64)>, Error> {
+    let context = match subject.application_id {
+        Some(id) => rule::get_application_context(&mut *conn, id).await?,
+        None => ApplicationContext::default(),
+    };
+
+    let entity = match &rule.action {
+        RuleAction::CreateReminder {
+            title,
+            message,
+            days_after,
+        } => {
+            let message = message.as_deref().map(|m| fill(m, &context));
+            let record = reminder::create_reminder(
+                &mut *conn,
+                subject.application_id,
+                subject.interaction_id,
+                None,
+                None,
+                subject.company_id,
+                subject.person_id,
+                &(today + Duration::days(*days_after)),
+                None,
+                None,
+                None,
+                &fill(title, &context),
+                message.as_deref(),
+                false,
+            )
+            .await?;
+            (EntityType::Reminder, record.id)
+        }
+        RuleAction::CreateNote {
+            title,
+            content,
+            note_type,
+        } => {
+            let content = content.as_deref().map(|c| fill(c, &context));
+            let record = note::create_note(
+                &mut *conn,
+                subject.interaction_id,
+                None,
+                subject.application_id,
+                subject.person_id,
+                subject.company_id,
+                note_type.as_ref(),
+                Some(&fill(title, &context)),
+                content.as_deref(),
+            )
+            .await?;
+            (EntityType::Note, record.id)
+        }
+        RuleAction::SetStage { stage } => {
+            let Some(id) = subject.application_id else {
+                return Ok(None);
+            };
+            if context.stage.as_ref() == Some(stage) {
+                return Ok(None);
+            }
+            application::update_application(
+                &mut *conn,
+                id,
+                Patch::Unchanged,
+                Some(stage),
+                None,
+                Patch::Unchanged,
+            )
+            .await?;
+            (EntityType::Application, id)
+        }
+    };
+
+    Ok(Some(entity))
+}
+
+/// Replaces `{company}`, `{job}` and `{stage}` in rule texts.
+fn fill(template: &str, context: &ApplicationContext) -> String {
+    template
+        .replace(
+            "{company}",
+            context.company.as_deref().unwrap_or("the company"),
+        )
+        .replace(
+            "{job}",
+            context.job_title.as_deref().unwrap_or("the position"),
+        )
+        .replace("{stage}", context.stage.as_ref().map_or("", Stage::as_str))
+}
+
+fn outcome(rule: &AutomationRule, subject: Subject, entity: (EntityType, i64)) -> RuleOutcome {
+    RuleOutcome {
+        rule_id: rule.id,
+        rule_name: rule.name.clone(),
+        action: rule.action.kind(),
+        application_id: subject.application_id,
+        entity_type: entity.0,
+        entity_id: entity.1,
+    }
+}
+
+/// Lists what rules did under `automations`, if they did anything.
+pub fn add_outcomes(data: &mut Value, outcomes: &[RuleOutcome]) {
+    if let (Value::Object(obj), false) = (data, outcomes.is_empty()) {
+        obj.insert("automations".to_string(), json!(outcomes));
+    }
+}
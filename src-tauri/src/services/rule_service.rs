@@ -0,0 +1,222 @@
+use crate::db::models::rule::{RuleAction, RuleTrigger};
+use crate::db::queries::rule;
+use crate::error::AppError;
+use crate::logger::*;
+use crate::services::rule_engine::run_inactivity_rules;
+use crate::services::service_types::{ApiResponse, ServiceResult};
+use chrono::NaiveDate;
+use serde_json::json;
+use sqlx::SqlitePool;
+
+/// Longest accepted inactivity period and reminder delay, in days.
+pub const MAX_RULE_DAYS: i64 = 365;
+
+// ======================================================
+// Validation
+// ======================================================
+pub fn normalize_rule_name(name: &str) -> Result<String, AppError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(AppError::invalid_field("name", "must not be empty"));
+    }
+    Ok(name.to_string())
+}
+
+pub fn validate_trigger(trigger: &RuleTrigger) -> Result<(), AppError> {
+    if let RuleTrigger::NoInteraction { days } = trigger {
+        if !(1..=MAX_RULE_DAYS).contains(days) {
+            return Err(AppError::invalid_field(
+                "trigger.days",
+                format!("must be between 1 and {}", MAX_RULE_DAYS),
+            ));
+        }
+    }
+    Ok(())
+}
+
+pub fn validate_action(action: &RuleAction) -> Result<(), AppError> {
+    match action {
+        RuleAction::CreateReminder {
+            title, days_after, ..
+        } => {
+            if title.trim().is_empty() {
+                return Err(AppError::invalid_field("action.title", "must not be empty"));
+            }
+            if !(0..=MAX_RULE_DAYS).contains(days_after) {
+                return Err(AppError::invalid_field(
+                    "action.daysAfter",
+                    format!("must be between 0 and {}", MAX_RULE_DAYS),
+                ));
+            }
+        }
+        RuleAction::CreateNote { title, .. } => {
+            if title.trim().is_empty() {
+                return Err(AppError::invalid_field("action.title", "must not be empty"));
+            }
+        }
+        RuleAction::SetStage { .. } => {}
+    }
+    Ok(())
+}
+
+// ======================================================
+// Create Rule
+// ======================================================
+pub async fn create_rule_service(
+    pool: &SqlitePool,
+    name: &str,
+    enabled: bool,
+    trigger: &RuleTrigger,
+    action: &RuleAction,
+) -> ServiceResult {
+    info!("Creating automation rule: {}", name);
+
+    let name = normalize_rule_name(name)?;
+    validate_trigger(trigger)?;
+    validate_action(action)?;
+
+    match rule::create_rule(pool, &name, enabled, trigger, action).await {
+        Ok(record) => {
+            info!("Automation rule created successfully. ID: {}", record.id);
+
+            Ok(ApiResponse::success(
+                format!("Rule '{}' created successfully.", record.name),
+                json!(record),
+            ))
+        }
+        Err(e) => {
+            error!("Error creating automation rule '{}': {}", name, e);
+            Err(AppError::from(e).context(format!("Failed to create rule '{}'", name)))
+        }
+    }
+}
+
+// ======================================================
+// Get Rule by ID / All Rules
+// ======================================================
+pub async fn get_rule_by_id_service(pool: &SqlitePool, id: &i64) -> ServiceResult {
+    info!("Retrieving automation rule by ID: {}", id);
+
+    match rule::get_rule_by_id(pool, *id).await {
+        Ok(record) => Ok(ApiResponse::success(
+            format!("Rule {} retrieved successfully.", id),
+            json!(record),
+        )),
+        Err(e) => {
+            error!("Error retrieving automation rule {}: {}", id, e);
+            Err(AppError::from(e).context(format!("Failed to retrieve rule {}", id)))
+        }
+    }
+}
+
+pub async fn get_all_rules_service(pool: &SqlitePool) -> ServiceResult {
+    info!("Retrieving all automation rules");
+
+    match rule::list_rules(pool).await {
+        Ok(rules) => {
+            info!("Automation rules retrieved successfully ({}).", rules.len());
+
+            Ok(ApiResponse::success(
+                "All rules retrieved successfully.",
+                json!(rules),
+            ))
+        }
+        Err(e) => {
+            error!("Error retrieving automation rules: {}", e);
+            Err(AppError::from(e).context("Failed to retrieve rules"))
+        }
+    }
+}
+
+// ======================================================
+// Update Rule
+// ======================================================
+pub async fn update_rule_service(
+    pool: &SqlitePool,
+    id: &i64,
+    name: Option<&str>,
+    enabled: Option<bool>,
+    trigger: Option<&RuleTrigger>,
+    action: Option<&RuleAction>,
+) -> ServiceResult {
+    info!("Updating automation rule with ID: {}", id);
+
+    let name = name.map(normalize_rule_name).transpose()?;
+    trigger.map(validate_trigger).transpose()?;
+    action.map(validate_action).transpose()?;
+
+    match rule::update_rule(pool, *id, name.as_deref(), enabled, trigger, action).await {
+        Ok(record) => {
+            info!("Automation rule updated successfully. ID: {}", id);
+
+            Ok(ApiResponse::success(
+                format!("Rule {} updated successfully.", id),
+                json!(record),
+            ))
+        }
+        Err(e) => {
+            error!("Error updating automation rule {}: {}", id, e);
+            Err(AppError::from(e).context(format!("Failed to update rule {}", id)))
+        }
+    }
+}
+
+// ======================================================
+// Delete Rule
+// ======================================================
+pub async fn delete_rule_service(pool: &SqlitePool, id: &i64) -> ServiceResult {
+    info!("Deleting automation rule with ID: {}", id);
+
+    match rule::delete_rule(pool, *id).await {
+        Ok(_) => {
+            info!("Automation rule deleted successfully. ID: {}", id);
+            Ok(ApiResponse::message(format!(
+                "Rule {} deleted successfully.",
+                id
+            )))
+        }
+        Err(e) => {
+            error!("Error deleting automation rule {}: {}", id, e);
+            Err(AppError::from(e).context(format!("Failed to delete rule {}", id)))
+        }
+    }
+}
+
+// ======================================================
+// Rule Runs
+// ======================================================
+pub async fn get_rule_runs_service(pool: &SqlitePool, id: &i64) -> ServiceResult {
+    info!("Retrieving runs of automation rule {}", id);
+
+    if let Err(e) = rule::get_rule_by_id(pool, *id).await {
+        error!("Error retrieving automation rule {}: {}", id, e);
+        return Err(AppError::from(e).context(format!("Failed to retrieve rule {}", id)));
+    }
+
+    match rule::list_rule_runs(pool, *id).await {
+        Ok(runs) => Ok(ApiResponse::success(
+            format!("Runs of rule {} retrieved successfully.", id),
+            json!(runs),
+        )),
+        Err(e) => {
+            error!("Error retrieving runs of automation rule {}: {}", id, e);
+            Err(AppError::from(e).context(format!("Failed to retrieve runs of rule {}", id)))
+        }
+    }
+}
+
+/// Checks `no_interaction` rules now instead of waiting for the scheduler.
+pub async fn run_inactivity_rules_service(pool: &SqlitePool, today: &NaiveDate) -> ServiceResult {
+    info!("Running inactivity rules for {}", today);
+
+    match run_inactivity_rules(pool, *today).await {
+        Ok(outcomes) => Ok(ApiResponse::success(
+            format!("{} inactivity rule(s) fired.", outcomes.len()),
+            json!(outcomes),
+        )),
+        Err(e) => {
+            error!("Error running inactivity rules: {}", e);
+            Err(AppError::from(e).context("Failed to run inactivity rules"))
+        }
+    }
+}
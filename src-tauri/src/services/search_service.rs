@@ -0,0 +1,49 @@
+use crate::db::queries::search;
+use crate::logger::*;
+use crate::services::service_types::JsonResult;
+use crate::services::service_utils::{add_display_label, fetch_display_label};
+use serde_json::{json, Value};
+use sqlx::SqlitePool;
+
+const DEFAULT_SEARCH_LIMIT: i64 = 50;
+const MAX_SEARCH_LIMIT: i64 = 200;
+
+// ======================================================
+// Search All Entities
+// ======================================================
+pub async fn search_service(pool: &SqlitePool, query: &str, limit: Option<i64>) -> JsonResult {
+    info!("Searching for '{}'", query);
+
+    let limit = limit
+        .unwrap_or(DEFAULT_SEARCH_LIMIT)
+        .clamp(1, MAX_SEARCH_LIMIT);
+
+    let result = search::search_all(pool, query, limit).await;
+
+    match result {
+        Ok(hits) => {
+            info!("Search completed ({} hits).", hits.len());
+
+            let mut data: Vec<Value> = Vec::with_capacity(hits.len());
+            for hit in &hits {
+                let label = fetch_display_label(pool, hit.entity_type, hit.entity_id).await;
+                data.push(add_display_label(hit, Some(label)));
+            }
+
+            let json = json!({
+                "status": "success",
+                "message": format!("Search returned {} results.", data.len()),
+                "data": data
+            });
+            Ok(json.to_string())
+        }
+        Err(e) => {
+            error!("Error searching for '{}': {}", query, e);
+            let json = json!({
+                "status": "error",
+                "message": format!("Failed to search: {}", e)
+            });
+            Err(json.to_string())
+        }
+    }
+}
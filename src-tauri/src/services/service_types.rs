@@ -1,2 +1,48 @@
+use crate::db::queries::list_query::Pagination;
+use crate::error::AppError;
+use serde::Serialize;
+use serde_json::Value;
+
 /// Common return type for all service functions returning JSON.
 pub type JsonResult = Result<String, String>;
+
+/// Typed return value of the entity services, serialized by Tauri as-is.
+pub type ServiceResult<T = Value> = Result<ApiResponse<T>, AppError>;
+
+/// Success envelope: `{ status: "success", message, data?, pagination? }`.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiResponse<T = Value> {
+    pub status: &'static str,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<T>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pagination: Option<Pagination>,
+}
+
+impl<T> ApiResponse<T> {
+    pub fn success(message: impl Into<String>, data: T) -> Self {
+        ApiResponse {
+            status: "success",
+            message: message.into(),
+            data: Some(data),
+            pagination: None,
+        }
+    }
+
+    /// Success without a payload, e.g. after a delete.
+    pub fn message(message: impl Into<String>) -> Self {
+        ApiResponse {
+            status: "success",
+            message: message.into(),
+            data: None,
+            pagination: None,
+        }
+    }
+
+    pub fn with_pagination(mut self, pagination: Pagination) -> Self {
+        self.pagination = Some(pagination);
+        self
+    }
+}
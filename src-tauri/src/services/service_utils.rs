@@ -1,5 +1,14 @@
+use crate::db::models::enums::EntityType;
+use crate::db::queries::{
+    application, company, interaction, job_listing, note, person, reminder, tag,
+};
+use crate::logger::*;
+use crate::services::application_service::fetch_joblisting_label;
+use crate::services::person_service::format_person_label;
+use crate::services::reminder_service::format_reminder_label;
 use serde::Serialize;
-use serde_json::Value;
+use serde_json::{json, Value};
+use sqlx::SqlitePool;
 
 /// Converts a record into JSON and appends a display label.
 /// If the provided label is None or empty, a fallback is generated using the record ID (if present).
@@ -30,3 +39,72 @@ where
 
     data
 }
+
+/// Resolves the display label of any entity by type and ID,
+/// using the same rules as the entity's own service.
+pub async fn fetch_display_label(pool: &SqlitePool, entity_type: EntityType, id: i64) -> String {
+    let label = match entity_type {
+        EntityType::Company => company::get_company_by_id(pool, id)
+            .await
+            .ok()
+            .map(|r| r.name),
+        EntityType::Person => person::get_person_by_id(pool, id)
+            .await
+            .ok()
+            .map(|r| format_person_label(&r.last_name, &r.first_name, r.id)),
+        EntityType::JobListing => job_listing::get_job_listing_by_id(pool, id)
+            .await
+            .ok()
+            .map(|r| r.title),
+        EntityType::Application => match application::get_application_by_id(pool, id).await {
+            Ok(r) => Some(fetch_joblisting_label(pool, r.job_listing_id, r.id).await),
+            Err(_) => None,
+        },
+        EntityType::Interaction => interaction::get_interaction_by_id(pool, id)
+            .await
+            .ok()
+            .and_then(|r| r.summary),
+        EntityType::Note => note::get_note_by_id(pool, id)
+            .await
+            .ok()
+            .and_then(|r| r.title),
+        EntityType::Reminder => reminder::get_reminder_by_id(pool, id)
+            .await
+            .ok()
+            .map(|r| format_reminder_label(&r.title, r.id)),
+    };
+
+    label
+        .filter(|l| !l.trim().is_empty())
+        .unwrap_or_else(|| format!("Record ID: {}", id))
+}
+
+/// Adds the record's `tags` array next to its display label.
+/// A failed lookup is logged and leaves the list empty.
+pub async fn add_tags(pool: &SqlitePool, entity_type: EntityType, data: &mut Value) {
+    add_tags_to_all(pool, entity_type, std::slice::from_mut(data)).await;
+}
+
+/// Same as [`add_tags`] for a whole page, with a single query.
+pub async fn add_tags_to_all(pool: &SqlitePool, entity_type: EntityType, items: &mut [Value]) {
+    let ids: Vec<i64> = items
+        .iter()
+        .filter_map(|v| v.get("id").and_then(|id| id.as_i64()))
+        .collect();
+
+    let mut tags = match tag::get_tags_for_entities(pool, entity_type, &ids).await {
+        Ok(tags) => tags,
+        Err(e) => {
+            warn!("Could not load tags for {}: {}", entity_type.as_str(), e);
+            Default::default()
+        }
+    };
+
+    for item in items.iter_mut() {
+        let id = item.get("id").and_then(|id| id.as_i64());
+        let item_tags = id.and_then(|id| tags.remove(&id)).unwrap_or_default();
+        if let Value::Object(ref mut obj) = item {
+            obj.insert("tags".to_string(), json!(item_tags));
+        }
+    }
+}
@@ -0,0 +1,319 @@
+use crate::db::models::enums::EntityType;
+use crate::db::models::patch::Patch;
+use crate::db::queries::tag;
+use crate::error::AppError;
+use crate::logger::*;
+use crate::services::service_types::{ApiResponse, ServiceResult};
+use serde_json::json;
+use sqlx::SqlitePool;
+
+/// Longest accepted tag name, in characters.
+pub const MAX_TAG_NAME_LEN: usize = 50;
+
+// ======================================================
+// Validation
+// ======================================================
+/// Trims the name and checks it is non-empty and not too long.
+pub fn normalize_tag_name(name: &str) -> Result<String, AppError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(AppError::invalid_field("name", "must not be empty"));
+    }
+    if name.chars().count() > MAX_TAG_NAME_LEN {
+        return Err(AppError::invalid_field(
+            "name",
+            format!("must be at most {} characters", MAX_TAG_NAME_LEN),
+        ));
+    }
+    Ok(name.to_string())
+}
+
+/// Accepts `#RRGGBB` and returns it lowercased.
+pub fn normalize_tag_color(color: &str) -> Result<String, AppError> {
+    let color = color.trim();
+    let valid = color.len() == 7
+        && color.starts_with('#')
+        && color[1..].chars().all(|c| c.is_ascii_hexdigit());
+    if !valid {
+        return Err(AppError::invalid_field("color", "must look like #RRGGBB"));
+    }
+    Ok(color.to_ascii_lowercase())
+}
+
+// ======================================================
+// Create Tag
+// ======================================================
+pub async fn create_tag_service(
+    pool: &SqlitePool,
+    name: &str,
+    color: Option<&str>,
+) -> ServiceResult {
+    info!("Creating tag: {}", name);
+
+    let name = normalize_tag_name(name)?;
+    let color = color.map(normalize_tag_color).transpose()?;
+
+    match tag::create_tag(pool, &name, color.as_deref()).await {
+        Ok(record) => {
+            info!("Tag created successfully. ID: {}", record.id);
+
+            Ok(ApiResponse::success(
+                format!("Tag '{}' created successfully.", record.name),
+                json!(record),
+            ))
+        }
+        Err(e) => {
+            error!("Error creating tag '{}': {}", name, e);
+            Err(AppError::from(e).context(format!("Failed to create tag '{}'", name)))
+        }
+    }
+}
+
+// ======================================================
+// Get All Tags
+// ======================================================
+pub async fn get_all_tags_service(pool: &SqlitePool) -> ServiceResult {
+    info!("Retrieving all tags");
+
+    match tag::list_tags(pool).await {
+        Ok(tags) => {
+            info!("Tags retrieved successfully ({}).", tags.len());
+
+            Ok(ApiResponse::success(
+                "All tags retrieved successfully.",
+                json!(tags),
+            ))
+        }
+        Err(e) => {
+            error!("Error retrieving tags: {}", e);
+            Err(AppError::from(e).context("Failed to retrieve tags"))
+        }
+    }
+}
+
+// ======================================================
+// Rename / Recolor Tag
+// ======================================================
+pub async fn rename_tag_service(pool: &SqlitePool, id: &i64, name: &str) -> ServiceResult {
+    info!("Renaming tag {} to '{}'", id, name);
+
+    let name = normalize_tag_name(name)?;
+
+    match tag::update_tag(pool, *id, Some(&name), Patch::Unchanged).await {
+        Ok(record) => {
+            info!("Tag renamed successfully. ID: {}", id);
+
+            Ok(ApiResponse::success(
+                format!("Tag {} renamed to '{}'.", id, record.name),
+                json!(record),
+            ))
+        }
+        Err(e) => {
+            error!("Error renaming tag {}: {}", id, e);
+            Err(AppError::from(e).context(format!("Failed to rename tag {}", id)))
+        }
+    }
+}
+
+pub async fn set_tag_color_service(
+    pool: &SqlitePool,
+    id: &i64,
+    color: Option<&str>,
+) -> ServiceResult {
+    info!("Setting color of tag {}", id);
+
+    // No color clears it
+    let color = match color.map(normalize_tag_color).transpose()? {
+        Some(c) => Patch::Set(c),
+        None => Patch::Clear,
+    };
+
+    match tag::update_tag(pool, *id, None, color.as_deref()).await {
+        Ok(record) => {
+            info!("Tag color updated successfully. ID: {}", id);
+
+            Ok(ApiResponse::success(
+                format!("Tag {} updated successfully.", id),
+                json!(record),
+            ))
+        }
+        Err(e) => {
+            error!("Error updating tag {}: {}", id, e);
+            Err(AppError::from(e).context(format!("Failed to update tag {}", id)))
+        }
+    }
+}
+
+// ======================================================
+// Merge Tags
+// ======================================================
+pub async fn merge_tags_service(
+    pool: &SqlitePool,
+    source_ids: &[i64],
+    target_id: &i64,
+) -> ServiceResult {
+    info!("Merging tags {:?} into {}", source_ids, target_id);
+
+    if source_ids.iter().all(|id| id == target_id) {
+        return Err(AppError::invalid_field(
+            "sourceIds",
+            "must contain at least one tag other than the target",
+        ));
+    }
+
+    match tag::merge_tags(pool, source_ids, *target_id).await {
+        Ok(record) => {
+            info!("Tags merged successfully into {}", target_id);
+
+            Ok(ApiResponse::success(
+                format!("Tags merged into '{}'.", record.name),
+                json!(record),
+            ))
+        }
+        Err(e) => {
+            error!("Error merging tags into {}: {}", target_id, e);
+            Err(AppError::from(e).context(format!("Failed to merge tags into {}", target_id)))
+        }
+    }
+}
+
+// ======================================================
+// Delete Tag
+// ======================================================
+pub async fn delete_tag_service(pool: &SqlitePool, id: &i64) -> ServiceResult {
+    info!("Deleting tag with ID: {}", id);
+
+    match tag::delete_tag(pool, *id).await {
+        Ok(_) => {
+            info!("Tag deleted successfully. ID: {}", id);
+
+            Ok(ApiResponse::message(format!(
+                "Tag {} deleted successfully.",
+                id
+            )))
+        }
+        Err(e) => {
+            error!("Error deleting tag {}: {}", id, e);
+            Err(AppError::from(e).context(format!("Failed to delete tag {}", id)))
+        }
+    }
+}
+
+// ======================================================
+// Attach / Detach
+// ======================================================
+pub async fn attach_tag_service(
+    pool: &SqlitePool,
+    tag_id: &i64,
+    entity_type: EntityType,
+    entity_id: &i64,
+) -> ServiceResult {
+    info!(
+        "Attaching tag {} to {} {}",
+        tag_id,
+        entity_type.as_str(),
+        entity_id
+    );
+
+    match tag::attach_tag(pool, *tag_id, entity_type, *entity_id).await {
+        Ok(()) => entity_tags_response(pool, entity_type, *entity_id, "Tag attached.").await,
+        Err(e) => {
+            error!(
+                "Error attaching tag {} to {} {}: {}",
+                tag_id,
+                entity_type.as_str(),
+                entity_id,
+                e
+            );
+            Err(AppError::from(e).context(format!(
+                "Failed to attach tag {} to {} {}",
+                tag_id,
+                entity_type.as_str(),
+                entity_id
+            )))
+        }
+    }
+}
+
+pub async fn detach_tag_service(
+    pool: &SqlitePool,
+    tag_id: &i64,
+    entity_type: EntityType,
+    entity_id: &i64,
+) -> ServiceResult {
+    info!(
+        "Detaching tag {} from {} {}",
+        tag_id,
+        entity_type.as_str(),
+        entity_id
+    );
+
+    match tag::detach_tag(pool, *tag_id, entity_type, *entity_id).await {
+        Ok(true) => entity_tags_response(pool, entity_type, *entity_id, "Tag detached.").await,
+        Ok(false) => Err(AppError::not_found(format!(
+            "Tag {} is not attached to {} {}",
+            tag_id,
+            entity_type.as_str(),
+            entity_id
+        ))),
+        Err(e) => {
+            error!(
+                "Error detaching tag {} from {} {}: {}",
+                tag_id,
+                entity_type.as_str(),
+                entity_id,
+                e
+            );
+            Err(AppError::from(e).context(format!(
+                "Failed to detach tag {} from {} {}",
+                tag_id,
+                entity_type.as_str(),
+                entity_id
+            )))
+        }
+    }
+}
+
+// ======================================================
+// Get Tags of a Record
+// ======================================================
+pub async fn get_entity_tags_service(
+    pool: &SqlitePool,
+    entity_type: EntityType,
+    entity_id: &i64,
+) -> ServiceResult {
+    info!("Retrieving tags of {} {}", entity_type.as_str(), entity_id);
+
+    entity_tags_response(
+        pool,
+        entity_type,
+        *entity_id,
+        "Tags retrieved successfully.",
+    )
+    .await
+}
+
+/// Responds with the record's current tags after a change.
+async fn entity_tags_response(
+    pool: &SqlitePool,
+    entity_type: EntityType,
+    entity_id: i64,
+    message: &str,
+) -> ServiceResult {
+    match tag::get_tags_for_entity(pool, entity_type, entity_id).await {
+        Ok(tags) => Ok(ApiResponse::success(message, json!(tags))),
+        Err(e) => {
+            error!(
+                "Error retrieving tags of {} {}: {}",
+                entity_type.as_str(),
+                entity_id,
+                e
+            );
+            Err(AppError::from(e).context(format!(
+                "Failed to retrieve tags of {} {}",
+                entity_type.as_str(),
+                entity_id
+            )))
+        }
+    }
+}
@@ -0,0 +1,189 @@
+use crate::db::models::enums::EntityType;
+use crate::db::queries::audit::AuditAction;
+use crate::db::queries::trash;
+use crate::error::{AppError, ConstraintKind};
+use crate::logger::*;
+use crate::services::audit_service::{record_change, snapshot};
+use crate::services::service_types::{ApiResponse, ServiceResult};
+use chrono::{Duration, Utc};
+use serde_json::json;
+use sqlx::SqlitePool;
+
+// ======================================================
+// Restore
+// ======================================================
+pub async fn restore_service(
+    pool: &SqlitePool,
+    entity_type: EntityType,
+    id: &i64,
+) -> ServiceResult {
+    info!("Restoring {} {} from the trash", entity_type.as_str(), id);
+
+    let before = snapshot(pool, entity_type, *id).await;
+    let result: Result<i64, AppError> = async {
+        if let Some((parent_type, parent_id)) =
+            trash::trashed_parent(pool, entity_type, *id).await?
+        {
+            return Err(AppError::ConstraintViolation {
+                message: format!(
+                    "{} {} is in the trash too; restore it first",
+                    parent_type.as_str(),
+                    parent_id
+                ),
+                constraint: ConstraintKind::ForeignKey,
+            });
+        }
+        Ok(trash::restore(pool, entity_type, *id).await?)
+    }
+    .await;
+
+    match result {
+        Ok(id) => {
+            info!("{} {} restored.", entity_type.as_str(), id);
+            record_change(
+                pool,
+                entity_type,
+                id,
+                AuditAction::Restore,
+                before,
+                "trash.Restore",
+            )
+            .await;
+            Ok(ApiResponse::success(
+                format!("{} {} restored successfully.", entity_type.as_str(), id),
+                json!({ "entityType": entity_type, "id": id }),
+            ))
+        }
+        Err(e) => {
+            error!("Error restoring {} {}: {}", entity_type.as_str(), id, e);
+            Err(e.context(format!("Failed to restore {} {}", entity_type.as_str(), id)))
+        }
+    }
+}
+
+// ======================================================
+// List Trash
+// ======================================================
+pub async fn list_trash_service(
+    pool: &SqlitePool,
+    entity_type: Option<EntityType>,
+) -> ServiceResult {
+    info!("Retrieving the trash");
+
+    match trash::list_trash(pool, entity_type).await {
+        Ok(items) => {
+            info!("Trash retrieved successfully ({}).", items.len());
+            Ok(ApiResponse::success(
+                format!("{} trashed record(s).", items.len()),
+                json!(items),
+            ))
+        }
+        Err(e) => {
+            error!("Error retrieving the trash: {}", e);
+            Err(AppError::from(e).context("Failed to retrieve the trash"))
+        }
+    }
+}
+
+// ======================================================
+// Empty Trash
+// ======================================================
+/// Deletes trashed records for good; with `older_than_days`, only those
+/// trashed at least that many days ago.
+pub async fn empty_trash_service(
+    pool: &SqlitePool,
+    entity_type: Option<EntityType>,
+    older_than_days: Option<i64>,
+) -> ServiceResult {
+    info!("Emptying the trash");
+
+    if older_than_days.is_some_and(|d| d < 0) {
+        return Err(AppError::invalid_field(
+            "olderThanDays",
+            "must not be negative",
+        ));
+    }
+    let before = older_than_days.map(|d| Utc::now().naive_utc() - Duration::days(d));
+
+    // What is about to go, for the audit log
+    let mut purged = Vec::new();
+    for item in trash::list_trash(pool, entity_type)
+        .await
+        .unwrap_or_default()
+    {
+        if before.is_none_or(|b| item.deleted_at < b) {
+            let record = snapshot(pool, item.entity_type, item.id).await;
+            purged.push((item.entity_type, item.id, record));
+        }
+    }
+
+    match trash::empty_trash(pool, entity_type, before.as_ref()).await {
+        Ok(removed) => {
+            info!("Trash emptied ({} record(s) removed).", removed);
+            for (entity_type, id, record) in purged {
+                record_change(
+                    pool,
+                    entity_type,
+                    id,
+                    AuditAction::Purge,
+                    record,
+                    "trash.EmptyTrash",
+                )
+                .await;
+            }
+            Ok(ApiResponse::success(
+                format!("{} record(s) deleted permanently.", removed),
+                json!({ "removed": removed }),
+            ))
+        }
+        Err(e) => {
+            error!("Error emptying the trash: {}", e);
+            Err(AppError::from(e).context("Failed to empty the trash"))
+        }
+    }
+}
+
+// ======================================================
+// Archive / Unarchive
+// ======================================================
+pub async fn set_archived_service(
+    pool: &SqlitePool,
+    entity_type: EntityType,
+    id: &i64,
+    archived: bool,
+) -> ServiceResult {
+    let verb = if archived { "archive" } else { "unarchive" };
+    info!("Trying to {} {} {}", verb, entity_type.as_str(), id);
+
+    let before = snapshot(pool, entity_type, *id).await;
+    match trash::set_archived(pool, entity_type, *id, archived).await {
+        Ok(id) => {
+            info!("{} {} {}d.", entity_type.as_str(), id, verb);
+            let (action, origin) = if archived {
+                (AuditAction::Archive, "trash.Archive")
+            } else {
+                (AuditAction::Unarchive, "trash.Unarchive")
+            };
+            record_change(pool, entity_type, id, action, before, origin).await;
+            Ok(ApiResponse::success(
+                format!("{} {} {}d successfully.", entity_type.as_str(), id, verb),
+                json!({ "entityType": entity_type, "id": id, "archived": archived }),
+            ))
+        }
+        Err(e) => {
+            error!(
+                "Error trying to {} {} {}: {}",
+                verb,
+                entity_type.as_str(),
+                id,
+                e
+            );
+            Err(AppError::from(e).context(format!(
+                "Failed to {} {} {}",
+                verb,
+                entity_type.as_str(),
+                id
+            )))
+        }
+    }
+}
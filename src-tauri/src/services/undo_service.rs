@@ -0,0 +1,264 @@
+//! Undo and redo for the current session. Every mutating command runs
+//! through [`undoable`], which remembers the audit entries it wrote as one
+//! operation; undoing an operation inverts those entries, newest first.
+//! Deletes only move records to the trash, so undoing one restores the
+//! record with its original id and links. Undoing a merge also takes the
+//! links copied to the survivor away again.
+
+use crate::db::connection::{DbState, Operation};
+use crate::db::queries::audit::{self, AuditAction, AuditEntry};
+use crate::db::queries::{dedupe, trash};
+use crate::error::AppError;
+use crate::logger::*;
+use crate::services::audit_service::{record_change, record_link_change, snapshot, write_fields};
+use crate::services::service_types::{ApiResponse, ServiceResult};
+use serde_json::{json, Map, Value};
+use sqlx::SqlitePool;
+use std::future::Future;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    Undo,
+    Redo,
+}
+
+impl Direction {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Direction::Undo => "undo",
+            Direction::Redo => "redo",
+        }
+    }
+
+    fn done(&self) -> &'static str {
+        match self {
+            Direction::Undo => "undone",
+            Direction::Redo => "redone",
+        }
+    }
+
+    fn origin(&self) -> &'static str {
+        match self {
+            Direction::Undo => "undo.Undo",
+            Direction::Redo => "undo.Redo",
+        }
+    }
+}
+
+// ======================================================
+// Tracking
+// ======================================================
+/// Runs a mutating service call and, if it succeeds, puts what it changed
+/// on the undo stack as one operation (rule side effects included). Only
+/// entries recorded by the call itself count, not those other tasks record
+/// while it runs.
+pub async fn undoable(
+    db: &DbState,
+    pool: &SqlitePool,
+    call: impl Future<Output = ServiceResult>,
+) -> ServiceResult {
+    let id = match audit::begin_operation(pool).await {
+        Ok(id) => id,
+        Err(e) => {
+            warn!("Could not track the change for undo: {}", e);
+            return call.await;
+        }
+    };
+    let result = audit::in_operation(id, call).await;
+
+    match audit::get_operation_entries(pool, id).await {
+        Ok(entries) if entries.is_empty() => {
+            if let Err(e) = audit::discard_operation(pool, id).await {
+                warn!("Could not discard empty operation {}: {}", id, e);
+            }
+        }
+        Ok(_) if result.is_ok() => db.undo_stack().push(Operation { id }),
+        Ok(_) => {}
+        Err(e) => warn!("Could not track the change for undo: {}", e),
+    }
+    result
+}
+
+// ======================================================
+// Inverting entries
+// ======================================================
+/// Field values on one side (`"before"` or `"after"`) of an update.
+fn side_of(changes: &Value, side: &str) -> Map<String, Value> {
+    changes
+        .as_object()
+        .map(|fields| {
+            fields
+                .iter()
+                .map(|(name, change)| (name.clone(), change[side].clone()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Undoes or redoes one audit entry, recording the result like any other
+/// change.
+async fn apply_entry(
+    pool: &SqlitePool,
+    entry: &AuditEntry,
+    direction: Direction,
+) -> Result<(), AppError> {
+    let (entity_type, id) = (entry.entity_type, entry.entity_id);
+    let before = snapshot(pool, entity_type, id).await;
+
+    use AuditAction::*;
+    use Direction::*;
+    let action = match (entry.action, direction) {
+        (Create | Restore, Undo) | (Delete, Redo) => {
+            trash::move_to_trash(pool, entity_type, id).await?;
+            Delete
+        }
+        (Delete, Undo) | (Create | Restore, Redo) => {
+            trash::restore(pool, entity_type, id).await?;
+            Restore
+        }
+        (Archive, Undo) | (Unarchive, Redo) => {
+            trash::set_archived(pool, entity_type, id, false).await?;
+            Unarchive
+        }
+        (Unarchive, Undo) | (Archive, Redo) => {
+            trash::set_archived(pool, entity_type, id, true).await?;
+            Archive
+        }
+        (Update | Revert, _) => {
+            let side = if direction == Undo { "before" } else { "after" };
+            let current = before
+                .clone()
+                .ok_or_else(|| AppError::not_found("record not found"))?;
+            let fields = side_of(&entry.changes, side);
+
+            // Links moved by a merge
+            if dedupe::LINK_FIELDS.iter().any(|f| fields.contains_key(*f)) {
+                let links = dedupe::get_links(pool, entity_type, id).await?;
+                dedupe::set_links(pool, entity_type, id, &fields).await?;
+                record_link_change(pool, entity_type, id, links, direction.origin()).await;
+            }
+            write_fields(pool, entity_type, id, &current, fields).await?;
+            Update
+        }
+        (Purge, _) => {
+            return Err(AppError::not_found(format!(
+                "{} {} was deleted permanently",
+                entity_type.as_str(),
+                id
+            )))
+        }
+    };
+
+    record_change(pool, entity_type, id, action, before, direction.origin()).await;
+    Ok(())
+}
+
+/// Undoes (newest entry first) or redoes (oldest first) one operation.
+async fn apply_operation(
+    pool: &SqlitePool,
+    op: Operation,
+    direction: Direction,
+) -> Result<Vec<Value>, AppError> {
+    let mut entries = audit::get_operation_entries(pool, op.id).await?;
+    if direction == Direction::Undo {
+        entries.reverse();
+    }
+
+    let mut applied = Vec::with_capacity(entries.len());
+    for entry in &entries {
+        apply_entry(pool, entry, direction).await?;
+        applied.push(json!({
+            "entityType": entry.entity_type,
+            "id": entry.entity_id,
+            "action": entry.action,
+        }));
+    }
+    Ok(applied)
+}
+
+// ======================================================
+// Undo / Redo
+// ======================================================
+async fn step_service(
+    pool: &SqlitePool,
+    db: &DbState,
+    direction: Direction,
+    steps: Option<u32>,
+) -> ServiceResult {
+    let steps = steps.unwrap_or(1);
+    if steps == 0 {
+        return Err(AppError::invalid_field("steps", "must be at least 1"));
+    }
+    info!("Trying to {} {} operation(s)", direction.as_str(), steps);
+
+    let mut operations = Vec::new();
+    for _ in 0..steps {
+        let next = match direction {
+            Direction::Undo => db.undo_stack().pop_done(),
+            Direction::Redo => db.undo_stack().pop_undone(),
+        };
+        let Some(op) = next else {
+            break;
+        };
+
+        // A failed operation is dropped: it may be partly applied already
+        let changes = match apply_operation(pool, op, direction).await {
+            Ok(changes) => changes,
+            Err(e) => {
+                error!(
+                    "Error trying to {} operation {}: {}",
+                    direction.as_str(),
+                    op.id,
+                    e
+                );
+                return Err(e.context(format!(
+                    "Failed to {} the last change ({} done before it)",
+                    direction.as_str(),
+                    operations.len()
+                )));
+            }
+        };
+        match direction {
+            Direction::Undo => db.undo_stack().push_undone(op),
+            Direction::Redo => db.undo_stack().push_redone(op),
+        }
+        operations.push(Value::from(changes));
+    }
+
+    info!("{} operation(s) {}.", operations.len(), direction.done());
+    let (can_undo, can_redo) = {
+        let stack = db.undo_stack();
+        (stack.undo_count(), stack.redo_count())
+    };
+    Ok(ApiResponse::success(
+        format!("{} change(s) {}.", operations.len(), direction.done()),
+        json!({
+            "operations": operations,
+            "canUndo": can_undo,
+            "canRedo": can_redo,
+        }),
+    ))
+}
+
+/// Undoes the last `steps` operations (default 1) of this session.
+pub async fn undo_service(pool: &SqlitePool, db: &DbState, steps: Option<u32>) -> ServiceResult {
+    step_service(pool, db, Direction::Undo, steps).await
+}
+
+/// Redoes the last `steps` undone operations (default 1). Any new change
+/// clears what can be redone.
+pub async fn redo_service(pool: &SqlitePool, db: &DbState, steps: Option<u32>) -> ServiceResult {
+    step_service(pool, db, Direction::Redo, steps).await
+}
+
+/// How many operations can be undone and redone.
+pub fn undo_status_service(db: &DbState) -> ServiceResult {
+    let stack = db.undo_stack();
+    Ok(ApiResponse::success(
+        "Undo status retrieved successfully.",
+        json!({
+            "canUndo": stack.undo_count(),
+            "canRedo": stack.redo_count(),
+        }),
+    ))
+}
@@ -0,0 +1,253 @@
+// src/settings.rs
+use serde::{Deserialize, Serialize};
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+pub const DEFAULT_PROFILE: &str = "default";
+
+const DATA_DIR_ENV: &str = "JOBTRACKR_DATA_DIR";
+const PROFILE_ENV: &str = "JOBTRACKR_PROFILE";
+const SETTINGS_FILE: &str = "settings.json";
+const PORTABLE_DIR: &str = "JobTrackrData";
+
+/// Persisted contents of `settings.json`.
+///
+/// The copy in `~/.JobTrackr` may redirect the data directory via `dataDir`;
+/// the copy inside the resolved data directory remembers the active profile.
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingsFile {
+    pub data_dir: Option<PathBuf>,
+    pub active_profile: Option<String>,
+    pub backup: Option<BackupPolicy>,
+}
+
+/// How many automatic snapshots to retain per day and per week.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+#[serde(rename_all = "camelCase", default)]
+pub struct BackupPolicy {
+    pub keep_daily: usize,
+    pub keep_weekly: usize,
+}
+
+impl Default for BackupPolicy {
+    fn default() -> Self {
+        BackupPolicy {
+            keep_daily: 7,
+            keep_weekly: 4,
+        }
+    }
+}
+
+/// Resolved runtime settings: where data lives and which profile is open.
+#[derive(Debug, Clone)]
+pub struct AppSettings {
+    pub data_dir: PathBuf,
+    pub active_profile: String,
+    pub backup: BackupPolicy,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileInfo {
+    pub name: String,
+    pub active: bool,
+    pub db_path: PathBuf,
+}
+
+// ======================================================
+// Resolution
+// ======================================================
+
+/// Default data directory: ~/.JobTrackr
+pub fn default_data_dir() -> PathBuf {
+    let home = dirs::home_dir().unwrap_or_else(|| {
+        eprintln!("FATAL: cannot determine home directory");
+        std::process::exit(1);
+    });
+    home.join(".JobTrackr")
+}
+
+fn cli_value(args: &[String], flag: &str) -> Option<String> {
+    args.iter().enumerate().find_map(|(i, arg)| {
+        if arg == flag {
+            args.get(i + 1).cloned()
+        } else {
+            arg.strip_prefix(&format!("{}=", flag)).map(str::to_string)
+        }
+    })
+}
+
+fn portable_data_dir() -> Option<PathBuf> {
+    env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(|dir| dir.join(PORTABLE_DIR)))
+}
+
+pub fn read_settings_file(dir: &Path) -> SettingsFile {
+    fs::read_to_string(dir.join(SETTINGS_FILE))
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default()
+}
+
+fn write_settings_file(dir: &Path, settings: &SettingsFile) -> std::io::Result<()> {
+    fs::create_dir_all(dir)?;
+    let json = serde_json::to_string_pretty(settings)
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+    fs::write(dir.join(SETTINGS_FILE), json)
+}
+
+impl AppSettings {
+    /// Resolves settings from the process arguments and environment.
+    pub fn resolve() -> Self {
+        let args: Vec<String> = env::args().collect();
+        Self::resolve_from(&args)
+    }
+
+    /// Data directory precedence:
+    /// `--data-dir <path>` > `--portable` > `JOBTRACKR_DATA_DIR` >
+    /// `dataDir` in ~/.JobTrackr/settings.json > ~/.JobTrackr
+    ///
+    /// Profile precedence:
+    /// `--profile <name>` > `JOBTRACKR_PROFILE` > `activeProfile` in the
+    /// data directory's settings.json > "default"
+    pub fn resolve_from(args: &[String]) -> Self {
+        let default_dir = default_data_dir();
+
+        let data_dir = cli_value(args, "--data-dir")
+            .map(PathBuf::from)
+            .or_else(|| {
+                args.iter()
+                    .any(|a| a == "--portable")
+                    .then(portable_data_dir)
+                    .flatten()
+            })
+            .or_else(|| env::var(DATA_DIR_ENV).ok().map(PathBuf::from))
+            .or_else(|| read_settings_file(&default_dir).data_dir)
+            .unwrap_or(default_dir);
+
+        let stored = read_settings_file(&data_dir);
+
+        let active_profile = cli_value(args, "--profile")
+            .or_else(|| env::var(PROFILE_ENV).ok())
+            .or(stored.active_profile)
+            .filter(|name| validate_profile_name(name).is_ok())
+            .unwrap_or_else(|| DEFAULT_PROFILE.to_string());
+
+        AppSettings {
+            data_dir,
+            active_profile,
+            backup: stored.backup.unwrap_or_default(),
+        }
+    }
+
+    /// Directory holding a profile's database and logs.
+    /// The default profile keeps the pre-profile layout directly in the data dir.
+    pub fn profile_dir(&self, profile: &str) -> PathBuf {
+        if profile == DEFAULT_PROFILE {
+            self.data_dir.clone()
+        } else {
+            self.data_dir.join("profiles").join(profile)
+        }
+    }
+
+    pub fn db_path(&self, profile: &str) -> PathBuf {
+        self.profile_dir(profile).join("jobtrackr.db")
+    }
+
+    pub fn log_dir(&self, profile: &str) -> PathBuf {
+        self.profile_dir(profile).join("logs")
+    }
+
+    pub fn active_db_path(&self) -> PathBuf {
+        self.db_path(&self.active_profile)
+    }
+
+    pub fn active_log_dir(&self) -> PathBuf {
+        self.log_dir(&self.active_profile)
+    }
+
+    /// Default destination for files exported from the active profile.
+    pub fn active_export_dir(&self) -> PathBuf {
+        self.profile_dir(&self.active_profile).join("exports")
+    }
+
+    /// Content-addressed store for attached documents.
+    pub fn active_attachment_dir(&self) -> PathBuf {
+        self.profile_dir(&self.active_profile).join("attachments")
+    }
+
+    /// Regenerated iCalendar feed calendar apps can subscribe to.
+    pub fn active_calendar_path(&self) -> PathBuf {
+        self.profile_dir(&self.active_profile).join("jobtrackr.ics")
+    }
+
+    /// All profiles found on disk. The default profile is always listed.
+    pub fn list_profiles(&self) -> Vec<ProfileInfo> {
+        let mut names = vec![DEFAULT_PROFILE.to_string()];
+
+        if let Ok(entries) = fs::read_dir(self.data_dir.join("profiles")) {
+            let mut named: Vec<String> = entries
+                .filter_map(|e| e.ok())
+                .filter(|e| e.path().is_dir())
+                .filter_map(|e| e.file_name().into_string().ok())
+                .filter(|name| validate_profile_name(name).is_ok())
+                .collect();
+            named.sort_by_key(|n| n.to_lowercase());
+            names.extend(named);
+        }
+
+        names
+            .into_iter()
+            .map(|name| ProfileInfo {
+                active: name == self.active_profile,
+                db_path: self.db_path(&name),
+                name,
+            })
+            .collect()
+    }
+
+    pub fn profile_exists(&self, profile: &str) -> bool {
+        profile == DEFAULT_PROFILE || self.profile_dir(profile).is_dir()
+    }
+
+    /// Remembers the active profile in the data directory's settings.json.
+    pub fn persist_active_profile(&self) -> std::io::Result<()> {
+        let mut file = read_settings_file(&self.data_dir);
+        file.active_profile = Some(self.active_profile.clone());
+        write_settings_file(&self.data_dir, &file)
+    }
+
+    /// Stores the backup retention policy in the data directory's settings.json.
+    pub fn persist_backup_policy(&self) -> std::io::Result<()> {
+        let mut file = read_settings_file(&self.data_dir);
+        file.backup = Some(self.backup);
+        write_settings_file(&self.data_dir, &file)
+    }
+}
+
+/// Profile names become directory names, so keep them to a safe character set.
+pub fn validate_profile_name(name: &str) -> Result<(), String> {
+    let trimmed = name.trim();
+    if trimmed.is_empty() || trimmed.len() > 64 {
+        return Err("Profile name must be between 1 and 64 characters.".to_string());
+    }
+    if trimmed != name {
+        return Err("Profile name must not start or end with whitespace.".to_string());
+    }
+    if name.starts_with('.') {
+        return Err("Profile name must not start with a dot.".to_string());
+    }
+    if !name
+        .chars()
+        .all(|c| c.is_alphanumeric() || matches!(c, ' ' | '-' | '_' | '.'))
+    {
+        return Err(
+            "Profile name may only contain letters, digits, spaces, '-', '_' and '.'.".to_string(),
+        );
+    }
+    Ok(())
+}
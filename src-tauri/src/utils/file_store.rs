@@ -0,0 +1,157 @@
+//! Content-addressed file store.
+//!
+//! Files are kept as `<root>/<first two hex digits>/<sha256>[.ext]`, so
+//! adding the same content twice stores it once.
+
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// Suffix of partially written files; never swept.
+const TEMP_SUFFIX: &str = ".part";
+
+pub struct FileStore {
+    root: PathBuf,
+}
+
+impl FileStore {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        FileStore { root: root.into() }
+    }
+
+    /// Absolute path of a stored file.
+    pub fn path_of(&self, storage_path: &str) -> PathBuf {
+        storage_path
+            .split('/')
+            .fold(self.root.clone(), |path, part| path.join(part))
+    }
+
+    /// Where content with this hash is kept; the extension is taken
+    /// from `filename` so opened files get the right application.
+    pub fn storage_path_for(sha256: &str, filename: &str) -> String {
+        match extension_of(Path::new(filename)) {
+            Some(ext) => format!("{}/{}.{}", &sha256[..2], sha256, ext),
+            None => format!("{}/{}", &sha256[..2], sha256),
+        }
+    }
+
+    /// Copies `source` to `storage_path` unless a file is already there.
+    pub fn put(&self, source: &Path, storage_path: &str) -> io::Result<()> {
+        let target = self.path_of(storage_path);
+        if target.exists() {
+            return Ok(());
+        }
+
+        let dir = target.parent().unwrap_or(&self.root);
+        fs::create_dir_all(dir)?;
+
+        // Copy under a temporary name so a crash never leaves a
+        // truncated file at the final path
+        let file_name = target.file_name().unwrap_or_default().to_string_lossy();
+        let temp = dir.join(format!("{}{}", file_name, TEMP_SUFFIX));
+        fs::copy(source, &temp)?;
+        fs::rename(&temp, &target)
+    }
+
+    /// Removes a stored file; a file that is already gone is not an error.
+    pub fn remove(&self, storage_path: &str) -> io::Result<()> {
+        match fs::remove_file(self.path_of(storage_path)) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        }
+    }
+
+    /// Deletes files not listed in `known` and older than `min_age`.
+    /// The age check spares files whose metadata is still being written.
+    pub fn sweep(&self, known: &HashSet<String>, min_age: Duration) -> io::Result<Vec<String>> {
+        let mut removed = Vec::new();
+        if !self.root.exists() {
+            return Ok(removed);
+        }
+
+        let now = SystemTime::now();
+        for dir in fs::read_dir(&self.root)? {
+            let dir = dir?;
+            if !dir.file_type()?.is_dir() {
+                continue;
+            }
+            let prefix = dir.file_name().to_string_lossy().into_owned();
+
+            for file in fs::read_dir(dir.path())? {
+                let file = file?;
+                let name = file.file_name().to_string_lossy().into_owned();
+                let storage_path = format!("{}/{}", prefix, name);
+                if name.ends_with(TEMP_SUFFIX) || known.contains(&storage_path) {
+                    continue;
+                }
+
+                let age = file
+                    .metadata()?
+                    .modified()
+                    .ok()
+                    .and_then(|m| now.duration_since(m).ok())
+                    .unwrap_or_default();
+                if age >= min_age {
+                    fs::remove_file(file.path())?;
+                    removed.push(storage_path);
+                }
+            }
+        }
+
+        Ok(removed)
+    }
+}
+
+/// Hex sha256 and size of a file, read in chunks.
+pub fn hash_file(path: &Path) -> io::Result<(String, u64)> {
+    let mut file = File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = [0u8; 64 * 1024];
+    let mut size = 0u64;
+
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+        size += read as u64;
+    }
+
+    let hex = hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+    Ok((hex, size))
+}
+
+/// Lowercased extension, if it is short and plain enough to keep.
+fn extension_of(path: &Path) -> Option<String> {
+    let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+    let plain =
+        !ext.is_empty() && ext.len() <= 10 && ext.chars().all(|c| c.is_ascii_alphanumeric());
+    plain.then_some(ext)
+}
+
+/// Best-effort MIME type from the file name.
+pub fn guess_mime_type(filename: &str) -> &'static str {
+    let ext = extension_of(Path::new(filename)).unwrap_or_default();
+    match ext.as_str() {
+        "pdf" => "application/pdf",
+        "doc" => "application/msword",
+        "docx" => "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+        "odt" => "application/vnd.oasis.opendocument.text",
+        "rtf" => "application/rtf",
+        "txt" => "text/plain",
+        "md" => "text/markdown",
+        "html" | "htm" => "text/html",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "zip" => "application/zip",
+        _ => "application/octet-stream",
+    }
+}
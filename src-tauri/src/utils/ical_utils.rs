@@ -0,0 +1,218 @@
+use chrono::{Duration, NaiveDate, NaiveDateTime, Offset, TimeZone};
+use chrono_tz::{OffsetComponents, OffsetName, Tz};
+
+/// Step used to look for offset changes; zones never change twice
+/// within it.
+const TRANSITION_SCAN_STEP: Duration = Duration::hours(6);
+
+/// Minimal RFC 5545 writer: CRLF line endings, text escaping and
+/// line folding at 75 octets.
+///
+/// Example:
+/// ```
+/// use jobtrackr_lib::utils::ical_utils::IcsBuilder;
+///
+/// let mut ics = IcsBuilder::calendar("-//Example//EN", "Example");
+/// ics.begin("VTODO");
+/// ics.text("SUMMARY", "Call back, then; relax");
+/// ics.end("VTODO");
+/// let text = ics.finish();
+/// assert!(text.contains("SUMMARY:Call back\\, then\\; relax\r\n"));
+/// ```
+pub struct IcsBuilder {
+    out: String,
+}
+
+impl IcsBuilder {
+    /// Starts a VCALENDAR with the given PRODID and display name.
+    pub fn calendar(prod_id: &str, name: &str) -> Self {
+        let mut builder = Self { out: String::new() };
+        builder.begin("VCALENDAR");
+        builder.raw("VERSION", "2.0");
+        builder.raw("PRODID", prod_id);
+        builder.raw("CALSCALE", "GREGORIAN");
+        builder.raw("METHOD", "PUBLISH");
+        builder.text("X-WR-CALNAME", name);
+        builder
+    }
+
+    pub fn begin(&mut self, component: &str) {
+        self.raw("BEGIN", component);
+    }
+
+    pub fn end(&mut self, component: &str) {
+        self.raw("END", component);
+    }
+
+    /// Writes a property whose value is already valid iCalendar syntax.
+    pub fn raw(&mut self, name: &str, value: &str) {
+        self.line(&format!("{}:{}", name, value));
+    }
+
+    /// Writes a TEXT property, escaping the value.
+    pub fn text(&mut self, name: &str, value: &str) {
+        self.raw(name, &escape_text(value));
+    }
+
+    /// Writes an all-day DATE property.
+    pub fn date(&mut self, name: &str, date: NaiveDate) {
+        self.raw(
+            &format!("{};VALUE=DATE", name),
+            &date.format("%Y%m%d").to_string(),
+        );
+    }
+
+    /// Writes a DATE-TIME property; the value must already be in UTC.
+    pub fn utc(&mut self, name: &str, at: NaiveDateTime) {
+        self.raw(name, &at.format("%Y%m%dT%H%M%SZ").to_string());
+    }
+
+    /// Writes a local DATE-TIME property: in `tzid` if given, floating
+    /// (the viewer's time zone) otherwise. A `tzid` needs a matching
+    /// [`IcsBuilder::timezone`] in the calendar.
+    pub fn local(&mut self, name: &str, at: NaiveDateTime, tzid: Option<&str>) {
+        let name = match tzid {
+            Some(tzid) => format!("{};TZID={}", name, tzid),
+            None => name.to_string(),
+        };
+        self.raw(&name, &at.format("%Y%m%dT%H%M%S").to_string());
+    }
+
+    /// Writes a VTIMEZONE for `tz` listing every change of its UTC offset
+    /// between `from` and `to`, so clients resolve `TZID` in that range
+    /// without knowing the zone.
+    pub fn timezone(&mut self, tz: Tz, from: NaiveDate, to: NaiveDate) {
+        let start = from.and_time(Default::default());
+        let end = to.and_time(Default::default());
+
+        self.begin("VTIMEZONE");
+        self.raw("TZID", tz.name());
+        let mut state = ZoneState::at(tz, start);
+        self.observance(start, &state, &state);
+
+        let mut at = start;
+        while at < end {
+            let next = at + TRANSITION_SCAN_STEP;
+            let next_state = ZoneState::at(tz, next);
+            if next_state != state {
+                let onset = find_transition(tz, at, next, &state);
+                self.observance(onset, &state, &next_state);
+                state = next_state;
+            }
+            at = next;
+        }
+        self.end("VTIMEZONE");
+    }
+
+    /// One STANDARD or DAYLIGHT sub-component, starting at the UTC
+    /// instant `onset`.
+    fn observance(&mut self, onset: NaiveDateTime, before: &ZoneState, after: &ZoneState) {
+        let component = if after.dst { "DAYLIGHT" } else { "STANDARD" };
+        self.begin(component);
+        // Local time under the offset in effect until then
+        let local = onset + Duration::seconds(before.offset.into());
+        self.raw("DTSTART", &local.format("%Y%m%dT%H%M%S").to_string());
+        self.raw("TZOFFSETFROM", &format_offset(before.offset));
+        self.raw("TZOFFSETTO", &format_offset(after.offset));
+        if let Some(name) = &after.name {
+            self.text("TZNAME", name);
+        }
+        self.end(component);
+    }
+
+    pub fn finish(mut self) -> String {
+        self.end("VCALENDAR");
+        self.out
+    }
+
+    fn line(&mut self, line: &str) {
+        self.out.push_str(&fold_line(line));
+        self.out.push_str("\r\n");
+    }
+}
+
+/// Escapes `\`, `;`, `,` and newlines in a TEXT value.
+pub fn escape_text(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            ';' => escaped.push_str("\\;"),
+            ',' => escaped.push_str("\\,"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => {}
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Folds a content line so no physical line exceeds 75 octets,
+/// never splitting a UTF-8 character.
+pub fn fold_line(line: &str) -> String {
+    const LIMIT: usize = 75;
+
+    let mut folded = String::with_capacity(line.len() + line.len() / LIMIT * 3);
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > LIMIT {
+            folded.push_str("\r\n ");
+            // The leading space counts towards the next line
+            width = 1;
+        }
+        folded.push(c);
+        width += c.len_utf8();
+    }
+    folded
+}
+
+/// The offset a zone is on at one instant.
+#[derive(Debug, PartialEq, Eq)]
+struct ZoneState {
+    /// Seconds east of UTC
+    offset: i32,
+    dst: bool,
+    name: Option<String>,
+}
+
+impl ZoneState {
+    fn at(tz: Tz, utc: NaiveDateTime) -> Self {
+        let offset = tz.offset_from_utc_datetime(&utc);
+        ZoneState {
+            offset: offset.fix().local_minus_utc(),
+            dst: !offset.dst_offset().is_zero(),
+            name: offset.abbreviation().map(str::to_string),
+        }
+    }
+}
+
+/// First second after `after` (still on `state`) that `tz` is on another
+/// state, given it is by `by`.
+fn find_transition(
+    tz: Tz,
+    mut after: NaiveDateTime,
+    mut by: NaiveDateTime,
+    state: &ZoneState,
+) -> NaiveDateTime {
+    while by - after > Duration::seconds(1) {
+        let mid = after + (by - after) / 2;
+        if ZoneState::at(tz, mid) == *state {
+            after = mid;
+        } else {
+            by = mid;
+        }
+    }
+    by
+}
+
+/// `+0100`, `-0330`; seconds only when there are any.
+fn format_offset(seconds: i32) -> String {
+    let sign = if seconds < 0 { '-' } else { '+' };
+    let seconds = seconds.unsigned_abs();
+    let (h, m, s) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+    if s == 0 {
+        format!("{}{:02}{:02}", sign, h, m)
+    } else {
+        format!("{}{:02}{:02}{:02}", sign, h, m, s)
+    }
+}
@@ -1 +1,5 @@
+pub mod file_store;
+pub mod ical_utils;
+pub mod recurrence;
+pub mod similarity;
 pub mod sql_utils;
@@ -0,0 +1,231 @@
+//! Reminder schedules: RRULE-style recurrence and wall-clock times.
+//!
+//! Only the subset the UI offers is understood: `FREQ` (DAILY, WEEKLY,
+//! MONTHLY), `INTERVAL` and an end given by either `UNTIL` or `COUNT`.
+//! As in RFC 5545, the start date is always the first occurrence and
+//! monthly rules skip months that lack the start's day (no Feb 31).
+
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime, TimeZone};
+use chrono_tz::Tz;
+use std::fmt;
+use std::str::FromStr;
+
+/// Longest accepted `INTERVAL`.
+pub const MAX_INTERVAL: u32 = 999;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecurrenceEnd {
+    Never,
+    /// Last possible date, inclusive
+    Until(NaiveDate),
+    /// Occurrences left, counting the start
+    Count(u32),
+}
+
+/// Example:
+/// ```
+/// use chrono::NaiveDate;
+/// use jobtrackr_lib::utils::recurrence::Recurrence;
+///
+/// let rule: Recurrence = "FREQ=WEEKLY;INTERVAL=2;COUNT=3".parse().unwrap();
+/// let start = NaiveDate::from_ymd_opt(2025, 3, 3).unwrap();
+/// let dates: Vec<_> = rule.occurrences(start).collect();
+/// assert_eq!(dates.len(), 3);
+/// assert_eq!(dates[2], NaiveDate::from_ymd_opt(2025, 3, 31).unwrap());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Recurrence {
+    pub frequency: Frequency,
+    pub interval: u32,
+    pub end: RecurrenceEnd,
+}
+
+impl Recurrence {
+    /// Occurrence dates from `start` on, in order.
+    pub fn occurrences(&self, start: NaiveDate) -> impl Iterator<Item = NaiveDate> {
+        let rule = *self;
+        let limit = match rule.end {
+            RecurrenceEnd::Count(n) => n as usize,
+            _ => usize::MAX,
+        };
+
+        (0u32..)
+            .map_while(move |step| rule.step(start, step))
+            .flatten()
+            .take_while(move |date| match rule.end {
+                RecurrenceEnd::Until(until) => *date <= until || *date == start,
+                _ => true,
+            })
+            .take(limit)
+    }
+
+    /// Occurrences from `start` on that fall within `from..=to`.
+    pub fn occurrences_between(
+        &self,
+        start: NaiveDate,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Vec<NaiveDate> {
+        self.occurrences(start)
+            .take_while(|date| *date <= to)
+            .filter(|date| *date >= from)
+            .collect()
+    }
+
+    /// The occurrence after `start`, with the rule that continues from it.
+    pub fn advance(&self, start: NaiveDate) -> Option<(NaiveDate, Recurrence)> {
+        let next = self.occurrences(start).nth(1)?;
+        let end = match self.end {
+            RecurrenceEnd::Count(n) => RecurrenceEnd::Count(n - 1),
+            end => end,
+        };
+        Some((next, Recurrence { end, ..*self }))
+    }
+
+    /// Date of the `step`-th repetition; `Some(None)` when that month
+    /// has no such day, `None` once dates run out.
+    fn step(&self, start: NaiveDate, step: u32) -> Option<Option<NaiveDate>> {
+        let n = step.checked_mul(self.interval)?;
+        match self.frequency {
+            Frequency::Daily => start.checked_add_signed(Duration::days(n as i64)).map(Some),
+            Frequency::Weekly => start
+                .checked_add_signed(Duration::weeks(n as i64))
+                .map(Some),
+            Frequency::Monthly => {
+                let months = start.month0() as i64 + n as i64;
+                let year = i32::try_from(start.year() as i64 + months / 12).ok()?;
+                let month = (months % 12) as u32 + 1;
+                // Out of chrono's range
+                NaiveDate::from_ymd_opt(year, month, 1)?;
+                Some(NaiveDate::from_ymd_opt(year, month, start.day()))
+            }
+        }
+    }
+}
+
+impl FromStr for Recurrence {
+    type Err = String;
+
+    /// Parses `FREQ=...;INTERVAL=...;UNTIL=...|COUNT=...`, optionally
+    /// prefixed with `RRULE:`. Keys and values are case-insensitive.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let body = s
+            .get(..6)
+            .filter(|prefix| prefix.eq_ignore_ascii_case("RRULE:"))
+            .map_or(s, |_| &s[6..]);
+
+        let mut frequency = None;
+        let mut interval = 1;
+        let mut until = None;
+        let mut count = None;
+
+        for part in body.split(';').map(str::trim).filter(|p| !p.is_empty()) {
+            let (key, value) = part
+                .split_once('=')
+                .ok_or_else(|| format!("'{}' is not KEY=VALUE", part))?;
+            let value = value.trim();
+            match key.trim().to_ascii_uppercase().as_str() {
+                "FREQ" => {
+                    frequency = Some(match value.to_ascii_uppercase().as_str() {
+                        "DAILY" => Frequency::Daily,
+                        "WEEKLY" => Frequency::Weekly,
+                        "MONTHLY" => Frequency::Monthly,
+                        other => {
+                            return Err(format!(
+                                "FREQ must be DAILY, WEEKLY or MONTHLY, got '{}'",
+                                other
+                            ))
+                        }
+                    })
+                }
+                "INTERVAL" => {
+                    interval = value
+                        .parse()
+                        .ok()
+                        .filter(|n| (1..=MAX_INTERVAL).contains(n))
+                        .ok_or_else(|| format!("INTERVAL must be between 1 and {}", MAX_INTERVAL))?
+                }
+                "UNTIL" => until = Some(parse_until(value)?),
+                "COUNT" => {
+                    count = Some(
+                        value
+                            .parse()
+                            .ok()
+                            .filter(|n| *n >= 1)
+                            .ok_or("COUNT must be a positive number")?,
+                    )
+                }
+                other => return Err(format!("{} is not supported", other)),
+            }
+        }
+
+        let end = match (until, count) {
+            (Some(_), Some(_)) => return Err("use either UNTIL or COUNT, not both".into()),
+            (Some(date), None) => RecurrenceEnd::Until(date),
+            (None, Some(n)) => RecurrenceEnd::Count(n),
+            (None, None) => RecurrenceEnd::Never,
+        };
+
+        Ok(Recurrence {
+            frequency: frequency.ok_or("FREQ is required")?,
+            interval,
+            end,
+        })
+    }
+}
+
+/// Accepts `YYYYMMDD`, `YYYYMMDDTHHMMSS[Z]` (time ignored) and `YYYY-MM-DD`.
+fn parse_until(value: &str) -> Result<NaiveDate, String> {
+    let date = value.split(['T', 't']).next().unwrap_or_default();
+    NaiveDate::parse_from_str(date, "%Y%m%d")
+        .or_else(|_| NaiveDate::parse_from_str(date, "%Y-%m-%d"))
+        .map_err(|_| format!("UNTIL must be a date (YYYYMMDD), got '{}'", value))
+}
+
+/// Canonical form, as stored in the database.
+impl fmt::Display for Recurrence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let frequency = match self.frequency {
+            Frequency::Daily => "DAILY",
+            Frequency::Weekly => "WEEKLY",
+            Frequency::Monthly => "MONTHLY",
+        };
+        write!(f, "FREQ={};INTERVAL={}", frequency, self.interval)?;
+        match self.end {
+            RecurrenceEnd::Never => Ok(()),
+            RecurrenceEnd::Until(date) => write!(f, ";UNTIL={}", date.format("%Y%m%d")),
+            RecurrenceEnd::Count(n) => write!(f, ";COUNT={}", n),
+        }
+    }
+}
+
+// ======================================================
+// Wall-clock times
+// ======================================================
+pub fn parse_timezone(name: &str) -> Result<Tz, String> {
+    name.trim()
+        .parse()
+        .map_err(|_| format!("'{}' is not an IANA time zone", name.trim()))
+}
+
+/// Converts a wall-clock time in `tz` to UTC. Ambiguous times (clocks
+/// going back) take the earlier instant; skipped ones (clocks going
+/// forward) are moved past the gap.
+pub fn wall_clock_to_utc<T: TimeZone>(tz: &T, date: NaiveDate, time: NaiveTime) -> NaiveDateTime {
+    let local = date.and_time(time);
+    (0..=3)
+        .find_map(|hours| {
+            tz.from_local_datetime(&(local + Duration::hours(hours)))
+                .earliest()
+        })
+        .map(|at| at.naive_utc())
+        .unwrap_or(local)
+}
@@ -0,0 +1,140 @@
+//! Normalization and fuzzy matching used to spot duplicate companies and
+//! persons.
+
+/// Legal forms and filler words that do not tell companies apart.
+const COMPANY_SUFFIXES: [&str; 22] = [
+    "ab",
+    "ag",
+    "aps",
+    "as",
+    "bv",
+    "co",
+    "company",
+    "corp",
+    "corporation",
+    "gmbh",
+    "group",
+    "inc",
+    "incorporated",
+    "kg",
+    "limited",
+    "llc",
+    "ltd",
+    "nv",
+    "oy",
+    "plc",
+    "sa",
+    "the",
+];
+
+/// Top-level domains stripped when a name is written as a domain.
+const NAME_TLDS: [&str; 8] = ["com", "io", "net", "org", "de", "dk", "co", "ai"];
+
+/// Lowercase words, everything else dropped.
+fn words(s: &str) -> Vec<String> {
+    s.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Comparable form of a company name: "ACME GmbH", "Acme, Inc." and
+/// "acme.com" all become "acme".
+pub fn normalize_company_name(name: &str) -> String {
+    let mut words = words(name);
+    // "acme.com" -> "acme"
+    if words.len() == 2 && NAME_TLDS.contains(&words[1].as_str()) && name.contains('.') {
+        words.pop();
+    }
+    let kept: Vec<_> = words
+        .iter()
+        .filter(|w| !COMPANY_SUFFIXES.contains(&w.as_str()))
+        .map(String::as_str)
+        .collect();
+    if kept.is_empty() {
+        words.join(" ")
+    } else {
+        kept.join(" ")
+    }
+}
+
+/// Comparable form of a person's name; first and last name in either order
+/// give the same result.
+pub fn normalize_person_name(first_name: &str, last_name: &str) -> String {
+    let mut words = words(&format!("{} {}", first_name, last_name));
+    words.sort();
+    words.join(" ")
+}
+
+/// Host of a website without scheme, `www.`, port or path:
+/// `https://www.Acme.com/jobs` -> `acme.com`.
+pub fn website_domain(url: &str) -> Option<String> {
+    let url = url.trim().to_lowercase();
+    let rest = url.split_once("://").map_or(url.as_str(), |(_, r)| r);
+    let host = rest.split(['/', '?', '#', ':']).next().unwrap_or("");
+    let host = host.strip_prefix("www.").unwrap_or(host);
+    host.contains('.').then(|| host.to_string())
+}
+
+/// First label of a domain, comparable with a normalized name:
+/// `acme.co.uk` -> `acme`.
+pub fn domain_stem(domain: &str) -> &str {
+    domain.split('.').next().unwrap_or(domain)
+}
+
+/// Digits of a phone number, `None` if too short to identify anyone. A
+/// leading `00` is treated like `+`.
+pub fn phone_digits(phone: &str) -> Option<String> {
+    let digits: String = phone.chars().filter(char::is_ascii_digit).collect();
+    let digits = digits
+        .strip_prefix("00")
+        .map(str::to_string)
+        .unwrap_or(digits);
+    (digits.len() >= 7).then_some(digits)
+}
+
+/// Whether two phone numbers are the same, allowing one of them to lack the
+/// country code.
+pub fn same_phone(a: &str, b: &str) -> bool {
+    match (phone_digits(a), phone_digits(b)) {
+        (Some(a), Some(b)) => a == b || a.ends_with(&b) || b.ends_with(&a),
+        _ => false,
+    }
+}
+
+pub fn normalize_email(email: &str) -> Option<String> {
+    let email = email.trim().to_lowercase();
+    email.contains('@').then_some(email)
+}
+
+/// Profile handle of a LinkedIn URL: `https://www.linkedin.com/in/Jane-Doe/`
+/// -> `jane-doe`.
+pub fn linkedin_handle(url: &str) -> Option<String> {
+    let url = url.trim().to_lowercase();
+    let (_, rest) = url.split_once("/in/")?;
+    let handle = rest.split(['/', '?', '#']).next().unwrap_or("");
+    (!handle.is_empty()).then(|| handle.to_string())
+}
+
+/// Edit-distance similarity between 0 (nothing in common) and 1 (equal).
+pub fn similarity(a: &str, b: &str) -> f64 {
+    let (a, b): (Vec<char>, Vec<char>) = (a.chars().collect(), b.chars().collect());
+    let longest = a.len().max(b.len());
+    if longest == 0 {
+        return 1.0;
+    }
+
+    // Levenshtein distance, one row at a time
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.iter().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(ca != cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    1.0 - row[b.len()] as f64 / longest as f64
+}
@@ -1,38 +1,111 @@
-use chrono::Utc;
+use crate::db::models::patch::Patch;
+use chrono::{NaiveDate, NaiveTime, Utc};
+use sqlx::query::QueryAs;
+use sqlx::sqlite::SqliteArguments;
+use sqlx::Sqlite;
+
+/// A typed bind parameter.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SqlValue {
+    Integer(i64),
+    Real(f64),
+    Text(String),
+}
+
+impl From<i64> for SqlValue {
+    fn from(v: i64) -> Self {
+        SqlValue::Integer(v)
+    }
+}
+
+impl From<f64> for SqlValue {
+    fn from(v: f64) -> Self {
+        SqlValue::Real(v)
+    }
+}
+
+impl From<bool> for SqlValue {
+    fn from(v: bool) -> Self {
+        SqlValue::Integer(v as i64)
+    }
+}
+
+impl From<&str> for SqlValue {
+    fn from(v: &str) -> Self {
+        SqlValue::Text(v.to_string())
+    }
+}
+
+impl From<String> for SqlValue {
+    fn from(v: String) -> Self {
+        SqlValue::Text(v)
+    }
+}
+
+/// Dates are stored as `YYYY-MM-DD` text.
+impl From<&NaiveDate> for SqlValue {
+    fn from(v: &NaiveDate) -> Self {
+        SqlValue::Text(v.format("%Y-%m-%d").to_string())
+    }
+}
+
+/// Times are stored as `HH:MM:SS` text.
+impl From<&NaiveTime> for SqlValue {
+    fn from(v: &NaiveTime) -> Self {
+        SqlValue::Text(v.format("%H:%M:%S").to_string())
+    }
+}
 
 /// Builds a dynamic UPDATE SQL statement and parameter list for partial updates.
 ///
+/// `Unchanged` fields are skipped, `Clear` sets the column to NULL and
+/// `Set` binds the typed value.
+///
 /// Returns:
-/// `(sql, Vec<String>)` where `sql` is the full UPDATE query,
+/// `(sql, Vec<SqlValue>)` where `sql` is the full UPDATE query,
 /// and the Vec contains bound values in correct order.
 ///
 /// Example:
 /// ```
-/// use jobtrackr_lib::utils::sql_utils::build_update_sql;
+/// use jobtrackr_lib::db::models::patch::Patch;
+/// use jobtrackr_lib::utils::sql_utils::{build_update_sql, SqlValue};
 ///
-/// let fields = vec![("first_name", Some("Chris")), ("email", None)];
+/// let fields = vec![
+///     ("first_name", Patch::Set(SqlValue::from("Chris"))),
+///     ("email", Patch::Unchanged),
+///     ("company_id", Patch::Clear),
+/// ];
 /// let (sql, binds) = build_update_sql("user", "id", 42, fields);
+/// assert_eq!(
+///     sql,
+///     "UPDATE user SET first_name = ?, company_id = NULL, updated_at = ? WHERE id = ? RETURNING *"
+/// );
+/// assert_eq!(binds.len(), 3);
 /// ```
 pub fn build_update_sql(
     table: &str,
     id_field: &str,
     id: i64,
-    fields: Vec<(&str, Option<&str>)>,
-) -> (String, Vec<String>) {
+    fields: Vec<(&str, Patch<SqlValue>)>,
+) -> (String, Vec<SqlValue>) {
     let mut set_clauses = Vec::new();
     let mut binds = Vec::new();
 
     for (col, val) in fields {
-        if let Some(v) = val {
-            set_clauses.push(format!("{} = ?", col));
-            binds.push(v.to_string());
+        match val {
+            Patch::Unchanged => {}
+            Patch::Clear => set_clauses.push(format!("{} = NULL", col)),
+            Patch::Set(v) => {
+                set_clauses.push(format!("{} = ?", col));
+                binds.push(v);
+            }
         }
     }
 
     // Always update timestamp
     set_clauses.push("updated_at = ?".to_string());
     let now = Utc::now().naive_utc().to_string();
-    binds.push(now);
+    binds.push(SqlValue::Text(now));
 
     // Final SQL
     let sql = format!(
@@ -43,7 +116,22 @@ pub fn build_update_sql(
     );
 
     // Add ID as last parameter
-    binds.push(id.to_string());
+    binds.push(SqlValue::Integer(id));
 
     (sql, binds)
 }
+
+/// Binds values produced by `build_update_sql` in order.
+pub fn bind_sql_values<'q, O>(
+    mut query: QueryAs<'q, Sqlite, O, SqliteArguments<'q>>,
+    values: &'q [SqlValue],
+) -> QueryAs<'q, Sqlite, O, SqliteArguments<'q>> {
+    for value in values {
+        query = match value {
+            SqlValue::Integer(i) => query.bind(*i),
+            SqlValue::Real(r) => query.bind(*r),
+            SqlValue::Text(t) => query.bind(t.as_str()),
+        };
+    }
+    query
+}
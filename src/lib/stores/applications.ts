@@ -1,7 +1,11 @@
 import { writable } from "svelte/store";
 import { invoke } from "@tauri-apps/api/core";
 import type { Application } from "$lib/types/application";
-import type { BackendResponse } from "$lib/types/backendResponse";
+import {
+  isAppError,
+  logCommandError,
+  type BackendResponse,
+} from "$lib/types/backendResponse";
 
 export const applications = writable<Application[]>([]);
 
@@ -11,16 +15,16 @@ export const applications = writable<Application[]>([]);
  * ---------------------------------------------------------------------
  */
 export async function loadApplications() {
-  const raw = await invoke<string>("handle_application_command", {
-    command: { action: "ListAll" },
-  });
+  try {
+    const res = await invoke<BackendResponse<Application[]>>("handle_application_command", {
+      command: { action: "ListAll" },
+    });
 
-  const res = JSON.parse(raw) as BackendResponse<Application[]>;
-
-  if (res.status === "success" && res.data) {
-    applications.set(res.data);
-  } else {
-    console.error(res.message);
+    if (res.data) {
+      applications.set(res.data);
+    }
+  } catch (e) {
+    logCommandError("loadApplications", e);
   }
 }
 
@@ -30,12 +34,18 @@ export async function loadApplications() {
  * ---------------------------------------------------------------------
  */
 export async function loadApplicationById(id: number) {
-  const raw = await invoke<string>("handle_application_command", {
-    command: { action: "GetById", payload: { id } },
-  });
-
-  const res = JSON.parse(raw) as BackendResponse<Application>;
-  return res.status === "success" ? (res.data ?? null) : null;
+  try {
+    const res = await invoke<BackendResponse<Application>>("handle_application_command", {
+      command: { action: "GetById", payload: { id } },
+    });
+    return res.data ?? null;
+  } catch (e) {
+    // A missing application is an expected answer, not a failure
+    if (!(isAppError(e) && e.kind === "notFound")) {
+      logCommandError("loadApplicationById", e);
+    }
+    return null;
+  }
 }
 
 /**
@@ -46,18 +56,16 @@ export async function loadApplicationById(id: number) {
 export async function createApplication(
   payload: Omit<Application, "id" | "createdAt" | "updatedAt">,
 ): Promise<Application> {
-  const raw = await invoke<string>("handle_application_command", {
-    command: { action: "Create", payload },
-  });
+  try {
+    const res = await invoke<BackendResponse<Application>>("handle_application_command", {
+      command: { action: "Create", payload },
+    });
 
-  const res = JSON.parse(raw) as BackendResponse<Application>;
-
-  if (res.status === "success" && res.data) {
     applications.update((list) => [...list, res.data!]);
-    return res.data;
-  } else {
-    console.error(res.message);
-    throw new Error(res.message);
+    return res.data!;
+  } catch (e) {
+    logCommandError("createApplication", e);
+    throw e;
   }
 }
 
@@ -70,18 +78,21 @@ export async function updateApplication(
   id: number,
   updates: Partial<Application>,
 ) {
-  const raw = await invoke<string>("handle_application_command", {
-    command: { action: "Update", payload: { id, ...updates } },
-  });
-
-  const res = JSON.parse(raw) as BackendResponse<Application>;
+  try {
+    const res = await invoke<BackendResponse<Application>>("handle_application_command", {
+      command: { action: "Update", payload: { id, ...updates } },
+    });
 
-  if (res.status === "success" && res.data) {
-    applications.update((list) =>
-      list.map((a) => (a.id === id ? res.data! : a)),
-    );
-  } else {
-    console.error(res.message);
+    if (res.data) {
+      applications.update((list) =>
+        list.map((a) => (a.id === id ? res.data! : a)),
+      );
+    }
+  } catch (e) {
+    if (isAppError(e) && e.kind === "notFound") {
+      applications.update((list) => list.filter((a) => a.id !== id));
+    }
+    logCommandError("updateApplication", e);
   }
 }
 
@@ -91,15 +102,16 @@ export async function updateApplication(
  * ---------------------------------------------------------------------
  */
 export async function deleteApplication(id: number) {
-  const raw = await invoke<string>("handle_application_command", {
-    command: { action: "Delete", payload: { id } },
-  });
-
-  const res = JSON.parse(raw) as BackendResponse<null>;
+  try {
+    await invoke<BackendResponse<null>>("handle_application_command", {
+      command: { action: "Delete", payload: { id } },
+    });
 
-  if (res.status === "success") {
     applications.update((list) => list.filter((a) => a.id !== id));
-  } else {
-    console.error(res.message);
+  } catch (e) {
+    if (isAppError(e) && e.kind === "notFound") {
+      applications.update((list) => list.filter((a) => a.id !== id));
+    }
+    logCommandError("deleteApplication", e);
   }
 }
@@ -1,7 +1,11 @@
 import { writable } from "svelte/store";
 import { invoke } from "@tauri-apps/api/core";
 import type { Company } from "$lib/types/company";
-import type { BackendResponse } from "$lib/types/backendResponse";
+import {
+  isAppError,
+  logCommandError,
+  type BackendResponse,
+} from "$lib/types/backendResponse";
 
 /**
  * ---------------------------------------------------------------------
@@ -16,15 +20,16 @@ export const companies = writable<Company[]>([]);
  * ---------------------------------------------------------------------
  */
 export async function loadCompanies() {
-  const raw = await invoke<string>("handle_company_command", {
-    command: { action: "ListAll" },
-  });
-  const res = JSON.parse(raw) as BackendResponse<Company[]>;
+  try {
+    const res = await invoke<BackendResponse<Company[]>>("handle_company_command", {
+      command: { action: "ListAll" },
+    });
 
-  if (res.status === "success" && res.data) {
-    companies.set(res.data);
-  } else {
-    console.error(res.message);
+    if (res.data) {
+      companies.set(res.data);
+    }
+  } catch (e) {
+    logCommandError("loadCompanies", e);
   }
 }
 
@@ -36,17 +41,16 @@ export async function loadCompanies() {
 export async function createCompany(
   payload: Omit<Company, "id" | "createdAt" | "updatedAt">,
 ): Promise<Company> {
-  const raw = await invoke<string>("handle_company_command", {
-    command: { action: "Create", payload },
-  });
-  const res = JSON.parse(raw) as BackendResponse<Company>;
+  try {
+    const res = await invoke<BackendResponse<Company>>("handle_company_command", {
+      command: { action: "Create", payload },
+    });
 
-  if (res.status === "success" && res.data) {
     companies.update((list) => [...list, res.data!]);
-    return res.data; // Add this line
-  } else {
-    console.error(res.message);
-    throw new Error(res.message); // Also throw on error
+    return res.data!;
+  } catch (e) {
+    logCommandError("createCompany", e);
+    throw e;
   }
 }
 
@@ -56,15 +60,19 @@ export async function createCompany(
  * ---------------------------------------------------------------------
  */
 export async function updateCompany(id: number, updates: Partial<Company>) {
-  const raw = await invoke<string>("handle_company_command", {
-    command: { action: "Update", payload: { id, ...updates } },
-  });
-  const res = JSON.parse(raw) as BackendResponse<Company>;
+  try {
+    const res = await invoke<BackendResponse<Company>>("handle_company_command", {
+      command: { action: "Update", payload: { id, ...updates } },
+    });
 
-  if (res.status === "success" && res.data) {
-    companies.update((list) => list.map((c) => (c.id === id ? res.data! : c)));
-  } else {
-    console.error(res.message);
+    if (res.data) {
+      companies.update((list) => list.map((c) => (c.id === id ? res.data! : c)));
+    }
+  } catch (e) {
+    if (isAppError(e) && e.kind === "notFound") {
+      companies.update((list) => list.filter((c) => c.id !== id));
+    }
+    logCommandError("updateCompany", e);
   }
 }
 
@@ -74,14 +82,16 @@ export async function updateCompany(id: number, updates: Partial<Company>) {
  * ---------------------------------------------------------------------
  */
 export async function deleteCompany(id: number) {
-  const raw = await invoke<string>("handle_company_command", {
-    command: { action: "Delete", payload: { id } },
-  });
-  const res = JSON.parse(raw) as BackendResponse<null>;
+  try {
+    await invoke<BackendResponse<null>>("handle_company_command", {
+      command: { action: "Delete", payload: { id } },
+    });
 
-  if (res.status === "success") {
     companies.update((list) => list.filter((c) => c.id !== id));
-  } else {
-    console.error(res.message);
+  } catch (e) {
+    if (isAppError(e) && e.kind === "notFound") {
+      companies.update((list) => list.filter((c) => c.id !== id));
+    }
+    logCommandError("deleteCompany", e);
   }
 }
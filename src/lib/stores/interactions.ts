@@ -1,7 +1,11 @@
 import { writable } from "svelte/store";
 import { invoke } from "@tauri-apps/api/core";
 import type { Interaction } from "$lib/types/interaction";
-import type { BackendResponse } from "$lib/types/backendResponse";
+import {
+  isAppError,
+  logCommandError,
+  type BackendResponse,
+} from "$lib/types/backendResponse";
 
 /**
  * ---------------------------------------------------------------------
@@ -16,16 +20,16 @@ export const interactions = writable<Interaction[]>([]);
  * ---------------------------------------------------------------------
  */
 export async function loadInteractions() {
-  const raw = await invoke<string>("handle_interaction_command", {
-    command: { action: "ListAll" },
-  });
+  try {
+    const res = await invoke<BackendResponse<Interaction[]>>("handle_interaction_command", {
+      command: { action: "ListAll" },
+    });
 
-  const res = JSON.parse(raw) as BackendResponse<Interaction[]>;
-
-  if (res.status === "success" && res.data) {
-    interactions.set(res.data);
-  } else {
-    console.error(res.message);
+    if (res.data) {
+      interactions.set(res.data);
+    }
+  } catch (e) {
+    logCommandError("loadInteractions", e);
   }
 }
 
@@ -37,18 +41,16 @@ export async function loadInteractions() {
 export async function createInteraction(
   payload: Omit<Interaction, "id" | "createdAt" | "updatedAt">,
 ): Promise<Interaction> {
-  const raw = await invoke<string>("handle_interaction_command", {
-    command: { action: "Create", payload },
-  });
-
-  const res = JSON.parse(raw) as BackendResponse<Interaction>;
+  try {
+    const res = await invoke<BackendResponse<Interaction>>("handle_interaction_command", {
+      command: { action: "Create", payload },
+    });
 
-  if (res.status === "success" && res.data) {
     interactions.update((list) => [...list, res.data!]);
-    return res.data;
-  } else {
-    console.error(res.message);
-    throw new Error(res.message);
+    return res.data!;
+  } catch (e) {
+    logCommandError("createInteraction", e);
+    throw e;
   }
 }
 
@@ -61,18 +63,21 @@ export async function updateInteraction(
   id: number,
   updates: Partial<Interaction>,
 ) {
-  const raw = await invoke<string>("handle_interaction_command", {
-    command: { action: "Update", payload: { id, ...updates } },
-  });
+  try {
+    const res = await invoke<BackendResponse<Interaction>>("handle_interaction_command", {
+      command: { action: "Update", payload: { id, ...updates } },
+    });
 
-  const res = JSON.parse(raw) as BackendResponse<Interaction>;
-
-  if (res.status === "success" && res.data) {
-    interactions.update((list) =>
-      list.map((i) => (i.id === id ? res.data! : i)),
-    );
-  } else {
-    console.error(res.message);
+    if (res.data) {
+      interactions.update((list) =>
+        list.map((i) => (i.id === id ? res.data! : i)),
+      );
+    }
+  } catch (e) {
+    if (isAppError(e) && e.kind === "notFound") {
+      interactions.update((list) => list.filter((i) => i.id !== id));
+    }
+    logCommandError("updateInteraction", e);
   }
 }
 
@@ -82,15 +87,16 @@ export async function updateInteraction(
  * ---------------------------------------------------------------------
  */
 export async function deleteInteraction(id: number) {
-  const raw = await invoke<string>("handle_interaction_command", {
-    command: { action: "Delete", payload: { id } },
-  });
-
-  const res = JSON.parse(raw) as BackendResponse<null>;
+  try {
+    await invoke<BackendResponse<null>>("handle_interaction_command", {
+      command: { action: "Delete", payload: { id } },
+    });
 
-  if (res.status === "success") {
     interactions.update((list) => list.filter((i) => i.id !== id));
-  } else {
-    console.error(res.message);
+  } catch (e) {
+    if (isAppError(e) && e.kind === "notFound") {
+      interactions.update((list) => list.filter((i) => i.id !== id));
+    }
+    logCommandError("deleteInteraction", e);
   }
 }
@@ -1,7 +1,11 @@
 import { writable } from "svelte/store";
 import { invoke } from "@tauri-apps/api/core";
 import type { JobListing } from "$lib/types/jobListing";
-import type { BackendResponse } from "$lib/types/backendResponse";
+import {
+  isAppError,
+  logCommandError,
+  type BackendResponse,
+} from "$lib/types/backendResponse";
 
 /**
  * ---------------------------------------------------------------------
@@ -16,16 +20,16 @@ export const jobListings = writable<JobListing[]>([]);
  * ---------------------------------------------------------------------
  */
 export async function loadJobListings() {
-  const raw = await invoke<string>("handle_job_listing_command", {
-    command: { action: "ListAll" },
-  });
+  try {
+    const res = await invoke<BackendResponse<JobListing[]>>("handle_job_listing_command", {
+      command: { action: "ListAll" },
+    });
 
-  const res = JSON.parse(raw) as BackendResponse<JobListing[]>;
-
-  if (res.status === "success" && res.data) {
-    jobListings.set(res.data);
-  } else {
-    console.error(res.message);
+    if (res.data) {
+      jobListings.set(res.data);
+    }
+  } catch (e) {
+    logCommandError("loadJobListings", e);
   }
 }
 
@@ -37,18 +41,16 @@ export async function loadJobListings() {
 export async function createJobListing(
   payload: Omit<JobListing, "id" | "createdAt" | "updatedAt">,
 ): Promise<JobListing> {
-  const raw = await invoke<string>("handle_job_listing_command", {
-    command: { action: "Create", payload },
-  });
-
-  const res = JSON.parse(raw) as BackendResponse<JobListing>;
+  try {
+    const res = await invoke<BackendResponse<JobListing>>("handle_job_listing_command", {
+      command: { action: "Create", payload },
+    });
 
-  if (res.status === "success" && res.data) {
     jobListings.update((list) => [...list, res.data!]);
-    return res.data;
-  } else {
-    console.error(res.message);
-    throw new Error(res.message);
+    return res.data!;
+  } catch (e) {
+    logCommandError("createJobListing", e);
+    throw e;
   }
 }
 
@@ -61,18 +63,21 @@ export async function updateJobListing(
   id: number,
   updates: Partial<JobListing>,
 ) {
-  const raw = await invoke<string>("handle_job_listing_command", {
-    command: { action: "Update", payload: { id, ...updates } },
-  });
+  try {
+    const res = await invoke<BackendResponse<JobListing>>("handle_job_listing_command", {
+      command: { action: "Update", payload: { id, ...updates } },
+    });
 
-  const res = JSON.parse(raw) as BackendResponse<JobListing>;
-
-  if (res.status === "success" && res.data) {
-    jobListings.update((list) =>
-      list.map((l) => (l.id === id ? res.data! : l)),
-    );
-  } else {
-    console.error(res.message);
+    if (res.data) {
+      jobListings.update((list) =>
+        list.map((l) => (l.id === id ? res.data! : l)),
+      );
+    }
+  } catch (e) {
+    if (isAppError(e) && e.kind === "notFound") {
+      jobListings.update((list) => list.filter((l) => l.id !== id));
+    }
+    logCommandError("updateJobListing", e);
   }
 }
 
@@ -82,15 +87,16 @@ export async function updateJobListing(
  * ---------------------------------------------------------------------
  */
 export async function deleteJobListing(id: number) {
-  const raw = await invoke<string>("handle_job_listing_command", {
-    command: { action: "Delete", payload: { id } },
-  });
-
-  const res = JSON.parse(raw) as BackendResponse<null>;
+  try {
+    await invoke<BackendResponse<null>>("handle_job_listing_command", {
+      command: { action: "Delete", payload: { id } },
+    });
 
-  if (res.status === "success") {
     jobListings.update((list) => list.filter((l) => l.id !== id));
-  } else {
-    console.error(res.message);
+  } catch (e) {
+    if (isAppError(e) && e.kind === "notFound") {
+      jobListings.update((list) => list.filter((l) => l.id !== id));
+    }
+    logCommandError("deleteJobListing", e);
   }
 }
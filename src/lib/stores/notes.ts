@@ -1,7 +1,11 @@
 import { writable } from "svelte/store";
 import { invoke } from "@tauri-apps/api/core";
 import type { Note } from "$lib/types/note";
-import type { BackendResponse } from "$lib/types/backendResponse";
+import {
+  isAppError,
+  logCommandError,
+  type BackendResponse,
+} from "$lib/types/backendResponse";
 
 /**
  * ---------------------------------------------------------------------
@@ -16,16 +20,16 @@ export const notes = writable<Note[]>([]);
  * ---------------------------------------------------------------------
  */
 export async function loadNotes() {
-  const raw = await invoke<string>("handle_note_command", {
-    command: { action: "ListAll" },
-  });
+  try {
+    const res = await invoke<BackendResponse<Note[]>>("handle_note_command", {
+      command: { action: "ListAll" },
+    });
 
-  const res = JSON.parse(raw) as BackendResponse<Note[]>;
-
-  if (res.status === "success" && res.data) {
-    notes.set(res.data);
-  } else {
-    console.error(res.message);
+    if (res.data) {
+      notes.set(res.data);
+    }
+  } catch (e) {
+    logCommandError("loadNotes", e);
   }
 }
 
@@ -37,18 +41,16 @@ export async function loadNotes() {
 export async function createNote(
   payload: Omit<Note, "id" | "createdAt" | "updatedAt">,
 ): Promise<Note> {
-  const raw = await invoke<string>("handle_note_command", {
-    command: { action: "Create", payload },
-  });
-
-  const res = JSON.parse(raw) as BackendResponse<Note>;
+  try {
+    const res = await invoke<BackendResponse<Note>>("handle_note_command", {
+      command: { action: "Create", payload },
+    });
 
-  if (res.status === "success" && res.data) {
     notes.update((list) => [...list, res.data!]);
-    return res.data;
-  } else {
-    console.error(res.message);
-    throw new Error(res.message);
+    return res.data!;
+  } catch (e) {
+    logCommandError("createNote", e);
+    throw e;
   }
 }
 
@@ -58,16 +60,19 @@ export async function createNote(
  * ---------------------------------------------------------------------
  */
 export async function updateNote(id: number, updates: Partial<Note>) {
-  const raw = await invoke<string>("handle_note_command", {
-    command: { action: "Update", payload: { id, ...updates } },
-  });
+  try {
+    const res = await invoke<BackendResponse<Note>>("handle_note_command", {
+      command: { action: "Update", payload: { id, ...updates } },
+    });
 
-  const res = JSON.parse(raw) as BackendResponse<Note>;
-
-  if (res.status === "success" && res.data) {
-    notes.update((list) => list.map((l) => (l.id === id ? res.data! : l)));
-  } else {
-    console.error(res.message);
+    if (res.data) {
+      notes.update((list) => list.map((l) => (l.id === id ? res.data! : l)));
+    }
+  } catch (e) {
+    if (isAppError(e) && e.kind === "notFound") {
+      notes.update((list) => list.filter((l) => l.id !== id));
+    }
+    logCommandError("updateNote", e);
   }
 }
 
@@ -77,15 +82,16 @@ export async function updateNote(id: number, updates: Partial<Note>) {
  * ---------------------------------------------------------------------
  */
 export async function deleteNote(id: number) {
-  const raw = await invoke<string>("handle_note_command", {
-    command: { action: "Delete", payload: { id } },
-  });
-
-  const res = JSON.parse(raw) as BackendResponse<null>;
+  try {
+    await invoke<BackendResponse<null>>("handle_note_command", {
+      command: { action: "Delete", payload: { id } },
+    });
 
-  if (res.status === "success") {
     notes.update((list) => list.filter((l) => l.id !== id));
-  } else {
-    console.error(res.message);
+  } catch (e) {
+    if (isAppError(e) && e.kind === "notFound") {
+      notes.update((list) => list.filter((l) => l.id !== id));
+    }
+    logCommandError("deleteNote", e);
   }
 }
@@ -1,7 +1,11 @@
 import { writable } from "svelte/store";
 import { invoke } from "@tauri-apps/api/core";
 import type { Person } from "$lib/types/person";
-import type { BackendResponse } from "$lib/types/backendResponse";
+import {
+  isAppError,
+  logCommandError,
+  type BackendResponse,
+} from "$lib/types/backendResponse";
 
 /**
  * ---------------------------------------------------------------------
@@ -16,16 +20,16 @@ export const people = writable<Person[]>([]);
  * ---------------------------------------------------------------------
  */
 export async function loadPeople() {
-  const raw = await invoke<string>("handle_person_command", {
-    command: { action: "ListAll" },
-  });
+  try {
+    const res = await invoke<BackendResponse<Person[]>>("handle_person_command", {
+      command: { action: "ListAll" },
+    });
 
-  const res = JSON.parse(raw) as BackendResponse<Person[]>;
-
-  if (res.status === "success" && res.data) {
-    people.set(res.data);
-  } else {
-    console.error(res.message);
+    if (res.data) {
+      people.set(res.data);
+    }
+  } catch (e) {
+    logCommandError("loadPeople", e);
   }
 }
 
@@ -37,18 +41,16 @@ export async function loadPeople() {
 export async function createPerson(
   payload: Omit<Person, "id" | "createdAt" | "updatedAt">,
 ): Promise<Person> {
-  const raw = await invoke<string>("handle_person_command", {
-    command: { action: "Create", payload },
-  });
-
-  const res = JSON.parse(raw) as BackendResponse<Person>;
+  try {
+    const res = await invoke<BackendResponse<Person>>("handle_person_command", {
+      command: { action: "Create", payload },
+    });
 
-  if (res.status === "success" && res.data) {
     people.update((list) => [...list, res.data!]);
-    return res.data;
-  } else {
-    console.error(res.message);
-    throw new Error(res.message);
+    return res.data!;
+  } catch (e) {
+    logCommandError("createPerson", e);
+    throw e;
   }
 }
 
@@ -58,16 +60,19 @@ export async function createPerson(
  * ---------------------------------------------------------------------
  */
 export async function updatePerson(id: number, updates: Partial<Person>) {
-  const raw = await invoke<string>("handle_person_command", {
-    command: { action: "Update", payload: { id, ...updates } },
-  });
+  try {
+    const res = await invoke<BackendResponse<Person>>("handle_person_command", {
+      command: { action: "Update", payload: { id, ...updates } },
+    });
 
-  const res = JSON.parse(raw) as BackendResponse<Person>;
-
-  if (res.status === "success" && res.data) {
-    people.update((list) => list.map((p) => (p.id === id ? res.data! : p)));
-  } else {
-    console.error(res.message);
+    if (res.data) {
+      people.update((list) => list.map((p) => (p.id === id ? res.data! : p)));
+    }
+  } catch (e) {
+    if (isAppError(e) && e.kind === "notFound") {
+      people.update((list) => list.filter((p) => p.id !== id));
+    }
+    logCommandError("updatePerson", e);
   }
 }
 
@@ -77,15 +82,16 @@ export async function updatePerson(id: number, updates: Partial<Person>) {
  * ---------------------------------------------------------------------
  */
 export async function deletePerson(id: number) {
-  const raw = await invoke<string>("handle_person_command", {
-    command: { action: "Delete", payload: { id } },
-  });
-
-  const res = JSON.parse(raw) as BackendResponse<null>;
+  try {
+    await invoke<BackendResponse<null>>("handle_person_command", {
+      command: { action: "Delete", payload: { id } },
+    });
 
-  if (res.status === "success") {
     people.update((list) => list.filter((p) => p.id !== id));
-  } else {
-    console.error(res.message);
+  } catch (e) {
+    if (isAppError(e) && e.kind === "notFound") {
+      people.update((list) => list.filter((p) => p.id !== id));
+    }
+    logCommandError("deletePerson", e);
   }
 }
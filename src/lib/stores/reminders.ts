@@ -1,7 +1,13 @@
 import { writable } from "svelte/store";
-import { invoke } from "@tauri-apps/api/core";
-import type { Reminder } from "$lib/types/reminder";
-import type { BackendResponse } from "$lib/types/backendResponse";
+import { invoke, type PluginListener } from "@tauri-apps/api/core";
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
+import { onAction, registerActionTypes } from "@tauri-apps/plugin-notification";
+import type { DueReminder, Reminder, ReminderOccurrence } from "$lib/types/reminder";
+import {
+  isAppError,
+  logCommandError,
+  type BackendResponse,
+} from "$lib/types/backendResponse";
 
 /**
  * ---------------------------------------------------------------------
@@ -10,22 +16,63 @@ import type { BackendResponse } from "$lib/types/backendResponse";
  */
 export const reminders = writable<Reminder[]>([]);
 
+/** Reminders announced by the scheduler and not yet acted upon */
+export const dueReminders = writable<DueReminder[]>([]);
+
+/** How long the Snooze button / notification action postpones a reminder */
+export const SNOOZE_MINUTES = 10;
+
+/** Must match `REMINDER_ACTION_TYPE` in reminder_scheduler.rs */
+const REMINDER_ACTION_TYPE = "reminder-due";
+
 /**
  * ---------------------------------------------------------------------
  * Load all reminders
  * ---------------------------------------------------------------------
  */
 export async function loadReminders() {
-  const raw = await invoke<string>("handle_reminder_command", {
-    command: { action: "ListAll" },
-  });
+  try {
+    const res = await invoke<BackendResponse<Reminder[]>>("handle_reminder_command", {
+      command: { action: "ListAll" },
+    });
+
+    if (res.data) {
+      reminders.set(res.data);
+    }
+  } catch (e) {
+    logCommandError("loadReminders", e);
+  }
+}
 
-  const res = JSON.parse(raw) as BackendResponse<Reminder[]>;
+/**
+ * ---------------------------------------------------------------------
+ * Load occurrences of open reminders within a date window
+ * ---------------------------------------------------------------------
+ */
+export async function loadUpcomingReminders(
+  from?: string,
+  to?: string,
+): Promise<ReminderOccurrence[]> {
+  try {
+    const res = await invoke<BackendResponse<ReminderOccurrence[]>>("handle_reminder_command", {
+      command: { action: "ListUpcoming", payload: { from, to } },
+    });
 
-  if (res.status === "success" && res.data) {
-    reminders.set(res.data);
-  } else {
-    console.error(res.message);
+    return res.data ?? [];
+  } catch (e) {
+    logCommandError("loadUpcomingReminders", e);
+    return [];
+  }
+}
+
+/**
+ * Completing a recurring reminder creates its next occurrence,
+ * returned as `nextOccurrence`
+ */
+function addNextOccurrence(data: Reminder & { nextOccurrence?: Reminder }) {
+  const next = data.nextOccurrence;
+  if (next) {
+    reminders.update((list) => [...list, next]);
   }
 }
 
@@ -37,18 +84,18 @@ export async function loadReminders() {
 export async function createReminder(
   payload: Omit<Reminder, "id" | "createdAt" | "updatedAt">,
 ): Promise<Reminder> {
-  const raw = await invoke<string>("handle_reminder_command", {
-    command: { action: "Create", payload },
-  });
-
-  const res = JSON.parse(raw) as BackendResponse<Reminder>;
+  try {
+    const res = await invoke<BackendResponse<Reminder>>("handle_reminder_command", {
+      command: { action: "Create", payload },
+    });
 
-  if (res.status === "success" && res.data) {
-    reminders.update((list) => [...list, res.data!]);
-    return res.data;
-  } else {
-    console.error(res.message);
-    throw new Error(res.message);
+    const created = res.data!;
+    reminders.update((list) => [...list, created]);
+    addNextOccurrence(created);
+    return created;
+  } catch (e) {
+    logCommandError("createReminder", e);
+    throw e;
   }
 }
 
@@ -58,16 +105,20 @@ export async function createReminder(
  * ---------------------------------------------------------------------
  */
 export async function updateReminder(id: number, updates: Partial<Reminder>) {
-  const raw = await invoke<string>("handle_reminder_command", {
-    command: { action: "Update", payload: { id, ...updates } },
-  });
-
-  const res = JSON.parse(raw) as BackendResponse<Reminder>;
+  try {
+    const res = await invoke<BackendResponse<Reminder>>("handle_reminder_command", {
+      command: { action: "Update", payload: { id, ...updates } },
+    });
 
-  if (res.status === "success" && res.data) {
-    reminders.update((list) => list.map((l) => (l.id === id ? res.data! : l)));
-  } else {
-    console.error(res.message);
+    if (res.data) {
+      reminders.update((list) => list.map((l) => (l.id === id ? res.data! : l)));
+      addNextOccurrence(res.data);
+    }
+  } catch (e) {
+    if (isAppError(e) && e.kind === "notFound") {
+      reminders.update((list) => list.filter((l) => l.id !== id));
+    }
+    logCommandError("updateReminder", e);
   }
 }
 
@@ -77,15 +128,115 @@ export async function updateReminder(id: number, updates: Partial<Reminder>) {
  * ---------------------------------------------------------------------
  */
 export async function deleteReminder(id: number) {
-  const raw = await invoke<string>("handle_reminder_command", {
-    command: { action: "Delete", payload: { id } },
+  try {
+    await invoke<BackendResponse<null>>("handle_reminder_command", {
+      command: { action: "Delete", payload: { id } },
+    });
+
+    reminders.update((list) => list.filter((l) => l.id !== id));
+  } catch (e) {
+    if (isAppError(e) && e.kind === "notFound") {
+      reminders.update((list) => list.filter((l) => l.id !== id));
+    }
+    logCommandError("deleteReminder", e);
+  }
+}
+
+/**
+ * ---------------------------------------------------------------------
+ * Listen for reminders becoming due
+ * ---------------------------------------------------------------------
+ */
+export async function listenForDueReminders(): Promise<UnlistenFn> {
+  return listen<DueReminder>("reminder-due", (event) => {
+    dueReminders.update((list) => [
+      ...list.filter((r) => r.id !== event.payload.id),
+      event.payload,
+    ]);
   });
+}
 
-  const res = JSON.parse(raw) as BackendResponse<null>;
+/**
+ * ---------------------------------------------------------------------
+ * Snooze a due reminder for a number of minutes
+ * ---------------------------------------------------------------------
+ */
+export async function snoozeReminder(id: number, minutes: number) {
+  try {
+    await invoke<BackendResponse<Reminder>>("handle_reminder_command", {
+      command: { action: "Snooze", payload: { id, minutes } },
+    });
 
-  if (res.status === "success") {
-    reminders.update((list) => list.filter((l) => l.id !== id));
-  } else {
-    console.error(res.message);
+    dueReminders.update((list) => list.filter((r) => r.id !== id));
+  } catch (e) {
+    if (isAppError(e) && e.kind === "notFound") {
+      dueReminders.update((list) => list.filter((r) => r.id !== id));
+    }
+    logCommandError("snoozeReminder", e);
+  }
+}
+
+/**
+ * ---------------------------------------------------------------------
+ * Mark a reminder as done
+ * ---------------------------------------------------------------------
+ */
+export async function markReminderDone(id: number) {
+  try {
+    const res = await invoke<BackendResponse<Reminder>>("handle_reminder_command", {
+      command: { action: "MarkDone", payload: { id } },
+    });
+
+    if (res.data) {
+      dueReminders.update((list) => list.filter((r) => r.id !== id));
+      reminders.update((list) => list.map((l) => (l.id === id ? res.data! : l)));
+      addNextOccurrence(res.data);
+    }
+  } catch (e) {
+    if (isAppError(e) && e.kind === "notFound") {
+      dueReminders.update((list) => list.filter((r) => r.id !== id));
+      reminders.update((list) => list.filter((l) => l.id !== id));
+    }
+    logCommandError("markReminderDone", e);
   }
 }
+
+/**
+ * ---------------------------------------------------------------------
+ * Handle Snooze / Done pressed on a reminder notification
+ *
+ * Notification actions exist on Android and iOS only; on desktop the
+ * registration rejects and null is returned, leaving the in-app
+ * DueReminders panel as the only way to act on a reminder.
+ * ---------------------------------------------------------------------
+ */
+export async function listenForReminderActions(): Promise<PluginListener | null> {
+  try {
+    await registerActionTypes([
+      {
+        id: REMINDER_ACTION_TYPE,
+        actions: [
+          { id: "snooze", title: `Snooze ${SNOOZE_MINUTES} min` },
+          { id: "done", title: "Done" },
+        ],
+      },
+    ]);
+  } catch {
+    return null;
+  }
+
+  return onAction((event) => {
+    const { actionId, notification } = event as unknown as {
+      actionId: string;
+      notification?: { extra?: { reminderId?: number } };
+    };
+    const id = notification?.extra?.reminderId;
+    if (id === undefined) return;
+
+    if (actionId === "snooze") {
+      snoozeReminder(id, SNOOZE_MINUTES);
+    } else if (actionId === "done") {
+      markReminderDone(id);
+    }
+  });
+}
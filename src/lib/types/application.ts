@@ -6,4 +6,15 @@ export interface Application extends BaseEntity {
   stage: Stage;
   appliedDate: string;
   applicationNotes?: string;
+  daysInStage?: number | null;
+}
+
+export interface StageEvent {
+  id: number;
+  applicationId: number;
+  fromStage: Stage | null;
+  toStage: Stage;
+  changedAt: string;
+  leftAt: string | null;
+  daysInStage: number;
 }
@@ -0,0 +1,21 @@
+import type { Application } from "./application";
+import type { ContactRole } from "./enums";
+import type { Person } from "./person";
+
+export interface ApplicationContact {
+  applicationId: number;
+  personId: number;
+  role: ContactRole;
+  createdAt: string;
+  updatedAt: string;
+}
+
+/** Item of `ListForApplication`. */
+export interface ApplicationContactPerson extends Person {
+  contactRole: ContactRole;
+}
+
+/** Item of `ListForPerson`. */
+export interface PersonApplication extends Application {
+  contactRole: ContactRole;
+}
@@ -0,0 +1,17 @@
+/** Entity types attachments can be linked to. */
+export type AttachableEntity = "application" | "job_listing" | "company" | "person";
+
+export interface Attachment {
+  id: number;
+  sha256: string;
+  /** Name of the file when it was first added. */
+  filename: string;
+  mimeType: string;
+  sizeBytes: number;
+  storagePath: string;
+  createdAt: string;
+  /** Set by `ListForEntity`. */
+  linkedAt?: string;
+  /** Set by `Add` when identical content was already stored. */
+  deduplicated?: boolean;
+}
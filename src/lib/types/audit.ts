@@ -0,0 +1,26 @@
+import type { TaggableEntity } from "./tag";
+
+export type AuditAction =
+  | "create"
+  | "update"
+  | "delete"
+  | "restore"
+  | "archive"
+  | "unarchive"
+  | "purge"
+  | "revert";
+
+/** One recorded change, as returned by `GetHistory` (newest first). */
+export interface AuditEntry {
+  id: number;
+  entityType: TaggableEntity;
+  entityId: number;
+  action: AuditAction;
+  /** Changed fields only. */
+  changes: Record<string, { before: unknown; after: unknown }>;
+  /** Command or rule the change came from, e.g. `company.Update` or `rule.3`. */
+  origin: string | null;
+  /** Undoable command the change was part of. */
+  operationId: number | null;
+  createdAt: string;
+}
@@ -1,5 +1,75 @@
+/**
+ * Resolved value of the entity commands. Failures reject with an
+ * `AppError` instead, so `status` is always "success".
+ */
 export interface BackendResponse<T> {
-  status: "success" | "error";
+  status: "success";
   message: string;
   data?: T;
+  pagination?: Pagination;
+}
+
+export interface Pagination {
+  total: number;
+  limit: number | null;
+  offset: number;
+  hasMore: boolean;
+}
+
+/**
+ * Rejection value of the entity commands (`handle_*_command`).
+ * `kind` tells failures apart without parsing `message`.
+ */
+export type AppError =
+  | { kind: "notFound"; message: string }
+  | { kind: "validation"; message: string; fields: FieldError[] }
+  | { kind: "conflict"; message: string }
+  | {
+      kind: "constraintViolation";
+      message: string;
+      constraint: "foreignKey" | "check" | "notNull" | "other";
+    }
+  | { kind: "database"; message: string }
+  | { kind: "io"; message: string };
+
+export interface FieldError {
+  field: string;
+  message: string;
+}
+
+/**
+ * Narrows an `invoke` rejection to an `AppError`. The stores branch on
+ * `kind`, e.g. dropping an entry that is already gone on `notFound`.
+ */
+export function isAppError(e: unknown): e is AppError {
+  return typeof e === "object" && e !== null && "kind" in e && "message" in e;
+}
+
+/**
+ * Logs a rejected command. Input problems are warnings; database and
+ * I/O failures, and rejections that aren't an `AppError` at all (e.g. an
+ * unknown command), are errors.
+ */
+export function logCommandError(command: string, e: unknown) {
+  if (!isAppError(e)) {
+    console.error(`${command} failed:`, e);
+    return;
+  }
+
+  switch (e.kind) {
+    case "validation":
+      console.warn(`${command}: ${e.message}`, e.fields);
+      break;
+    case "notFound":
+    case "conflict":
+      console.warn(`${command}: ${e.message}`);
+      break;
+    case "constraintViolation":
+      console.warn(`${command}: ${e.message} (${e.constraint})`);
+      break;
+    case "database":
+    case "io":
+      console.error(`${command}: ${e.message}`);
+      break;
+  }
 }
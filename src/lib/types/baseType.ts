@@ -1,6 +1,13 @@
+import type { TagRef } from "./tag";
+
 export interface BaseEntity {
   id?: number;
   displayLabel?: string;
+  tags?: TagRef[];
   createdAt?: string;
   updatedAt?: string;
+  /** Set while the record is archived. */
+  archivedAt?: string | null;
+  /** Set while the record is in the trash. */
+  deletedAt?: string | null;
 }
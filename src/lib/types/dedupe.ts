@@ -0,0 +1,17 @@
+export type DuplicateReason =
+  | "name"
+  | "similarName"
+  | "domain"
+  | "email"
+  | "phone"
+  | "linkedin";
+
+/** One likely duplicate, as returned by `FindDuplicates` (best match first). */
+export interface DuplicatePair {
+  entityType: "company" | "person";
+  first: { id: number; label: string };
+  second: { id: number; label: string };
+  /** 0..1 */
+  score: number;
+  reasons: DuplicateReason[];
+}
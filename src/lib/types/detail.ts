@@ -0,0 +1,52 @@
+import type { Application, StageEvent } from "./application";
+import type { ApplicationContactPerson, PersonApplication } from "./applicationContact";
+import type { Attachment } from "./attachment";
+import type { Company } from "./company";
+import type { Interaction } from "./interaction";
+import type { JobListing } from "./jobListing";
+import type { Note } from "./note";
+import type { Person } from "./person";
+import type { Reminder } from "./reminder";
+
+// Results of the `GetDetail` actions
+
+export interface CompanyDetail {
+  company: Company;
+  jobListings: JobListing[];
+  persons: Person[];
+  interactions: Interaction[];
+  notes: Note[];
+  reminders: Reminder[];
+  attachments: Attachment[];
+}
+
+export interface JobListingDetail {
+  jobListing: JobListing;
+  company: Company;
+  applications: Application[];
+  notes: Note[];
+  reminders: Reminder[];
+  attachments: Attachment[];
+}
+
+export interface PersonDetail {
+  person: Person;
+  company: Company | null;
+  applications: PersonApplication[];
+  interactions: Interaction[];
+  notes: Note[];
+  reminders: Reminder[];
+  attachments: Attachment[];
+}
+
+export interface ApplicationDetail {
+  application: Application;
+  jobListing: JobListing | null;
+  company: Company | null;
+  stageHistory: StageEvent[];
+  contacts: ApplicationContactPerson[];
+  interactions: Interaction[];
+  notes: Note[];
+  reminders: Reminder[];
+  attachments: Attachment[];
+}
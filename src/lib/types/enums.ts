@@ -51,21 +51,56 @@ export const SeniorityLevelDisplay: Record<SeniorityLevel, string> = {
 
 // ======================================================
 
+/** Common ISO 4217 codes offered in pickers; the backend accepts any. */
 export const Currency = {
   USD: "USD",
   EUR: "EUR",
   GBP: "GBP",
   DKK: "DKK",
-  Other: "other",
+  SEK: "SEK",
+  NOK: "NOK",
+  CHF: "CHF",
+  PLN: "PLN",
+  CAD: "CAD",
+  AUD: "AUD",
+  JPY: "JPY",
+  INR: "INR",
+  XXX: "XXX",
 } as const;
-export type Currency = (typeof Currency)[keyof typeof Currency];
+/** An ISO 4217 currency code such as "EUR"; "XXX" means none in particular. */
+export type Currency = string;
 
-export const CurrencyDisplay: Record<Currency, string> = {
+export const CurrencyDisplay: Record<string, string> = {
   USD: "USD ($)",
   EUR: "EUR (€)",
   GBP: "GBP (£)",
   DKK: "DKK (kr)",
-  other: "Other",
+  SEK: "SEK (kr)",
+  NOK: "NOK (kr)",
+  CHF: "CHF (Fr.)",
+  PLN: "PLN (zł)",
+  CAD: "CAD ($)",
+  AUD: "AUD ($)",
+  JPY: "JPY (¥)",
+  INR: "INR (₹)",
+  XXX: "Other",
+};
+
+// ======================================================
+
+export const PayPeriod = {
+  Hourly: "hourly",
+  Daily: "daily",
+  Monthly: "monthly",
+  Yearly: "yearly",
+} as const;
+export type PayPeriod = (typeof PayPeriod)[keyof typeof PayPeriod];
+
+export const PayPeriodDisplay: Record<PayPeriod, string> = {
+  hourly: "Per hour",
+  daily: "Per day",
+  monthly: "Per month",
+  yearly: "Per year",
 };
 
 // ======================================================
@@ -93,6 +128,25 @@ export const RoleDisplay: Record<Role, string> = {
 
 // ======================================================
 
+export const ContactRole = {
+  Recruiter: "recruiter",
+  Referrer: "referrer",
+  Interviewer: "interviewer",
+  HiringManager: "hiring_manager",
+  Other: "other",
+} as const;
+export type ContactRole = (typeof ContactRole)[keyof typeof ContactRole];
+
+export const ContactRoleDisplay: Record<ContactRole, string> = {
+  recruiter: "Recruiter",
+  referrer: "Referrer",
+  interviewer: "Interviewer",
+  hiring_manager: "Hiring manager",
+  other: "Other",
+};
+
+// ======================================================
+
 export const Stage = {
   Applied: "applied",
   Screening: "screening",
@@ -104,6 +158,7 @@ export const Stage = {
   Rejected: "rejected",
   Withdrawn: "withdrawn",
   OnHold: "on_hold",
+  Ghosted: "ghosted",
   Other: "other",
 } as const;
 export type Stage = (typeof Stage)[keyof typeof Stage];
@@ -119,6 +174,7 @@ export const StageDisplay: Record<Stage, string> = {
   rejected: "Rejected",
   withdrawn: "Withdrawn",
   on_hold: "On hold",
+  ghosted: "Ghosted",
   other: "Other",
 };
 
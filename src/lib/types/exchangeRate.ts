@@ -0,0 +1,15 @@
+import type { Currency } from "./enums";
+
+/** One unit of `currency` is worth `rate` units of the base currency. */
+export interface ExchangeRate {
+  currency: Currency;
+  rate: number;
+  isBase: boolean;
+  updatedAt: string;
+}
+
+/** Returned by `ListRates` and `SetBaseCurrency`. */
+export interface ExchangeRates {
+  baseCurrency: Currency | null;
+  rates: ExchangeRate[];
+}
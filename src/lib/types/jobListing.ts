@@ -1,5 +1,5 @@
 import type { BaseEntity } from "./baseType";
-import type { WorkType, SeniorityLevel, Currency } from "./enums";
+import type { WorkType, SeniorityLevel, Currency, PayPeriod } from "./enums";
 
 export interface JobListing extends BaseEntity {
   companyId: number | undefined;
@@ -10,6 +10,11 @@ export interface JobListing extends BaseEntity {
   salaryMin?: number;
   salaryMax?: number;
   currency?: Currency;
+  payPeriod?: PayPeriod;
+  /** Yearly amounts in `baseCurrency`; null without an exchange rate. */
+  annualSalaryMin?: number | null;
+  annualSalaryMax?: number | null;
+  baseCurrency?: Currency | null;
   description?: string;
   url?: string;
 }
@@ -0,0 +1,43 @@
+export type FilterOp =
+  | "eq"
+  | "ne"
+  | "lt"
+  | "lte"
+  | "gt"
+  | "gte"
+  | "contains"
+  | "startsWith"
+  | "in"
+  | "notIn"
+  | "between"
+  | "isNull"
+  | "isNotNull";
+
+export interface ListFilter {
+  field: string;
+  op: FilterOp;
+  value?: unknown;
+}
+
+export interface ListSort {
+  field: string;
+  direction?: "asc" | "desc";
+}
+
+export type Inclusion = "exclude" | "include" | "only";
+
+/** Payload accepted by every `ListAll` command. */
+export interface ListOptions {
+  filters?: ListFilter[];
+  sort?: ListSort[];
+  limit?: number;
+  offset?: number;
+  /** Tag IDs; only tagged records are returned. */
+  tags?: number[];
+  /** Whether a record needs all of `tags` (default) or any of them. */
+  tagMatch?: "all" | "any";
+  /** Archived records: left out by default. */
+  archived?: Inclusion;
+  /** Trashed records: left out by default. */
+  trashed?: Inclusion;
+}
@@ -4,7 +4,17 @@ export interface Reminder extends BaseEntity {
   title: string;
   message?: string;
   reminderDate: string;
+  /** HH:MM:SS wall-clock time; none means all day */
+  reminderTime?: string;
+  /** IANA time zone; none means the device's local time */
+  timezone?: string;
+  /** RRULE subset, e.g. "FREQ=WEEKLY;INTERVAL=1;UNTIL=20251231" */
+  recurrence?: string;
   isCompleted: boolean;
+  /** When the scheduler announced it (UTC) */
+  notifiedAt?: string | null;
+  /** Snoozed: not announced again before this (UTC) */
+  snoozedUntil?: string | null;
   applicationId?: number;
   jobListingId?: number;
   interactionId?: number;
@@ -12,3 +22,29 @@ export interface Reminder extends BaseEntity {
   companyId?: number;
   personId?: number;
 }
+
+/** Payload of the `reminder-due` event emitted by the scheduler */
+export interface DueReminder {
+  id: number;
+  title: string;
+  message?: string;
+  reminderDate: string;
+  reminderTime?: string;
+  timezone?: string;
+  displayLabel: string;
+  overdue: boolean;
+}
+
+/** One occurrence returned by `ListUpcoming` */
+export interface ReminderOccurrence extends Reminder {
+  occurrenceDate: string;
+  /** UTC due time of timed reminders */
+  dueAt?: string;
+}
+
+export enum RepeatFrequency {
+  Never = "NEVER",
+  Daily = "DAILY",
+  Weekly = "WEEKLY",
+  Monthly = "MONTHLY",
+}
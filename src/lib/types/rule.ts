@@ -0,0 +1,59 @@
+import type { InteractionType, NoteType, Stage } from "./enums";
+import type { TaggableEntity } from "./tag";
+
+/** What an automation rule reacts to. */
+export type RuleTrigger =
+  | { event: "application_created" }
+  /** Any stage when `stage` is omitted. */
+  | { event: "stage_changed"; stage?: Stage | null }
+  /** Any type when `interactionType` is omitted. */
+  | { event: "interaction_logged"; interactionType?: InteractionType | null }
+  /** Open applications without interactions for `days` days. */
+  | { event: "no_interaction"; days: number };
+
+/** What it does. Titles and texts may use `{company}`, `{job}` and `{stage}`. */
+export type RuleAction =
+  | {
+      type: "create_reminder";
+      title: string;
+      message?: string | null;
+      daysAfter: number;
+    }
+  | {
+      type: "create_note";
+      title: string;
+      content?: string | null;
+      noteType?: NoteType | null;
+    }
+  | { type: "set_stage"; stage: Stage };
+
+export interface AutomationRule {
+  id: number;
+  name: string;
+  enabled: boolean;
+  trigger: RuleTrigger;
+  action: RuleAction;
+  createdAt: string;
+  updatedAt: string;
+}
+
+export interface RuleRun {
+  id: number;
+  ruleId: number;
+  applicationId?: number | null;
+  /** Date of the last activity, for `no_interaction` rules. */
+  anchor?: string | null;
+  entityType?: TaggableEntity | null;
+  entityId?: number | null;
+  firedAt: string;
+}
+
+/** Listed as `automations` in create/update payloads when rules fired. */
+export interface RuleOutcome {
+  ruleId: number;
+  ruleName: string;
+  action: RuleAction["type"];
+  applicationId?: number | null;
+  entityType: TaggableEntity;
+  entityId: number;
+}
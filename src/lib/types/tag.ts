@@ -0,0 +1,23 @@
+/** Entity types that can carry tags (`entityType` in tag commands). */
+export type TaggableEntity =
+  | "company"
+  | "person"
+  | "job_listing"
+  | "application"
+  | "interaction"
+  | "note"
+  | "reminder";
+
+/** Short form embedded as `tags` in every entity payload. */
+export interface TagRef {
+  id: number;
+  name: string;
+  color?: string | null;
+}
+
+export interface Tag extends TagRef {
+  /** Only set by `ListAll`. */
+  usageCount?: number;
+  createdAt?: string;
+  updatedAt?: string;
+}
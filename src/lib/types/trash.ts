@@ -0,0 +1,9 @@
+import type { TaggableEntity } from "./tag";
+
+/** One record in the trash, as returned by `ListTrash`. */
+export interface TrashItem {
+  entityType: TaggableEntity;
+  id: number;
+  label: string;
+  deletedAt: string;
+}
@@ -0,0 +1,17 @@
+import type { AuditAction } from "./audit";
+import type { TaggableEntity } from "./tag";
+
+/** One change of an undone or redone operation. */
+export interface UndoChange {
+  entityType: TaggableEntity;
+  id: number;
+  /** The recorded action that was inverted or re-applied. */
+  action: AuditAction;
+}
+
+/** Returned by `Undo` and `Redo`; `Status` returns only the counts. */
+export interface UndoResult {
+  operations: UndoChange[][];
+  canUndo: number;
+  canRedo: number;
+}